@@ -0,0 +1,127 @@
+//! Response parsing benchmarks.
+//!
+//! Run with `cargo bench --features sync --bench messages`. Before the timings, the benchmark prints the number of heap
+//! allocations made per frame, compared with splitting each frame into a `Vec<String>`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ibapi::messages::ResponseMessage;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// TickPrice: type, version, request id, tick type, price, size, attributes
+const TICK_PRICE: &str = "1\x006\x009000\x001\x00185.50\x00100\x007\x00";
+
+// MarketDepthL2: type, version, request id, position, market maker, operation, side, price, size, smart depth
+const MARKET_DEPTH: &str = "13\x001\x009000\x003\x00NSDQ\x001\x000\x00185.49\x00300\x001\x00";
+
+// RealTimeBars: type, version, request id, time, open, high, low, close, volume, wap, count
+const REALTIME_BAR: &str = "50\x003\x009000\x001681133400\x00185.50\x00185.75\x00185.25\x00185.60\x001200\x00185.52\x0042\x00";
+
+fn decode_tick_price(frame: &str) -> f64 {
+    let mut message = ResponseMessage::from(frame);
+    message.skip(); // message type
+    message.skip(); // version
+    let request_id = message.next_int().unwrap();
+    let tick_type = message.next_int().unwrap();
+    let price = message.next_double().unwrap();
+    let size = message.next_double().unwrap();
+    let attributes = message.next_int().unwrap();
+    price + size + (request_id + tick_type + attributes) as f64
+}
+
+fn decode_market_depth(frame: &str) -> f64 {
+    let mut message = ResponseMessage::from(frame);
+    message.skip(); // message type
+    message.skip(); // version
+    let request_id = message.next_int().unwrap();
+    let position = message.next_int().unwrap();
+    let market_maker = message.next_str().unwrap().len();
+    let operation = message.next_int().unwrap();
+    let side = message.next_int().unwrap();
+    let price = message.next_double().unwrap();
+    let size = message.next_double().unwrap();
+    let smart_depth = message.next_bool().unwrap();
+    price + size + (request_id + position + operation + side + market_maker as i32 + smart_depth as i32) as f64
+}
+
+fn decode_realtime_bar(frame: &str) -> f64 {
+    let mut message = ResponseMessage::from(frame);
+    message.skip(); // message type
+    message.skip(); // version
+    let request_id = message.next_int().unwrap();
+    let time = message.next_long().unwrap();
+    let mut total = (request_id as i64 + time) as f64;
+    for _ in 0..6 {
+        total += message.next_double().unwrap();
+    }
+    total + message.next_int().unwrap() as f64
+}
+
+// Field-per-allocation parsing, as done before frames were kept in a single buffer.
+fn split_fields(frame: &str) -> Vec<String> {
+    let mut fields: Vec<String> = frame.split('\0').map(|field| field.to_string()).collect();
+    fields.pop();
+    fields
+}
+
+fn allocations(f: impl Fn()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn report_allocations() {
+    println!("allocations per frame (single buffer / field per allocation)");
+    for (name, frame, decode) in [
+        ("tick_price", TICK_PRICE, decode_tick_price as fn(&str) -> f64),
+        ("market_depth", MARKET_DEPTH, decode_market_depth),
+        ("realtime_bar", REALTIME_BAR, decode_realtime_bar),
+    ] {
+        let current = allocations(|| {
+            black_box(decode(black_box(frame)));
+        });
+        let split = allocations(|| {
+            black_box(split_fields(black_box(frame)));
+        });
+        println!("  {name:<14} {current:>3} / {split:>3}");
+    }
+}
+
+fn parse_benchmarks(c: &mut Criterion) {
+    report_allocations();
+
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("tick_price", |b| b.iter(|| decode_tick_price(black_box(TICK_PRICE))));
+    group.bench_function("market_depth", |b| b.iter(|| decode_market_depth(black_box(MARKET_DEPTH))));
+    group.bench_function("realtime_bar", |b| b.iter(|| decode_realtime_bar(black_box(REALTIME_BAR))));
+    group.bench_function("split_fields/realtime_bar", |b| b.iter(|| split_fields(black_box(REALTIME_BAR))));
+    group.finish();
+}
+
+criterion_group!(benches, parse_benchmarks);
+criterion_main!(benches);
@@ -1,7 +1,7 @@
 //! Captures raw responses for order listing operations (open orders, completed orders, executions)
 
+use ibapi::blocking::Client;
 use ibapi::orders::{ExecutionFilter, Executions, Orders};
-use ibapi::Client;
 use std::env;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
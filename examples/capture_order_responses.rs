@@ -1,8 +1,8 @@
 //! Captures raw order response messages from TWS for test data generation
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::orders::{order_builder, Action, PlaceOrder};
-use ibapi::Client;
 use std::env;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1,6 +1,6 @@
 //! Simple example to capture next_valid_order_id response for testing
 
-use ibapi::Client;
+use ibapi::blocking::Client;
 use std::env;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -7,10 +7,11 @@ use core::str::FromStr;
 use std::fs;
 use std::path::Path;
 
+use ibapi::blocking::trace;
+use ibapi::blocking::Client;
 use ibapi::messages::parser_registry::{MessageParserRegistry, ParsedField};
 use ibapi::messages::*;
 use ibapi::prelude::*;
-use ibapi::trace;
 use serde::{Deserialize, Serialize};
 
 /// Represents a field in a TWS message
@@ -7,7 +7,7 @@
 //! ```
 
 use ibapi::accounts::{types::AccountGroup, AccountSummaryResult, AccountSummaryTags};
-use ibapi::Client;
+use ibapi::blocking::Client;
 
 fn main() {
     env_logger::init();
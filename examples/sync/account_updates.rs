@@ -7,7 +7,7 @@
 //! ```
 
 use ibapi::accounts::{types::AccountId, AccountUpdate};
-use ibapi::Client;
+use ibapi::blocking::Client;
 
 fn main() {
     env_logger::init();
@@ -8,10 +8,10 @@
 
 use std::collections::VecDeque;
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::realtime::{Bar, BarSize, WhatToShow};
 use ibapi::orders::{order_builder, Action, PlaceOrder};
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
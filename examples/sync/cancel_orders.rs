@@ -8,7 +8,7 @@
 
 use clap::{arg, Command};
 
-use ibapi::Client;
+use ibapi::blocking::Client;
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
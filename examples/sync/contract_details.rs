@@ -6,8 +6,8 @@
 //! cargo run --features sync --example contract_details
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
-use ibapi::Client;
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
@@ -6,9 +6,9 @@
 //! cargo run --features sync --example histogram_data
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::historical::BarSize;
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
@@ -6,9 +6,9 @@
 //! cargo run --features sync --example historical_data_options
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::contracts::{Contract, SecurityType};
 use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
-use ibapi::Client;
 
 // This example demonstrates how to request historical data for an options contract.
 // Historical data is not available to expired options contracts.
@@ -10,9 +10,9 @@
 
 use clap::{arg, Command};
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
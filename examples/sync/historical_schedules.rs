@@ -9,9 +9,9 @@
 use clap::{arg, Command};
 use time::macros::datetime;
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::historical::ToDuration;
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
@@ -11,8 +11,8 @@ use time::format_description::FormatItem;
 use time::macros::format_description;
 use time::{OffsetDateTime, PrimitiveDateTime};
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
@@ -6,7 +6,8 @@
 //! cargo run --features sync --example market_data
 //! ```
 
-use ibapi::{contracts::Contract, market_data::realtime::TickTypes, Client};
+use ibapi::blocking::Client;
+use ibapi::{contracts::Contract, market_data::realtime::TickTypes};
 
 // This example demonstrates how to request realtime market data for a contract.
 
@@ -38,6 +39,7 @@ fn main() {
             TickTypes::RequestParameters(tick_request_parameters) => println!("{tick_request_parameters:?}"),
             TickTypes::SnapshotEnd => subscription.cancel(),
             TickTypes::Notice(notice) => println!("{notice:?}"),
+            TickTypes::MarketDataType(market_data_type) => println!("{market_data_type:?}"),
         }
     }
 }
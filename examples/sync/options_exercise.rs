@@ -6,10 +6,10 @@
 //! cargo run --features sync --example options_exercise
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::{
     contracts::{Contract, SecurityType},
     orders::ExerciseAction,
-    Client,
 };
 
 fn main() {
@@ -6,10 +6,10 @@
 //! cargo run --features sync --example options_purchase
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::{
     contracts::{Contract, SecurityType},
     orders::{self, order_builder, PlaceOrder},
-    Client,
 };
 
 fn main() {
@@ -10,9 +10,9 @@ use anyhow::Ok;
 use clap::builder::PossibleValue;
 use clap::{arg, Command};
 
-use ibapi::client::Subscription;
+use ibapi::blocking::Client;
+use ibapi::blocking::Subscription;
 use ibapi::orders::Orders;
-use ibapi::Client;
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
@@ -9,9 +9,9 @@
 use clap::{arg, ArgMatches, Command};
 use log::{debug, info};
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::orders::{self, order_builder, PlaceOrder};
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
@@ -8,7 +8,8 @@
 
 use std::env;
 
-use ibapi::{accounts::types::AccountId, Client};
+use ibapi::accounts::types::AccountId;
+use ibapi::blocking::Client;
 
 pub fn main() {
     env_logger::init();
@@ -9,6 +9,7 @@
 use std::sync::Arc;
 use std::thread;
 
+use ibapi::blocking::Client;
 use ibapi::prelude::*;
 
 fn main() {
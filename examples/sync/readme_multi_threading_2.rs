@@ -8,6 +8,7 @@
 
 use std::thread;
 
+use ibapi::blocking::Client;
 use ibapi::prelude::*;
 
 fn main() {
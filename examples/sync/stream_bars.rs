@@ -11,9 +11,9 @@ use std::time::Duration;
 
 use clap::{arg, ArgMatches, Command};
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::realtime::{BarSize, WhatToShow};
-use ibapi::Client;
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
@@ -6,9 +6,10 @@
 //! cargo run --features sync --example stream_retry
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::market_data::realtime::{BarSize, WhatToShow};
-use ibapi::{Client, Error};
+use ibapi::Error;
 
 fn main() {
     env_logger::init();
@@ -10,6 +10,7 @@
 //! - Commission reports
 //! - System messages
 
+use ibapi::blocking::Client;
 use ibapi::{contracts::ContractBuilder, prelude::*};
 use std::sync::Arc;
 use std::thread;
@@ -6,8 +6,8 @@
 //! cargo run --features sync --example switch_market_data_type
 //! ```
 
+use ibapi::blocking::Client;
 use ibapi::market_data::MarketDataType;
-use ibapi::Client;
 
 fn main() {
     env_logger::init();
@@ -8,8 +8,8 @@
 
 use std::time::Duration;
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
-use ibapi::Client;
 
 // This example demonstrates how to stream tick by tick data for the last price of a contract.
 
@@ -1,7 +1,7 @@
 //! Test trace functionality
 
-use ibapi::client::Client;
-use ibapi::trace;
+use ibapi::blocking::trace;
+use ibapi::blocking::Client;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("debug")).init();
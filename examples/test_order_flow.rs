@@ -1,8 +1,8 @@
 //! Test complete order flow to capture exact message formats
 
+use ibapi::blocking::Client;
 use ibapi::contracts::Contract;
 use ibapi::orders::{order_builder, Action};
-use ibapi::Client;
 use std::env;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
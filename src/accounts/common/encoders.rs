@@ -165,7 +165,7 @@ mod tests {
         let message = super::encode_request_positions().expect("error encoding request");
 
         assert_eq!(message[0], OutgoingMessages::RequestPositions.to_field());
-        assert_eq!(message[1], "1");
+        assert_eq!(&message[1], "1");
     }
 
     #[test]
@@ -173,7 +173,7 @@ mod tests {
         let message = super::encode_cancel_positions().expect("error encoding request");
 
         assert_eq!(message[0], OutgoingMessages::CancelPositions.to_field());
-        assert_eq!(message[1], "1");
+        assert_eq!(&message[1], "1");
     }
 
     #[test]
@@ -188,8 +188,8 @@ mod tests {
         assert_eq!(message[0], OutgoingMessages::RequestPositionsMulti.to_field());
         assert_eq!(message[1], version.to_field());
         assert_eq!(message[2], request_id.to_field());
-        assert_eq!(message[3], "U1234567");
-        assert_eq!(message[4], "TARGET2024");
+        assert_eq!(&message[3], "U1234567");
+        assert_eq!(&message[4], "TARGET2024");
     }
 
     #[test]
@@ -234,8 +234,8 @@ mod tests {
             assert_eq!(message[0], OutgoingMessages::RequestPositionsMulti.to_field(), "Case: {} - type", tc.name);
             assert_eq!(message[1], version.to_field(), "Case: {} - version", tc.name);
             assert_eq!(message[2], request_id.to_field(), "Case: {} - request_id", tc.name);
-            assert_eq!(message[3], tc.expected_account_field, "Case: {} - account", tc.name);
-            assert_eq!(message[4], tc.expected_model_field, "Case: {} - model_code", tc.name);
+            assert_eq!(&message[3], tc.expected_account_field, "Case: {} - account", tc.name);
+            assert_eq!(&message[4], tc.expected_model_field, "Case: {} - model_code", tc.name);
         }
     }
 
@@ -256,7 +256,7 @@ mod tests {
         let message = super::encode_request_family_codes().expect("error encoding request");
 
         assert_eq!(message[0], OutgoingMessages::RequestFamilyCodes.to_field());
-        assert_eq!(message[1], "1");
+        assert_eq!(&message[1], "1");
     }
 
     #[test]
@@ -269,8 +269,8 @@ mod tests {
 
         assert_eq!(request_no_model[0], OutgoingMessages::RequestPnL.to_field(), "type (no model)");
         assert_eq!(request_no_model[1], request_id.to_field(), "request_id (no model)");
-        assert_eq!(request_no_model[2], "DU1234567", "account (no model)");
-        assert_eq!(request_no_model[3], "", "model_code (no model)");
+        assert_eq!(&request_no_model[2], "DU1234567", "account (no model)");
+        assert_eq!(&request_no_model[3], "", "model_code (no model)");
 
         let request_id_with_model = 3001;
         let model_code_some = ModelCode("TestModelPnl".to_string());
@@ -279,8 +279,8 @@ mod tests {
 
         assert_eq!(request_with_model[0], OutgoingMessages::RequestPnL.to_field(), "type (with model)");
         assert_eq!(request_with_model[1], request_id_with_model.to_field(), "request_id (with model)");
-        assert_eq!(request_with_model[2], "DU1234567", "account (with model)");
-        assert_eq!(request_with_model[3], "TestModelPnl", "model_code (with model)");
+        assert_eq!(&request_with_model[2], "DU1234567", "account (with model)");
+        assert_eq!(&request_with_model[3], "TestModelPnl", "model_code (with model)");
     }
 
     #[test]
@@ -303,8 +303,8 @@ mod tests {
 
         assert_eq!(request_no_model[0], OutgoingMessages::RequestPnLSingle.to_field(), "type (no model)");
         assert_eq!(request_no_model[1], request_id.to_field(), "request_id (no model)");
-        assert_eq!(request_no_model[2], "DU1234567", "account (no model)");
-        assert_eq!(request_no_model[3], "", "model_code (no model)");
+        assert_eq!(&request_no_model[2], "DU1234567", "account (no model)");
+        assert_eq!(&request_no_model[3], "", "model_code (no model)");
         assert_eq!(request_no_model[4], 1001.to_field(), "contract_id (no model)");
 
         let request_id_with_model = 3002;
@@ -317,8 +317,8 @@ mod tests {
 
         assert_eq!(request_with_model[0], OutgoingMessages::RequestPnLSingle.to_field(), "type (with model)");
         assert_eq!(request_with_model[1], request_id_with_model.to_field(), "request_id (with model)");
-        assert_eq!(request_with_model[2], "DU456", "account (with model)");
-        assert_eq!(request_with_model[3], "MyModelPnlSingle", "model_code (with model)");
+        assert_eq!(&request_with_model[2], "DU456", "account (with model)");
+        assert_eq!(&request_with_model[3], "MyModelPnlSingle", "model_code (with model)");
         assert_eq!(request_with_model[4], 1002.to_field(), "contract_id (with model)");
     }
 
@@ -342,7 +342,7 @@ mod tests {
         assert_eq!(request[0], OutgoingMessages::RequestAccountSummary.to_field());
         assert_eq!(request[1], version.to_field());
         assert_eq!(request[2], request_id.to_field());
-        assert_eq!(request[3], "All");
+        assert_eq!(&request[3], "All");
         assert_eq!(request[4], tags.join(","));
     }
 
@@ -350,14 +350,14 @@ mod tests {
     fn test_encode_request_managed_accounts() {
         let message = super::encode_request_managed_accounts().expect("encoding failed");
         assert_eq!(message[0], OutgoingMessages::RequestManagedAccounts.to_field());
-        assert_eq!(message[1], "1"); // Version
+        assert_eq!(&message[1], "1"); // Version
     }
 
     #[test]
     fn test_encode_request_server_time() {
         let message = super::encode_request_server_time().expect("encoding failed");
         assert_eq!(message[0], OutgoingMessages::RequestCurrentTime.to_field());
-        assert_eq!(message[1], "1"); // Version
+        assert_eq!(&message[1], "1"); // Version
     }
 
     #[test]
@@ -381,7 +381,7 @@ mod tests {
         assert_eq!(request_sv_ge10[0], OutgoingMessages::RequestAccountData.to_field());
         assert_eq!(request_sv_ge10[1], version.to_field());
         assert_eq!(request_sv_ge10[2], true.to_field());
-        assert_eq!(request_sv_ge10[3], "DU1234567");
+        assert_eq!(&request_sv_ge10[3], "DU1234567");
     }
 
     #[test]
@@ -419,8 +419,8 @@ mod tests {
         assert_eq!(request[0], OutgoingMessages::RequestAccountUpdatesMulti.to_field());
         assert_eq!(request[1], version.to_field());
         assert_eq!(request[2], request_id.to_field());
-        assert_eq!(request[3], "DU1234567");
-        assert_eq!(request[4], "");
+        assert_eq!(&request[3], "DU1234567");
+        assert_eq!(&request[4], "");
         assert_eq!(request[5], subscribe.to_field());
     }
 
@@ -478,8 +478,8 @@ mod tests {
             );
             assert_eq!(message[1], version.to_field(), "Case: {} - version", tc.name);
             assert_eq!(message[2], request_id.to_field(), "Case: {} - request_id", tc.name);
-            assert_eq!(message[3], tc.expected_account_field, "Case: {} - account", tc.name);
-            assert_eq!(message[4], tc.expected_model_field, "Case: {} - model_code", tc.name);
+            assert_eq!(&message[3], tc.expected_account_field, "Case: {} - account", tc.name);
+            assert_eq!(&message[4], tc.expected_model_field, "Case: {} - model_code", tc.name);
             assert_eq!(message[5], subscribe.to_field(), "Case: {} - subscribe", tc.name);
         }
     }
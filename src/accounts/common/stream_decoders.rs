@@ -188,7 +188,7 @@ mod tests {
             let request = AccountSummaryResult::cancel_message(TEST_SERVER_VERSION, Some(TEST_REQUEST_ID), None).unwrap();
 
             assert_eq!(request[0], OutgoingMessages::CancelAccountSummary.to_string());
-            assert_eq!(request[1], "1"); // version
+            assert_eq!(&request[1], "1"); // version
             assert_eq!(request[2], TEST_REQUEST_ID.to_string());
         }
 
@@ -312,7 +312,7 @@ mod tests {
             let request = PositionUpdate::cancel_message(TEST_SERVER_VERSION, None, None).unwrap();
 
             assert_eq!(request[0], OutgoingMessages::CancelPositions.to_string());
-            assert_eq!(request[1], "1");
+            assert_eq!(&request[1], "1");
         }
 
         #[test]
@@ -362,7 +362,7 @@ mod tests {
             let request = PositionUpdateMulti::cancel_message(TEST_SERVER_VERSION, Some(TEST_REQUEST_ID), None).unwrap();
 
             assert_eq!(request[0], OutgoingMessages::CancelPositionsMulti.to_string());
-            assert_eq!(request[1], "1"); // version
+            assert_eq!(&request[1], "1"); // version
             assert_eq!(request[2], TEST_REQUEST_ID.to_string());
         }
 
@@ -507,7 +507,7 @@ mod tests {
             let request = AccountUpdateMulti::cancel_message(TEST_SERVER_VERSION, Some(TEST_REQUEST_ID), None).unwrap();
 
             assert_eq!(request[0], OutgoingMessages::CancelAccountUpdatesMulti.to_string());
-            assert_eq!(request[1], "1"); // version
+            assert_eq!(&request[1], "1"); // version
             assert_eq!(request[2], TEST_REQUEST_ID.to_string());
         }
 
@@ -1,11 +1,17 @@
 //! Synchronous implementation of account management functionality
 
+use std::time::Duration;
+
 use time::OffsetDateTime;
 
-use crate::client::{ClientRequestBuilders, SharesChannel, Subscription};
-use crate::messages::OutgoingMessages;
+use crate::client::builders::sync::ClientRequestBuilders;
+use crate::client::sync::Client;
+use crate::common::request_helpers::sync_helpers as request_helpers;
+use crate::messages::{OutgoingMessages, ResponseMessage};
 use crate::protocol::{check_version, Features};
-use crate::{Client, Error};
+use crate::subscriptions::sync::{SharesChannel, Subscription};
+use crate::transport::InternalSubscription;
+use crate::Error;
 
 use super::common::{decoders, encoders};
 use super::types::{AccountGroup, AccountId, ContractId, ModelCode};
@@ -16,8 +22,8 @@ impl SharesChannel for Subscription<'_, PositionUpdate> {}
 
 // Subscribes to position updates for all accessible accounts.
 // All positions sent initially, and then only updates as positions change.
-pub fn positions(client: &Client) -> Result<Subscription<PositionUpdate>, Error> {
-    crate::common::request_helpers::shared_subscription(
+pub fn positions(client: &Client) -> Result<Subscription<'_, PositionUpdate>, Error> {
+    request_helpers::shared_subscription(
         client,
         Features::POSITIONS,
         OutgoingMessages::RequestPositions,
@@ -40,7 +46,7 @@ pub fn positions_multi<'a>(
 
 // Determine whether an account exists under an account family and find the account family code.
 pub fn family_codes(client: &Client) -> Result<Vec<FamilyCode>, Error> {
-    crate::common::request_helpers::one_shot_request(
+    request_helpers::one_shot_request(
         client,
         Features::FAMILY_CODES,
         OutgoingMessages::RequestFamilyCodes,
@@ -57,7 +63,7 @@ pub fn family_codes(client: &Client) -> Result<Vec<FamilyCode>, Error> {
 // * `account`    - account for which to receive PnL updates
 // * `model_code` - specify to request PnL updates for a specific model
 pub fn pnl<'a>(client: &'a Client, account: &AccountId, model_code: Option<&ModelCode>) -> Result<Subscription<'a, PnL>, Error> {
-    crate::common::request_helpers::request_with_id(client, Features::PNL, |id| encoders::encode_request_pnl(id, account, model_code))
+    request_helpers::request_with_id(client, Features::PNL, |id| encoders::encode_request_pnl(id, account, model_code))
 }
 
 // Requests real time updates for daily PnL of individual positions.
@@ -73,19 +79,19 @@ pub fn pnl_single<'a>(
     contract_id: ContractId,
     model_code: Option<&ModelCode>,
 ) -> Result<Subscription<'a, PnLSingle>, Error> {
-    crate::common::request_helpers::request_with_id(client, Features::REALIZED_PNL, |id| {
+    request_helpers::request_with_id(client, Features::REALIZED_PNL, |id| {
         encoders::encode_request_pnl_single(id, account, contract_id, model_code)
     })
 }
 
 pub fn account_summary<'a>(client: &'a Client, group: &AccountGroup, tags: &[&str]) -> Result<Subscription<'a, AccountSummaryResult>, Error> {
-    crate::common::request_helpers::request_with_id(client, Features::ACCOUNT_SUMMARY, |id| {
+    request_helpers::request_with_id(client, Features::ACCOUNT_SUMMARY, |id| {
         encoders::encode_request_account_summary(id, group, tags)
     })
 }
 
 pub fn account_updates<'a>(client: &'a Client, account: &AccountId) -> Result<Subscription<'a, AccountUpdate>, Error> {
-    crate::common::request_helpers::shared_request(client, OutgoingMessages::RequestAccountData, || {
+    request_helpers::shared_request(client, OutgoingMessages::RequestAccountData, || {
         encoders::encode_request_account_updates(client.server_version(), account)
     })
 }
@@ -104,7 +110,7 @@ pub fn account_updates_multi<'a>(
 }
 
 pub fn managed_accounts(client: &Client) -> Result<Vec<String>, Error> {
-    crate::common::request_helpers::one_shot_with_retry(
+    request_helpers::one_shot_with_retry(
         client,
         OutgoingMessages::RequestManagedAccounts,
         encoders::encode_request_managed_accounts,
@@ -119,31 +125,64 @@ pub fn managed_accounts(client: &Client) -> Result<Vec<String>, Error> {
 }
 
 pub fn server_time(client: &Client) -> Result<OffsetDateTime, Error> {
-    crate::common::request_helpers::one_shot_with_retry(
+    let mut previous = client.server_time_requests.lock()?;
+    discard_responses(previous.take());
+
+    request_helpers::one_shot_with_retry(
         client,
         OutgoingMessages::RequestCurrentTime,
         encoders::encode_request_server_time,
-        |message| {
-            message.skip(); // message type
-            message.skip(); // message version
-            let timestamp = message.next_long()?;
-            match OffsetDateTime::from_unix_timestamp(timestamp) {
-                Ok(date) => Ok(date),
-                Err(e) => Err(Error::Simple(format!("Error parsing date: {e}"))),
-            }
-        },
+        decode_server_time,
         || Err(Error::Simple("No response from server".to_string())),
     )
 }
 
+/// Requests the server time, waiting a limited time for the response.
+///
+/// Responses share one channel, so a response arriving after its request timed out would be taken for the next one.
+/// The request is kept until the next server time request, which discards the responses it left behind.
+pub(crate) fn server_time_timeout(client: &Client, timeout: Duration) -> Result<OffsetDateTime, Error> {
+    let mut previous = client.server_time_requests.lock()?;
+    discard_responses(previous.take());
+
+    let request = encoders::encode_request_server_time()?;
+    let subscription = client.shared_request(OutgoingMessages::RequestCurrentTime).send_raw(request)?;
+    let response = subscription.next_timeout(timeout);
+    *previous = Some(subscription);
+
+    match response {
+        Some(Ok(mut message)) => decode_server_time(&mut message),
+        Some(Err(e)) => Err(e),
+        None => Err(Error::Simple(format!("No response from server within {timeout:?}"))),
+    }
+}
+
+fn discard_responses(previous: Option<InternalSubscription>) {
+    if let Some(previous) = previous {
+        while previous.try_next().is_some() {}
+    }
+}
+
+fn decode_server_time(message: &mut ResponseMessage) -> Result<OffsetDateTime, Error> {
+    message.skip(); // message type
+    message.skip(); // message version
+    let timestamp = message.next_long()?;
+    match OffsetDateTime::from_unix_timestamp(timestamp) {
+        Ok(date) => Ok(date),
+        Err(e) => Err(Error::Simple(format!("Error parsing date: {e}"))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::accounts::types::{AccountGroup, AccountId, ContractId, ModelCode};
     use crate::accounts::{AccountSummaryTags, AccountUpdateMulti};
+    use crate::client::sync::Client;
     use crate::testdata::responses;
-    use crate::{server_versions, stubs::MessageBusStub, Client, Error};
+    use crate::{server_versions, stubs::MessageBusStub, Error};
     use std::sync::{Arc, RwLock};
 
+    use crate::common::test_utils::helpers::sync::{create_test_client, create_test_client_with_responses, create_test_client_with_version};
     use crate::common::test_utils::helpers::*;
 
     #[test]
@@ -359,6 +398,31 @@ mod tests {
         assert_request_messages(&message_bus_invalid, &["49|1|"]);
     }
 
+    #[test]
+    fn test_server_time_waits_for_heartbeat() {
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let (client, message_bus) = create_test_client_with_responses(vec!["49|1|1678890000|".into()]);
+        let client = Arc::new(client);
+
+        // A heartbeat in flight holds the server time requests.
+        let previous = client.server_time_requests.lock().unwrap();
+        let started = Instant::now();
+        let user = {
+            let client = Arc::clone(&client);
+            thread::spawn(move || client.server_time())
+        };
+        thread::sleep(Duration::from_millis(100));
+        assert_request_messages(&message_bus, &[]);
+        drop(previous);
+
+        assert!(user.join().unwrap().is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_request_messages(&message_bus, &["49|1|"]);
+    }
+
     #[test]
     fn test_account_updates() {
         use crate::accounts::AccountUpdate;
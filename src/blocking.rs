@@ -0,0 +1,60 @@
+//! Blocking (thread-based) API.
+//!
+//! This module is available whenever the `sync` feature is enabled. With only `sync` enabled,
+//! [crate::Client] is the same type as [Client]. When `async` is enabled as well, the crate root
+//! exposes the async API and the blocking API is reached through this module instead:
+//!
+//! ```no_run
+//! use ibapi::blocking::Client;
+//!
+//! let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+//! println!("server time: {}", client.server_time().expect("request failed"));
+//! ```
+//!
+//! Both APIs share the same contract, order and market data types, so values can be passed
+//! between a blocking client and an async client in the same program.
+
+pub use crate::client::sync::Client;
+pub use crate::client::{ConnectionEvent, ConnectionEvents, ReplayMatching, ReplayOptions};
+pub use crate::contracts::options::chain::sync::OptionChainSubscription;
+pub use crate::market_data::historical::pacing::sync::HistoricalScheduler;
+pub use crate::market_data::historical::range::sync::HistoricalRange;
+pub use crate::market_data::historical::store::sync::HistoricalStore;
+pub use crate::market_data::historical::sync::{
+    TickSubscription, TickSubscriptionIter, TickSubscriptionOwnedIter, TickSubscriptionTimeoutIter, TickSubscriptionTryIter,
+};
+pub use crate::market_data::lines::sync::{LineManager, ManagedSubscription};
+pub use crate::market_data::realtime::aggregator::sync::AggregatedBars;
+pub use crate::market_data::realtime::fallback::sync::FallbackSubscription;
+pub use crate::market_data::realtime::order_book::sync::OrderBookSubscription;
+pub use crate::market_data::realtime::tick_mux::sync::TickMux;
+pub use crate::market_data::realtime::ticker::sync::TickerSubscription;
+pub use crate::subscriptions::sync::{
+    SharesChannel, Subscription, SubscriptionIter, SubscriptionOwnedIter, SubscriptionTimeoutIter, SubscriptionTryIter,
+};
+
+/// Server interaction tracing for the blocking API.
+pub mod trace {
+    pub use crate::trace::sync::{last_interaction, record_request, record_response};
+    pub use crate::trace::Interaction;
+}
+
+/// Connection health monitoring for the blocking API.
+pub mod health {
+    pub use crate::health::sync::HealthMonitor;
+    pub use crate::health::{Health, HealthConfig, HealthEvent, HealthStatus};
+}
+
+/// Connection pools for the blocking API.
+pub mod pool {
+    pub use crate::pool::sync::ClientPool;
+    pub use crate::pool::{PoolOptions, Route};
+}
+
+/// A prelude for the blocking API.
+///
+/// Same as [crate::prelude], except that [Client] and [Subscription] refer to the blocking types.
+pub mod prelude {
+    pub use super::{Client, Subscription};
+    pub use crate::prelude::*;
+}
@@ -3,11 +3,14 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use tokio::sync::RwLock;
+
 use time::OffsetDateTime;
 use time_tz::Tz;
 
 use crate::connection::{r#async::AsyncConnection, ConnectionMetadata};
 use crate::messages::{OutgoingMessages, RequestMessage};
+use crate::metrics::Metrics;
 use crate::transport::{
     r#async::{AsyncInternalSubscription, AsyncTcpMessageBus},
     AsyncMessageBus,
@@ -18,7 +21,7 @@ use super::id_generator::ClientIdManager;
 use crate::accounts;
 use crate::accounts::types::{AccountGroup, AccountId, ContractId, ModelCode};
 use crate::accounts::{AccountSummaryResult, AccountUpdate, AccountUpdateMulti, FamilyCode, PnL, PnLSingle, PositionUpdate, PositionUpdateMulti};
-use crate::subscriptions::Subscription;
+use crate::subscriptions::{BufferConfig, Subscription};
 
 /// Asynchronous TWS API Client
 #[derive(Clone)]
@@ -31,6 +34,8 @@ pub struct Client {
 
     client_id: i32,                   // ID of client.
     id_manager: Arc<ClientIdManager>, // Manages request and order ID generation
+    // Read to send market data requests, written while a fallback subscription switches the market data type.
+    pub(crate) market_data_type_lock: Arc<RwLock<()>>,
 }
 
 impl Client {
@@ -78,6 +83,7 @@ impl Client {
             message_bus,
             client_id: connection_metadata.client_id,
             id_manager: Arc::new(ClientIdManager::new(connection_metadata.next_order_id)),
+            market_data_type_lock: Arc::new(RwLock::new(())),
         };
 
         Ok(client)
@@ -98,6 +104,45 @@ impl Client {
         self.client_id
     }
 
+    /// Sets the buffer of subscriptions started by the given request type.
+    ///
+    /// Applies to subscriptions started after the call. A bounded buffer caps memory and applies its
+    /// [OverflowPolicy](crate::subscriptions::OverflowPolicy) once full; [Subscription::dropped_messages] reports how
+    /// many messages the policy dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::messages::OutgoingMessages;
+    /// use ibapi::subscriptions::{BufferConfig, OverflowPolicy};
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     // Keep the latest value of each tick type instead of queueing every tick.
+    ///     client.set_subscription_buffer(OutgoingMessages::RequestMarketData, BufferConfig::bounded(256, OverflowPolicy::ConflateByKey));
+    /// }
+    /// ```
+    pub fn set_subscription_buffer(&self, message_type: OutgoingMessages, config: BufferConfig) {
+        self.message_bus.set_buffer_config(message_type, config);
+    }
+
+    /// Sets the buffer of subscriptions without a buffer set by [Client::set_subscription_buffer].
+    ///
+    /// Order updates and responses shared by message type, such as positions, are not affected.
+    pub fn set_default_subscription_buffer(&self, config: BufferConfig) {
+        self.message_bus.set_default_buffer_config(config);
+    }
+
+    /// Installs the [Metrics] receiving message rates, decode latency, channel backlogs and connection resets.
+    ///
+    /// See [crate::metrics] for the metrics emitted.
+    pub fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        self.message_bus.set_metrics(metrics);
+    }
+
     /// Returns the next order ID
     pub fn next_order_id(&self) -> i32 {
         self.id_manager.next_order_id()
@@ -519,6 +564,131 @@ impl Client {
         crate::market_data::realtime::market_data(self, contract, generic_ticks, snapshot, regulatory_snapshot).await
     }
 
+    /// Requests market data with the generic ticks given as [GenericTick](crate::market_data::realtime::GenericTick)s.
+    ///
+    /// See [Client::market_data] for the arguments and the ticks returned. Each generic tick lists the tick types it
+    /// produces with `GenericTick::tick_types`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::GenericTick;
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let contract = Contract::stock("AAPL");
+    ///     let generic_ticks = [GenericTick::RtVolume, GenericTick::Shortable];
+    ///
+    ///     let mut subscription = client
+    ///         .market_data_with_generic_ticks(&contract, &generic_ticks, false, false)
+    ///         .await
+    ///         .expect("error requesting market data");
+    ///
+    ///     while let Some(tick) = subscription.next().await {
+    ///         println!("{tick:?}");
+    ///     }
+    /// }
+    /// ```
+    pub async fn market_data_with_generic_ticks(
+        &self,
+        contract: &crate::contracts::Contract,
+        generic_ticks: &[crate::market_data::realtime::GenericTick],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+    ) -> Result<Subscription<crate::market_data::realtime::TickTypes>, Error> {
+        let generic_ticks = crate::market_data::realtime::GenericTick::encode(generic_ticks);
+        crate::market_data::realtime::market_data(self, contract, &[&generic_ticks], snapshot, regulatory_snapshot).await
+    }
+
+    /// Requests streaming market data and keeps the latest values in a [Ticker](crate::market_data::realtime::Ticker).
+    ///
+    /// # Arguments
+    /// * `contract`      - Contract for which the data is being requested
+    /// * `generic_ticks` - Additional data to request, such as `GenericTick::RtVolume` for the VWAP,
+    ///   `GenericTick::Shortable` for shortable shares and `GenericTick::Dividends`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::{GenericTick, TickerField};
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let contract = Contract::stock("AAPL");
+    ///     let mut subscription = client
+    ///         .ticker(&contract, &[GenericTick::RtVolume, GenericTick::Shortable])
+    ///         .await
+    ///         .expect("market data request failed");
+    ///
+    ///     while let Some(Ok(changed)) = subscription.next().await {
+    ///         if changed.contains(&TickerField::Last) {
+    ///             println!("last: {:?}", subscription.ticker().last);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn ticker(
+        &self,
+        contract: &crate::contracts::Contract,
+        generic_ticks: &[crate::market_data::realtime::GenericTick],
+    ) -> Result<crate::market_data::realtime::ticker::r#async::TickerSubscription, Error> {
+        let subscription = self.market_data_with_generic_ticks(contract, generic_ticks, false, false).await?;
+        Ok(crate::market_data::realtime::ticker::r#async::TickerSubscription::new(subscription))
+    }
+
+    /// Requests streaming market data that falls back to delayed data when the account lacks a live subscription.
+    ///
+    /// Takes the arguments of [Client::market_data]. When TWS reports that live data for the contract is not subscribed
+    /// (codes 354, 10089 and 10167), the stream continues with `fallback` data. Each tick is tagged with the
+    /// [MarketDataType](crate::market_data::MarketDataType) it came from.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::MarketDataFallback;
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let contract = Contract::stock("AAPL");
+    ///     let mut subscription = client
+    ///         .market_data_with_fallback(&contract, &[], false, false, MarketDataFallback::DelayedFrozen)
+    ///         .await
+    ///         .expect("market data request failed");
+    ///
+    ///     while let Some(Ok(tick)) = subscription.next().await {
+    ///         println!("{:?}: {:?}", tick.market_data_type, tick.tick);
+    ///     }
+    /// }
+    /// ```
+    pub async fn market_data_with_fallback(
+        &self,
+        contract: &crate::contracts::Contract,
+        generic_ticks: &[&str],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+        fallback: crate::market_data::realtime::MarketDataFallback,
+    ) -> Result<crate::market_data::realtime::fallback::r#async::FallbackSubscription, Error> {
+        let request = crate::market_data::realtime::fallback::MarketDataRequest {
+            contract: contract.clone(),
+            generic_ticks: generic_ticks.iter().map(|tick| tick.to_string()).collect(),
+            snapshot,
+            regulatory_snapshot,
+        };
+        crate::market_data::realtime::fallback::r#async::FallbackSubscription::new(self, request, fallback).await
+    }
+
     /// Requests real time bars
     /// Currently, only 5 seconds bars are provided.
     ///
@@ -679,6 +849,34 @@ impl Client {
         crate::market_data::realtime::tick_by_tick_midpoint(self, contract, number_of_ticks, ignore_size).await
     }
 
+    /// Creates a [TickMux](crate::market_data::realtime::TickMux) merging the tick-by-tick data of many contracts into one stream.
+    ///
+    /// # Arguments
+    /// * `tick_types` - Tick types to request for each contract added to the mux
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::TickByTickType;
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let mut mux = client.tick_mux(&[TickByTickType::BidAsk]);
+    ///     mux.add_all(&[Contract::stock("AAPL"), Contract::stock("MSFT")]).await.expect("tick-by-tick request failed");
+    ///
+    ///     while let Some(event) = mux.next().await {
+    ///         println!("{event:?}");
+    ///     }
+    /// }
+    /// ```
+    pub fn tick_mux(&self, tick_types: &[crate::market_data::realtime::TickByTickType]) -> crate::market_data::realtime::tick_mux::r#async::TickMux {
+        crate::market_data::realtime::tick_mux::r#async::TickMux::new(self, tick_types)
+    }
+
     /// Requests the contract's market depth (order book).
     ///
     /// This request returns the full available market depth and updates whenever there's a change in the order book.
@@ -736,6 +934,44 @@ impl Client {
         crate::market_data::realtime::market_depth(self, contract, number_of_rows, is_smart_depth).await
     }
 
+    /// Requests market depth and applies the updates to an [OrderBook](crate::market_data::realtime::OrderBook).
+    ///
+    /// # Arguments
+    /// * `contract`       - Contract for which the depth is being requested
+    /// * `number_of_rows` - Number of rows on each side of the order book
+    /// * `is_smart_depth` - Flag indicates that this is smart depth request
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let contract = Contract::stock("AAPL");
+    ///     let mut subscription = client.order_book(&contract, 5, true).await.expect("error requesting market depth");
+    ///
+    ///     while let Some(Ok(_)) = subscription.next().await {
+    ///         println!("{:?}", subscription.book().top(5));
+    ///     }
+    /// }
+    /// ```
+    pub async fn order_book(
+        &self,
+        contract: &crate::contracts::Contract,
+        number_of_rows: i32,
+        is_smart_depth: bool,
+    ) -> Result<crate::market_data::realtime::order_book::r#async::OrderBookSubscription, Error> {
+        let subscription = crate::market_data::realtime::market_depth(self, contract, number_of_rows, is_smart_depth).await?;
+        Ok(crate::market_data::realtime::order_book::r#async::OrderBookSubscription::new(
+            subscription,
+            number_of_rows.max(0) as usize,
+        ))
+    }
+
     /// Requests venues for which market data is returned to market_depth (those with market makers)
     ///
     /// # Examples
@@ -779,7 +1015,7 @@ impl Client {
     /// }
     /// ```
     pub async fn switch_market_data_type(&self, market_data_type: crate::market_data::MarketDataType) -> Result<(), Error> {
-        crate::market_data::switch_market_data_type(self, market_data_type).await
+        crate::market_data::r#async::switch_market_data_type(self, market_data_type).await
     }
 
     /// Returns the timestamp of earliest available historical data for a contract and data type.
@@ -873,6 +1109,57 @@ impl Client {
         crate::market_data::historical::historical_data(self, contract, end_date, duration, bar_size, what_to_show, use_rth).await
     }
 
+    /// Requests historical bars between `start` and `end`, splitting ranges longer than a single request allows.
+    ///
+    /// The start is clamped to the earliest data available for the contract. Chunks are requested oldest first within
+    /// the historical data pacing limits, and bars are returned in order without duplicates.
+    ///
+    /// # Arguments
+    /// * `contract`     - Contract to retrieve bars for.
+    /// * `start`        - start of the range, inclusive.
+    /// * `end`          - end of the range, exclusive.
+    /// * `bar_size`     - size of the bars.
+    /// * `what_to_show` - requested bar type, trades if `None`.
+    /// * `use_rth`      - use regular trading hours.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use time::macros::datetime;
+    ///
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::historical::{BarSize, WhatToShow};
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let contract = Contract::stock("AAPL");
+    ///     let mut range = client
+    ///         .historical_data_range(&contract, datetime!(2023-01-01 0:00 UTC), datetime!(2025-01-01 0:00 UTC), BarSize::Min, Some(WhatToShow::Trades), true)
+    ///         .await
+    ///         .expect("historical data request failed");
+    ///
+    ///     while let Some(bars) = range.next_chunk().await {
+    ///         let bars = bars.expect("historical data request failed");
+    ///         let progress = range.progress();
+    ///         println!("{} bars, {}/{} chunks", bars.len(), progress.chunks_completed, progress.chunks_total);
+    ///     }
+    /// }
+    /// ```
+    pub async fn historical_data_range(
+        &self,
+        contract: &crate::contracts::Contract,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        bar_size: crate::market_data::historical::BarSize,
+        what_to_show: Option<crate::market_data::historical::WhatToShow>,
+        use_rth: bool,
+    ) -> Result<crate::market_data::historical::range::r#async::HistoricalRange, Error> {
+        crate::market_data::historical::range::r#async::HistoricalRange::new(self, contract, start, end, bar_size, what_to_show, use_rth).await
+    }
+
     /// Requests historical schedule.
     ///
     /// # Arguments
@@ -1367,6 +1654,62 @@ impl Client {
         crate::contracts::option_chain(self, symbol, exchange, security_type, contract_id).await
     }
 
+    /// Requests snapshots of the options of `underlying` selected by `filter`, with their quotes and greeks.
+    ///
+    /// The underlying's contract id is resolved if not set. Snapshots are requested in batches of at most
+    /// [max_lines](crate::contracts::options::OptionChainFilter::max_lines) options. Options without market data, such
+    /// as strikes not listed for an expiration, keep the error returned for them.
+    ///
+    /// # Arguments
+    /// * `underlying` - Stock, index or future the options are written on.
+    /// * `filter`     - Expirations, strikes, rights and moneyness of the options to request.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::options::{Moneyness, OptionChainFilter};
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+    ///
+    ///     let filter = OptionChainFilter::new().moneyness(Moneyness::Near(0.05));
+    ///     let snapshot = client
+    ///         .option_chain_snapshot(&Contract::stock("AAPL"), &filter)
+    ///         .await
+    ///         .expect("option chain request failed");
+    ///
+    ///     for quote in snapshot.quotes.iter().filter(|quote| quote.error.is_none()) {
+    ///         println!("{} {}: iv {:?}", quote.contract.strike, quote.contract.right, quote.implied_volatility());
+    ///     }
+    /// }
+    /// ```
+    pub async fn option_chain_snapshot(
+        &self,
+        underlying: &crate::contracts::Contract,
+        filter: &crate::contracts::options::OptionChainFilter,
+    ) -> Result<crate::contracts::options::OptionChainSnapshot, Error> {
+        crate::contracts::options::chain::r#async::option_chain_snapshot(self, underlying, filter).await
+    }
+
+    /// Streams market data for the options of `underlying` selected by `filter`.
+    ///
+    /// Every option holds a market data line until the subscription is cancelled, so the filter must select no more than
+    /// [max_lines](crate::contracts::options::OptionChainFilter::max_lines) options.
+    ///
+    /// # Arguments
+    /// * `underlying` - Stock, index or future the options are written on.
+    /// * `filter`     - Expirations, strikes, rights and moneyness of the options to stream.
+    pub async fn option_chain_stream(
+        &self,
+        underlying: &crate::contracts::Contract,
+        filter: &crate::contracts::options::OptionChainFilter,
+    ) -> Result<crate::contracts::options::chain::r#async::OptionChainSubscription, Error> {
+        crate::contracts::options::chain::r#async::option_chain_stream(self, underlying, filter).await
+    }
+
     // === Order Management ===
 
     /// Subscribes to order update events. Only one subscription can be active at a time.
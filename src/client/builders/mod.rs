@@ -11,8 +11,5 @@ pub mod r#async;
 mod common;
 
 // Re-export builders based on feature
-#[cfg(feature = "sync")]
-pub use sync::{ClientRequestBuilders, SubscriptionBuilderExt};
-
 #[cfg(feature = "async")]
 pub use r#async::{ClientRequestBuilders, SubscriptionBuilderExt};
@@ -11,21 +11,24 @@ pub mod sync;
 pub mod r#async;
 
 // Re-export the appropriate Client based on feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::Client;
 
 #[cfg(feature = "async")]
 pub use r#async::Client;
 
-// Re-export subscription types from subscriptions module
 #[cfg(feature = "sync")]
-pub use crate::subscriptions::{SharesChannel, Subscription};
-
+pub use crate::transport::failover::{ConnectionEvent, ConnectionEvents};
 #[cfg(feature = "sync")]
-pub(crate) use crate::subscriptions::{ResponseContext, StreamDecoder};
+pub use crate::transport::replay::{ReplayMatching, ReplayOptions};
+
+// Re-export subscription types from subscriptions module
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use crate::subscriptions::{SharesChannel, Subscription};
 
 #[cfg(feature = "async")]
 pub use crate::subscriptions::Subscription;
 
 // Re-export builder traits (internal use only)
+#[cfg(feature = "async")]
 pub(crate) use builders::{ClientRequestBuilders, SubscriptionBuilderExt};
@@ -6,10 +6,11 @@
 
 use std::fmt::Debug;
 use std::net::TcpStream;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
-use log::debug;
+use log::{debug, warn};
 use time::{Date, OffsetDateTime};
 use time_tz::Tz;
 
@@ -22,10 +23,14 @@ use crate::market_data::historical::{self, HistogramEntry};
 use crate::market_data::realtime::{self, Bar, BarSize, DepthMarketDataDescription, MarketDepths, MidPoint, TickTypes, WhatToShow};
 use crate::market_data::MarketDataType;
 use crate::messages::{OutgoingMessages, RequestMessage};
+use crate::metrics::Metrics;
 use crate::news::NewsArticle;
 use crate::orders::{CancelOrder, Executions, ExerciseOptions, Order, OrderUpdate, Orders, PlaceOrder};
 use crate::scanner::ScannerData;
-use crate::subscriptions::Subscription;
+use crate::subscriptions::sync::Subscription;
+use crate::subscriptions::BufferConfig;
+use crate::transport::failover::{ConnectionEvents, Endpoints};
+use crate::transport::replay::{ReplayOptions, ReplaySocket};
 use crate::transport::{InternalSubscription, MessageBus, TcpMessageBus, TcpSocket};
 use crate::wsh::AutoFill;
 use crate::{accounts, contracts, market_data, news, orders, scanner, wsh};
@@ -47,8 +52,13 @@ pub struct Client {
     pub(crate) time_zone: Option<&'static Tz>,
     pub(crate) message_bus: Arc<dyn MessageBus>,
 
-    client_id: i32,              // ID of client.
-    id_manager: ClientIdManager, // Manages request and order ID generation
+    client_id: i32,                   // ID of client.
+    id_manager: Arc<ClientIdManager>, // Manages request and order ID generation
+    // Read to send market data requests, written while a fallback subscription switches the market data type.
+    pub(crate) market_data_type_lock: RwLock<()>,
+    // Held while a server time request waits for its response, as responses share one channel. Keeps the last request
+    // so responses it left behind after timing out are discarded before the next one.
+    pub(crate) server_time_requests: Mutex<Option<InternalSubscription>>,
 }
 
 impl Client {
@@ -63,7 +73,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -86,6 +96,93 @@ impl Client {
         Client::new(connection_metadata, message_bus)
     }
 
+    /// Establishes connection to the first reachable endpoint, failing over to the next one when the connection is lost.
+    ///
+    /// Endpoints are tried in order. When a lost connection cannot be restored to the active endpoint within the
+    /// reconnection backoff, the client moves on to the next endpoint, wrapping around to the first after the last. Each
+    /// connection repeats the handshake and continues the order id sequence from the next valid id sent by the new
+    /// endpoint. Use [Client::connection_events] to learn which endpoint is active.
+    ///
+    /// Endpoints are expected to run the same server version as the one connected first.
+    ///
+    /// Failover is only supported by the blocking client.
+    ///
+    /// # Arguments
+    /// * `endpoints` - addresses of TWS or IB Gateway instances, in order of preference
+    /// * `client_id` - unique client identifier for the connection
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect_with_failover(&["gateway-1:4002", "gateway-2:4002"], 100).expect("connection failed");
+    ///
+    /// println!("connected to {:?}", client.active_endpoint());
+    /// ```
+    pub fn connect_with_failover(endpoints: &[&str], client_id: i32) -> Result<Client, Error> {
+        let mut last_error = Error::InvalidArgument("at least one endpoint is required".into());
+
+        for (index, address) in endpoints.iter().enumerate() {
+            let connection = TcpStream::connect(address)
+                .map_err(Error::from)
+                .and_then(|stream| TcpSocket::with_endpoints(stream, Endpoints::new(endpoints, index)?))
+                .and_then(|socket| Connection::connect(socket, client_id));
+
+            match connection {
+                Ok(connection) => {
+                    let connection_metadata = connection.connection_metadata();
+                    let message_bus = Arc::new(TcpMessageBus::new(connection)?);
+                    message_bus.process_messages(connection_metadata.server_version, Duration::from_secs(1))?;
+
+                    return Client::new(connection_metadata, message_bus);
+                }
+                Err(e) => {
+                    warn!("failed to connect to {address}: {e}");
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Creates a client that replays a session captured with `IBAPI_RECORDING_DIR`, instead of connecting to TWS.
+    ///
+    /// Recorded responses are fed through the normal message dispatcher. Each request sent by the client releases the
+    /// responses that followed the matching recorded request, so bugs captured in production can be reproduced offline.
+    ///
+    /// Replay is only supported by the blocking client.
+    ///
+    /// # Arguments
+    /// * `dir`     - directory containing the recorded `NNNN-request.msg` and `NNNN-response.msg` files, or the
+    ///   `recording-NNNN.jsonl` or `recording-NNNN.bin` logs written when `IBAPI_RECORDING_FORMAT` is set
+    /// * `options` - how requests are matched and whether the original timing is preserved
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::client::sync::Client;
+    /// use ibapi::client::{ReplayMatching, ReplayOptions};
+    ///
+    /// let options = ReplayOptions::default().matching(ReplayMatching::Content).preserve_timing(true);
+    /// let client = Client::replay("/tmp/logs/2025-07-15-19-05-0", options).expect("replay failed");
+    ///
+    /// println!("server time: {:?}", client.server_time());
+    /// ```
+    pub fn replay(dir: impl AsRef<Path>, options: ReplayOptions) -> Result<Client, Error> {
+        let socket = ReplaySocket::open(dir, options)?;
+        let client_id = socket.client_id();
+
+        let connection = Connection::connect(socket, client_id)?;
+        let connection_metadata = connection.connection_metadata();
+
+        let message_bus = Arc::new(TcpMessageBus::new(connection)?);
+        message_bus.process_messages(connection_metadata.server_version, Duration::from_secs(1))?;
+
+        Client::new(connection_metadata, message_bus)
+    }
+
     fn new(connection_metadata: ConnectionMetadata, message_bus: Arc<dyn MessageBus>) -> Result<Client, Error> {
         let client = Client {
             server_version: connection_metadata.server_version,
@@ -93,9 +190,13 @@ impl Client {
             time_zone: connection_metadata.time_zone,
             message_bus,
             client_id: connection_metadata.client_id,
-            id_manager: ClientIdManager::new(connection_metadata.next_order_id),
+            id_manager: Arc::new(ClientIdManager::new(connection_metadata.next_order_id)),
+            market_data_type_lock: RwLock::new(()),
+            server_time_requests: Mutex::new(None),
         };
 
+        client.message_bus.track_order_ids(Arc::clone(&client.id_manager));
+
         Ok(client)
     }
 
@@ -126,7 +227,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// // Connect to the TWS server at the given address with client ID.
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -136,7 +237,7 @@ impl Client {
     /// println!("next_valid_order_id: {next_valid_order_id}");
     /// ```
     pub fn next_valid_order_id(&self) -> Result<i32, Error> {
-        orders::next_valid_order_id(self)
+        orders::sync::next_valid_order_id(self)
     }
 
     /// Sets the current value of order ID.
@@ -150,7 +251,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     /// let server_version = client.server_version();
@@ -165,6 +266,88 @@ impl Client {
         self.connection_time
     }
 
+    /// Returns the address of the TWS or Gateway endpoint in use.
+    pub fn active_endpoint(&self) -> Option<String> {
+        self.message_bus.active_endpoint()
+    }
+
+    /// Subscribes to reconnections, failovers and the final disconnection of the client.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::client::ConnectionEvent;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect_with_failover(&["gateway-1:4002", "gateway-2:4002"], 100).expect("connection failed");
+    ///
+    /// let events = client.connection_events();
+    /// while let Some(event) = events.next_event() {
+    ///     if let ConnectionEvent::FailedOver { from, to } = event {
+    ///         println!("lost {from}, now connected to {to}");
+    ///     }
+    /// }
+    /// ```
+    pub fn connection_events(&self) -> ConnectionEvents {
+        self.message_bus.connection_events()
+    }
+
+    /// Sets the buffer of subscriptions started by the given request type.
+    ///
+    /// Applies to subscriptions started after the call. Subscriptions are unbounded by default, so a consumer that falls
+    /// behind grows its buffer without limit. A bounded buffer caps memory and applies its [OverflowPolicy](crate::subscriptions::OverflowPolicy) once full;
+    /// [Subscription::dropped_messages] reports how many messages the policy dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::messages::OutgoingMessages;
+    /// use ibapi::subscriptions::{BufferConfig, OverflowPolicy};
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// // Keep the latest value of each tick type instead of queueing every tick.
+    /// client.set_subscription_buffer(OutgoingMessages::RequestMarketData, BufferConfig::bounded(256, OverflowPolicy::ConflateByKey));
+    /// ```
+    pub fn set_subscription_buffer(&self, message_type: OutgoingMessages, config: BufferConfig) {
+        self.message_bus.set_buffer_config(message_type, config);
+    }
+
+    /// Sets the buffer of subscriptions without a buffer set by [Client::set_subscription_buffer].
+    ///
+    /// Order updates and responses shared by message type, such as positions, are always unbounded.
+    pub fn set_default_subscription_buffer(&self, config: BufferConfig) {
+        self.message_bus.set_default_buffer_config(config);
+    }
+
+    /// Installs the [Metrics] receiving message rates, decode latency, channel backlogs and reconnects.
+    ///
+    /// See [crate::metrics] for the metrics emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use ibapi::metrics::{Label, Metrics};
+    /// use ibapi::blocking::Client;
+    ///
+    /// struct LogMetrics;
+    ///
+    /// impl Metrics for LogMetrics {
+    ///     fn increment_counter(&self, name: &'static str, labels: &[Label], value: u64) {
+    ///         println!("{name} {labels:?} +{value}");
+    ///     }
+    /// }
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// client.set_metrics(Arc::new(LogMetrics));
+    /// ```
+    pub fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        self.message_bus.set_metrics(metrics);
+    }
+
     // === Accounts ===
 
     /// TWS's current time. TWS is synchronized with the server (not local computer) using NTP and this function will receive the current time in TWS.
@@ -172,14 +355,14 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     /// let server_time = client.server_time().expect("error requesting server time");
     /// println!("server time: {server_time:?}");
     /// ```
     pub fn server_time(&self) -> Result<OffsetDateTime, Error> {
-        accounts::server_time(self)
+        accounts::sync::server_time(self)
     }
 
     /// Subscribes to [PositionUpdate]s for all accessible accounts.
@@ -188,7 +371,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::accounts::PositionUpdate;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -200,8 +383,8 @@ impl Client {
     ///     }
     /// }
     /// ```
-    pub fn positions(&self) -> Result<Subscription<PositionUpdate>, Error> {
-        accounts::positions(self)
+    pub fn positions(&self) -> Result<Subscription<'_, PositionUpdate>, Error> {
+        accounts::sync::positions(self)
     }
 
     /// Subscribes to [PositionUpdateMulti] updates for account and/or model.
@@ -214,7 +397,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -226,8 +409,12 @@ impl Client {
     ///     println!("{position:?}")
     /// }
     /// ```
-    pub fn positions_multi(&self, account: Option<&AccountId>, model_code: Option<&ModelCode>) -> Result<Subscription<PositionUpdateMulti>, Error> {
-        accounts::positions_multi(self, account, model_code)
+    pub fn positions_multi(
+        &self,
+        account: Option<&AccountId>,
+        model_code: Option<&ModelCode>,
+    ) -> Result<Subscription<'_, PositionUpdateMulti>, Error> {
+        accounts::sync::positions_multi(self, account, model_code)
     }
 
     /// Creates subscription for real time daily PnL and unrealized PnL updates.
@@ -239,7 +426,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     /// use ibapi::accounts::types::AccountId;
@@ -250,8 +437,8 @@ impl Client {
     ///     println!("{pnl:?}")
     /// }
     /// ```
-    pub fn pnl(&self, account: &AccountId, model_code: Option<&ModelCode>) -> Result<Subscription<PnL>, Error> {
-        accounts::pnl(self, account, model_code)
+    pub fn pnl(&self, account: &AccountId, model_code: Option<&ModelCode>) -> Result<Subscription<'_, PnL>, Error> {
+        accounts::sync::pnl(self, account, model_code)
     }
 
     /// Requests real time updates for daily PnL of individual positions.
@@ -264,7 +451,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -284,7 +471,7 @@ impl Client {
         contract_id: ContractId,
         model_code: Option<&ModelCode>,
     ) -> Result<Subscription<'a, PnLSingle>, Error> {
-        accounts::pnl_single(self, account, contract_id, model_code)
+        accounts::sync::pnl_single(self, account, contract_id, model_code)
     }
 
     /// Requests a specific account’s summary. Subscribes to the account summary as presented in the TWS’ Account Summary tab. Data received is specified by using a specific tags value.
@@ -296,7 +483,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::accounts::AccountSummaryTags;
     /// use ibapi::accounts::types::AccountGroup;
     ///
@@ -310,7 +497,7 @@ impl Client {
     /// }
     /// ```
     pub fn account_summary<'a>(&'a self, group: &AccountGroup, tags: &[&str]) -> Result<Subscription<'a, AccountSummaryResult>, Error> {
-        accounts::account_summary(self, group, tags)
+        accounts::sync::account_summary(self, group, tags)
     }
 
     /// Subscribes to a specific account’s information and portfolio.
@@ -323,7 +510,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::accounts::AccountUpdate;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -343,7 +530,7 @@ impl Client {
     /// }
     /// ```
     pub fn account_updates<'a>(&'a self, account: &AccountId) -> Result<Subscription<'a, AccountUpdate>, Error> {
-        accounts::account_updates(self, account)
+        accounts::sync::account_updates(self, account)
     }
 
     /// Requests account updates for account and/or model.
@@ -357,7 +544,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::accounts::AccountUpdateMulti;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -381,7 +568,7 @@ impl Client {
         account: Option<&AccountId>,
         model_code: Option<&ModelCode>,
     ) -> Result<Subscription<'a, AccountUpdateMulti>, Error> {
-        accounts::account_updates_multi(self, account, model_code)
+        accounts::sync::account_updates_multi(self, account, model_code)
     }
 
     /// Requests the accounts to which the logged user has access to.
@@ -389,7 +576,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -397,7 +584,7 @@ impl Client {
     /// println!("managed accounts: {accounts:?}")
     /// ```
     pub fn managed_accounts(&self) -> Result<Vec<String>, Error> {
-        accounts::managed_accounts(self)
+        accounts::sync::managed_accounts(self)
     }
 
     // === Contracts ===
@@ -412,7 +599,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -424,12 +611,12 @@ impl Client {
     /// }
     /// ```
     pub fn contract_details(&self, contract: &Contract) -> Result<Vec<contracts::ContractDetails>, Error> {
-        contracts::contract_details(self, contract)
+        contracts::sync::contract_details(self, contract)
     }
 
     /// Get current [FamilyCode]s for all accessible accounts.
     pub fn family_codes(&self) -> Result<Vec<FamilyCode>, Error> {
-        accounts::family_codes(self)
+        accounts::sync::family_codes(self)
     }
 
     /// Requests details about a given market rule
@@ -438,7 +625,7 @@ impl Client {
     /// A list of market rule ids can be obtained by invoking [Self::contract_details()] for a particular contract.
     /// The returned market rule ID list will provide the market rule ID for the instrument in the correspond valid exchange list in [contracts::ContractDetails].
     pub fn market_rule(&self, market_rule_id: i32) -> Result<contracts::MarketRule, Error> {
-        contracts::market_rule(self, market_rule_id)
+        contracts::sync::market_rule(self, market_rule_id)
     }
 
     /// Requests matching stock symbols.
@@ -449,7 +636,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -459,7 +646,7 @@ impl Client {
     /// }
     /// ```
     pub fn matching_symbols(&self, pattern: &str) -> Result<impl Iterator<Item = contracts::ContractDescription>, Error> {
-        Ok(contracts::matching_symbols(self, pattern)?.into_iter())
+        Ok(contracts::sync::matching_symbols(self, pattern)?.into_iter())
     }
 
     /// Calculates an option’s price based on the provided volatility and its underlying’s price.
@@ -472,7 +659,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -482,7 +669,7 @@ impl Client {
     /// println!("calculation: {calculation:?}");
     /// ```
     pub fn calculate_option_price(&self, contract: &Contract, volatility: f64, underlying_price: f64) -> Result<OptionComputation, Error> {
-        contracts::calculate_option_price(self, contract, volatility, underlying_price)
+        contracts::sync::calculate_option_price(self, contract, volatility, underlying_price)
     }
 
     /// Calculates the implied volatility based on the hypothetical option price and underlying price.
@@ -495,7 +682,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -505,7 +692,7 @@ impl Client {
     /// println!("calculation: {calculation:?}");
     /// ```
     pub fn calculate_implied_volatility(&self, contract: &Contract, option_price: f64, underlying_price: f64) -> Result<OptionComputation, Error> {
-        contracts::calculate_implied_volatility(self, contract, option_price, underlying_price)
+        contracts::sync::calculate_implied_volatility(self, contract, option_price, underlying_price)
     }
 
     /// Requests security definition option parameters for viewing a contract’s option chain.
@@ -519,7 +706,8 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::{contracts::SecurityType, Client};
+    /// use ibapi::blocking::Client;
+    /// use ibapi::contracts::SecurityType;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -542,8 +730,66 @@ impl Client {
         exchange: &str,
         security_type: SecurityType,
         contract_id: i32,
-    ) -> Result<Subscription<contracts::OptionChain>, Error> {
-        contracts::option_chain(self, symbol, exchange, security_type, contract_id)
+    ) -> Result<Subscription<'_, contracts::OptionChain>, Error> {
+        contracts::sync::option_chain(self, symbol, exchange, security_type, contract_id)
+    }
+
+    /// Requests snapshots of the options of `underlying` selected by `filter`, with their quotes and greeks.
+    ///
+    /// The underlying's contract id is resolved if not set. Snapshots are requested in batches of at most
+    /// [max_lines](contracts::options::OptionChainFilter::max_lines) options. Options without market data, such as
+    /// strikes not listed for an expiration, keep the error returned for them.
+    ///
+    /// # Arguments
+    /// * `underlying` - Stock, index or future the options are written on.
+    /// * `filter`     - Expirations, strikes, rights and moneyness of the options to request.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::options::{Moneyness, OptionChainFilter};
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let filter = OptionChainFilter::new().moneyness(Moneyness::Near(0.05));
+    /// let snapshot = client.option_chain_snapshot(&Contract::stock("AAPL"), &filter).expect("option chain request failed");
+    ///
+    /// for quote in snapshot.quotes.iter().filter(|quote| quote.error.is_none()) {
+    ///     println!(
+    ///         "{} {} {}: bid {:?} ask {:?} iv {:?}",
+    ///         quote.contract.last_trade_date_or_contract_month,
+    ///         quote.contract.strike,
+    ///         quote.contract.right,
+    ///         quote.bid(),
+    ///         quote.ask(),
+    ///         quote.implied_volatility()
+    ///     );
+    /// }
+    /// ```
+    pub fn option_chain_snapshot(
+        &self,
+        underlying: &Contract,
+        filter: &contracts::options::OptionChainFilter,
+    ) -> Result<contracts::options::OptionChainSnapshot, Error> {
+        contracts::options::chain::sync::option_chain_snapshot(self, underlying, filter)
+    }
+
+    /// Streams market data for the options of `underlying` selected by `filter`.
+    ///
+    /// Every option holds a market data line until the subscription is cancelled, so the filter must select no more than
+    /// [max_lines](contracts::options::OptionChainFilter::max_lines) options.
+    ///
+    /// # Arguments
+    /// * `underlying` - Stock, index or future the options are written on.
+    /// * `filter`     - Expirations, strikes, rights and moneyness of the options to stream.
+    pub fn option_chain_stream(
+        &self,
+        underlying: &Contract,
+        filter: &contracts::options::OptionChainFilter,
+    ) -> Result<contracts::options::chain::sync::OptionChainSubscription<'_>, Error> {
+        contracts::options::chain::sync::option_chain_stream(self, underlying, filter)
     }
 
     // === Orders ===
@@ -554,7 +800,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -563,8 +809,8 @@ impl Client {
     ///    println!("{order_data:?}")
     /// }
     /// ```
-    pub fn all_open_orders(&self) -> Result<Subscription<Orders>, Error> {
-        orders::all_open_orders(self)
+    pub fn all_open_orders(&self) -> Result<Subscription<'_, Orders>, Error> {
+        orders::sync::all_open_orders(self)
     }
 
     /// Requests status updates about future orders placed from TWS. Can only be used with client ID 0.
@@ -575,7 +821,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 0).expect("connection failed");
     ///
@@ -584,8 +830,8 @@ impl Client {
     ///    println!("{order_data:?}")
     /// }
     /// ```
-    pub fn auto_open_orders(&self, auto_bind: bool) -> Result<Subscription<Orders>, Error> {
-        orders::auto_open_orders(self, auto_bind)
+    pub fn auto_open_orders(&self, auto_bind: bool) -> Result<Subscription<'_, Orders>, Error> {
+        orders::sync::auto_open_orders(self, auto_bind)
     }
 
     /// Cancels an active [Order] placed by the same API client ID.
@@ -597,7 +843,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -607,8 +853,8 @@ impl Client {
     ///    println!("{result:?}");
     /// }
     /// ```
-    pub fn cancel_order(&self, order_id: i32, manual_order_cancel_time: &str) -> Result<Subscription<CancelOrder>, Error> {
-        orders::cancel_order(self, order_id, manual_order_cancel_time)
+    pub fn cancel_order(&self, order_id: i32, manual_order_cancel_time: &str) -> Result<Subscription<'_, CancelOrder>, Error> {
+        orders::sync::cancel_order(self, order_id, manual_order_cancel_time)
     }
 
     /// Requests completed [Order]s.
@@ -619,7 +865,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -628,8 +874,8 @@ impl Client {
     ///    println!("{order_data:?}")
     /// }
     /// ```
-    pub fn completed_orders(&self, api_only: bool) -> Result<Subscription<Orders>, Error> {
-        orders::completed_orders(self, api_only)
+    pub fn completed_orders(&self, api_only: bool) -> Result<Subscription<'_, Orders>, Error> {
+        orders::sync::completed_orders(self, api_only)
     }
 
     /// Requests current day's (since midnight) executions matching the filter.
@@ -644,7 +890,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::orders::ExecutionFilter;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -659,8 +905,8 @@ impl Client {
     ///    println!("{execution_data:?}")
     /// }
     /// ```
-    pub fn executions(&self, filter: orders::ExecutionFilter) -> Result<Subscription<Executions>, Error> {
-        orders::executions(self, filter)
+    pub fn executions(&self, filter: orders::ExecutionFilter) -> Result<Subscription<'_, Executions>, Error> {
+        orders::sync::executions(self, filter)
     }
 
     /// Cancels all open [Order]s.
@@ -668,14 +914,14 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
     /// client.global_cancel().expect("request failed");
     /// ```
     pub fn global_cancel(&self) -> Result<(), Error> {
-        orders::global_cancel(self)
+        orders::sync::global_cancel(self)
     }
 
     /// Requests all open orders places by this specific API client (identified by the API client id).
@@ -684,7 +930,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -693,8 +939,8 @@ impl Client {
     ///    println!("{order_data:?}")
     /// }
     /// ```
-    pub fn open_orders(&self) -> Result<Subscription<Orders>, Error> {
-        orders::open_orders(self)
+    pub fn open_orders(&self) -> Result<Subscription<'_, Orders>, Error> {
+        orders::sync::open_orders(self)
     }
 
     /// Places or modifies an [Order].
@@ -710,7 +956,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use ibapi::orders::{order_builder, Action, PlaceOrder};
     ///
@@ -734,8 +980,8 @@ impl Client {
     ///    }
     /// }
     /// ```
-    pub fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<PlaceOrder>, Error> {
-        orders::place_order(self, order_id, contract, order)
+    pub fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<'_, PlaceOrder>, Error> {
+        orders::sync::place_order(self, order_id, contract, order)
     }
 
     /// Submits or modifies an [Order] without returning a subscription.
@@ -757,7 +1003,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use ibapi::orders::{order_builder, Action};
     ///
@@ -788,7 +1034,7 @@ impl Client {
     /// # }
     /// ```
     pub fn submit_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<(), Error> {
-        orders::submit_order(self, order_id, contract, order)
+        orders::sync::submit_order(self, order_id, contract, order)
     }
 
     /// Creates a subscription stream for receiving real-time order updates.
@@ -820,7 +1066,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::orders::OrderUpdate;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -864,8 +1110,8 @@ impl Client {
     ///
     /// This stream provides updates for all orders, not just a specific order.
     /// To track a specific order, filter the updates by order ID.
-    pub fn order_update_stream(&self) -> Result<Subscription<OrderUpdate>, Error> {
-        orders::order_update_stream(self)
+    pub fn order_update_stream(&self) -> Result<Subscription<'_, OrderUpdate>, Error> {
+        orders::sync::order_update_stream(self)
     }
 
     /// Exercises an options contract.
@@ -888,7 +1134,7 @@ impl Client {
         ovrd: bool,
         manual_order_time: Option<OffsetDateTime>,
     ) -> Result<Subscription<'a, ExerciseOptions>, Error> {
-        orders::exercise_options(self, contract, exercise_action, exercise_quantity, account, ovrd, manual_order_time)
+        orders::sync::exercise_options(self, contract, exercise_action, exercise_quantity, account, ovrd, manual_order_time)
     }
 
     // === Historical Market Data ===
@@ -896,7 +1142,7 @@ impl Client {
     /// Returns the timestamp of earliest available historical data for a contract and data type.
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use ibapi::market_data::historical::{self, WhatToShow};
     ///
@@ -911,7 +1157,7 @@ impl Client {
     /// print!("head_timestamp: {result:?}");
     /// ```
     pub fn head_timestamp(&self, contract: &Contract, what_to_show: historical::WhatToShow, use_rth: bool) -> Result<OffsetDateTime, Error> {
-        historical::head_timestamp(self, contract, what_to_show, use_rth)
+        historical::sync::head_timestamp(self, contract, what_to_show, use_rth)
     }
 
     /// Requests interval of historical data ending at specified time for [Contract].
@@ -930,7 +1176,7 @@ impl Client {
     /// use time::macros::datetime;
     ///
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -956,7 +1202,53 @@ impl Client {
         what_to_show: historical::WhatToShow,
         use_rth: bool,
     ) -> Result<historical::HistoricalData, Error> {
-        historical::historical_data(self, contract, interval_end, duration, bar_size, Some(what_to_show), use_rth)
+        historical::sync::historical_data(self, contract, interval_end, duration, bar_size, Some(what_to_show), use_rth)
+    }
+
+    /// Requests historical bars between `start` and `end`, splitting ranges longer than a single request allows.
+    ///
+    /// The start is clamped to the earliest data available for the contract. Chunks are requested oldest first within
+    /// the historical data pacing limits, and bars are returned in order without duplicates.
+    ///
+    /// # Arguments
+    /// * `contract`     - [Contract] to retrieve bars for.
+    /// * `start`        - start of the range, inclusive.
+    /// * `end`          - end of the range, exclusive.
+    /// * `bar_size`     - [historical::BarSize] to return.
+    /// * `what_to_show` - requested bar type: [historical::WhatToShow].
+    /// * `use_rth`      - use regular trading hours.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use time::macros::datetime;
+    ///
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::historical::{BarSize, WhatToShow};
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let range = client
+    ///     .historical_data_range(&contract, datetime!(2023-01-01 0:00 UTC), datetime!(2025-01-01 0:00 UTC), BarSize::Min, WhatToShow::Trades, true)
+    ///     .expect("historical data request failed");
+    ///
+    /// let bars = range
+    ///     .collect_bars_with_progress(|progress| println!("{}/{} chunks", progress.chunks_completed, progress.chunks_total))
+    ///     .expect("historical data request failed");
+    /// println!("{} bars", bars.len());
+    /// ```
+    pub fn historical_data_range(
+        &self,
+        contract: &Contract,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        bar_size: historical::BarSize,
+        what_to_show: historical::WhatToShow,
+        use_rth: bool,
+    ) -> Result<historical::range::sync::HistoricalRange<'_>, Error> {
+        historical::range::sync::HistoricalRange::new(self, contract, start, end, bar_size, what_to_show, use_rth)
     }
 
     /// Requests [Schedule](historical::Schedule) for an interval of given duration
@@ -972,7 +1264,7 @@ impl Client {
     /// ```no_run
     /// use time::macros::datetime;
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::market_data::historical::ToDuration;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -995,7 +1287,7 @@ impl Client {
         interval_end: OffsetDateTime,
         duration: historical::Duration,
     ) -> Result<historical::Schedule, Error> {
-        historical::historical_schedule(self, contract, Some(interval_end), duration)
+        historical::sync::historical_schedule(self, contract, Some(interval_end), duration)
     }
 
     /// Requests [historical::Schedule] for interval ending at current time.
@@ -1008,7 +1300,7 @@ impl Client {
     ///
     /// ```no_run
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::market_data::historical::ToDuration;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1026,7 +1318,7 @@ impl Client {
     /// }
     /// ```
     pub fn historical_schedules_ending_now(&self, contract: &Contract, duration: historical::Duration) -> Result<historical::Schedule, Error> {
-        historical::historical_schedule(self, contract, None, duration)
+        historical::sync::historical_schedule(self, contract, None, duration)
     }
 
     /// Requests historical time & sales data (Bid/Ask) for an instrument.
@@ -1045,7 +1337,7 @@ impl Client {
     /// use time::macros::datetime;
     ///
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1067,8 +1359,8 @@ impl Client {
         number_of_ticks: i32,
         use_rth: bool,
         ignore_size: bool,
-    ) -> Result<historical::TickSubscription<historical::TickBidAsk>, Error> {
-        historical::historical_ticks_bid_ask(self, contract, start, end, number_of_ticks, use_rth, ignore_size)
+    ) -> Result<historical::sync::TickSubscription<historical::TickBidAsk>, Error> {
+        historical::sync::historical_ticks_bid_ask(self, contract, start, end, number_of_ticks, use_rth, ignore_size)
     }
 
     /// Requests historical time & sales data (Midpoint) for an instrument.
@@ -1086,7 +1378,7 @@ impl Client {
     /// use time::macros::datetime;
     ///
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1107,8 +1399,8 @@ impl Client {
         end: Option<OffsetDateTime>,
         number_of_ticks: i32,
         use_rth: bool,
-    ) -> Result<historical::TickSubscription<historical::TickMidpoint>, Error> {
-        historical::historical_ticks_mid_point(self, contract, start, end, number_of_ticks, use_rth)
+    ) -> Result<historical::sync::TickSubscription<historical::TickMidpoint>, Error> {
+        historical::sync::historical_ticks_mid_point(self, contract, start, end, number_of_ticks, use_rth)
     }
 
     /// Requests historical time & sales data (Trades) for an instrument.
@@ -1126,7 +1418,7 @@ impl Client {
     /// use time::macros::datetime;
     ///
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1147,8 +1439,8 @@ impl Client {
         end: Option<OffsetDateTime>,
         number_of_ticks: i32,
         use_rth: bool,
-    ) -> Result<historical::TickSubscription<historical::TickLast>, Error> {
-        historical::historical_ticks_trade(self, contract, start, end, number_of_ticks, use_rth)
+    ) -> Result<historical::sync::TickSubscription<historical::TickLast>, Error> {
+        historical::sync::historical_ticks_trade(self, contract, start, end, number_of_ticks, use_rth)
     }
 
     /// Requests data histogram of specified contract.
@@ -1164,7 +1456,7 @@ impl Client {
     /// use time::macros::datetime;
     //
     /// use ibapi::contracts::Contract;
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::market_data::historical::BarSize;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1180,7 +1472,7 @@ impl Client {
     /// }
     /// ```
     pub fn histogram_data(&self, contract: &Contract, use_rth: bool, period: historical::BarSize) -> Result<Vec<HistogramEntry>, Error> {
-        historical::histogram_data(self, contract, use_rth, period)
+        historical::sync::histogram_data(self, contract, use_rth, period)
     }
 
     // === Realtime Market Data ===
@@ -1193,7 +1485,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use ibapi::market_data::realtime::{BarSize, WhatToShow};
     ///
@@ -1213,7 +1505,7 @@ impl Client {
         what_to_show: WhatToShow,
         use_rth: bool,
     ) -> Result<Subscription<'a, Bar>, Error> {
-        realtime::realtime_bars(self, contract, &bar_size, &what_to_show, use_rth, Vec::default())
+        realtime::sync::realtime_bars(self, contract, &bar_size, &what_to_show, use_rth, Vec::default())
     }
 
     /// Requests tick by tick AllLast ticks.
@@ -1226,7 +1518,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1248,7 +1540,7 @@ impl Client {
         number_of_ticks: i32,
         ignore_size: bool,
     ) -> Result<Subscription<'a, realtime::Trade>, Error> {
-        realtime::tick_by_tick_all_last(self, contract, number_of_ticks, ignore_size)
+        realtime::sync::tick_by_tick_all_last(self, contract, number_of_ticks, ignore_size)
     }
 
     /// Requests tick by tick BidAsk ticks.
@@ -1261,7 +1553,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1283,7 +1575,7 @@ impl Client {
         number_of_ticks: i32,
         ignore_size: bool,
     ) -> Result<Subscription<'a, realtime::BidAsk>, Error> {
-        realtime::tick_by_tick_bid_ask(self, contract, number_of_ticks, ignore_size)
+        realtime::sync::tick_by_tick_bid_ask(self, contract, number_of_ticks, ignore_size)
     }
 
     /// Requests tick by tick Last ticks.
@@ -1296,7 +1588,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1318,7 +1610,7 @@ impl Client {
         number_of_ticks: i32,
         ignore_size: bool,
     ) -> Result<Subscription<'a, realtime::Trade>, Error> {
-        realtime::tick_by_tick_last(self, contract, number_of_ticks, ignore_size)
+        realtime::sync::tick_by_tick_last(self, contract, number_of_ticks, ignore_size)
     }
 
     /// Requests tick by tick MidPoint ticks.
@@ -1331,7 +1623,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1353,7 +1645,32 @@ impl Client {
         number_of_ticks: i32,
         ignore_size: bool,
     ) -> Result<Subscription<'a, MidPoint>, Error> {
-        realtime::tick_by_tick_midpoint(self, contract, number_of_ticks, ignore_size)
+        realtime::sync::tick_by_tick_midpoint(self, contract, number_of_ticks, ignore_size)
+    }
+
+    /// Creates a [realtime::TickMux] merging the tick-by-tick data of many contracts into one stream.
+    ///
+    /// # Arguments
+    /// * `tick_types` - Tick types to request for each contract added to the mux
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::TickByTickType;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let mut mux = client.tick_mux(&[TickByTickType::BidAsk]);
+    /// mux.add_all(&[Contract::stock("AAPL"), Contract::stock("MSFT")]).expect("tick-by-tick request failed");
+    ///
+    /// for event in mux {
+    ///     println!("{event:?}");
+    /// }
+    /// ```
+    pub fn tick_mux(&self, tick_types: &[realtime::TickByTickType]) -> realtime::tick_mux::sync::TickMux<'_> {
+        realtime::tick_mux::sync::TickMux::new(self, tick_types)
     }
 
     /// Switches market data type returned from request_market_data requests to Live, Frozen, Delayed, or FrozenDelayed.
@@ -1364,7 +1681,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::market_data::{MarketDataType};
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1374,7 +1691,7 @@ impl Client {
     /// println!("market data switched: {market_data_type:?}");
     /// ```
     pub fn switch_market_data_type(&self, market_data_type: MarketDataType) -> Result<(), Error> {
-        market_data::switch_market_data_type(self, market_data_type)
+        market_data::sync::switch_market_data_type(self, market_data_type)
     }
 
     /// Requests the contract's market depth (order book).
@@ -1388,7 +1705,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1410,7 +1727,42 @@ impl Client {
         number_of_rows: i32,
         is_smart_depth: bool,
     ) -> Result<Subscription<'a, MarketDepths>, Error> {
-        realtime::market_depth(self, contract, number_of_rows, is_smart_depth)
+        realtime::sync::market_depth(self, contract, number_of_rows, is_smart_depth)
+    }
+
+    /// Requests market depth and applies the updates to a [realtime::OrderBook].
+    ///
+    /// # Arguments
+    /// * `contract`       - Contract for which the depth is being requested
+    /// * `number_of_rows` - Number of rows on each side of the order book
+    /// * `is_smart_depth` - Flag indicates that this is smart depth request
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let mut subscription = client.order_book(&contract, 5, true).expect("error requesting market depth");
+    ///
+    /// while subscription.next().is_some() {
+    ///     println!("{:?}", subscription.book().top(5));
+    /// }
+    /// ```
+    pub fn order_book(
+        &self,
+        contract: &Contract,
+        number_of_rows: i32,
+        is_smart_depth: bool,
+    ) -> Result<realtime::order_book::sync::OrderBookSubscription<'_>, Error> {
+        let subscription = realtime::sync::market_depth(self, contract, number_of_rows, is_smart_depth)?;
+        Ok(realtime::order_book::sync::OrderBookSubscription::new(
+            subscription,
+            number_of_rows.max(0) as usize,
+        ))
     }
 
     /// Requests venues for which market data is returned to market_depth (those with market makers)
@@ -1418,7 +1770,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     /// let exchanges = client.market_depth_exchanges().expect("error requesting market depth exchanges");
@@ -1427,7 +1779,7 @@ impl Client {
     /// }
     /// ```
     pub fn market_depth_exchanges(&self) -> Result<Vec<DepthMarketDataDescription>, Error> {
-        realtime::market_depth_exchanges(self)
+        realtime::sync::market_depth_exchanges(self)
     }
 
     /// Requests real time market data.
@@ -1459,7 +1811,8 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::{contracts::Contract, market_data::realtime::TickTypes, Client};
+    /// use ibapi::blocking::Client;
+    /// use ibapi::{contracts::Contract, market_data::realtime::TickTypes};
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1485,6 +1838,7 @@ impl Client {
     ///         TickTypes::OptionComputation(option_computation) => println!("{option_computation:?}"),
     ///         TickTypes::RequestParameters(tick_request_parameters) => println!("{tick_request_parameters:?}"),
     ///         TickTypes::Notice(notice) => println!("{notice:?}"),
+    ///         TickTypes::MarketDataType(market_data_type) => println!("{market_data_type:?}"),
     ///         TickTypes::SnapshotEnd => subscription.cancel(),
     ///     }
     /// }
@@ -1495,8 +1849,121 @@ impl Client {
         generic_ticks: &[&str],
         snapshot: bool,
         regulatory_snapshot: bool,
-    ) -> Result<Subscription<TickTypes>, Error> {
-        realtime::market_data(self, contract, generic_ticks, snapshot, regulatory_snapshot)
+    ) -> Result<Subscription<'_, TickTypes>, Error> {
+        realtime::sync::market_data(self, contract, generic_ticks, snapshot, regulatory_snapshot)
+    }
+
+    /// Requests market data with the generic ticks given as [realtime::GenericTick]s.
+    ///
+    /// See [Client::market_data] for the arguments and the ticks returned. Each generic tick lists the tick types it
+    /// produces with [realtime::GenericTick::tick_types].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::GenericTick;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let generic_ticks = [GenericTick::RtVolume, GenericTick::Shortable];
+    ///
+    /// let subscription = client
+    ///     .market_data_with_generic_ticks(&contract, &generic_ticks, false, false)
+    ///     .expect("error requesting market data");
+    ///
+    /// for tick in &subscription {
+    ///     println!("{tick:?}");
+    /// }
+    /// ```
+    pub fn market_data_with_generic_ticks(
+        &self,
+        contract: &Contract,
+        generic_ticks: &[realtime::GenericTick],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+    ) -> Result<Subscription<'_, TickTypes>, Error> {
+        let generic_ticks = realtime::GenericTick::encode(generic_ticks);
+        realtime::sync::market_data(self, contract, &[&generic_ticks], snapshot, regulatory_snapshot)
+    }
+
+    /// Requests streaming market data and keeps the latest values in a [realtime::Ticker].
+    ///
+    /// # Arguments
+    /// * `contract`      - Contract for which the data is being requested
+    /// * `generic_ticks` - Additional data to request, such as [realtime::GenericTick::RtVolume] for the VWAP,
+    ///   [realtime::GenericTick::Shortable] for shortable shares and [realtime::GenericTick::Dividends].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::{GenericTick, TickerField};
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let mut subscription = client
+    ///     .ticker(&contract, &[GenericTick::RtVolume, GenericTick::Shortable])
+    ///     .expect("market data request failed");
+    ///
+    /// while let Some(changed) = subscription.next() {
+    ///     if changed.contains(&TickerField::Last) {
+    ///         println!("last: {:?}", subscription.ticker().last);
+    ///     }
+    /// }
+    /// ```
+    pub fn ticker(
+        &self,
+        contract: &Contract,
+        generic_ticks: &[realtime::GenericTick],
+    ) -> Result<realtime::ticker::sync::TickerSubscription<'_>, Error> {
+        let subscription = self.market_data_with_generic_ticks(contract, generic_ticks, false, false)?;
+        Ok(realtime::ticker::sync::TickerSubscription::new(subscription))
+    }
+
+    /// Requests streaming market data that falls back to delayed data when the account lacks a live subscription.
+    ///
+    /// Takes the arguments of [Client::market_data]. When TWS reports that live data for the contract is not subscribed
+    /// (codes 354, 10089 and 10167), the stream continues with `fallback` data. Each tick is tagged with the
+    /// [MarketDataType] it came from.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::MarketDataFallback;
+    /// use ibapi::blocking::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let subscription = client
+    ///     .market_data_with_fallback(&contract, &[], false, false, MarketDataFallback::DelayedFrozen)
+    ///     .expect("market data request failed");
+    ///
+    /// for tick in subscription {
+    ///     println!("{:?}: {:?}", tick.market_data_type, tick.tick);
+    /// }
+    /// ```
+    pub fn market_data_with_fallback(
+        &self,
+        contract: &Contract,
+        generic_ticks: &[&str],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+        fallback: realtime::MarketDataFallback,
+    ) -> Result<realtime::fallback::sync::FallbackSubscription<'_>, Error> {
+        let request = realtime::fallback::MarketDataRequest {
+            contract: contract.clone(),
+            generic_ticks: generic_ticks.iter().map(|tick| tick.to_string()).collect(),
+            snapshot,
+            regulatory_snapshot,
+        };
+        realtime::fallback::sync::FallbackSubscription::new(self, request, fallback)
     }
 
     // === News ===
@@ -1506,7 +1973,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1516,7 +1983,7 @@ impl Client {
     /// }
     /// ```
     pub fn news_providers(&self) -> Result<Vec<news::NewsProvider>, Error> {
-        news::news_providers(self)
+        news::sync::news_providers(self)
     }
 
     /// Subscribes to IB's News Bulletins.
@@ -1528,7 +1995,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1537,8 +2004,8 @@ impl Client {
     ///   println!("news bulletin {news_bulletin:?}");
     /// }
     /// ```
-    pub fn news_bulletins(&self, all_messages: bool) -> Result<Subscription<news::NewsBulletin>, Error> {
-        news::news_bulletins(self, all_messages)
+    pub fn news_bulletins(&self, all_messages: bool) -> Result<Subscription<'_, news::NewsBulletin>, Error> {
+        news::sync::news_bulletins(self, all_messages)
     }
 
     /// Requests historical news headlines.
@@ -1554,7 +2021,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract; // Or remove if conId is always known
     /// use time::macros::datetime;
     ///
@@ -1589,8 +2056,8 @@ impl Client {
         start_time: OffsetDateTime,
         end_time: OffsetDateTime,
         total_results: u8,
-    ) -> Result<Subscription<news::NewsArticle>, Error> {
-        news::historical_news(self, contract_id, provider_codes, start_time, end_time, total_results)
+    ) -> Result<Subscription<'_, news::NewsArticle>, Error> {
+        news::sync::historical_news(self, contract_id, provider_codes, start_time, end_time, total_results)
     }
 
     /// Requests news article body given articleId.
@@ -1603,7 +2070,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1615,7 +2082,7 @@ impl Client {
     /// println!("{article:?}");
     /// ```
     pub fn news_article(&self, provider_code: &str, article_id: &str) -> Result<news::NewsArticleBody, Error> {
-        news::news_article(self, provider_code, article_id)
+        news::sync::news_article(self, provider_code, article_id)
     }
 
     /// Requests realtime contract specific news
@@ -1628,7 +2095,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -1641,8 +2108,8 @@ impl Client {
     ///     println!("{article:?}");
     /// }
     /// ```
-    pub fn contract_news(&self, contract: &Contract, provider_codes: &[&str]) -> Result<Subscription<NewsArticle>, Error> {
-        news::contract_news(self, contract, provider_codes)
+    pub fn contract_news(&self, contract: &Contract, provider_codes: &[&str]) -> Result<Subscription<'_, NewsArticle>, Error> {
+        news::sync::contract_news(self, contract, provider_codes)
     }
 
     /// Requests realtime BroadTape News
@@ -1654,7 +2121,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1665,8 +2132,8 @@ impl Client {
     ///     println!("{article:?}");
     /// }
     /// ```
-    pub fn broad_tape_news(&self, provider_code: &str) -> Result<Subscription<NewsArticle>, Error> {
-        news::broad_tape_news(self, provider_code)
+    pub fn broad_tape_news(&self, provider_code: &str) -> Result<Subscription<'_, NewsArticle>, Error> {
+        news::sync::broad_tape_news(self, provider_code)
     }
 
     // === Scanner ===
@@ -1676,7 +2143,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::scanner::ScannerSubscription;
     /// use ibapi::orders::TagValue; // Or ensure common::TagValue is the correct path
     ///
@@ -1721,7 +2188,7 @@ impl Client {
     /// };
     /// ```
     pub fn scanner_parameters(&self) -> Result<String, Error> {
-        scanner::scanner_parameters(self)
+        scanner::sync::scanner_parameters(self)
     }
 
     /// Starts a subscription to market scan results based on the provided parameters.
@@ -1729,7 +2196,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::scanner::ScannerSubscription;
     /// use ibapi::orders::TagValue;
     ///
@@ -1777,8 +2244,8 @@ impl Client {
         &self,
         subscription: &scanner::ScannerSubscription,
         filter: &Vec<orders::TagValue>,
-    ) -> Result<Subscription<Vec<ScannerData>>, Error> {
-        scanner::scanner_subscription(self, subscription, filter)
+    ) -> Result<Subscription<'_, Vec<ScannerData>>, Error> {
+        scanner::sync::scanner_subscription(self, subscription, filter)
     }
 
     // == Wall Street Horizon
@@ -1788,7 +2255,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1796,7 +2263,7 @@ impl Client {
     /// println!("{metadata:?}");
     /// ```
     pub fn wsh_metadata(&self) -> Result<wsh::WshMetadata, Error> {
-        wsh::wsh_metadata(self)
+        wsh::sync::wsh_metadata(self)
     }
 
     /// Requests event data for a specified contract from the Wall Street Horizons (WSH) calendar.
@@ -1812,7 +2279,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1828,7 +2295,7 @@ impl Client {
         limit: Option<i32>,
         auto_fill: Option<AutoFill>,
     ) -> Result<wsh::WshEventData, Error> {
-        wsh::wsh_event_data_by_contract(self, contract_id, start_date, end_date, limit, auto_fill)
+        wsh::sync::wsh_event_data_by_contract(self, contract_id, start_date, end_date, limit, auto_fill)
     }
 
     /// Requests event data from the Wall Street Horizons (WSH) calendar using a JSON filter.
@@ -1842,7 +2309,7 @@ impl Client {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -1855,8 +2322,8 @@ impl Client {
         filter: &str,
         limit: Option<i32>,
         auto_fill: Option<AutoFill>,
-    ) -> Result<Subscription<wsh::WshEventData>, Error> {
-        wsh::wsh_event_data_by_filter(self, filter, limit, auto_fill)
+    ) -> Result<Subscription<'_, wsh::WshEventData>, Error> {
+        wsh::sync::wsh_event_data_by_filter(self, filter, limit, auto_fill)
     }
 
     // == Internal Use ==
@@ -1869,7 +2336,9 @@ impl Client {
             time_zone: None,
             message_bus,
             client_id: 100,
-            id_manager: ClientIdManager::new(-1),
+            id_manager: Arc::new(ClientIdManager::new(-1)),
+            market_data_type_lock: RwLock::new(()),
+            server_time_requests: Mutex::new(None),
         }
     }
 
@@ -1934,7 +2403,7 @@ impl Debug for Client {
 /// ```no_run
 /// use ibapi::contracts::Contract;
 /// use ibapi::market_data::realtime::{BarSize, WhatToShow};
-/// use ibapi::Client;
+/// use ibapi::blocking::Client;
 ///
 /// let connection_url = "127.0.0.1:4002";
 /// let client = Client::connect(connection_url, 100).expect("connection to TWS failed!");
@@ -1956,4 +2425,4 @@ impl Debug for Client {
 /// Subscriptions can be explicitly canceled using the [cancel](Subscription::cancel) method.
 ///
 // Re-export SharesChannel trait from subscriptions module
-pub use crate::subscriptions::SharesChannel;
+pub use crate::subscriptions::sync::SharesChannel;
@@ -56,3 +56,33 @@ fn test_subscription_cancel_only_sends_once() {
     let after_drop = message_bus.request_messages().len();
     assert_eq!(after_drop, 2, "Should still have two messages after drop");
 }
+
+#[test]
+fn test_replay_recorded_session() {
+    use crate::client::{ReplayMatching, ReplayOptions};
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let recording = [
+        ("0000-response.msg", "173|20230405 22:20:39 PST|"),
+        ("0001-request.msg", "71|2|100||"),
+        ("0002-response.msg", "9|1|90|"),
+        ("0003-response.msg", "15|1|DU1234567|"),
+        ("0004-request.msg", "49|1|"),
+        ("0005-response.msg", "49|1|1752606307|"),
+        ("0006-request.msg", "17|1|"),
+        ("0007-response.msg", "15|1|DU1234567,DU7654321|"),
+    ];
+    for (file, content) in recording {
+        std::fs::write(dir.path().join(file), content).unwrap();
+    }
+
+    let client = Client::replay(dir.path(), ReplayOptions::default().matching(ReplayMatching::Content)).unwrap();
+
+    assert_eq!(client.client_id(), 100);
+    assert_eq!(client.server_version(), 173);
+    assert_eq!(client.next_order_id(), 90);
+
+    // requested out of recorded order
+    assert_eq!(client.managed_accounts().unwrap(), vec!["DU1234567", "DU7654321"]);
+    assert_eq!(client.server_time().unwrap().unix_timestamp(), 1752606307);
+}
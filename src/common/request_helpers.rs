@@ -2,10 +2,13 @@
 
 // Sync implementations
 #[cfg(feature = "sync")]
-mod sync_helpers {
-    use crate::client::{Client, ClientRequestBuilders, SharesChannel, StreamDecoder, Subscription, SubscriptionBuilderExt};
+pub(crate) mod sync_helpers {
+    use crate::client::builders::sync::{ClientRequestBuilders, SubscriptionBuilderExt};
+    use crate::client::sync::Client;
     use crate::messages::{OutgoingMessages, RequestMessage, ResponseMessage};
     use crate::protocol::{check_version, ProtocolFeature};
+    use crate::subscriptions::sync::{SharesChannel, Subscription};
+    use crate::subscriptions::StreamDecoder;
     use crate::Error;
 
     /// Helper for requests that need a request ID and return a subscription
@@ -80,7 +83,7 @@ mod sync_helpers {
         processor: impl Fn(&mut ResponseMessage) -> Result<R, Error>,
         on_none: impl Fn() -> Result<R, Error>,
     ) -> Result<R, Error> {
-        crate::common::retry::retry_on_connection_reset(|| {
+        crate::common::retry::sync_retry::retry_on_connection_reset(|| {
             let request = encoder()?;
             let subscription = client.shared_request(message_type).send_raw(request)?;
 
@@ -188,9 +191,5 @@ mod async_helpers {
     }
 }
 
-// Re-export based on feature flags
-#[cfg(feature = "sync")]
-pub use sync_helpers::*;
-
 #[cfg(feature = "async")]
 pub use async_helpers::*;
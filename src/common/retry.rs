@@ -11,7 +11,7 @@ pub const DEFAULT_MAX_RETRIES: u32 = 3;
 
 // Sync implementations
 #[cfg(feature = "sync")]
-mod sync_retry {
+pub(crate) mod sync_retry {
     use super::*;
 
     /// Retry logic for sync one-shot operations with configurable retry limit
@@ -74,10 +74,6 @@ mod async_retry {
     }
 }
 
-// Re-export based on feature flags
-#[cfg(feature = "sync")]
-pub use sync_retry::*;
-
 #[cfg(feature = "async")]
 pub use async_retry::*;
 
@@ -88,6 +84,7 @@ mod tests {
     #[cfg(feature = "sync")]
     mod sync_tests {
         use super::*;
+        use crate::common::retry::sync_retry::{retry_on_connection_reset, retry_on_connection_reset_with_limit};
         use std::cell::RefCell;
 
         #[test]
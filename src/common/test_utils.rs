@@ -4,44 +4,99 @@
 #[allow(dead_code)] // These utilities will be used by other modules
 pub mod helpers {
     use crate::stubs::MessageBusStub;
-    use crate::{server_versions, Client};
-    use std::sync::{Arc, RwLock};
 
-    /// Creates a test client with an empty message bus
-    pub fn create_test_client() -> (Client, Arc<MessageBusStub>) {
-        create_test_client_with_version(server_versions::SIZE_RULES)
-    }
+    /// Test client constructors for the blocking client
+    #[cfg(feature = "sync")]
+    pub mod sync {
+        use crate::stubs::MessageBusStub;
+        use crate::{client::sync::Client, server_versions};
+        use std::sync::{Arc, RwLock};
 
-    /// Creates a test client with a specific server version
-    pub fn create_test_client_with_version(server_version: i32) -> (Client, Arc<MessageBusStub>) {
-        let message_bus = Arc::new(MessageBusStub {
-            request_messages: RwLock::new(vec![]),
-            response_messages: vec![],
-        });
-        let client = Client::stubbed(message_bus.clone(), server_version);
-        (client, message_bus)
-    }
+        /// Creates a test client with an empty message bus
+        pub fn create_test_client() -> (Client, Arc<MessageBusStub>) {
+            create_test_client_with_version(server_versions::SIZE_RULES)
+        }
+
+        /// Creates a test client with a specific server version
+        pub fn create_test_client_with_version(server_version: i32) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: vec![],
+            });
+            let client = Client::stubbed(message_bus.clone(), server_version);
+            (client, message_bus)
+        }
 
-    /// Creates a test client with specified response messages
-    pub fn create_test_client_with_responses(responses: Vec<String>) -> (Client, Arc<MessageBusStub>) {
-        let message_bus = Arc::new(MessageBusStub {
-            request_messages: RwLock::new(vec![]),
-            response_messages: responses,
-        });
-        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
-        (client, message_bus)
+        /// Creates a test client with specified response messages
+        pub fn create_test_client_with_responses(responses: Vec<String>) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: responses,
+            });
+            let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+            (client, message_bus)
+        }
+
+        /// Creates a test client with specified response messages and server version
+        pub fn create_test_client_with_responses_and_version(responses: Vec<String>, server_version: i32) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: responses,
+            });
+            let client = Client::stubbed(message_bus.clone(), server_version);
+            (client, message_bus)
+        }
     }
 
-    /// Creates a test client with specified response messages and server version
-    pub fn create_test_client_with_responses_and_version(responses: Vec<String>, server_version: i32) -> (Client, Arc<MessageBusStub>) {
-        let message_bus = Arc::new(MessageBusStub {
-            request_messages: RwLock::new(vec![]),
-            response_messages: responses,
-        });
-        let client = Client::stubbed(message_bus.clone(), server_version);
-        (client, message_bus)
+    /// Test client constructors for the async client
+    #[cfg(feature = "async")]
+    pub mod r#async {
+        use crate::stubs::MessageBusStub;
+        use crate::{client::r#async::Client, server_versions};
+        use std::sync::{Arc, RwLock};
+
+        /// Creates a test client with an empty message bus
+        pub fn create_test_client() -> (Client, Arc<MessageBusStub>) {
+            create_test_client_with_version(server_versions::SIZE_RULES)
+        }
+
+        /// Creates a test client with a specific server version
+        pub fn create_test_client_with_version(server_version: i32) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: vec![],
+            });
+            let client = Client::stubbed(message_bus.clone(), server_version);
+            (client, message_bus)
+        }
+
+        /// Creates a test client with specified response messages
+        pub fn create_test_client_with_responses(responses: Vec<String>) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: responses,
+            });
+            let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+            (client, message_bus)
+        }
+
+        /// Creates a test client with specified response messages and server version
+        pub fn create_test_client_with_responses_and_version(responses: Vec<String>, server_version: i32) -> (Client, Arc<MessageBusStub>) {
+            let message_bus = Arc::new(MessageBusStub {
+                request_messages: RwLock::new(vec![]),
+                response_messages: responses,
+            });
+            let client = Client::stubbed(message_bus.clone(), server_version);
+            (client, message_bus)
+        }
     }
 
+    #[cfg(all(feature = "sync", not(feature = "async")))]
+    pub use sync::*;
+
+    #[cfg(feature = "async")]
+    pub use r#async::*;
+
     /// Asserts that the request messages match expected values
     pub fn assert_request_messages(message_bus: &MessageBusStub, expected: &[&str]) {
         let request_messages = message_bus.request_messages.read().unwrap();
@@ -1,16 +1,19 @@
 //! Asynchronous connection implementation
 
+use std::time::Instant;
+
 use log::debug;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 
-use super::common::{parse_connection_time, AccountInfo, ConnectionHandler, ConnectionProtocol};
+use super::common::{decode_response, encode_request, parse_connection_time, AccountInfo, ConnectionHandler, ConnectionProtocol};
 use super::ConnectionMetadata;
 use crate::errors::Error;
 use crate::messages::{RequestMessage, ResponseMessage};
+use crate::metrics::MetricsRecorder;
 use crate::trace;
-use crate::transport::recorder::MessageRecorder;
+use crate::transport::recorder::{Correlation, MessageRecorder};
 
 type Response = Result<ResponseMessage, Error>;
 
@@ -22,6 +25,9 @@ pub struct AsyncConnection {
     pub(crate) connection_metadata: Mutex<ConnectionMetadata>,
     pub(crate) recorder: MessageRecorder,
     pub(crate) connection_handler: ConnectionHandler,
+    pub(crate) metrics: MetricsRecorder,
+    // Reused to frame outgoing messages
+    write_buffer: Mutex<Vec<u8>>,
 }
 
 impl AsyncConnection {
@@ -36,8 +42,10 @@ impl AsyncConnection {
                 client_id,
                 ..Default::default()
             }),
-            recorder: MessageRecorder::from_env(),
+            recorder: MessageRecorder::from_env().client_id(client_id),
             connection_handler: ConnectionHandler::default(),
+            metrics: MetricsRecorder::new(),
+            write_buffer: Mutex::new(Vec::new()),
         };
 
         connection.establish_connection().await?;
@@ -75,19 +83,25 @@ impl AsyncConnection {
 
     /// Write a message to the connection
     pub(crate) async fn write_message(&self, message: &RequestMessage) -> Result<(), Error> {
-        self.recorder.record_request(message);
-        let encoded = message.encode();
-        debug!("-> {encoded:?}");
+        self.write_correlated_message(message, Correlation::None).await
+    }
+
+    /// Write a message sent for a request or order id to the connection
+    pub(crate) async fn write_correlated_message(&self, message: &RequestMessage, correlation: Correlation) -> Result<(), Error> {
+        self.recorder.record_request(message, correlation);
+        debug!("-> {:?}", message.as_str());
 
         // Record the request if debug logging is enabled
         if log::log_enabled!(log::Level::Debug) {
-            trace::record_request(encoded.clone()).await;
+            trace::record_request(message.encode()).await;
         }
 
-        let length_encoded = crate::messages::encode_length(&encoded);
+        let mut packet = self.write_buffer.lock().await;
+        encode_request(message, &mut packet)?;
 
         let mut socket = self.socket.lock().await;
-        socket.write_all(&length_encoded).await?;
+        socket.write_all(&packet).await?;
+        self.metrics.request_sent(message);
         Ok(())
     }
 
@@ -109,16 +123,19 @@ impl AsyncConnection {
             socket.read_exact(&mut data).await?;
         }
 
-        let raw_string = String::from_utf8(data)?;
-        debug!("<- {raw_string:?}");
+        let started = self.metrics.is_enabled().then(Instant::now);
+        let message = decode_response(data)?;
+        if let Some(started) = started {
+            self.metrics.message_decoded(message.message_type(), started.elapsed());
+        }
 
         // Record the response if debug logging is enabled
         if log::log_enabled!(log::Level::Debug) {
-            trace::record_response(raw_string.clone()).await;
+            let raw_string = message.encode();
+            debug!("<- {raw_string:?}");
+            trace::record_response(raw_string).await;
         }
 
-        let message = ResponseMessage::from(&raw_string);
-
         self.recorder.record_response(&message);
 
         Ok(message)
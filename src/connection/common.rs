@@ -6,7 +6,7 @@ use time::OffsetDateTime;
 use time_tz::{timezones, OffsetResult, PrimitiveDateTimeExt, Tz};
 
 use crate::errors::Error;
-use crate::messages::{encode_length, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::messages::{encode_length, write_length_prefixed, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
 use crate::server_versions;
 
 /// Data exchanged during the connection handshake
@@ -59,6 +59,19 @@ impl Default for ConnectionHandler {
     }
 }
 
+/// Frames a request for the wire into a reusable buffer.
+pub(crate) fn encode_request(message: &RequestMessage, packet: &mut Vec<u8>) -> Result<(), Error> {
+    packet.clear();
+    write_length_prefixed(message.as_str().as_bytes(), packet);
+    Ok(())
+}
+
+/// Decodes a message read from the wire.
+pub(crate) fn decode_response(data: Vec<u8>) -> Result<ResponseMessage, Error> {
+    let raw_string = String::from_utf8(data)?;
+    Ok(ResponseMessage::from(&raw_string))
+}
+
 impl ConnectionProtocol for ConnectionHandler {
     type Error = Error;
 
@@ -205,4 +218,21 @@ mod tests {
         assert!(encoded.contains("71")); // StartApi message type
         assert!(encoded.contains("123")); // client_id
     }
+    #[test]
+    fn test_encode_request_reuses_buffer() {
+        let handler = ConnectionHandler::default();
+        let message = handler.format_start_api(123, 150);
+
+        let mut packet = Vec::with_capacity(64);
+        let buffer = packet.as_ptr();
+
+        encode_request(&message, &mut packet).unwrap();
+        assert_eq!(&packet[..4], ((packet.len() - 4) as u32).to_be_bytes());
+        assert_eq!(&packet[4..], message.as_str().as_bytes());
+
+        let first = packet.clone();
+        encode_request(&message, &mut packet).unwrap();
+        assert_eq!(packet, first, "buffer is cleared between frames");
+        assert_eq!(packet.as_ptr(), buffer, "buffer is not reallocated");
+    }
 }
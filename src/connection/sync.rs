@@ -1,15 +1,18 @@
 //! Synchronous connection implementation
 
 use std::sync::Mutex;
+use std::time::Instant;
 
-use log::{debug, info};
+use log::{debug, info, warn};
 
-use super::common::{parse_connection_time, AccountInfo, ConnectionHandler, ConnectionProtocol};
+use super::common::{decode_response, encode_request, parse_connection_time, AccountInfo, ConnectionHandler, ConnectionProtocol};
 use super::ConnectionMetadata;
 use crate::errors::Error;
 use crate::messages::{RequestMessage, ResponseMessage};
+use crate::metrics::MetricsRecorder;
 use crate::trace;
-use crate::transport::recorder::MessageRecorder;
+use crate::transport::failover::{ConnectionEvent, ConnectionEventSenders};
+use crate::transport::recorder::{Correlation, MessageRecorder};
 use crate::transport::sync::{FibonacciBackoff, Stream, MAX_RETRIES};
 
 type Response = Result<ResponseMessage, Error>;
@@ -23,6 +26,10 @@ pub struct Connection<S: Stream> {
     pub(crate) max_retries: i32,
     pub(crate) recorder: MessageRecorder,
     pub(crate) connection_handler: ConnectionHandler,
+    pub(crate) metrics: MetricsRecorder,
+    pub(crate) events: ConnectionEventSenders,
+    // Reused to frame outgoing messages
+    write_buffer: Mutex<Vec<u8>>,
 }
 
 impl<S: Stream> Connection<S> {
@@ -36,8 +43,11 @@ impl<S: Stream> Connection<S> {
                 ..Default::default()
             }),
             max_retries: MAX_RETRIES,
-            recorder: MessageRecorder::from_env(),
+            recorder: MessageRecorder::from_env().client_id(client_id),
             connection_handler: ConnectionHandler::default(),
+            metrics: MetricsRecorder::new(),
+            events: ConnectionEventSenders::default(),
+            write_buffer: Mutex::new(Vec::new()),
         };
 
         connection.establish_connection()?;
@@ -57,12 +67,43 @@ impl<S: Stream> Connection<S> {
         connection_metadata.server_version
     }
 
-    /// Reconnect to TWS with fibonacci backoff
+    /// Reconnect to TWS, failing over to the next endpoint each time the backoff for one is exhausted
     pub fn reconnect(&self) -> Result<(), Error> {
+        let lost = self.socket.endpoint();
+
+        for attempt in 0..self.socket.endpoint_count() {
+            if attempt > 0 {
+                let previous = self.socket.endpoint();
+                self.socket.next_endpoint();
+                warn!("giving up on {previous}, failing over to {}", self.socket.endpoint());
+            }
+
+            if self.reconnect_endpoint().is_ok() {
+                let endpoint = self.socket.endpoint();
+                info!("connected to {endpoint}");
+
+                let event = if endpoint == lost {
+                    ConnectionEvent::Reconnected { endpoint }
+                } else {
+                    ConnectionEvent::FailedOver { from: lost, to: endpoint }
+                };
+                self.events.send(event);
+
+                return Ok(());
+            }
+        }
+
+        self.events.send(ConnectionEvent::Disconnected);
+        Err(Error::ConnectionFailed)
+    }
+
+    /// Reconnect to the active endpoint with fibonacci backoff
+    fn reconnect_endpoint(&self) -> Result<(), Error> {
         let mut backoff = FibonacciBackoff::new(30);
 
         for i in 0..self.max_retries {
             let next_delay = backoff.next_delay();
+            self.metrics.reconnect_attempted();
             info!("next reconnection attempt in {next_delay:#?}");
 
             self.socket.sleep(next_delay);
@@ -93,33 +134,42 @@ impl<S: Stream> Connection<S> {
 
     /// Write a message to the connection
     pub(crate) fn write_message(&self, message: &RequestMessage) -> Result<(), Error> {
-        self.recorder.record_request(message);
-        let encoded = message.encode();
-        debug!("-> {encoded:?}");
+        self.write_correlated_message(message, Correlation::None)
+    }
+
+    /// Write a message sent for a request or order id to the connection
+    pub(crate) fn write_correlated_message(&self, message: &RequestMessage, correlation: Correlation) -> Result<(), Error> {
+        self.recorder.record_request(message, correlation);
+        debug!("-> {:?}", message.as_str());
 
         // Record the request if debug logging is enabled
         if log::log_enabled!(log::Level::Debug) {
-            trace::record_request(encoded.clone());
+            trace::sync::record_request(message.encode());
         }
 
-        let length_encoded = crate::messages::encode_length(&encoded);
-        self.socket.write_all(&length_encoded)?;
+        let mut packet = self.write_buffer.lock()?;
+        encode_request(message, &mut packet)?;
+        self.socket.write_all(&packet)?;
+        self.metrics.request_sent(message);
         Ok(())
     }
 
     /// Read a message from the connection
     pub(crate) fn read_message(&self) -> Response {
         let data = self.socket.read_message()?;
-        let raw_string = String::from_utf8(data)?;
-        debug!("<- {raw_string:?}");
+        let started = self.metrics.is_enabled().then(Instant::now);
+        let message = decode_response(data)?;
+        if let Some(started) = started {
+            self.metrics.message_decoded(message.message_type(), started.elapsed());
+        }
 
         // Record the response if debug logging is enabled
         if log::log_enabled!(log::Level::Debug) {
-            trace::record_response(raw_string.clone());
+            let raw_string = message.encode();
+            debug!("<- {raw_string:?}");
+            trace::sync::record_response(raw_string);
         }
 
-        let message = ResponseMessage::from(&raw_string);
-
         self.recorder.record_response(&message);
 
         Ok(message)
@@ -211,6 +261,9 @@ impl<S: Stream> Connection<S> {
             max_retries: MAX_RETRIES,
             recorder: MessageRecorder::new(false, String::from("")),
             connection_handler: ConnectionHandler::default(),
+            metrics: MetricsRecorder::new(),
+            events: ConnectionEventSenders::default(),
+            write_buffer: Mutex::new(Vec::new()),
         }
     }
 }
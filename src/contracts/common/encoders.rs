@@ -239,7 +239,7 @@ mod tests {
 
         assert_eq!(message[0], OutgoingMessages::RequestMatchingSymbols.to_field(), "message.type");
         assert_eq!(message[1], request_id.to_field(), "message.request_id");
-        assert_eq!(message[2], pattern, "message.pattern");
+        assert_eq!(&message[2], pattern, "message.pattern");
     }
 
     #[test]
@@ -307,7 +307,7 @@ mod tests {
 
         assert_eq!(message[15], volatility.to_field(), "message.volatility");
         assert_eq!(message[16], underlying_price.to_field(), "message.underlying_price");
-        assert_eq!(message[17], "", "message.empty_field");
+        assert_eq!(&message[17], "", "message.empty_field");
     }
 
     #[test]
@@ -363,7 +363,7 @@ mod tests {
 
         assert_eq!(message[15], option_price.to_field(), "message.option_price");
         assert_eq!(message[16], underlying_price.to_field(), "message.underlying_price");
-        assert_eq!(message[17], "", "message.empty_field");
+        assert_eq!(&message[17], "", "message.empty_field");
     }
 
     #[test]
@@ -23,11 +23,13 @@ mod common;
 
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
 
+pub mod options;
+pub mod schedule;
 pub mod tick_types;
 
 // Models
@@ -561,9 +563,6 @@ pub struct PriceIncrement {
 }
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
-pub(crate) use sync::{calculate_implied_volatility, calculate_option_price, contract_details, market_rule, matching_symbols, option_chain};
-
 #[cfg(feature = "async")]
 pub(crate) use r#async::{calculate_implied_volatility, calculate_option_price, contract_details, market_rule, matching_symbols, option_chain};
 
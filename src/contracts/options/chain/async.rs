@@ -0,0 +1,325 @@
+//! Option chain snapshots for the async client.
+
+use std::time::Duration;
+
+use futures::future::{join_all, select_all};
+use log::{debug, warn};
+use time::OffsetDateTime;
+use tokio::time::{timeout_at, Instant};
+
+use super::{
+    chain_exchange, check_lines, option_contracts, reference_price, snapshot_timeout, OptionChainFilter, OptionChainSnapshot, OptionChainUpdate,
+    OptionQuote,
+};
+use crate::client::r#async::Client;
+use crate::contracts::{Contract, OptionChain};
+use crate::market_data::realtime::{TickTypes, Ticker};
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+pub(crate) async fn option_chain_snapshot(client: &Client, underlying: &Contract, filter: &OptionChainFilter) -> Result<OptionChainSnapshot, Error> {
+    let (underlying, chains) = resolve(client, underlying).await?;
+    let underlying_price = underlying_price(client, &underlying, filter.timeout).await;
+    let contracts = option_contracts(&underlying, &chains, filter, underlying_price)?;
+
+    let mut quotes = Vec::with_capacity(contracts.len());
+    for batch in contracts.chunks(filter.max_lines) {
+        debug!("requesting snapshots of {} options of {}", batch.len(), underlying.symbol);
+        quotes.extend(snapshots(client, batch, filter.timeout).await);
+    }
+
+    Ok(OptionChainSnapshot {
+        underlying,
+        underlying_price,
+        quotes,
+    })
+}
+
+pub(crate) async fn option_chain_stream(
+    client: &Client,
+    underlying: &Contract,
+    filter: &OptionChainFilter,
+) -> Result<OptionChainSubscription, Error> {
+    let (underlying, chains) = resolve(client, underlying).await?;
+    let underlying_price = underlying_price(client, &underlying, filter.timeout).await;
+    let contracts = option_contracts(&underlying, &chains, filter, underlying_price)?;
+    check_lines(&contracts, filter)?;
+    Ok(subscribe(client, underlying, underlying_price, contracts).await)
+}
+
+async fn subscribe(client: &Client, underlying: Contract, underlying_price: Option<f64>, contracts: Vec<Contract>) -> OptionChainSubscription {
+    let mut quotes = Vec::with_capacity(contracts.len());
+    let mut subscriptions = Vec::with_capacity(contracts.len());
+    for contract in contracts {
+        let mut quote = OptionQuote::new(contract);
+        match client.market_data(&quote.contract, &[], false, false).await {
+            Ok(subscription) => subscriptions.push(Some(subscription)),
+            Err(err) => {
+                quote.error = Some(err);
+                subscriptions.push(None);
+            }
+        }
+        quotes.push(quote);
+    }
+
+    OptionChainSubscription {
+        underlying,
+        underlying_price,
+        quotes,
+        subscriptions,
+    }
+}
+
+// Resolves the contract id of the underlying and requests its option chains.
+async fn resolve(client: &Client, underlying: &Contract) -> Result<(Contract, Vec<OptionChain>), Error> {
+    let underlying = if underlying.contract_id > 0 {
+        underlying.clone()
+    } else {
+        client
+            .contract_details(underlying)
+            .await?
+            .into_iter()
+            .next()
+            .map(|details| details.contract)
+            .ok_or_else(|| Error::InvalidArgument(format!("no contract found for {}", underlying.symbol)))?
+    };
+
+    let mut subscription = client
+        .option_chain(
+            &underlying.symbol,
+            chain_exchange(&underlying),
+            underlying.security_type.clone(),
+            underlying.contract_id,
+        )
+        .await?;
+
+    let mut chains = Vec::new();
+    while let Some(chain) = subscription.next().await {
+        chains.push(chain?);
+    }
+    Ok((underlying, chains))
+}
+
+async fn underlying_price(client: &Client, underlying: &Contract, timeout: Duration) -> Option<f64> {
+    let quote = snapshots(client, std::slice::from_ref(underlying), timeout).await.pop()?;
+    if let Some(err) = &quote.error {
+        warn!("no market data for {}: {err}", underlying.symbol);
+    }
+    reference_price(&quote.ticker)
+}
+
+// Requests snapshots of all contracts at once and waits up to `timeout` for them to end.
+async fn snapshots(client: &Client, contracts: &[Contract], timeout: Duration) -> Vec<OptionQuote> {
+    let deadline = Instant::now() + timeout;
+    join_all(contracts.iter().map(|contract| async move {
+        let mut quote = OptionQuote::new(contract.clone());
+        match client.market_data(contract, &[], true, false).await {
+            Ok(mut subscription) => match timeout_at(deadline, collect_snapshot(&mut subscription, &mut quote.ticker)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => quote.error = Some(err),
+                Err(_) => quote.error = Some(snapshot_timeout(timeout)),
+            },
+            Err(err) => quote.error = Some(err),
+        }
+        quote
+    }))
+    .await
+}
+
+// Applies ticks until the snapshot ends. Returns an error if the stream ends first.
+async fn collect_snapshot(subscription: &mut Subscription<TickTypes>, ticker: &mut Ticker) -> Result<(), Error> {
+    while let Some(tick) = subscription.next().await {
+        match tick? {
+            TickTypes::SnapshotEnd => return Ok(()),
+            tick => {
+                ticker.update(&tick, OffsetDateTime::now_utc());
+            }
+        }
+    }
+    Err(Error::UnexpectedEndOfStream)
+}
+
+/// Streaming market data of the options selected from a chain.
+///
+/// Each call to [OptionChainSubscription::next] waits for market data that changes one of the [OptionQuote]s and
+/// returns the update.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::options::{Moneyness, OptionChainFilter, OptionChainUpdate};
+/// use ibapi::contracts::Contract;
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let filter = OptionChainFilter::new().moneyness(Moneyness::Near(0.02));
+///     let mut subscription = client
+///         .option_chain_stream(&Contract::stock("AAPL"), &filter)
+///         .await
+///         .expect("option chain request failed");
+///
+///     while let Some(update) = subscription.next().await {
+///         if let OptionChainUpdate::Changed { index, .. } = update {
+///             let quote = &subscription.quotes()[index];
+///             println!("{} {} {}: {:?}", quote.contract.last_trade_date_or_contract_month, quote.contract.strike, quote.contract.right, quote.greeks());
+///         }
+///     }
+/// }
+/// ```
+pub struct OptionChainSubscription {
+    underlying: Contract,
+    underlying_price: Option<f64>,
+    quotes: Vec<OptionQuote>,
+    subscriptions: Vec<Option<Subscription<TickTypes>>>,
+}
+
+impl OptionChainSubscription {
+    /// Returns the underlying, with its contract id resolved.
+    pub fn underlying(&self) -> &Contract {
+        &self.underlying
+    }
+
+    /// Returns the price of the underlying when the options were selected.
+    pub fn underlying_price(&self) -> Option<f64> {
+        self.underlying_price
+    }
+
+    /// Returns the quote of each option, sorted by expiration, strike and right.
+    pub fn quotes(&self) -> &[OptionQuote] {
+        &self.quotes
+    }
+
+    /// Waits for market data that changes a quote. Returns `None` once the market data of every option ended.
+    pub async fn next(&mut self) -> Option<OptionChainUpdate> {
+        loop {
+            let (index, tick) = {
+                let pending: Vec<_> = self
+                    .subscriptions
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, subscription)| {
+                        let subscription = subscription.as_mut()?;
+                        Some(Box::pin(async move { (index, subscription.next().await) }))
+                    })
+                    .collect();
+                if pending.is_empty() {
+                    return None;
+                }
+                select_all(pending).await.0
+            };
+
+            match tick {
+                Some(Ok(tick)) => {
+                    if let TickTypes::Notice(notice) = &tick {
+                        warn!("market data notice {}: {}", notice.code, notice.message);
+                    }
+                    let fields = self.quotes[index].ticker.update(&tick, OffsetDateTime::now_utc());
+                    if !fields.is_empty() {
+                        return Some(OptionChainUpdate::Changed { index, fields });
+                    }
+                }
+                Some(Err(error)) => {
+                    self.subscriptions[index] = None;
+                    self.quotes[index].error = Some(error.clone());
+                    return Some(OptionChainUpdate::Failed { index, error });
+                }
+                None => self.subscriptions[index] = None,
+            }
+        }
+    }
+
+    /// Cancels the market data of all options.
+    pub async fn cancel(&self) {
+        for subscription in self.subscriptions.iter().flatten() {
+            subscription.cancel().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client(response_messages: &[&str]) -> Client {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: response_messages.iter().map(|message| message.to_string()).collect(),
+        });
+        Client::stubbed(message_bus, server_versions::SIZE_RULES)
+    }
+
+    const TICKS: &[&str] = &[
+        "1|2|9001|4|3.20|10|0|",
+        "21|9001|13|0|0.25|0.52|3.20|0|0.04|0.18|-0.06|185.5|",
+        "57|1|9001|",
+    ];
+
+    fn options() -> Vec<Contract> {
+        vec![
+            Contract::option("AAPL", "20240119", 185.0, "C"),
+            Contract::option("AAPL", "20240119", 190.0, "C"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_resolve_option_chains() {
+        let client = client(&["75|9000|SMART|265598|AAPL|100|2|20240119|20240216|3|180|185|190|", "76|9000|"]);
+        let underlying = Contract {
+            contract_id: 265598,
+            ..Contract::stock("AAPL")
+        };
+
+        let (_, chains) = resolve(&client, &underlying).await.expect("option chain request failed");
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].expirations, vec!["20240119", "20240216"]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshots() {
+        let client = client(TICKS);
+
+        let quotes = snapshots(&client, &options(), Duration::from_secs(1)).await;
+        assert_eq!(quotes.len(), 2);
+
+        let quote = &quotes[0];
+        assert_eq!(quote.last(), Some(3.2));
+        assert_eq!(quote.greeks().and_then(|greeks| greeks.theta), Some(-0.06));
+        assert!(quote.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_not_ended() {
+        let client = client(&TICKS[..2]);
+
+        let quotes = snapshots(&client, &options(), Duration::from_millis(100)).await;
+        assert_eq!(quotes.len(), 2);
+        for quote in &quotes {
+            assert_eq!(quote.last(), Some(3.2), "ticks received before the snapshot ended are kept");
+            assert!(quote.error.is_some(), "snapshot without end reported as complete");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_option_chain_subscription() {
+        let client = client(TICKS);
+
+        let mut subscription = subscribe(&client, Contract::stock("AAPL"), None, options()).await;
+
+        let mut updated = vec![false; 2];
+        while let Ok(Some(update)) = tokio::time::timeout(Duration::from_millis(200), subscription.next()).await {
+            match update {
+                OptionChainUpdate::Changed { index, .. } => updated[index] = true,
+                OptionChainUpdate::Failed { error, .. } => panic!("unexpected error: {error}"),
+            }
+        }
+        assert_eq!(updated, vec![true, true]);
+        assert_eq!(subscription.quotes()[1].implied_volatility(), Some(0.25));
+    }
+}
@@ -0,0 +1,419 @@
+//! Quotes and greeks across an option chain.
+//!
+//! [option_chain](crate::Client::option_chain) only lists the expirations and strikes of a chain. An option chain
+//! snapshot selects the options matching an [OptionChainFilter], requests their market data in batches that stay within
+//! the market data line limit and collects the quotes and greeks TWS sends into an [OptionQuote] per option.
+
+use std::time::Duration;
+
+use time::macros::format_description;
+use time::Date;
+
+use super::OptionRight;
+use crate::contracts::{Contract, OptionChain, SecurityType};
+use crate::market_data::realtime::{Greeks, Ticker, TickerField};
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+const DEFAULT_MAX_LINES: usize = 50;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Selects options by their strike relative to the price of the underlying.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Moneyness {
+    /// Calls with strikes below and puts with strikes above the underlying price.
+    InTheMoney,
+    /// Calls with strikes above and puts with strikes below the underlying price.
+    OutOfTheMoney,
+    /// Strikes within the given fraction of the underlying price, such as `0.05` for 5%.
+    Near(f64),
+}
+
+impl Moneyness {
+    fn matches(&self, right: OptionRight, strike: f64, price: f64) -> bool {
+        match (self, right) {
+            (Moneyness::InTheMoney, OptionRight::Call) | (Moneyness::OutOfTheMoney, OptionRight::Put) => strike < price,
+            (Moneyness::InTheMoney, OptionRight::Put) | (Moneyness::OutOfTheMoney, OptionRight::Call) => strike > price,
+            (Moneyness::Near(fraction), _) => (strike - price).abs() <= fraction * price,
+        }
+    }
+}
+
+/// Selects the options of an option chain snapshot.
+///
+/// By default all expirations, strikes and rights of the SMART chains are selected, 50 options are requested at a time
+/// and each batch waits up to 15 seconds for its snapshots.
+///
+/// # Examples
+///
+/// ```
+/// use time::macros::date;
+///
+/// use ibapi::contracts::options::{Moneyness, OptionChainFilter, OptionRight};
+///
+/// let filter = OptionChainFilter::new()
+///     .expirations(date!(2025 - 01 - 01), date!(2025 - 03 - 31))
+///     .rights(&[OptionRight::Call])
+///     .moneyness(Moneyness::Near(0.05))
+///     .max_lines(40);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionChainFilter {
+    exchange: String,
+    trading_class: Option<String>,
+    strikes: Option<(f64, f64)>,
+    expirations: Option<(Date, Date)>,
+    rights: Vec<OptionRight>,
+    moneyness: Option<Moneyness>,
+    max_lines: usize,
+    timeout: Duration,
+}
+
+impl Default for OptionChainFilter {
+    fn default() -> Self {
+        Self {
+            exchange: "SMART".to_owned(),
+            trading_class: None,
+            strikes: None,
+            expirations: None,
+            rights: vec![OptionRight::Call, OptionRight::Put],
+            moneyness: None,
+            max_lines: DEFAULT_MAX_LINES,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl OptionChainFilter {
+    /// Creates a filter selecting every option of the SMART chains.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the chains listed on `exchange`.
+    pub fn exchange(mut self, exchange: &str) -> Self {
+        self.exchange = exchange.to_owned();
+        self
+    }
+
+    /// Selects the chains of a trading class, such as `SPXW`.
+    pub fn trading_class(mut self, trading_class: &str) -> Self {
+        self.trading_class = Some(trading_class.to_owned());
+        self
+    }
+
+    /// Selects strikes from `min` to `max`, inclusive.
+    pub fn strikes(mut self, min: f64, max: f64) -> Self {
+        self.strikes = Some((min, max));
+        self
+    }
+
+    /// Selects expirations from `first` to `last`, inclusive.
+    pub fn expirations(mut self, first: Date, last: Date) -> Self {
+        self.expirations = Some((first, last));
+        self
+    }
+
+    /// Selects the given rights.
+    pub fn rights(mut self, rights: &[OptionRight]) -> Self {
+        self.rights = rights.to_vec();
+        self
+    }
+
+    /// Selects strikes by their moneyness, using the last price of the underlying.
+    pub fn moneyness(mut self, moneyness: Moneyness) -> Self {
+        self.moneyness = Some(moneyness);
+        self
+    }
+
+    /// Maximum number of market data lines used at a time. Zero is treated as one.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self
+    }
+
+    /// Time to wait for a batch of snapshots.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn selects_chain(&self, chain: &OptionChain) -> bool {
+        chain.exchange == self.exchange && self.trading_class.as_ref().is_none_or(|class| *class == chain.trading_class)
+    }
+
+    fn selects_expiration(&self, expiration: &str) -> bool {
+        let Some((first, last)) = self.expirations else {
+            return true;
+        };
+        Date::parse(expiration, format_description!("[year][month][day]")).is_ok_and(|date| first <= date && date <= last)
+    }
+
+    fn selects_strike(&self, right: OptionRight, strike: f64, price: Option<f64>) -> bool {
+        let in_range = self.strikes.is_none_or(|(min, max)| min <= strike && strike <= max);
+        let moneyness = match (self.moneyness, price) {
+            (Some(moneyness), Some(price)) => moneyness.matches(right, strike, price),
+            _ => true,
+        };
+        in_range && moneyness
+    }
+}
+
+/// Market data of an option.
+#[derive(Debug, Clone)]
+pub struct OptionQuote {
+    /// The option.
+    pub contract: Contract,
+    /// Quotes and model values received for the option.
+    pub ticker: Ticker,
+    /// Error returned for the option's market data request, such as for a strike not listed for the expiration, or when the
+    /// snapshot did not end within the filter's timeout.
+    pub error: Option<Error>,
+}
+
+impl OptionQuote {
+    pub(crate) fn new(contract: Contract) -> Self {
+        Self {
+            contract,
+            ticker: Ticker::default(),
+            error: None,
+        }
+    }
+
+    /// Returns the bid price.
+    pub fn bid(&self) -> Option<f64> {
+        self.ticker.bid.map(|bid| bid.value)
+    }
+
+    /// Returns the ask price.
+    pub fn ask(&self) -> Option<f64> {
+        self.ticker.ask.map(|ask| ask.value)
+    }
+
+    /// Returns the last price.
+    pub fn last(&self) -> Option<f64> {
+        self.ticker.last.map(|last| last.value)
+    }
+
+    /// Returns the model values computed by TWS.
+    pub fn greeks(&self) -> Option<Greeks> {
+        self.ticker.model_greeks.map(|greeks| greeks.value)
+    }
+
+    /// Returns the implied volatility of the TWS model.
+    pub fn implied_volatility(&self) -> Option<f64> {
+        self.greeks().and_then(|greeks| greeks.implied_volatility)
+    }
+}
+
+/// Quotes of the options selected from a chain, sorted by expiration, strike and right.
+#[derive(Debug, Clone)]
+pub struct OptionChainSnapshot {
+    /// The underlying, with its contract id resolved.
+    pub underlying: Contract,
+    /// Last price of the underlying, or the midpoint or close when it has not traded.
+    pub underlying_price: Option<f64>,
+    /// Quote of each option.
+    pub quotes: Vec<OptionQuote>,
+}
+
+/// Update to an option of a streaming option chain.
+#[derive(Debug, Clone)]
+pub enum OptionChainUpdate {
+    /// Market data changed the quote at `index`.
+    Changed {
+        /// Index of the quote.
+        index: usize,
+        /// Fields of the ticker that changed.
+        fields: Vec<TickerField>,
+    },
+    /// Market data for the quote at `index` ended with an error.
+    Failed {
+        /// Index of the quote.
+        index: usize,
+        /// Error returned for the request.
+        error: Error,
+    },
+}
+
+// Exchange passed to the option chain request. Futures options are listed by the exchange of the future.
+pub(crate) fn chain_exchange(underlying: &Contract) -> &str {
+    if underlying.security_type == SecurityType::Future {
+        &underlying.exchange
+    } else {
+        ""
+    }
+}
+
+// Error of a quote whose snapshot did not end within the timeout.
+pub(crate) fn snapshot_timeout(timeout: Duration) -> Error {
+    Error::Simple(format!("market data snapshot not received within {timeout:?}"))
+}
+
+// Price the moneyness of the options is measured against.
+pub(crate) fn reference_price(ticker: &Ticker) -> Option<f64> {
+    ticker
+        .last
+        .map(|last| last.value)
+        .or_else(|| ticker.midpoint())
+        .or_else(|| ticker.close.map(|close| close.value))
+}
+
+// Options of the chains selected by the filter, sorted by expiration, strike and right.
+pub(crate) fn option_contracts(
+    underlying: &Contract,
+    chains: &[OptionChain],
+    filter: &OptionChainFilter,
+    underlying_price: Option<f64>,
+) -> Result<Vec<Contract>, Error> {
+    if filter.moneyness.is_some() && underlying_price.is_none() {
+        return Err(Error::Simple(format!(
+            "no price for {} to select options by moneyness",
+            underlying.symbol
+        )));
+    }
+
+    let security_type = if underlying.security_type == SecurityType::Future {
+        SecurityType::FuturesOption
+    } else {
+        SecurityType::Option
+    };
+
+    let mut contracts = Vec::new();
+    for chain in chains.iter().filter(|chain| filter.selects_chain(chain)) {
+        for expiration in chain.expirations.iter().filter(|expiration| filter.selects_expiration(expiration)) {
+            for &strike in &chain.strikes {
+                for &right in &filter.rights {
+                    if !filter.selects_strike(right, strike, underlying_price) {
+                        continue;
+                    }
+                    contracts.push(Contract {
+                        symbol: underlying.symbol.clone(),
+                        security_type: security_type.clone(),
+                        last_trade_date_or_contract_month: expiration.clone(),
+                        strike,
+                        right: right.as_str().to_owned(),
+                        multiplier: chain.multiplier.clone(),
+                        exchange: chain.exchange.clone(),
+                        currency: underlying.currency.clone(),
+                        trading_class: chain.trading_class.clone(),
+                        ..Contract::default()
+                    });
+                }
+            }
+        }
+    }
+
+    contracts.sort_by(|a, b| {
+        a.last_trade_date_or_contract_month
+            .cmp(&b.last_trade_date_or_contract_month)
+            .then(a.strike.total_cmp(&b.strike))
+            .then(a.right.cmp(&b.right))
+            .then(a.trading_class.cmp(&b.trading_class))
+    });
+    Ok(contracts)
+}
+
+// Streaming keeps a line open per option, so every option must fit the budget at once.
+pub(crate) fn check_lines(contracts: &[Contract], filter: &OptionChainFilter) -> Result<(), Error> {
+    if contracts.len() > filter.max_lines {
+        return Err(Error::InvalidArgument(format!(
+            "filter selects {} options, more than the {} market data lines allowed",
+            contracts.len(),
+            filter.max_lines
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn chains() -> Vec<OptionChain> {
+        vec![
+            OptionChain {
+                underlying_contract_id: 265598,
+                trading_class: "AAPL".to_owned(),
+                multiplier: "100".to_owned(),
+                exchange: "SMART".to_owned(),
+                expirations: vec!["20240216".to_owned(), "20240119".to_owned()],
+                strikes: vec![180.0, 185.0, 190.0],
+            },
+            OptionChain {
+                underlying_contract_id: 265598,
+                trading_class: "AAPL".to_owned(),
+                multiplier: "100".to_owned(),
+                exchange: "CBOE".to_owned(),
+                expirations: vec!["20240119".to_owned()],
+                strikes: vec![185.0],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_option_contracts() {
+        let underlying = Contract::stock("AAPL");
+
+        let contracts = option_contracts(&underlying, &chains(), &OptionChainFilter::new(), None).unwrap();
+        assert_eq!(contracts.len(), 12);
+        assert_eq!(contracts[0].last_trade_date_or_contract_month, "20240119");
+        assert_eq!((contracts[0].strike, contracts[0].right.as_str()), (180.0, "C"));
+        assert_eq!((contracts[1].strike, contracts[1].right.as_str()), (180.0, "P"));
+        assert_eq!(contracts[0].security_type, SecurityType::Option);
+        assert_eq!(contracts[0].multiplier, "100");
+
+        let filter = OptionChainFilter::new()
+            .expirations(date!(2024 - 01 - 01), date!(2024 - 01 - 31))
+            .strikes(182.0, 200.0)
+            .rights(&[OptionRight::Put]);
+        let contracts = option_contracts(&underlying, &chains(), &filter, None).unwrap();
+        let strikes: Vec<f64> = contracts.iter().map(|contract| contract.strike).collect();
+        assert_eq!(strikes, vec![185.0, 190.0]);
+    }
+
+    #[test]
+    fn test_moneyness() {
+        let underlying = Contract::stock("AAPL");
+        let select = |moneyness| {
+            let filter = OptionChainFilter::new()
+                .expirations(date!(2024 - 01 - 19), date!(2024 - 01 - 19))
+                .moneyness(moneyness);
+            option_contracts(&underlying, &chains(), &filter, Some(186.0))
+                .unwrap()
+                .into_iter()
+                .map(|contract| (contract.strike, contract.right))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            select(Moneyness::InTheMoney),
+            vec![(180.0, "C".to_owned()), (185.0, "C".to_owned()), (190.0, "P".to_owned())]
+        );
+        assert_eq!(
+            select(Moneyness::OutOfTheMoney),
+            vec![(180.0, "P".to_owned()), (185.0, "P".to_owned()), (190.0, "C".to_owned())]
+        );
+        assert_eq!(select(Moneyness::Near(0.01)), vec![(185.0, "C".to_owned()), (185.0, "P".to_owned())]);
+
+        let filter = OptionChainFilter::new().moneyness(Moneyness::InTheMoney);
+        assert!(option_contracts(&underlying, &chains(), &filter, None).is_err());
+    }
+
+    #[test]
+    fn test_check_lines() {
+        let contracts = vec![Contract::option("AAPL", "20240119", 185.0, "C"); 3];
+        assert!(check_lines(&contracts, &OptionChainFilter::new().max_lines(3)).is_ok());
+        assert!(matches!(
+            check_lines(&contracts, &OptionChainFilter::new().max_lines(2)),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
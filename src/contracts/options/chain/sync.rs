@@ -0,0 +1,365 @@
+//! Option chain snapshots for the blocking client.
+
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use time::OffsetDateTime;
+
+use super::{
+    chain_exchange, check_lines, option_contracts, reference_price, snapshot_timeout, OptionChainFilter, OptionChainSnapshot, OptionChainUpdate,
+    OptionQuote,
+};
+use crate::client::sync::Client;
+use crate::contracts::{Contract, OptionChain};
+use crate::market_data::realtime::{TickTypes, Ticker};
+use crate::subscriptions::sync::{select, Subscription};
+use crate::Error;
+
+pub(crate) fn option_chain_snapshot(client: &Client, underlying: &Contract, filter: &OptionChainFilter) -> Result<OptionChainSnapshot, Error> {
+    let (underlying, chains) = resolve(client, underlying)?;
+    let underlying_price = underlying_price(client, &underlying, filter.timeout);
+    let contracts = option_contracts(&underlying, &chains, filter, underlying_price)?;
+
+    let mut quotes = Vec::with_capacity(contracts.len());
+    for batch in contracts.chunks(filter.max_lines) {
+        debug!("requesting snapshots of {} options of {}", batch.len(), underlying.symbol);
+        quotes.extend(snapshots(client, batch, filter.timeout));
+    }
+
+    Ok(OptionChainSnapshot {
+        underlying,
+        underlying_price,
+        quotes,
+    })
+}
+
+pub(crate) fn option_chain_stream<'a>(
+    client: &'a Client,
+    underlying: &Contract,
+    filter: &OptionChainFilter,
+) -> Result<OptionChainSubscription<'a>, Error> {
+    let (underlying, chains) = resolve(client, underlying)?;
+    let underlying_price = underlying_price(client, &underlying, filter.timeout);
+    let contracts = option_contracts(&underlying, &chains, filter, underlying_price)?;
+    check_lines(&contracts, filter)?;
+    Ok(subscribe(client, underlying, underlying_price, contracts))
+}
+
+fn subscribe(client: &Client, underlying: Contract, underlying_price: Option<f64>, contracts: Vec<Contract>) -> OptionChainSubscription<'_> {
+    let mut quotes = Vec::with_capacity(contracts.len());
+    let mut subscriptions = Vec::with_capacity(contracts.len());
+    for contract in contracts {
+        let mut quote = OptionQuote::new(contract);
+        match client.market_data(&quote.contract, &[], false, false) {
+            Ok(subscription) => subscriptions.push(Some(subscription)),
+            Err(err) => {
+                quote.error = Some(err);
+                subscriptions.push(None);
+            }
+        }
+        quotes.push(quote);
+    }
+
+    OptionChainSubscription {
+        underlying,
+        underlying_price,
+        quotes,
+        subscriptions,
+    }
+}
+
+// Resolves the contract id of the underlying and requests its option chains.
+fn resolve(client: &Client, underlying: &Contract) -> Result<(Contract, Vec<OptionChain>), Error> {
+    let underlying = if underlying.contract_id > 0 {
+        underlying.clone()
+    } else {
+        client
+            .contract_details(underlying)?
+            .into_iter()
+            .next()
+            .map(|details| details.contract)
+            .ok_or_else(|| Error::InvalidArgument(format!("no contract found for {}", underlying.symbol)))?
+    };
+
+    let chains = client
+        .option_chain(
+            &underlying.symbol,
+            chain_exchange(&underlying),
+            underlying.security_type.clone(),
+            underlying.contract_id,
+        )?
+        .into_iter()
+        .collect();
+    Ok((underlying, chains))
+}
+
+fn underlying_price(client: &Client, underlying: &Contract, timeout: Duration) -> Option<f64> {
+    let quote = snapshots(client, std::slice::from_ref(underlying), timeout).pop()?;
+    if let Some(err) = &quote.error {
+        warn!("no market data for {}: {err}", underlying.symbol);
+    }
+    reference_price(&quote.ticker)
+}
+
+// Requests snapshots of all contracts at once and waits up to `timeout` for them to end.
+fn snapshots(client: &Client, contracts: &[Contract], timeout: Duration) -> Vec<OptionQuote> {
+    let deadline = Instant::now() + timeout;
+    let subscriptions: Vec<_> = contracts.iter().map(|contract| client.market_data(contract, &[], true, false)).collect();
+
+    contracts
+        .iter()
+        .zip(subscriptions)
+        .map(|(contract, subscription)| {
+            let mut quote = OptionQuote::new(contract.clone());
+            match subscription {
+                Ok(subscription) => quote.error = collect_snapshot(&subscription, &mut quote.ticker, deadline, timeout),
+                Err(err) => quote.error = Some(err),
+            }
+            quote
+        })
+        .collect()
+}
+
+// Applies ticks until the snapshot ends. Messages for other requests are skipped rather than ending the snapshot.
+// Returns an error if the snapshot did not end by the deadline.
+fn collect_snapshot(subscription: &Subscription<'_, TickTypes>, ticker: &mut Ticker, deadline: Instant, timeout: Duration) -> Option<Error> {
+    let Some(receiver) = subscription.receiver() else {
+        return Some(subscription.error().unwrap_or(Error::UnexpectedEndOfStream));
+    };
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let Some((_, response)) = select(&[receiver], Some(remaining)) else {
+            debug!("snapshot timed out");
+            return Some(snapshot_timeout(timeout));
+        };
+        let Some(response) = response else {
+            return Some(subscription.error().unwrap_or(Error::UnexpectedEndOfStream));
+        };
+
+        match subscription.process(response) {
+            Some(TickTypes::SnapshotEnd) => return None,
+            Some(tick) => {
+                ticker.update(&tick, OffsetDateTime::now_utc());
+            }
+            None => {
+                if let Some(err) = subscription.error() {
+                    return Some(err);
+                }
+            }
+        }
+    }
+}
+
+/// Streaming market data of the options selected from a chain.
+///
+/// Each call to `next` waits for market data that changes one of the [OptionQuote]s and returns the update.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::options::{Moneyness, OptionChainFilter, OptionChainUpdate};
+/// use ibapi::contracts::Contract;
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let filter = OptionChainFilter::new().moneyness(Moneyness::Near(0.02));
+/// let mut subscription = client.option_chain_stream(&Contract::stock("AAPL"), &filter).expect("option chain request failed");
+///
+/// while let Some(update) = subscription.next() {
+///     if let OptionChainUpdate::Changed { index, .. } = update {
+///         let quote = &subscription.quotes()[index];
+///         println!("{} {} {}: {:?}", quote.contract.last_trade_date_or_contract_month, quote.contract.strike, quote.contract.right, quote.greeks());
+///     }
+/// }
+/// ```
+pub struct OptionChainSubscription<'a> {
+    underlying: Contract,
+    underlying_price: Option<f64>,
+    quotes: Vec<OptionQuote>,
+    subscriptions: Vec<Option<Subscription<'a, TickTypes>>>,
+}
+
+impl OptionChainSubscription<'_> {
+    /// Returns the underlying, with its contract id resolved.
+    pub fn underlying(&self) -> &Contract {
+        &self.underlying
+    }
+
+    /// Returns the price of the underlying when the options were selected.
+    pub fn underlying_price(&self) -> Option<f64> {
+        self.underlying_price
+    }
+
+    /// Returns the quote of each option, sorted by expiration, strike and right.
+    pub fn quotes(&self) -> &[OptionQuote] {
+        &self.quotes
+    }
+
+    /// Waits up to `timeout` for market data that changes a quote.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<OptionChainUpdate> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if let Some(update) = self.receive(Some(remaining))? {
+                return Some(update);
+            }
+        }
+    }
+
+    /// Returns an update from the market data already received, if any.
+    pub fn try_next(&mut self) -> Option<OptionChainUpdate> {
+        loop {
+            if let Some(update) = self.receive(Some(Duration::ZERO))? {
+                return Some(update);
+            }
+        }
+    }
+
+    /// Cancels the market data of all options.
+    pub fn cancel(&mut self) {
+        for subscription in self.subscriptions.iter_mut().filter_map(Option::take) {
+            subscription.cancel();
+        }
+    }
+
+    // Waits for a response on any open subscription. Returns `None` on timeout or once all subscriptions ended, and
+    // `Some(None)` for responses that did not change a quote.
+    fn receive(&mut self, timeout: Option<Duration>) -> Option<Option<OptionChainUpdate>> {
+        let (indexes, receivers): (Vec<usize>, Vec<_>) = self
+            .subscriptions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, subscription)| Some((index, subscription.as_ref()?.receiver()?)))
+            .unzip();
+
+        let (selected, response) = select(&receivers, timeout)?;
+        let index = indexes[selected];
+
+        let Some(response) = response else {
+            debug!("market data of {} disconnected", self.quotes[index].contract.symbol);
+            self.subscriptions[index] = None;
+            return Some(None);
+        };
+
+        let subscription = self.subscriptions[index].as_ref()?;
+        let Some(tick) = subscription.process(response) else {
+            let Some(error) = subscription.error() else {
+                return Some(None);
+            };
+            self.subscriptions[index] = None;
+            self.quotes[index].error = Some(error.clone());
+            return Some(Some(OptionChainUpdate::Failed { index, error }));
+        };
+
+        if let TickTypes::Notice(notice) = &tick {
+            warn!("market data notice {}: {}", notice.code, notice.message);
+        }
+        let fields = self.quotes[index].ticker.update(&tick, OffsetDateTime::now_utc());
+        Some((!fields.is_empty()).then_some(OptionChainUpdate::Changed { index, fields }))
+    }
+}
+
+impl Iterator for OptionChainSubscription<'_> {
+    type Item = OptionChainUpdate;
+
+    /// Blocks until market data changes a quote. Returns `None` once the market data of every option ended.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(update) = self.receive(None)? {
+                return Some(update);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client(response_messages: &[&str]) -> (Client, Arc<MessageBusStub>) {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: response_messages.iter().map(|message| message.to_string()).collect(),
+        });
+        (Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES), message_bus)
+    }
+
+    const TICKS: &[&str] = &[
+        "1|2|9001|4|3.20|10|0|",
+        "21|9001|13|0|0.25|0.52|3.20|0|0.04|0.18|-0.06|185.5|",
+        "57|1|9001|",
+    ];
+
+    fn options() -> Vec<Contract> {
+        vec![
+            Contract::option("AAPL", "20240119", 185.0, "C"),
+            Contract::option("AAPL", "20240119", 190.0, "C"),
+        ]
+    }
+
+    #[test]
+    fn test_resolve_option_chains() {
+        let (client, message_bus) = client(&["75|9000|SMART|265598|AAPL|100|2|20240119|20240216|3|180|185|190|", "76|9000|"]);
+        let underlying = Contract {
+            contract_id: 265598,
+            ..Contract::stock("AAPL")
+        };
+
+        let (resolved, chains) = resolve(&client, &underlying).expect("option chain request failed");
+        assert_eq!(resolved, underlying);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].strikes, vec![180.0, 185.0, 190.0]);
+        assert_eq!(message_bus.request_messages().len(), 1, "contract id already known");
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let (client, message_bus) = client(TICKS);
+
+        let quotes = snapshots(&client, &options(), Duration::from_secs(1));
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(message_bus.request_messages().len(), 2);
+
+        let quote = &quotes[1];
+        assert_eq!(quote.contract.strike, 190.0);
+        assert_eq!(quote.last(), Some(3.2));
+        assert_eq!(quote.implied_volatility(), Some(0.25));
+        assert_eq!(quote.greeks().and_then(|greeks| greeks.delta), Some(0.52));
+        assert!(quote.error.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_not_ended() {
+        let (client, _) = client(&TICKS[..2]);
+
+        let quotes = snapshots(&client, &options(), Duration::from_millis(100));
+        assert_eq!(quotes.len(), 2);
+        for quote in &quotes {
+            assert_eq!(quote.last(), Some(3.2), "ticks received before the snapshot ended are kept");
+            assert!(quote.error.is_some(), "snapshot without end reported as complete");
+        }
+    }
+
+    #[test]
+    fn test_option_chain_subscription() {
+        let (client, _) = client(TICKS);
+
+        let mut subscription = subscribe(&client, Contract::stock("AAPL"), Some(185.5), options());
+        assert_eq!(subscription.quotes().len(), 2);
+        assert_eq!(subscription.underlying_price(), Some(185.5));
+
+        let mut updated = vec![false; 2];
+        for update in subscription.by_ref() {
+            if let OptionChainUpdate::Changed { index, .. } = update {
+                updated[index] = true;
+            }
+        }
+        assert_eq!(updated, vec![true, true]);
+        assert_eq!(subscription.quotes()[0].implied_volatility(), Some(0.25));
+    }
+}
@@ -0,0 +1,48 @@
+//! Tools for working with options.
+
+use std::fmt;
+
+pub mod chain;
+pub mod pricing;
+
+pub use chain::{Moneyness, OptionChainFilter, OptionChainSnapshot, OptionChainUpdate, OptionQuote};
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use chain::sync::OptionChainSubscription;
+
+#[cfg(feature = "async")]
+pub use chain::r#async::OptionChainSubscription;
+
+/// Right of an option contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionRight {
+    /// Right to buy the underlying.
+    Call,
+    /// Right to sell the underlying.
+    Put,
+}
+
+impl OptionRight {
+    /// Returns the right as used in [Contract::right](crate::contracts::Contract::right), `C` or `P`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Call => "C",
+            Self::Put => "P",
+        }
+    }
+
+    /// Parses a contract's right, accepting `C`, `CALL`, `P` and `PUT`.
+    pub fn from_right(right: &str) -> Option<Self> {
+        match right.to_ascii_uppercase().as_str() {
+            "C" | "CALL" => Some(Self::Call),
+            "P" | "PUT" => Some(Self::Put),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OptionRight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
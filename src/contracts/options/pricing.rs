@@ -0,0 +1,833 @@
+//! Option prices, implied volatilities and greeks computed locally.
+//!
+//! [calculate_option_price](crate::Client::calculate_option_price) and
+//! [calculate_implied_volatility](crate::Client::calculate_implied_volatility) ask TWS for every computation. An
+//! [OptionPricer] computes the same values without a connection, returning them as an [OptionComputation] so they can
+//! be compared with the values TWS sends.
+//!
+//! European options are priced with Black-Scholes. American options are priced with a Cox-Ross-Rubinstein binomial tree
+//! or the Bjerksund-Stensland (2002) approximation. Volatilities, rates and dividend yields are annualized decimals, so
+//! 20% is `0.2`.
+//!
+//! Greeks follow the TWS conventions: vega and rho are per one point (1%) change in volatility and rate, and theta is
+//! per calendar day.
+
+use std::f64::consts::{PI, SQRT_2};
+
+use time::macros::format_description;
+use time::{Date, Duration, OffsetDateTime};
+
+use super::OptionRight;
+use crate::contracts::tick_types::TickType;
+use crate::contracts::{Contract, OptionComputation};
+use crate::Error;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+const DEFAULT_STEPS: usize = 200;
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 10.0;
+/// Cash-settled US index options with European exercise, by symbol or trading class, assumed by [ExerciseStyle::of].
+const EUROPEAN_INDEX_OPTIONS: [&str; 12] = ["SPX", "SPXW", "XSP", "NDX", "NDXP", "XND", "RUT", "RUTW", "MRUT", "VIX", "VIXW", "XEO"];
+
+/// Model used to price an option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PricingModel {
+    /// Black-Scholes for European options.
+    BlackScholes,
+    /// Cox-Ross-Rubinstein binomial tree with the given number of steps, for American options.
+    Binomial(usize),
+    /// Bjerksund-Stensland (2002) approximation, for American options.
+    BjerksundStensland,
+}
+
+/// When an option can be exercised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExerciseStyle {
+    /// Exercisable on any day up to expiration, like stock and ETF options.
+    American,
+    /// Exercisable only at expiration, like SPX, NDX and RUT options.
+    European,
+}
+
+impl ExerciseStyle {
+    /// Returns the exercise style assumed for an option contract when the caller does not know it.
+    ///
+    /// Options on the cash-settled US SPX, XSP, NDX, XND, RUT, MRUT, VIX and XEO indexes are European. Any other
+    /// option, including OEX, options on ETFs such as SPY and European index options listed outside the US, is assumed
+    /// to be American.
+    pub fn of(contract: &Contract) -> Self {
+        let european = |name: &str| EUROPEAN_INDEX_OPTIONS.contains(&name.to_ascii_uppercase().as_str());
+        if european(&contract.symbol) || european(&contract.trading_class) {
+            ExerciseStyle::European
+        } else {
+            ExerciseStyle::American
+        }
+    }
+
+    /// Returns the model used to price options of this style: Black-Scholes for European options and a binomial
+    /// tree of 200 steps for American options.
+    pub fn pricing_model(&self) -> PricingModel {
+        match self {
+            ExerciseStyle::American => PricingModel::Binomial(DEFAULT_STEPS),
+            ExerciseStyle::European => PricingModel::BlackScholes,
+        }
+    }
+}
+
+/// Price and greeks of an option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Valuation {
+    /// Option price.
+    pub price: f64,
+    /// Change in price for a one unit change in the underlying price.
+    pub delta: f64,
+    /// Change in delta for a one unit change in the underlying price.
+    pub gamma: f64,
+    /// Change in price for a one point change in volatility.
+    pub vega: f64,
+    /// Change in price over one calendar day.
+    pub theta: f64,
+    /// Change in price for a one point change in the interest rate.
+    pub rho: f64,
+}
+
+/// Prices an option and solves for its implied volatility.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::contracts::options::pricing::{OptionPricer, PricingModel};
+/// use ibapi::contracts::options::OptionRight;
+///
+/// let pricer = OptionPricer::new(OptionRight::Put, 100.0, 0.5)
+///     .rate(0.05)
+///     .dividend_yield(0.01)
+///     .model(PricingModel::BjerksundStensland);
+///
+/// let computation = pricer.option_price(0.25, 95.0).expect("invalid inputs");
+/// let implied = pricer.implied_volatility(computation.option_price.unwrap(), 95.0).expect("no implied volatility");
+/// assert!((implied.implied_volatility.unwrap() - 0.25).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionPricer {
+    right: OptionRight,
+    strike: f64,
+    time_to_expiry: f64,
+    rate: f64,
+    dividend_yield: f64,
+    model: PricingModel,
+}
+
+impl OptionPricer {
+    /// Creates a Black-Scholes pricer for an option expiring in `time_to_expiry` years.
+    pub fn new(right: OptionRight, strike: f64, time_to_expiry: f64) -> Self {
+        Self {
+            right,
+            strike,
+            time_to_expiry,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            model: PricingModel::BlackScholes,
+        }
+    }
+
+    /// Creates a pricer for an option contract as of `now`, expiring at the end of its expiration date (UTC).
+    ///
+    /// The model follows `exercise_style`: European options are priced with Black-Scholes, and American options with a
+    /// binomial tree of 200 steps. Pass [ExerciseStyle::of] when the style of the contract is not known. Use
+    /// [model](Self::model) to choose another model.
+    pub fn for_contract(contract: &Contract, exercise_style: ExerciseStyle, now: OffsetDateTime) -> Result<Self, Error> {
+        let right =
+            OptionRight::from_right(&contract.right).ok_or_else(|| Error::InvalidArgument(format!("invalid option right: {}", contract.right)))?;
+
+        let expiration = &contract.last_trade_date_or_contract_month;
+        let expiration = Date::parse(expiration, format_description!("[year][month][day]"))
+            .map_err(|_| Error::InvalidArgument(format!("invalid expiration date: {expiration}")))?;
+        let expires = expiration.midnight().assume_utc() + Duration::DAY;
+        let time_to_expiry = ((expires - now).as_seconds_f64() / Duration::DAY.as_seconds_f64() / DAYS_PER_YEAR).max(0.0);
+
+        Ok(Self::new(right, contract.strike, time_to_expiry).exercise_style(exercise_style))
+    }
+
+    /// Sets the continuously compounded risk free rate.
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Sets the continuous dividend yield of the underlying.
+    pub fn dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Sets the pricing model to the one for an exercise style.
+    pub fn exercise_style(self, exercise_style: ExerciseStyle) -> Self {
+        self.model(exercise_style.pricing_model())
+    }
+
+    /// Sets the pricing model.
+    pub fn model(mut self, model: PricingModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Computes the price and greeks for a volatility and underlying price.
+    pub fn valuation(&self, volatility: f64, underlying_price: f64) -> Result<Valuation, Error> {
+        self.validate(volatility, underlying_price)?;
+
+        let inputs = Inputs {
+            right: self.right,
+            spot: underlying_price,
+            strike: self.strike,
+            time: self.time_to_expiry,
+            rate: self.rate,
+            dividend_yield: self.dividend_yield,
+            volatility,
+        };
+
+        if inputs.time == 0.0 {
+            return Ok(inputs.at_expiry());
+        }
+
+        Ok(match self.model {
+            PricingModel::BlackScholes => inputs.black_scholes(),
+            PricingModel::Binomial(steps) => inputs.numerical(|inputs| inputs.binomial(steps.max(1))),
+            PricingModel::BjerksundStensland => inputs.numerical(Inputs::bjerksund_stensland),
+        })
+    }
+
+    /// Computes the option price and greeks, like [calculate_option_price](crate::Client::calculate_option_price).
+    pub fn option_price(&self, volatility: f64, underlying_price: f64) -> Result<OptionComputation, Error> {
+        let valuation = self.valuation(volatility, underlying_price)?;
+        Ok(self.computation(valuation, volatility, underlying_price))
+    }
+
+    /// Solves for the volatility matching `option_price`, like
+    /// [calculate_implied_volatility](crate::Client::calculate_implied_volatility).
+    ///
+    /// Returns an error if no volatility between 0.01% and 1000% prices the option at `option_price`.
+    pub fn implied_volatility(&self, option_price: f64, underlying_price: f64) -> Result<OptionComputation, Error> {
+        let price = |volatility: f64| self.valuation(volatility, underlying_price).map(|valuation| valuation.price);
+
+        let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+        let (low_price, high_price) = (price(low)?, price(high)?);
+        if option_price < low_price || option_price > high_price {
+            return Err(Error::InvalidArgument(format!(
+                "option price {option_price} outside the range {low_price:.6} to {high_price:.6} of the model"
+            )));
+        }
+
+        // The price increases with volatility, so bisection converges without derivatives, which the American
+        // models only approximate.
+        let mut volatility = (low + high) / 2.0;
+        for _ in 0..100 {
+            volatility = (low + high) / 2.0;
+            let difference = price(volatility)? - option_price;
+            if difference.abs() < 1e-10 || high - low < 1e-10 {
+                break;
+            }
+            if difference > 0.0 {
+                high = volatility;
+            } else {
+                low = volatility;
+            }
+        }
+
+        self.option_price(volatility, underlying_price)
+    }
+
+    fn validate(&self, volatility: f64, underlying_price: f64) -> Result<(), Error> {
+        let check = |name: &str, value: f64, valid: bool| {
+            if value.is_finite() && valid {
+                Ok(())
+            } else {
+                Err(Error::InvalidArgument(format!("invalid {name}: {value}")))
+            }
+        };
+        check("volatility", volatility, volatility > 0.0)?;
+        check("underlying price", underlying_price, underlying_price > 0.0)?;
+        check("strike", self.strike, self.strike > 0.0)?;
+        check("time to expiry", self.time_to_expiry, self.time_to_expiry >= 0.0)?;
+        check("rate", self.rate, true)?;
+        check("dividend yield", self.dividend_yield, true)
+    }
+
+    fn computation(&self, valuation: Valuation, volatility: f64, underlying_price: f64) -> OptionComputation {
+        OptionComputation {
+            field: TickType::ModelOption,
+            tick_attribute: None,
+            implied_volatility: Some(volatility),
+            delta: Some(valuation.delta),
+            option_price: Some(valuation.price),
+            present_value_dividend: Some(underlying_price * (1.0 - (-self.dividend_yield * self.time_to_expiry).exp())),
+            gamma: Some(valuation.gamma),
+            vega: Some(valuation.vega),
+            theta: Some(valuation.theta),
+            underlying_price: Some(underlying_price),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Inputs {
+    right: OptionRight,
+    spot: f64,
+    strike: f64,
+    time: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+}
+
+impl Inputs {
+    fn intrinsic(&self, spot: f64) -> f64 {
+        match self.right {
+            OptionRight::Call => (spot - self.strike).max(0.0),
+            OptionRight::Put => (self.strike - spot).max(0.0),
+        }
+    }
+
+    fn at_expiry(&self) -> Valuation {
+        let in_the_money = self.intrinsic(self.spot) > 0.0;
+        let delta = match (self.right, in_the_money) {
+            (OptionRight::Call, true) => 1.0,
+            (OptionRight::Put, true) => -1.0,
+            _ => 0.0,
+        };
+        Valuation {
+            price: self.intrinsic(self.spot),
+            delta,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: 0.0,
+            rho: 0.0,
+        }
+    }
+
+    fn black_scholes(&self) -> Valuation {
+        let Inputs {
+            spot,
+            strike,
+            time,
+            rate,
+            dividend_yield,
+            volatility,
+            ..
+        } = *self;
+
+        let sqrt_time = time.sqrt();
+        let d1 = ((spot / strike).ln() + (rate - dividend_yield + volatility * volatility / 2.0) * time) / (volatility * sqrt_time);
+        let d2 = d1 - volatility * sqrt_time;
+        let dividend_discount = (-dividend_yield * time).exp();
+        let discount = (-rate * time).exp();
+
+        let gamma = dividend_discount * normal_pdf(d1) / (spot * volatility * sqrt_time);
+        let vega = spot * dividend_discount * normal_pdf(d1) * sqrt_time / 100.0;
+        let decay = -spot * dividend_discount * normal_pdf(d1) * volatility / (2.0 * sqrt_time);
+
+        let (price, delta, theta, rho) = match self.right {
+            OptionRight::Call => (
+                spot * dividend_discount * normal_cdf(d1) - strike * discount * normal_cdf(d2),
+                dividend_discount * normal_cdf(d1),
+                decay - rate * strike * discount * normal_cdf(d2) + dividend_yield * spot * dividend_discount * normal_cdf(d1),
+                strike * time * discount * normal_cdf(d2),
+            ),
+            OptionRight::Put => (
+                strike * discount * normal_cdf(-d2) - spot * dividend_discount * normal_cdf(-d1),
+                dividend_discount * (normal_cdf(d1) - 1.0),
+                decay + rate * strike * discount * normal_cdf(-d2) - dividend_yield * spot * dividend_discount * normal_cdf(-d1),
+                -strike * time * discount * normal_cdf(-d2),
+            ),
+        };
+
+        Valuation {
+            price,
+            delta,
+            gamma,
+            vega,
+            theta: theta / DAYS_PER_YEAR,
+            rho: rho / 100.0,
+        }
+    }
+
+    // Greeks by finite differences, for models without closed form greeks.
+    fn numerical(&self, price: impl Fn(&Inputs) -> f64) -> Valuation {
+        let value = price(self);
+
+        let bump = self.spot * 0.01;
+        let up = price(&Inputs {
+            spot: self.spot + bump,
+            ..*self
+        });
+        let down = price(&Inputs {
+            spot: self.spot - bump,
+            ..*self
+        });
+
+        let vol_bump = (self.volatility / 2.0).min(0.01);
+        let vega = (price(&Inputs {
+            volatility: self.volatility + vol_bump,
+            ..*self
+        }) - price(&Inputs {
+            volatility: self.volatility - vol_bump,
+            ..*self
+        })) / (2.0 * vol_bump)
+            / 100.0;
+
+        let rate_bump = 1e-4;
+        let rho = (price(&Inputs {
+            rate: self.rate + rate_bump,
+            ..*self
+        }) - price(&Inputs {
+            rate: self.rate - rate_bump,
+            ..*self
+        })) / (2.0 * rate_bump)
+            / 100.0;
+
+        let day = 1.0 / DAYS_PER_YEAR;
+        let theta = if self.time > day {
+            price(&Inputs {
+                time: self.time - day,
+                ..*self
+            }) - value
+        } else {
+            self.intrinsic(self.spot) - value
+        };
+
+        Valuation {
+            price: value,
+            delta: (up - down) / (2.0 * bump),
+            gamma: (up - 2.0 * value + down) / (bump * bump),
+            vega,
+            theta,
+            rho,
+        }
+    }
+
+    // Averages trees of `steps` and `steps + 1` steps, which damps the odd-even oscillation of the price.
+    fn binomial(&self, steps: usize) -> f64 {
+        (self.tree(steps) + self.tree(steps + 1)) / 2.0
+    }
+
+    fn tree(&self, steps: usize) -> f64 {
+        let dt = self.time / steps as f64;
+        let up = (self.volatility * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let probability = (((self.rate - self.dividend_yield) * dt).exp() - down) / (up - down);
+        let discount = (-self.rate * dt).exp();
+
+        let mut values: Vec<f64> = (0..=steps)
+            .map(|i| self.intrinsic(self.spot * up.powi(i as i32) * down.powi((steps - i) as i32)))
+            .collect();
+
+        for step in (0..steps).rev() {
+            for i in 0..=step {
+                let continuation = discount * (probability * values[i + 1] + (1.0 - probability) * values[i]);
+                let spot = self.spot * up.powi(i as i32) * down.powi((step - i) as i32);
+                values[i] = continuation.max(self.intrinsic(spot));
+            }
+        }
+        values[0]
+    }
+
+    fn bjerksund_stensland(&self) -> f64 {
+        let carry = self.rate - self.dividend_yield;
+        match self.right {
+            OptionRight::Call => bjerksund_stensland_call(self.spot, self.strike, self.time, self.rate, carry, self.volatility),
+            // Put-call transformation: P(S, K, T, r, b, v) = C(K, S, T, r - b, -b, v)
+            OptionRight::Put => bjerksund_stensland_call(self.strike, self.spot, self.time, self.rate - carry, -carry, self.volatility),
+        }
+    }
+}
+
+fn bjerksund_stensland_call(spot: f64, strike: f64, time: f64, rate: f64, carry: f64, volatility: f64) -> f64 {
+    let variance = volatility * volatility;
+
+    // Never optimal to exercise early, the price is the European price.
+    if carry >= rate {
+        return Inputs {
+            right: OptionRight::Call,
+            spot,
+            strike,
+            time,
+            rate,
+            dividend_yield: rate - carry,
+            volatility,
+        }
+        .black_scholes()
+        .price;
+    }
+
+    let t1 = 0.5 * (5f64.sqrt() - 1.0) * time;
+    let beta = (0.5 - carry / variance) + ((carry / variance - 0.5).powi(2) + 2.0 * rate / variance).sqrt();
+    let b_infinity = beta / (beta - 1.0) * strike;
+    let b_zero = strike.max(rate / (rate - carry) * strike);
+
+    let h1 = -(carry * t1 + 2.0 * volatility * t1.sqrt()) * strike * strike / ((b_infinity - b_zero) * b_zero);
+    let h2 = -(carry * time + 2.0 * volatility * time.sqrt()) * strike * strike / ((b_infinity - b_zero) * b_zero);
+    let i1 = b_zero + (b_infinity - b_zero) * (1.0 - h1.exp());
+    let i2 = b_zero + (b_infinity - b_zero) * (1.0 - h2.exp());
+
+    if spot >= i2 {
+        return spot - strike;
+    }
+
+    let alpha1 = (i1 - strike) * i1.powf(-beta);
+    let alpha2 = (i2 - strike) * i2.powf(-beta);
+
+    let phi = |gamma: f64, h: f64, i: f64, t: f64| phi(spot, t, gamma, h, i, rate, carry, volatility);
+    let psi = |gamma: f64, h: f64| psi(spot, time, gamma, h, i2, i1, t1, rate, carry, volatility);
+
+    alpha2 * spot.powf(beta) - alpha2 * phi(beta, i2, i2, t1) + phi(1.0, i2, i2, t1) - phi(1.0, i1, i2, t1) - strike * phi(0.0, i2, i2, t1)
+        + strike * phi(0.0, i1, i2, t1)
+        + alpha1 * phi(beta, i1, i2, t1)
+        - alpha1 * psi(beta, i1)
+        + psi(1.0, i1)
+        - psi(1.0, strike)
+        - strike * psi(0.0, i1)
+        + strike * psi(0.0, strike)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn phi(spot: f64, time: f64, gamma: f64, h: f64, i: f64, rate: f64, carry: f64, volatility: f64) -> f64 {
+    let variance = volatility * volatility;
+    let lambda = (-rate + gamma * carry + 0.5 * gamma * (gamma - 1.0) * variance) * time;
+    let d = -((spot / h).ln() + (carry + (gamma - 0.5) * variance) * time) / (volatility * time.sqrt());
+    let kappa = 2.0 * carry / variance + (2.0 * gamma - 1.0);
+
+    lambda.exp() * spot.powf(gamma) * (normal_cdf(d) - (i / spot).powf(kappa) * normal_cdf(d - 2.0 * (i / spot).ln() / (volatility * time.sqrt())))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn psi(spot: f64, time: f64, gamma: f64, h: f64, i2: f64, i1: f64, t1: f64, rate: f64, carry: f64, volatility: f64) -> f64 {
+    let variance = volatility * volatility;
+    let drift = carry + (gamma - 0.5) * variance;
+    let (sd1, sd) = (volatility * t1.sqrt(), volatility * time.sqrt());
+
+    let e1 = ((spot / i1).ln() + drift * t1) / sd1;
+    let e2 = ((i2 * i2 / (spot * i1)).ln() + drift * t1) / sd1;
+    let e3 = ((spot / i1).ln() - drift * t1) / sd1;
+    let e4 = ((i2 * i2 / (spot * i1)).ln() - drift * t1) / sd1;
+
+    let f1 = ((spot / h).ln() + drift * time) / sd;
+    let f2 = ((i2 * i2 / (spot * h)).ln() + drift * time) / sd;
+    let f3 = ((i1 * i1 / (spot * h)).ln() + drift * time) / sd;
+    let f4 = ((spot * i1 * i1 / (h * i2 * i2)).ln() + drift * time) / sd;
+
+    let rho = (t1 / time).sqrt();
+    let lambda = -rate + gamma * carry + 0.5 * gamma * (gamma - 1.0) * variance;
+    let kappa = 2.0 * carry / variance + (2.0 * gamma - 1.0);
+
+    (lambda * time).exp()
+        * spot.powf(gamma)
+        * (bivariate_normal_cdf(-e1, -f1, rho)
+            - (i2 / spot).powf(kappa) * bivariate_normal_cdf(-e2, -f2, rho)
+            - (i1 / spot).powf(kappa) * bivariate_normal_cdf(-e3, -f3, -rho)
+            + (i1 / i2).powf(kappa) * bivariate_normal_cdf(-e4, -f4, -rho))
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+// Cumulative standard normal distribution, Hart (1968) as given by West (2005), accurate to double precision.
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs();
+    let tail = if z > 37.0 {
+        0.0
+    } else {
+        let e = (-z * z / 2.0).exp();
+        if z < 10.0 / SQRT_2 {
+            let numerator = [
+                3.52624965998911e-02,
+                0.700383064443688,
+                6.37396220353165,
+                33.912866078383,
+                112.079291497871,
+                221.213596169931,
+                220.206867912376,
+            ]
+            .iter()
+            .fold(0.0, |acc, c| acc * z + c);
+            let denominator = [
+                8.83883476483184e-02,
+                1.75566716318264,
+                16.064177579207,
+                86.7807322029461,
+                296.564248779674,
+                637.333633378831,
+                793.826512519948,
+                440.413735824752,
+            ]
+            .iter()
+            .fold(0.0, |acc, c| acc * z + c);
+            e * numerator / denominator
+        } else {
+            let b = z + 1.0 / (z + 2.0 / (z + 3.0 / (z + 4.0 / (z + 0.65))));
+            e / b / (2.0 * PI).sqrt()
+        }
+    };
+
+    if x > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+// Cumulative bivariate standard normal distribution P(X < x, Y < y) with correlation `rho`, Genz (2004).
+fn bivariate_normal_cdf(x: f64, y: f64, rho: f64) -> f64 {
+    const W3: [f64; 3] = [0.1713244923791705, 0.3607615730481384, 0.4679139345726904];
+    const X3: [f64; 3] = [0.9324695142031522, 0.6612093864662647, 0.238619186083197];
+    const W6: [f64; 6] = [
+        0.04717533638651177,
+        0.1069393259953183,
+        0.1600783285433464,
+        0.2031674267230659,
+        0.2334925365383547,
+        0.2491470458134029,
+    ];
+    const X6: [f64; 6] = [
+        0.9815606342467191,
+        0.904117256370475,
+        0.769902674194305,
+        0.5873179542866171,
+        0.3678314989981802,
+        0.1252334085114692,
+    ];
+    const W10: [f64; 10] = [
+        0.01761400713915212,
+        0.04060142980038694,
+        0.06267204833410906,
+        0.08327674157670475,
+        0.1019301198172404,
+        0.1181945319615184,
+        0.1316886384491766,
+        0.1420961093183821,
+        0.1491729864726037,
+        0.1527533871307259,
+    ];
+    const X10: [f64; 10] = [
+        0.9931285991850949,
+        0.9639719272779138,
+        0.912234428251326,
+        0.8391169718222188,
+        0.7463319064601508,
+        0.636053680726515,
+        0.5108670019508271,
+        0.3737060887154196,
+        0.2277858511416451,
+        0.07652652113349733,
+    ];
+
+    let (weights, abscissas): (&[f64], &[f64]) = if rho.abs() < 0.3 {
+        (&W3, &X3)
+    } else if rho.abs() < 0.75 {
+        (&W6, &X6)
+    } else {
+        (&W10, &X10)
+    };
+
+    // Genz computes the upper probability P(X > h, Y > k).
+    let h = -x;
+    let mut k = -y;
+    let mut hk = h * k;
+    let mut bvn = 0.0;
+
+    if rho.abs() < 0.925 {
+        let hs = (h * h + k * k) / 2.0;
+        let asr = rho.asin();
+        for (w, a) in weights.iter().zip(abscissas) {
+            for sign in [-1.0, 1.0] {
+                let sn = (asr * (sign * a + 1.0) / 2.0).sin();
+                bvn += w * ((sn * hk - hs) / (1.0 - sn * sn)).exp();
+            }
+        }
+        return bvn * asr / (4.0 * PI) + normal_cdf(-h) * normal_cdf(-k);
+    }
+
+    if rho < 0.0 {
+        k = -k;
+        hk = -hk;
+    }
+
+    if rho.abs() < 1.0 {
+        let a_squared = (1.0 - rho) * (1.0 + rho);
+        let mut a = a_squared.sqrt();
+        let bs = (h - k) * (h - k);
+        let c = (4.0 - hk) / 8.0;
+        let d = (12.0 - hk) / 16.0;
+
+        let asr = -(bs / a_squared + hk) / 2.0;
+        if asr > -100.0 {
+            bvn = a * asr.exp() * (1.0 - c * (bs - a_squared) * (1.0 - d * bs / 5.0) / 3.0 + c * d * a_squared * a_squared / 5.0);
+        }
+        if -hk < 100.0 {
+            let b = bs.sqrt();
+            bvn -= (-hk / 2.0).exp() * (2.0 * PI).sqrt() * normal_cdf(-b / a) * b * (1.0 - c * bs * (1.0 - d * bs / 5.0) / 3.0);
+        }
+
+        a /= 2.0;
+        for (w, abscissa) in weights.iter().zip(abscissas) {
+            for sign in [-1.0, 1.0] {
+                let xs = (a * (sign * abscissa + 1.0)).powi(2);
+                let rs = (1.0 - xs).sqrt();
+                let asr = -(bs / xs + hk) / 2.0;
+                if asr > -100.0 {
+                    bvn += a * w * asr.exp() * ((-hk * (1.0 - rs) / (2.0 * (1.0 + rs))).exp() / rs - (1.0 + c * xs * (1.0 + d * xs)));
+                }
+            }
+        }
+        bvn = -bvn / (2.0 * PI);
+    }
+
+    if rho > 0.0 {
+        bvn + normal_cdf(-h.max(k))
+    } else {
+        let bvn = -bvn;
+        if k > h {
+            bvn + normal_cdf(k) - normal_cdf(h)
+        } else {
+            bvn
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{actual} is not within {tolerance} of {expected}");
+    }
+
+    #[test]
+    fn test_normal_distributions() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-15);
+        assert_close(normal_cdf(1.96), 0.9750021048517795, 1e-14);
+        assert_close(normal_cdf(-8.0), 6.22096057427178e-16, 1e-22);
+
+        for rho in [-0.95, -0.5, 0.0, 0.2, 0.5, 0.8, 0.95] {
+            assert_close(bivariate_normal_cdf(0.0, 0.0, rho), 0.25 + rho.asin() / (2.0 * PI), 1e-12);
+        }
+        assert_close(bivariate_normal_cdf(1.0, 0.5, 0.0), normal_cdf(1.0) * normal_cdf(0.5), 1e-12);
+        assert_close(bivariate_normal_cdf(8.0, 0.3, 0.9), normal_cdf(0.3), 1e-9);
+        assert_close(bivariate_normal_cdf(-0.3, 0.7, -0.95), bivariate_normal_cdf(0.7, -0.3, -0.95), 1e-12);
+    }
+
+    #[test]
+    fn test_black_scholes() {
+        let call = OptionPricer::new(OptionRight::Call, 100.0, 1.0).rate(0.05);
+        let valuation = call.valuation(0.2, 100.0).unwrap();
+        assert_close(valuation.price, 10.450583572185565, 1e-9);
+        assert_close(valuation.delta, 0.6368306511756191, 1e-9);
+        assert_close(valuation.gamma, 0.018762017345846895, 1e-9);
+        assert_close(valuation.vega, 0.3752403469169379, 1e-9);
+        assert_close(valuation.theta, -6.414027546438197 / 365.0, 1e-9);
+        assert_close(valuation.rho, 0.5323248154537634, 1e-9);
+
+        let put = OptionPricer::new(OptionRight::Put, 100.0, 1.0).rate(0.05).valuation(0.2, 100.0).unwrap();
+        assert_close(put.price, 5.573526022256971, 1e-9);
+        assert_close(valuation.price - put.price, 100.0 - 100.0 * (-0.05f64).exp(), 1e-9);
+    }
+
+    #[test]
+    fn test_american_models() {
+        let pricer = |model| OptionPricer::new(OptionRight::Put, 100.0, 1.0).rate(0.08).model(model);
+
+        let european = pricer(PricingModel::BlackScholes).valuation(0.25, 90.0).unwrap();
+        let binomial = pricer(PricingModel::Binomial(1000)).valuation(0.25, 90.0).unwrap();
+        let bjerksund = pricer(PricingModel::BjerksundStensland).valuation(0.25, 90.0).unwrap();
+
+        assert!(binomial.price > european.price, "early exercise premium");
+        // Bjerksund-Stensland is a lower bound on the American price.
+        assert!(bjerksund.price > european.price && bjerksund.price <= binomial.price);
+        assert_close(bjerksund.price, binomial.price, 0.1);
+        assert_close(bjerksund.delta, binomial.delta, 0.01);
+        assert_close(bjerksund.vega, binomial.vega, 0.01);
+
+        // Deep in the money American puts are worth their intrinsic value.
+        let deep = pricer(PricingModel::BjerksundStensland).valuation(0.25, 40.0).unwrap();
+        assert_close(deep.price, 60.0, 1e-9);
+
+        // Without dividends an American call is never exercised early.
+        let call = OptionPricer::new(OptionRight::Call, 100.0, 1.0).rate(0.05);
+        let european = call.valuation(0.2, 100.0).unwrap();
+        let american = call.model(PricingModel::BjerksundStensland).valuation(0.2, 100.0).unwrap();
+        assert_close(american.price, european.price, 1e-9);
+    }
+
+    #[test]
+    fn test_implied_volatility() {
+        for model in [PricingModel::BlackScholes, PricingModel::Binomial(200), PricingModel::BjerksundStensland] {
+            let pricer = OptionPricer::new(OptionRight::Call, 105.0, 0.25)
+                .rate(0.04)
+                .dividend_yield(0.02)
+                .model(model);
+            let computation = pricer.option_price(0.3, 100.0).unwrap();
+            assert_eq!(computation.field, TickType::ModelOption);
+            assert_eq!(computation.underlying_price, Some(100.0));
+
+            let implied = pricer.implied_volatility(computation.option_price.unwrap(), 100.0).unwrap();
+            assert_close(implied.implied_volatility.unwrap(), 0.3, 1e-6);
+        }
+
+        let pricer = OptionPricer::new(OptionRight::Call, 100.0, 1.0);
+        assert!(pricer.implied_volatility(0.0001, 150.0).is_err(), "below intrinsic value");
+        assert!(pricer.valuation(0.0, 100.0).is_err());
+    }
+
+    #[test]
+    fn test_for_contract() {
+        let contract = Contract::option("AAPL", "20240119", 185.0, "P");
+        let pricer = OptionPricer::for_contract(&contract, ExerciseStyle::American, datetime!(2024-01-18 0:00 UTC)).unwrap();
+        assert_eq!(pricer.right, OptionRight::Put);
+        assert_eq!(pricer.strike, 185.0);
+        assert_close(pricer.time_to_expiry, 2.0 / 365.0, 1e-12);
+        assert_eq!(pricer.model, PricingModel::Binomial(DEFAULT_STEPS));
+
+        let expired = OptionPricer::for_contract(&contract, ExerciseStyle::American, datetime!(2024-02-01 0:00 UTC)).unwrap();
+        let valuation = expired.valuation(0.2, 180.0).unwrap();
+        assert_eq!((valuation.price, valuation.delta), (5.0, -1.0));
+
+        assert!(OptionPricer::for_contract(
+            &Contract::option("AAPL", "202401", 185.0, "P"),
+            ExerciseStyle::American,
+            datetime!(2024-01-18 0:00 UTC)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_exercise_style() {
+        let now = datetime!(2024-01-18 0:00 UTC);
+        for symbol in ["SPX", "NDX", "RUT", "xsp"] {
+            let contract = Contract::option(symbol, "20240119", 4800.0, "C");
+            assert_eq!(ExerciseStyle::of(&contract), ExerciseStyle::European, "{symbol}");
+            assert_eq!(
+                OptionPricer::for_contract(&contract, ExerciseStyle::of(&contract), now).unwrap().model,
+                PricingModel::BlackScholes,
+                "{symbol}"
+            );
+        }
+
+        let mut weekly = Contract::option("SPX", "20240119", 4800.0, "C");
+        weekly.symbol = "".into();
+        weekly.trading_class = "SPXW".into();
+        assert_eq!(ExerciseStyle::of(&weekly), ExerciseStyle::European);
+
+        for symbol in ["AAPL", "SPY", "OEX"] {
+            let contract = Contract::option(symbol, "20240119", 185.0, "C");
+            assert_eq!(ExerciseStyle::of(&contract), ExerciseStyle::American, "{symbol}");
+        }
+
+        // A European option outside the default list.
+        let contract = Contract::option("ESTX50", "20240119", 4500.0, "C");
+        assert_eq!(ExerciseStyle::of(&contract), ExerciseStyle::American);
+        let pricer = OptionPricer::for_contract(&contract, ExerciseStyle::European, now).unwrap();
+        assert_eq!(pricer.model, PricingModel::BlackScholes);
+    }
+}
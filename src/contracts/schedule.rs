@@ -0,0 +1,331 @@
+//! Trading sessions of a contract.
+//!
+//! [ContractDetails] describes when a contract trades with `trading_hours` and `liquid_hours`, such as
+//! `20240101:0930-20240101:1600;20240102:CLOSED`, in the time zone named by `time_zone_id`. A [TradingSchedule] parses
+//! these into [Session]s, the same sessions returned by `historical_schedules`, and answers when the market is open.
+
+use time::macros::format_description;
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::{timezones, OffsetDateTimeExt, OffsetResult, PrimitiveDateTimeExt, Tz};
+
+use crate::contracts::ContractDetails;
+use crate::market_data::historical::{Schedule, Session};
+use crate::Error;
+
+/// Trading sessions of a contract in its time zone.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::contracts::schedule::TradingSchedule;
+/// use ibapi::contracts::ContractDetails;
+/// use time::OffsetDateTime;
+///
+/// // Usually returned by `contract_details`.
+/// let details = ContractDetails {
+///     liquid_hours: vec!["20240102:0930-20240102:1600".to_owned(), "20240103:CLOSED".to_owned()],
+///     time_zone_id: "US/Eastern".to_owned(),
+///     ..Default::default()
+/// };
+/// let schedule = TradingSchedule::liquid_hours(&details).expect("invalid liquid hours");
+///
+/// let now = OffsetDateTime::now_utc();
+/// if schedule.is_open(now) {
+///     println!("open until {:?}", schedule.next_close(now));
+/// } else {
+///     println!("opens at {:?}", schedule.next_open(now));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradingSchedule {
+    time_zone: String,
+    sessions: Vec<Session>,
+    closed: Vec<Date>,
+}
+
+impl TradingSchedule {
+    /// Creates a schedule from sessions in the named time zone.
+    pub fn new(time_zone: &str, sessions: impl IntoIterator<Item = Session>) -> Result<Self, Error> {
+        find_time_zone(time_zone)?;
+
+        let mut sessions: Vec<Session> = sessions.into_iter().collect();
+        sessions.sort_by_key(|session| session.start);
+
+        Ok(Self {
+            time_zone: time_zone.to_owned(),
+            sessions,
+            closed: Vec::new(),
+        })
+    }
+
+    /// Creates a schedule from the trading hours of a contract.
+    pub fn trading_hours(details: &ContractDetails) -> Result<Self, Error> {
+        Self::parse(&details.trading_hours, &details.time_zone_id)
+    }
+
+    /// Creates a schedule from the liquid hours of a contract, its regular trading hours.
+    pub fn liquid_hours(details: &ContractDetails) -> Result<Self, Error> {
+        Self::parse(&details.liquid_hours, &details.time_zone_id)
+    }
+
+    fn parse(hours: &[String], time_zone: &str) -> Result<Self, Error> {
+        let (sessions, closed) = parse_trading_hours(hours, time_zone)?;
+        let mut schedule = Self::new(time_zone, sessions)?;
+        schedule.closed = closed;
+        Ok(schedule)
+    }
+
+    /// Returns the name of the time zone of the sessions.
+    pub fn time_zone(&self) -> &str {
+        &self.time_zone
+    }
+
+    /// Returns the sessions, sorted by start.
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    /// Returns the days listed as closed.
+    pub fn closed_days(&self) -> &[Date] {
+        &self.closed
+    }
+
+    /// Returns the session open at `at`.
+    pub fn session_at(&self, at: OffsetDateTime) -> Option<&Session> {
+        self.sessions.iter().find(|session| session.start <= at && at < session.end)
+    }
+
+    /// Returns true if a session is open at `at`.
+    pub fn is_open(&self, at: OffsetDateTime) -> bool {
+        self.session_at(at).is_some()
+    }
+
+    /// Returns the next time after `at` that the market opens, skipping sessions that continue the one before.
+    pub fn next_open(&self, at: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.sessions
+            .iter()
+            .map(|session| session.start)
+            .find(|&start| start > at && !self.is_open(start - Duration::NANOSECOND))
+    }
+
+    /// Returns the next time after `at` that the market closes, following sessions that continue one another.
+    pub fn next_close(&self, at: OffsetDateTime) -> Option<OffsetDateTime> {
+        let mut close = match self.session_at(at) {
+            Some(session) => session.end,
+            None => self.sessions.iter().find(|session| session.start > at)?.end,
+        };
+        while let Some(session) = self.session_at(close) {
+            close = session.end;
+        }
+        Some(close)
+    }
+
+    /// Returns the sessions that are open at some time between `start` and `end`.
+    pub fn sessions_between(&self, start: OffsetDateTime, end: OffsetDateTime) -> impl Iterator<Item = &Session> {
+        self.sessions.iter().filter(move |session| session.start < end && session.end > start)
+    }
+
+    /// Returns the sessions of a trading day.
+    pub fn sessions_on(&self, date: Date) -> impl Iterator<Item = &Session> {
+        self.sessions.iter().filter(move |session| session.reference == date)
+    }
+
+    /// Returns how long the market is open on a trading day.
+    pub fn hours_on(&self, date: Date) -> Duration {
+        self.sessions_on(date).map(|session| session.end - session.start).sum()
+    }
+
+    /// Returns the trading days of the schedule.
+    pub fn trading_days(&self) -> Vec<Date> {
+        let mut days: Vec<Date> = self.sessions.iter().map(|session| session.reference).collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// Returns true if the market closes early on `date`, before the typical close of the trading days in the schedule.
+    ///
+    /// The typical close is the median local time of day that the trading days of the schedule close.
+    pub fn is_half_day(&self, date: Date) -> bool {
+        match (self.close_on(date), self.typical_close()) {
+            (Some(close), Some(typical)) => close < typical,
+            _ => false,
+        }
+    }
+
+    /// Returns the trading days that close early. See [TradingSchedule::is_half_day].
+    pub fn half_days(&self) -> Vec<Date> {
+        self.trading_days().into_iter().filter(|&date| self.is_half_day(date)).collect()
+    }
+
+    // Local time of day that the last session of a trading day closes.
+    fn close_on(&self, date: Date) -> Option<Time> {
+        let time_zone = find_time_zone(&self.time_zone).ok()?;
+        let end = self.sessions_on(date).map(|session| session.end).max()?;
+        Some(end.to_timezone(time_zone).time())
+    }
+
+    fn typical_close(&self) -> Option<Time> {
+        let mut closes: Vec<Time> = self.trading_days().into_iter().filter_map(|date| self.close_on(date)).collect();
+        closes.sort();
+        closes.get(closes.len() / 2).copied()
+    }
+}
+
+impl TryFrom<&Schedule> for TradingSchedule {
+    type Error = Error;
+
+    /// Creates a trading schedule from a schedule returned by `historical_schedules`.
+    fn try_from(schedule: &Schedule) -> Result<Self, Error> {
+        Self::new(&schedule.time_zone, schedule.sessions.iter().copied())
+    }
+}
+
+fn find_time_zone(name: &str) -> Result<&'static Tz, Error> {
+    timezones::find_by_name(name)
+        .first()
+        .copied()
+        .ok_or_else(|| Error::InvalidArgument(format!("unknown time zone: {name}")))
+}
+
+/// Parses trading or liquid hours such as `20240101:0930-20240101:1600;20240102:CLOSED` into sessions and closed days.
+///
+/// The older `20090507:0700-1830,1830-2330` format is also accepted.
+pub(crate) fn parse_trading_hours(hours: &[String], time_zone_id: &str) -> Result<(Vec<Session>, Vec<Date>), Error> {
+    let time_zone = find_time_zone(time_zone_id)?;
+
+    let invalid = |entry: &str| Error::InvalidArgument(format!("invalid trading hours: {entry}"));
+    let date_format = format_description!("[year][month][day]");
+    let time_format = format_description!("[year][month][day][hour][minute]");
+
+    let local = |date: &str, time: &str, entry: &str| -> Result<OffsetDateTime, Error> {
+        let time = PrimitiveDateTime::parse(&format!("{date}{time}"), time_format).map_err(|_| invalid(entry))?;
+        match time.assume_timezone(time_zone) {
+            OffsetResult::Some(time) | OffsetResult::Ambiguous(time, _) => Ok(time),
+            OffsetResult::None => Err(invalid(entry)),
+        }
+    };
+
+    let mut sessions = Vec::new();
+    let mut closed = Vec::new();
+    for entry in hours.iter().flat_map(|hours| hours.split(';')).filter(|entry| !entry.is_empty()) {
+        let (day, ranges) = entry.split_once(':').ok_or_else(|| invalid(entry))?;
+        let reference = Date::parse(day, date_format).map_err(|_| invalid(entry))?;
+        if ranges == "CLOSED" {
+            closed.push(reference);
+            continue;
+        }
+
+        for range in ranges.split(',') {
+            let (open, close) = range.split_once('-').ok_or_else(|| invalid(entry))?;
+            let (open_day, open) = open.split_once(':').unwrap_or((day, open));
+            let (close_day, close) = close.split_once(':').unwrap_or((day, close));
+
+            let start = local(open_day, open, entry)?;
+            let mut end = local(close_day, close, entry)?;
+            if end <= start {
+                end += Duration::days(1);
+            }
+            sessions.push(Session { reference, start, end });
+        }
+    }
+    Ok((sessions, closed))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::market_data::historical::common::decoders::decode_historical_schedule;
+    use crate::messages::ResponseMessage;
+
+    fn details(liquid_hours: &str, trading_hours: &str, time_zone_id: &str) -> ContractDetails {
+        ContractDetails {
+            liquid_hours: liquid_hours.split(';').map(String::from).collect(),
+            trading_hours: trading_hours.split(';').map(String::from).collect(),
+            time_zone_id: time_zone_id.to_owned(),
+            ..ContractDetails::default()
+        }
+    }
+
+    #[test]
+    fn test_trading_schedule() {
+        let details = details(
+            "20241127:0930-20241127:1600;20241128:CLOSED;20241129:0930-20241129:1300;20241202:0930-20241202:1600",
+            "20241127:0400-20241127:2000;20241128:CLOSED;20241129:0400-20241129:1700;20241202:0400-20241202:2000",
+            "US/Eastern",
+        );
+
+        let schedule = TradingSchedule::liquid_hours(&details).unwrap();
+        assert_eq!(schedule.time_zone(), "US/Eastern");
+        assert_eq!(schedule.sessions().len(), 3);
+        assert_eq!(schedule.closed_days(), &[date!(2024 - 11 - 28)]);
+
+        assert!(schedule.is_open(datetime!(2024-11-27 14:30 UTC)));
+        assert!(!schedule.is_open(datetime!(2024-11-27 21:00 UTC)), "the session end is exclusive");
+        assert!(!schedule.is_open(datetime!(2024-11-28 15:00 UTC)), "Thanksgiving");
+
+        assert_eq!(
+            schedule.next_close(datetime!(2024-11-27 15:00 UTC)),
+            Some(datetime!(2024-11-27 21:00 UTC))
+        );
+        assert_eq!(schedule.next_open(datetime!(2024-11-27 15:00 UTC)), Some(datetime!(2024-11-29 14:30 UTC)));
+        assert_eq!(
+            schedule.next_close(datetime!(2024-11-28 15:00 UTC)),
+            Some(datetime!(2024-11-29 18:00 UTC))
+        );
+        assert_eq!(schedule.next_open(datetime!(2024-12-02 15:00 UTC)), None);
+
+        let between: Vec<Date> = schedule
+            .sessions_between(datetime!(2024-11-27 20:00 UTC), datetime!(2024-11-29 15:00 UTC))
+            .map(|session| session.reference)
+            .collect();
+        assert_eq!(between, vec![date!(2024 - 11 - 27), date!(2024 - 11 - 29)]);
+
+        assert_eq!(schedule.hours_on(date!(2024 - 11 - 29)), Duration::minutes(210));
+        assert_eq!(schedule.half_days(), vec![date!(2024 - 11 - 29)]);
+        assert!(!schedule.is_half_day(date!(2024 - 11 - 28)), "closed days are not half days");
+
+        let extended = TradingSchedule::trading_hours(&details).unwrap();
+        assert!(extended.is_open(datetime!(2024-11-27 09:00 UTC)));
+        assert_eq!(extended.half_days(), vec![date!(2024 - 11 - 29)]);
+    }
+
+    #[test]
+    fn test_contiguous_sessions() {
+        let legacy = details("", "20090507:0700-1830,1830-2330;20090508:CLOSED", "US/Central");
+        let schedule = TradingSchedule::trading_hours(&legacy).unwrap();
+
+        assert_eq!(schedule.sessions().len(), 2);
+        assert_eq!(schedule.sessions()[1].start, datetime!(2009-05-07 23:30 UTC));
+        assert_eq!(schedule.sessions()[1].end, datetime!(2009-05-08 04:30 UTC));
+        assert_eq!(
+            schedule.next_close(datetime!(2009-05-07 13:00 UTC)),
+            Some(datetime!(2009-05-08 04:30 UTC))
+        );
+        assert_eq!(schedule.next_open(datetime!(2009-05-07 11:00 UTC)), Some(datetime!(2009-05-07 12:00 UTC)));
+        assert_eq!(schedule.next_open(datetime!(2009-05-07 13:00 UTC)), None);
+
+        assert!(TradingSchedule::trading_hours(&details("", "20240301", "US/Eastern")).is_err());
+        assert!(TradingSchedule::trading_hours(&details("", "", "Nowhere/Land")).is_err());
+    }
+
+    #[test]
+    fn test_agrees_with_historical_schedule() {
+        let mut message = ResponseMessage::from(
+            "106\09000\020230414-09:30:00\020230417-16:00:00\0US/Eastern\02\020230414-09:30:00\020230414-16:00:00\020230414\020230417-09:30:00\020230417-16:00:00\020230417\0",
+        );
+        let historical = TradingSchedule::try_from(&decode_historical_schedule(&mut message).unwrap()).unwrap();
+
+        let details = details(
+            "20230414:0930-20230414:1600;20230415:CLOSED;20230416:CLOSED;20230417:0930-20230417:1600",
+            "",
+            "US/Eastern",
+        );
+        let liquid = TradingSchedule::liquid_hours(&details).unwrap();
+
+        assert_eq!(historical.sessions(), liquid.sessions());
+        assert_eq!(historical.time_zone(), liquid.time_zone());
+    }
+}
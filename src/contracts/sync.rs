@@ -1,11 +1,13 @@
 use super::common::{decoders, encoders};
 use super::*;
-use crate::client::{ClientRequestBuilders, Subscription};
-use crate::common::request_helpers;
+use crate::client::builders::sync::ClientRequestBuilders;
+use crate::client::sync::Client;
+use crate::common::request_helpers::sync_helpers as request_helpers;
 use crate::messages::{IncomingMessages, OutgoingMessages};
 use crate::protocol::{check_version, Features};
+use crate::subscriptions::sync::Subscription;
 use crate::subscriptions::StreamDecoder;
-use crate::{Client, Error};
+use crate::Error;
 use log::{error, info};
 
 // Requests contract information.
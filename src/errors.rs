@@ -6,6 +6,7 @@
 
 use std::{num::ParseIntError, string::FromUtf8Error, sync::Arc};
 
+use crate::market_data::lines::LineKind;
 use crate::messages::{ResponseMessage, CODE_INDEX, MESSAGE_INDEX};
 
 #[derive(Debug, Clone)]
@@ -34,6 +35,8 @@ pub enum Error {
     Message(i32, String),
     /// Returned when attempting to create a subscription that already exists.
     AlreadySubscribed,
+    /// Returned by a `LineManager` when all lines of a kind are in use.
+    LinesExhausted(LineKind, usize),
 }
 
 impl std::error::Error for Error {}
@@ -62,6 +65,7 @@ impl std::fmt::Display for Error {
             Error::InvalidArgument(ref err) => write!(f, "InvalidArgument: {err}"),
             Error::Message(code, message) => write!(f, "[{code}] {message}"),
             Error::AlreadySubscribed => write!(f, "AlreadySubscribed"),
+            Error::LinesExhausted(kind, limit) => write!(f, "no {kind} lines available, all {limit} in use"),
         }
     }
 }
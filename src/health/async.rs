@@ -0,0 +1,155 @@
+//! Health monitor for the async client.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use time::OffsetDateTime;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::{self, JoinHandle};
+
+use super::{Health, HealthConfig, HealthEvent, HealthTracker};
+use crate::client::r#async::Client;
+use crate::trace::spans::instrument_worker;
+
+/// Sends heartbeats on a background task and tracks the health of the connection.
+///
+/// The async client does not reconnect, so [HealthConfig::reconnect] has no effect. The task stops when the monitor
+/// is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use ibapi::health::{HealthConfig, HealthEvent, HealthMonitor};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let config = HealthConfig::default().interval(Duration::from_secs(5)).max_missed(2);
+///     let mut monitor = HealthMonitor::start(&client, config);
+///
+///     while let Some(event) = monitor.next_event().await {
+///         match event {
+///             HealthEvent::Unhealthy(health) => println!("connection lost: {health:?}"),
+///             HealthEvent::Recovered(health) => println!("connection recovered, latency {:?}", health.latency),
+///         }
+///     }
+/// }
+/// ```
+pub struct HealthMonitor {
+    tracker: Arc<Mutex<HealthTracker>>,
+    events: UnboundedReceiver<HealthEvent>,
+    handle: JoinHandle<()>,
+}
+
+impl HealthMonitor {
+    /// Starts sending heartbeats on the client's connection. Must be called from within a Tokio runtime.
+    pub fn start(client: &Client, config: HealthConfig) -> HealthMonitor {
+        let tracker = Arc::new(Mutex::new(HealthTracker::new(config, client.connection_time())));
+        let (events_send, events) = mpsc::unbounded_channel();
+
+        let handle = task::spawn(instrument_worker(
+            "health",
+            run(client.clone(), config, Arc::clone(&tracker), events_send),
+        ));
+
+        HealthMonitor { tracker, events, handle }
+    }
+
+    /// Returns a snapshot of the connection health.
+    pub fn health(&self) -> Health {
+        self.tracker.lock().map(|tracker| tracker.health().clone()).unwrap_or_default()
+    }
+
+    /// Waits until the health changes. Returns `None` once the monitor has stopped.
+    pub async fn next_event(&mut self) -> Option<HealthEvent> {
+        self.events.recv().await
+    }
+
+    /// Returns the next change in health if one is available.
+    pub fn try_next_event(&mut self) -> Option<HealthEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for HealthMonitor {
+    fn drop(&mut self) {
+        debug!("health monitor stopped");
+        self.handle.abort();
+    }
+}
+
+async fn run(client: Client, config: HealthConfig, tracker: Arc<Mutex<HealthTracker>>, events: UnboundedSender<HealthEvent>) {
+    loop {
+        tokio::time::sleep(config.interval).await;
+
+        let sent = OffsetDateTime::now_utc();
+        let started = Instant::now();
+        let response = heartbeat(&client, config.timeout).await;
+
+        let transition = {
+            let Ok(mut tracker) = tracker.lock() else {
+                return;
+            };
+            match response {
+                Some(server_time) => tracker.answered(sent, started.elapsed(), server_time),
+                None => tracker.missed(),
+            }
+        };
+
+        if let Some(event) = transition.event {
+            let _ = events.send(event);
+        }
+    }
+}
+
+// Requests the server time, giving up after the timeout.
+async fn heartbeat(client: &Client, timeout: Duration) -> Option<OffsetDateTime> {
+    match tokio::time::timeout(timeout, client.server_time()).await {
+        Ok(Ok(server_time)) => Some(server_time),
+        Ok(Err(err)) => {
+            warn!("heartbeat failed: {err}");
+            None
+        }
+        Err(_) => {
+            warn!("heartbeat timed out after {timeout:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::health::HealthStatus;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[tokio::test]
+    async fn test_heartbeat_measures_latency() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec!["49|1|1678323335|".to_owned()],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        let monitor = HealthMonitor::start(&client, HealthConfig::default().interval(Duration::from_millis(10)));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while monitor.health().latency.is_none() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let health = monitor.health();
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert!(health.latency.is_some());
+        assert!(health.clock_skew.is_some());
+        assert_eq!(health.missed_heartbeats, 0);
+    }
+}
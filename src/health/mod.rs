@@ -0,0 +1,244 @@
+//! Connection health monitoring.
+//!
+//! A half-open socket to TWS or the Gateway is not noticed by the dispatcher, which just keeps waiting for the next
+//! message. A [HealthMonitor] sends a heartbeat, a `server_time` request, at a fixed interval. It measures the
+//! round-trip latency and the skew between the local and the server clock, and declares the connection unhealthy
+//! after a configurable number of consecutive heartbeats go unanswered.
+//!
+//! The monitor keeps a [Health] snapshot and emits a [HealthEvent] each time the connection becomes unhealthy or
+//! recovers. The blocking client can also force a reconnection when the connection becomes unhealthy. The async client
+//! does not reconnect yet, so it only reports the change.
+//!
+//! The server reports its time in whole seconds, so the clock skew is only accurate to about a second.
+
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use sync::HealthMonitor;
+
+#[cfg(feature = "async")]
+pub use r#async::HealthMonitor;
+
+/// Options for a [HealthMonitor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthConfig {
+    interval: Duration,
+    timeout: Duration,
+    max_missed: u32,
+    reconnect: bool,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(5),
+            max_missed: 3,
+            reconnect: true,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Sets the time between heartbeats. Defaults to 10 seconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how long to wait for a heartbeat response before counting it as missed. Defaults to 5 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the number of consecutive missed heartbeats after which the connection is unhealthy. Defaults to 3.
+    pub fn max_missed(mut self, max_missed: u32) -> Self {
+        self.max_missed = max_missed.max(1);
+        self
+    }
+
+    /// Sets whether the blocking client reconnects when the connection becomes unhealthy. Defaults to true.
+    pub fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+}
+
+/// Whether the connection answers heartbeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HealthStatus {
+    /// Heartbeats are answered. A new connection is healthy until heartbeats are missed.
+    #[default]
+    Healthy,
+    /// The configured number of consecutive heartbeats went unanswered.
+    Unhealthy,
+}
+
+/// Snapshot of the connection health.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Health {
+    /// Current status.
+    pub status: HealthStatus,
+    /// Time the client connected, as reported by the server.
+    pub connection_time: Option<OffsetDateTime>,
+    /// Local time of the last answered heartbeat.
+    pub last_heartbeat: Option<OffsetDateTime>,
+    /// Round-trip time of the last answered heartbeat.
+    pub latency: Option<Duration>,
+    /// Server clock minus local clock, measured at the last answered heartbeat.
+    pub clock_skew: Option<time::Duration>,
+    /// Heartbeats missed since the last answered one.
+    pub missed_heartbeats: u32,
+}
+
+/// Change in the connection health.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthEvent {
+    /// The connection stopped answering heartbeats.
+    Unhealthy(Health),
+    /// An unhealthy connection answered a heartbeat again.
+    Recovered(Health),
+}
+
+/// Outcome of recording a heartbeat.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Transition {
+    pub(crate) event: Option<HealthEvent>,
+    pub(crate) reconnect: bool,
+}
+
+/// Tracks the health of a connection from heartbeat results.
+#[derive(Debug)]
+pub(crate) struct HealthTracker {
+    config: HealthConfig,
+    health: Health,
+}
+
+impl HealthTracker {
+    pub(crate) fn new(config: HealthConfig, connection_time: Option<OffsetDateTime>) -> Self {
+        Self {
+            config,
+            health: Health {
+                connection_time,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub(crate) fn health(&self) -> &Health {
+        &self.health
+    }
+
+    /// Records an answered heartbeat sent at `sent`, whose response arrived `latency` later.
+    pub(crate) fn answered(&mut self, sent: OffsetDateTime, latency: Duration, server_time: OffsetDateTime) -> Transition {
+        let received = sent + latency;
+        let midpoint = sent + latency / 2;
+
+        self.health.last_heartbeat = Some(received);
+        self.health.latency = Some(latency);
+        self.health.clock_skew = Some(server_time - midpoint);
+        self.health.missed_heartbeats = 0;
+
+        if self.health.status == HealthStatus::Unhealthy {
+            self.health.status = HealthStatus::Healthy;
+            return Transition {
+                event: Some(HealthEvent::Recovered(self.health.clone())),
+                reconnect: false,
+            };
+        }
+
+        Transition::default()
+    }
+
+    /// Records a heartbeat that failed or timed out.
+    pub(crate) fn missed(&mut self) -> Transition {
+        self.health.missed_heartbeats += 1;
+
+        // Reconnection is retried after every run of missed heartbeats.
+        let reconnect = self.config.reconnect && self.health.missed_heartbeats.is_multiple_of(self.config.max_missed);
+
+        if self.health.status == HealthStatus::Healthy && self.health.missed_heartbeats >= self.config.max_missed {
+            self.health.status = HealthStatus::Unhealthy;
+            return Transition {
+                event: Some(HealthEvent::Unhealthy(self.health.clone())),
+                reconnect,
+            };
+        }
+
+        Transition { event: None, reconnect }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_answered_heartbeat_measures_latency_and_skew() {
+        let mut tracker = HealthTracker::new(HealthConfig::default(), None);
+
+        let sent = datetime!(2025-04-15 19:38:30.000 UTC);
+        let transition = tracker.answered(sent, Duration::from_millis(200), datetime!(2025-04-15 19:38:32 UTC));
+
+        assert_eq!(transition, Transition::default());
+
+        let health = tracker.health();
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert_eq!(health.latency, Some(Duration::from_millis(200)));
+        assert_eq!(health.clock_skew, Some(time::Duration::milliseconds(1900)));
+        assert_eq!(health.last_heartbeat, Some(datetime!(2025-04-15 19:38:30.200 UTC)));
+    }
+
+    #[test]
+    fn test_missed_heartbeats_mark_connection_unhealthy() {
+        let mut tracker = HealthTracker::new(HealthConfig::default().max_missed(2).reconnect(false), None);
+
+        assert_eq!(tracker.missed(), Transition::default());
+
+        let transition = tracker.missed();
+        let Some(HealthEvent::Unhealthy(health)) = transition.event else {
+            panic!("expected unhealthy event, got {transition:?}");
+        };
+        assert_eq!(health.missed_heartbeats, 2);
+        assert!(!transition.reconnect);
+
+        // Already unhealthy, so no further events.
+        assert_eq!(tracker.missed(), Transition::default());
+        assert_eq!(tracker.health().missed_heartbeats, 3);
+    }
+
+    #[test]
+    fn test_answered_heartbeat_recovers() {
+        let mut tracker = HealthTracker::new(HealthConfig::default().max_missed(1), None);
+        tracker.missed();
+
+        let sent = datetime!(2025-04-15 19:38:30 UTC);
+        let transition = tracker.answered(sent, Duration::from_millis(10), sent);
+
+        let Some(HealthEvent::Recovered(health)) = transition.event else {
+            panic!("expected recovered event, got {transition:?}");
+        };
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert_eq!(health.missed_heartbeats, 0);
+    }
+
+    #[test]
+    fn test_reconnect_after_each_run_of_missed_heartbeats() {
+        let mut tracker = HealthTracker::new(HealthConfig::default().max_missed(2), None);
+
+        let reconnects: Vec<bool> = (0..6).map(|_| tracker.missed().reconnect).collect();
+
+        assert_eq!(reconnects, vec![false, true, false, true, false, true]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Health monitor for the blocking client.
+
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{self, Receiver, RecvTimeoutError, Sender};
+use log::{debug, warn};
+use time::OffsetDateTime;
+
+use super::{Health, HealthConfig, HealthEvent, HealthTracker};
+use crate::accounts::sync::server_time_timeout;
+use crate::client::sync::Client;
+use crate::trace::spans::enter_worker;
+
+/// Sends heartbeats on a background thread and tracks the health of the connection.
+///
+/// The thread stops when the monitor is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use ibapi::blocking::health::HealthMonitor;
+/// use ibapi::blocking::Client;
+/// use ibapi::health::{HealthConfig, HealthEvent};
+///
+/// let client = Arc::new(Client::connect("127.0.0.1:4002", 100).expect("connection failed"));
+///
+/// let config = HealthConfig::default().interval(Duration::from_secs(5)).max_missed(2);
+/// let monitor = HealthMonitor::start(&client, config);
+///
+/// while let Some(event) = monitor.next_event() {
+///     match event {
+///         HealthEvent::Unhealthy(health) => println!("connection lost: {health:?}"),
+///         HealthEvent::Recovered(health) => println!("connection recovered, latency {:?}", health.latency),
+///     }
+/// }
+/// ```
+pub struct HealthMonitor {
+    tracker: Arc<Mutex<HealthTracker>>,
+    events: Receiver<HealthEvent>,
+    _stop: Sender<()>,
+    _handle: JoinHandle<()>,
+}
+
+impl HealthMonitor {
+    /// Starts sending heartbeats on the client's connection.
+    pub fn start(client: &Arc<Client>, config: HealthConfig) -> HealthMonitor {
+        let tracker = Arc::new(Mutex::new(HealthTracker::new(config, client.connection_time())));
+        let (events_send, events) = channel::unbounded();
+        let (stop, stopped) = channel::bounded::<()>(0);
+
+        let handle = {
+            let client = Arc::clone(client);
+            let tracker = Arc::clone(&tracker);
+            thread::spawn(move || {
+                let _span = enter_worker("health");
+                run(client, config, tracker, events_send, stopped)
+            })
+        };
+
+        HealthMonitor {
+            tracker,
+            events,
+            _stop: stop,
+            _handle: handle,
+        }
+    }
+
+    /// Returns a snapshot of the connection health.
+    pub fn health(&self) -> Health {
+        self.tracker.lock().map(|tracker| tracker.health().clone()).unwrap_or_default()
+    }
+
+    /// Blocks until the health changes. Returns `None` once the monitor has stopped.
+    pub fn next_event(&self) -> Option<HealthEvent> {
+        self.events.recv().ok()
+    }
+
+    /// Returns the next change in health if one is available.
+    pub fn try_next_event(&self) -> Option<HealthEvent> {
+        self.events.try_recv().ok()
+    }
+
+    /// Waits up to `timeout` for the health to change.
+    pub fn next_event_timeout(&self, timeout: Duration) -> Option<HealthEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+}
+
+fn run(client: Arc<Client>, config: HealthConfig, tracker: Arc<Mutex<HealthTracker>>, events: Sender<HealthEvent>, stopped: Receiver<()>) {
+    loop {
+        match stopped.recv_timeout(config.interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => {
+                debug!("health monitor stopped");
+                return;
+            }
+        }
+
+        let sent = OffsetDateTime::now_utc();
+        let started = Instant::now();
+        let response = heartbeat(&client, config.timeout);
+
+        let transition = {
+            let Ok(mut tracker) = tracker.lock() else {
+                return;
+            };
+            match response {
+                Some(server_time) => tracker.answered(sent, started.elapsed(), server_time),
+                None => tracker.missed(),
+            }
+        };
+
+        if transition.reconnect {
+            warn!("connection unhealthy, forcing reconnection");
+            client.message_bus.force_reconnect();
+        }
+        if let Some(event) = transition.event {
+            let _ = events.send(event);
+        }
+    }
+}
+
+// Requests the server time, giving up after the timeout.
+fn heartbeat(client: &Client, timeout: Duration) -> Option<OffsetDateTime> {
+    match server_time_timeout(client, timeout) {
+        Ok(server_time) => Some(server_time),
+        Err(err) => {
+            warn!("heartbeat failed: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::health::HealthStatus;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[test]
+    fn test_heartbeat_measures_latency() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec!["49|1|1678323335|".to_owned()],
+        });
+        let client = Arc::new(Client::stubbed(message_bus, server_versions::SIZE_RULES));
+
+        let monitor = HealthMonitor::start(&client, HealthConfig::default().interval(Duration::from_millis(10)));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while monitor.health().latency.is_none() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let health = monitor.health();
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert!(health.latency.is_some());
+        assert!(health.clock_skew.is_some());
+        assert_eq!(health.missed_heartbeats, 0);
+    }
+
+    #[test]
+    fn test_heartbeat_times_out() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+        let client = Arc::new(Client::stubbed(message_bus, server_versions::SIZE_RULES));
+
+        let config = HealthConfig::default()
+            .interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(10))
+            .max_missed(2)
+            .reconnect(false);
+        let monitor = HealthMonitor::start(&client, config);
+
+        match monitor.next_event_timeout(Duration::from_secs(5)) {
+            Some(HealthEvent::Unhealthy(health)) => assert!(health.missed_heartbeats >= 2),
+            event => panic!("unexpected event: {event:?}"),
+        }
+    }
+}
@@ -36,16 +36,8 @@ compile_error!(
      In Cargo.toml:\n\
          ibapi = { version = \"2.0\", features = [\"sync\"] }  # or [\"async\"]\n\
      \n\
-     Note: The 'sync' and 'async' features are mutually exclusive."
-);
-
-#[cfg(all(feature = "sync", feature = "async"))]
-compile_error!(
-    "The 'sync' and 'async' features are mutually exclusive.\n\
-     Please enable only one of them:\n\
-     \n\
-     For synchronous API: --features sync\n\
-     For asynchronous API: --features async"
+     Both features may be enabled together. The async API is then available as\n\
+     ibapi::Client and the blocking API as ibapi::blocking::Client."
 );
 
 /// Describes items present in an account.
@@ -57,6 +49,10 @@ pub mod accounts;
 /// It manages the routing of messages between TWS and the application.
 pub mod client;
 
+/// Blocking (thread-based) API, usable alongside the async API.
+#[cfg(feature = "sync")]
+pub mod blocking;
+
 pub(crate) mod transport;
 
 /// Connection management
@@ -89,12 +85,25 @@ pub mod wsh;
 /// Server interaction tracing for debugging and monitoring
 pub mod trace;
 
+/// Pluggable metrics for the transport and subscriptions.
+pub mod metrics;
+
+/// Connection health monitoring with heartbeats.
+pub mod health;
+
+/// Pools of connections with distinct client ids.
+pub mod pool;
+
 /// A prelude module for convenient importing of commonly used types.
 pub mod prelude;
 
 /// Protocol version checking and constants for TWS API features.
 pub mod protocol;
 
+/// Mock TWS/Gateway for testing applications without a live connection.
+#[cfg(feature = "testing")]
+pub mod testing;
+
 mod server_versions;
 
 #[doc(inline)]
@@ -102,11 +111,7 @@ pub use errors::Error;
 
 #[doc(inline)]
 pub use client::Client;
-use std::sync::LazyLock;
-use time::{
-    format_description::{self, BorrowedFormatItem},
-    Date,
-};
+use time::{format_description::BorrowedFormatItem, macros::format_description, Date};
 
 #[cfg(test)]
 pub(crate) mod stubs;
@@ -121,6 +126,11 @@ pub(crate) mod testdata;
 
 pub(crate) trait ToField {
     fn to_field(&self) -> String;
+
+    /// Appends the field to an encoded message.
+    fn write_field(&self, out: &mut String) {
+        out.push_str(&self.to_field());
+    }
 }
 
 impl ToField for bool {
@@ -131,71 +141,107 @@ impl ToField for bool {
             String::from("0")
         }
     }
+
+    fn write_field(&self, out: &mut String) {
+        out.push(if *self { '1' } else { '0' });
+    }
 }
 
 impl ToField for String {
     fn to_field(&self) -> String {
         self.clone()
     }
+
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self);
+    }
 }
 
 impl ToField for Option<String> {
     fn to_field(&self) -> String {
         encode_option_field(self)
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_option_field(self, out);
+    }
 }
 
 impl ToField for &str {
     fn to_field(&self) -> String {
         <&str>::clone(self).to_string()
     }
+
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self);
+    }
 }
 
 impl ToField for Option<&str> {
     fn to_field(&self) -> String {
         encode_option_field(self)
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_option_field(self, out);
+    }
 }
 
 impl ToField for usize {
     fn to_field(&self) -> String {
         self.to_string()
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_display_field(self, out);
+    }
 }
 
 impl ToField for i32 {
     fn to_field(&self) -> String {
         self.to_string()
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_display_field(self, out);
+    }
 }
 
 impl ToField for Option<i32> {
     fn to_field(&self) -> String {
         encode_option_field(self)
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_option_field(self, out);
+    }
 }
 
 impl ToField for f64 {
     fn to_field(&self) -> String {
         self.to_string()
     }
+
+    fn write_field(&self, out: &mut String) {
+        write_display_field(self, out);
+    }
 }
 
 impl ToField for Option<f64> {
     fn to_field(&self) -> String {
         encode_option_field(self)
     }
-}
 
-fn date_format() -> Vec<BorrowedFormatItem<'static>> {
-    format_description::parse("[year][month][day]").unwrap()
+    fn write_field(&self, out: &mut String) {
+        write_option_field(self, out);
+    }
 }
 
-static DATE_FORMAT: LazyLock<Vec<BorrowedFormatItem<'static>>> = LazyLock::new(date_format);
+const DATE_FORMAT: &[BorrowedFormatItem<'static>] = format_description!("[year][month][day]");
 
 impl ToField for Date {
     fn to_field(&self) -> String {
-        self.format(&DATE_FORMAT).unwrap()
+        self.format(DATE_FORMAT).unwrap()
     }
 }
 
@@ -212,5 +258,18 @@ fn encode_option_field<T: ToField>(val: &Option<T>) -> String {
     }
 }
 
+fn write_option_field<T: ToField>(val: &Option<T>, out: &mut String) {
+    if let Some(val) = val {
+        val.write_field(out);
+    }
+}
+
+fn write_display_field(val: &impl std::fmt::Display, out: &mut String) {
+    use std::fmt::Write;
+
+    // Writing to a String cannot fail.
+    let _ = write!(out, "{val}");
+}
+
 // max attempts to retry failed tws requests
 const MAX_RETRIES: i32 = 5;
@@ -4,16 +4,12 @@
 //! and historical market data. It includes support for various data types,
 //! subscription management, and market data type configuration.
 
-use crate::{server_versions, Error};
-
-#[cfg(feature = "sync")]
-use crate::{messages::OutgoingMessages, Client};
-
 pub mod historical;
+pub mod lines;
 pub mod realtime;
 
 /// By default, only real-time market data sending is enabled.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MarketDataType {
     /// Disables frozen, delayed and delayed-frozen market data sending.
     Live = 1,
@@ -26,23 +22,36 @@ pub enum MarketDataType {
 }
 
 #[cfg(feature = "sync")]
-pub(crate) fn switch_market_data_type(client: &Client, market_data_type: MarketDataType) -> Result<(), Error> {
-    client.check_server_version(server_versions::REQ_MARKET_DATA_TYPE, "It does not support market data type requests.")?;
+pub(crate) mod sync {
+    use super::{encoders, MarketDataType};
+    use crate::client::sync::Client;
+    use crate::messages::OutgoingMessages;
+    use crate::{server_versions, Error};
+
+    pub(crate) fn switch_market_data_type(client: &Client, market_data_type: MarketDataType) -> Result<(), Error> {
+        client.check_server_version(server_versions::REQ_MARKET_DATA_TYPE, "It does not support market data type requests.")?;
 
-    let message = encoders::encode_request_market_data_type(market_data_type)?;
-    let _ = client.send_shared_request(OutgoingMessages::RequestMarketDataType, message)?;
+        let message = encoders::encode_request_market_data_type(market_data_type)?;
+        let _ = client.send_shared_request(OutgoingMessages::RequestMarketDataType, message)?;
 
-    Ok(())
+        Ok(())
+    }
 }
 
 #[cfg(feature = "async")]
-pub(crate) async fn switch_market_data_type(client: &crate::client::r#async::Client, market_data_type: MarketDataType) -> Result<(), Error> {
-    client.check_server_version(server_versions::REQ_MARKET_DATA_TYPE, "It does not support market data type requests.")?;
+pub(crate) mod r#async {
+    use super::{encoders, MarketDataType};
+    use crate::client::r#async::Client;
+    use crate::{server_versions, Error};
+
+    pub(crate) async fn switch_market_data_type(client: &Client, market_data_type: MarketDataType) -> Result<(), Error> {
+        client.check_server_version(server_versions::REQ_MARKET_DATA_TYPE, "It does not support market data type requests.")?;
 
-    let message = encoders::encode_request_market_data_type(market_data_type)?;
-    client.send_message(message).await?;
+        let message = encoders::encode_request_market_data_type(market_data_type)?;
+        client.send_message(message).await?;
 
-    Ok(())
+        Ok(())
+    }
 }
 
 mod encoders {
@@ -84,18 +93,19 @@ mod encoders {
                 let message = result.unwrap();
 
                 assert_eq!(message[0], OutgoingMessages::RequestMarketDataType.to_field());
-                assert_eq!(message[1], "1"); // VERSION
+                assert_eq!(&message[1], "1"); // VERSION
                 assert_eq!(message[2], (market_data_type as i32).to_string());
             }
         }
     }
 }
 
-#[cfg(all(test, feature = "sync", not(feature = "async")))]
+#[cfg(all(test, feature = "sync"))]
 mod tests {
     use std::sync::{Arc, RwLock};
 
-    use crate::{market_data::MarketDataType, server_versions, stubs::MessageBusStub, Client};
+    use crate::client::sync::Client;
+    use crate::{market_data::MarketDataType, server_versions, stubs::MessageBusStub};
 
     #[test]
     fn test_switch_market_data_type() {
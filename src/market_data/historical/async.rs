@@ -346,27 +346,27 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestHeadTimestamp.to_field(), "message.type");
-        assert_eq!(request.fields[1], "9000", "message.request_id");
-        assert_eq!(request.fields[2], contract.contract_id.to_field(), "message.contract_id");
-        assert_eq!(request.fields[3], contract.symbol, "message.symbol");
-        assert_eq!(request.fields[4], contract.security_type.to_field(), "message.security_type");
+        assert_eq!(&request[0], OutgoingMessages::RequestHeadTimestamp.to_field(), "message.type");
+        assert_eq!(&request[1], "9000", "message.request_id");
+        assert_eq!(&request[2], contract.contract_id.to_field(), "message.contract_id");
+        assert_eq!(&request[3], contract.symbol, "message.symbol");
+        assert_eq!(&request[4], contract.security_type.to_field(), "message.security_type");
         assert_eq!(
-            request.fields[5], contract.last_trade_date_or_contract_month,
+            &request[5], contract.last_trade_date_or_contract_month,
             "message.last_trade_date_or_contract_month"
         );
-        assert_eq!(request.fields[6], contract.strike.to_field(), "message.strike");
-        assert_eq!(request.fields[7], contract.right, "message.right");
-        assert_eq!(request.fields[8], contract.multiplier, "message.multiplier");
-        assert_eq!(request.fields[9], contract.exchange, "message.exchange");
-        assert_eq!(request.fields[10], contract.primary_exchange, "message.primary_exchange");
-        assert_eq!(request.fields[11], contract.currency, "message.currency");
-        assert_eq!(request.fields[12], contract.local_symbol, "message.local_symbol");
-        assert_eq!(request.fields[13], contract.trading_class, "message.trading_class");
-        assert_eq!(request.fields[14], contract.include_expired.to_field(), "message.include_expired");
-        assert_eq!(request.fields[15], use_rth.to_field(), "message.use_rth");
-        assert_eq!(request.fields[16], what_to_show.to_field(), "message.what_to_show");
-        assert_eq!(request.fields[17], "2", "message.date_format");
+        assert_eq!(&request[6], contract.strike.to_field(), "message.strike");
+        assert_eq!(&request[7], contract.right, "message.right");
+        assert_eq!(&request[8], contract.multiplier, "message.multiplier");
+        assert_eq!(&request[9], contract.exchange, "message.exchange");
+        assert_eq!(&request[10], contract.primary_exchange, "message.primary_exchange");
+        assert_eq!(&request[11], contract.currency, "message.currency");
+        assert_eq!(&request[12], contract.local_symbol, "message.local_symbol");
+        assert_eq!(&request[13], contract.trading_class, "message.trading_class");
+        assert_eq!(&request[14], contract.include_expired.to_field(), "message.include_expired");
+        assert_eq!(&request[15], use_rth.to_field(), "message.use_rth");
+        assert_eq!(&request[16], what_to_show.to_field(), "message.what_to_show");
+        assert_eq!(&request[17], "2", "message.date_format");
     }
 
     #[tokio::test]
@@ -411,30 +411,26 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
+        assert_eq!(&request[0], OutgoingMessages::RequestHistogramData.to_field(), "message.message_type");
+        assert_eq!(&request[1], "9000", "message.request_id");
+        assert_eq!(&request[2], contract.contract_id.to_field(), "message.contract_id");
+        assert_eq!(&request[3], contract.symbol, "message.symbol");
+        assert_eq!(&request[4], contract.security_type.to_field(), "message.security_type");
         assert_eq!(
-            request.fields[0],
-            OutgoingMessages::RequestHistogramData.to_field(),
-            "message.message_type"
-        );
-        assert_eq!(request.fields[1], "9000", "message.request_id");
-        assert_eq!(request.fields[2], contract.contract_id.to_field(), "message.contract_id");
-        assert_eq!(request.fields[3], contract.symbol, "message.symbol");
-        assert_eq!(request.fields[4], contract.security_type.to_field(), "message.security_type");
-        assert_eq!(
-            request.fields[5], contract.last_trade_date_or_contract_month,
+            &request[5], contract.last_trade_date_or_contract_month,
             "message.last_trade_date_or_contract_month"
         );
-        assert_eq!(request.fields[6], contract.strike.to_field(), "message.strike");
-        assert_eq!(request.fields[7], contract.right, "message.right");
-        assert_eq!(request.fields[8], contract.multiplier, "message.multiplier");
-        assert_eq!(request.fields[9], contract.exchange, "message.exchange");
-        assert_eq!(request.fields[10], contract.primary_exchange, "message.primary_exchange");
-        assert_eq!(request.fields[11], contract.currency, "message.currency");
-        assert_eq!(request.fields[12], contract.local_symbol, "message.local_symbol");
-        assert_eq!(request.fields[13], contract.trading_class, "message.trading_class");
-        assert_eq!(request.fields[14], contract.include_expired.to_field(), "message.include_expired");
-        assert_eq!(request.fields[15], use_rth.to_field(), "message.use_rth");
-        assert_eq!(request.fields[16], period.to_field(), "message.duration");
+        assert_eq!(&request[6], contract.strike.to_field(), "message.strike");
+        assert_eq!(&request[7], contract.right, "message.right");
+        assert_eq!(&request[8], contract.multiplier, "message.multiplier");
+        assert_eq!(&request[9], contract.exchange, "message.exchange");
+        assert_eq!(&request[10], contract.primary_exchange, "message.primary_exchange");
+        assert_eq!(&request[11], contract.currency, "message.currency");
+        assert_eq!(&request[12], contract.local_symbol, "message.local_symbol");
+        assert_eq!(&request[13], contract.trading_class, "message.trading_class");
+        assert_eq!(&request[14], contract.include_expired.to_field(), "message.include_expired");
+        assert_eq!(&request[15], use_rth.to_field(), "message.use_rth");
+        assert_eq!(&request[16], period.to_field(), "message.duration");
     }
 
     #[tokio::test]
@@ -500,11 +496,7 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(
-            request.fields[0],
-            OutgoingMessages::RequestHistoricalData.to_field(),
-            "Wrong message type"
-        );
+        assert_eq!(&request[0], OutgoingMessages::RequestHistoricalData.to_field(), "Wrong message type");
     }
 
     #[tokio::test]
@@ -633,9 +625,9 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestHistoricalData.to_field(), "message.type");
-        assert_eq!(request.fields[1], "9000", "message.request_id"); // request_id will be generated
-                                                                     // The rest of the fields follow the same pattern as historical data request
+        assert_eq!(&request[0], OutgoingMessages::RequestHistoricalData.to_field(), "message.type");
+        assert_eq!(&request[1], "9000", "message.request_id"); // request_id will be generated
+                                                               // The rest of the fields follow the same pattern as historical data request
     }
 
     #[tokio::test]
@@ -776,31 +768,31 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestHistoricalTicks.to_field(), "message.type");
-        assert_eq!(request.fields[1], "9000", "message.request_id");
-        assert_eq!(request.fields[2], contract.contract_id.to_field(), "message.contract_id");
-        assert_eq!(request.fields[3], contract.symbol, "message.symbol");
-        assert_eq!(request.fields[4], contract.security_type.to_field(), "message.security_type");
+        assert_eq!(&request[0], OutgoingMessages::RequestHistoricalTicks.to_field(), "message.type");
+        assert_eq!(&request[1], "9000", "message.request_id");
+        assert_eq!(&request[2], contract.contract_id.to_field(), "message.contract_id");
+        assert_eq!(&request[3], contract.symbol, "message.symbol");
+        assert_eq!(&request[4], contract.security_type.to_field(), "message.security_type");
         assert_eq!(
-            request.fields[5], contract.last_trade_date_or_contract_month,
+            &request[5], contract.last_trade_date_or_contract_month,
             "message.last_trade_date_or_contract_month"
         );
-        assert_eq!(request.fields[6], contract.strike.to_field(), "message.strike");
-        assert_eq!(request.fields[7], contract.right, "message.right");
-        assert_eq!(request.fields[8], contract.multiplier, "message.multiplier");
-        assert_eq!(request.fields[9], contract.exchange, "message.exchange");
-        assert_eq!(request.fields[10], contract.primary_exchange, "message.primary_exchange");
-        assert_eq!(request.fields[11], contract.currency, "message.currency");
-        assert_eq!(request.fields[12], contract.local_symbol, "message.local_symbol");
-        assert_eq!(request.fields[13], contract.trading_class, "message.trading_class");
-        assert_eq!(request.fields[14], contract.include_expired.to_field(), "message.include_expired");
-        assert_eq!(request.fields[15], start.to_field(), "message.start");
-        assert_eq!(request.fields[16], end.to_field(), "message.end");
-        assert_eq!(request.fields[17], number_of_ticks.to_field(), "message.number_of_ticks");
-        assert_eq!(request.fields[18], "BID_ASK", "message.what_to_show");
-        assert_eq!(request.fields[19], use_rth.to_field(), "message.use_rth");
-        assert_eq!(request.fields[20], "0", "message.ignore_size"); // false = 0
-        assert_eq!(request.fields[21], "", "message.misc_options");
+        assert_eq!(&request[6], contract.strike.to_field(), "message.strike");
+        assert_eq!(&request[7], contract.right, "message.right");
+        assert_eq!(&request[8], contract.multiplier, "message.multiplier");
+        assert_eq!(&request[9], contract.exchange, "message.exchange");
+        assert_eq!(&request[10], contract.primary_exchange, "message.primary_exchange");
+        assert_eq!(&request[11], contract.currency, "message.currency");
+        assert_eq!(&request[12], contract.local_symbol, "message.local_symbol");
+        assert_eq!(&request[13], contract.trading_class, "message.trading_class");
+        assert_eq!(&request[14], contract.include_expired.to_field(), "message.include_expired");
+        assert_eq!(&request[15], start.to_field(), "message.start");
+        assert_eq!(&request[16], end.to_field(), "message.end");
+        assert_eq!(&request[17], number_of_ticks.to_field(), "message.number_of_ticks");
+        assert_eq!(&request[18], "BID_ASK", "message.what_to_show");
+        assert_eq!(&request[19], use_rth.to_field(), "message.use_rth");
+        assert_eq!(&request[20], "0", "message.ignore_size"); // false = 0
+        assert_eq!(&request[21], "", "message.misc_options");
     }
 
     #[tokio::test]
@@ -836,7 +828,7 @@ mod tests {
         // Verify request message
         let request_messages = message_bus.request_messages.read().unwrap();
         let request = &request_messages[0];
-        assert_eq!(request.fields[18], "MIDPOINT", "message.what_to_show");
+        assert_eq!(&request[18], "MIDPOINT", "message.what_to_show");
     }
 
     #[tokio::test]
@@ -876,7 +868,7 @@ mod tests {
         // Verify request message
         let request_messages = message_bus.request_messages.read().unwrap();
         let request = &request_messages[0];
-        assert_eq!(request.fields[18], "TRADES", "message.what_to_show");
+        assert_eq!(&request[18], "TRADES", "message.what_to_show");
     }
 
     #[tokio::test]
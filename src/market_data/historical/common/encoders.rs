@@ -274,7 +274,7 @@ mod tests {
         }
 
         assert_eq!(message[i], keep_up_to_date.to_field(), "message.keep_up_to_date");
-        assert_eq!(message[i + 1], "", "message.chart_options");
+        assert_eq!(&message[i + 1], "", "message.chart_options");
     }
 
     #[test]
@@ -328,7 +328,7 @@ mod tests {
         assert_eq!(message[18], what_to_show.to_field(), "message.what_to_show");
         assert_eq!(message[19], use_rth.to_field(), "message.use_rth");
         assert_eq!(message[20], ignore_size.to_field(), "message.ignore_size");
-        assert_eq!(message[21], "", "message.misc_options");
+        assert_eq!(&message[21], "", "message.misc_options");
     }
 
     #[test]
@@ -9,6 +9,26 @@ use crate::messages::{IncomingMessages, ResponseMessage};
 use crate::{Error, ToField};
 
 pub(crate) mod common;
+pub mod pacing;
+pub mod range;
+pub mod store;
+
+pub use pacing::PacingConfig;
+pub use range::RangeProgress;
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use pacing::sync::HistoricalScheduler;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use range::sync::HistoricalRange;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use store::sync::HistoricalStore;
+
+#[cfg(feature = "async")]
+pub use pacing::r#async::HistoricalScheduler;
+#[cfg(feature = "async")]
+pub use range::r#async::HistoricalRange;
+#[cfg(feature = "async")]
+pub use store::r#async::HistoricalStore;
 
 #[cfg(feature = "sync")]
 pub mod sync;
@@ -402,9 +422,6 @@ impl ToField for Option<WhatToShow> {
 }
 
 // Re-export functions based on active feature
-#[cfg(feature = "sync")]
-pub use sync::*;
-
 #[cfg(feature = "async")]
 pub use r#async::*;
 
@@ -438,7 +455,7 @@ impl TickDecoder<TickMidpoint> for TickMidpoint {
 }
 
 // Re-export TickSubscription and iterator types based on active feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::{TickSubscription, TickSubscriptionIter, TickSubscriptionOwnedIter, TickSubscriptionTimeoutIter, TickSubscriptionTryIter};
 
 #[cfg(feature = "async")]
@@ -0,0 +1,277 @@
+//! Historical data pacing for the async client.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use log::{debug, warn};
+use time::OffsetDateTime;
+use tokio::sync::OnceCell;
+
+use super::{is_pacing_violation, Pacer, PacingConfig, RequestKey};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::historical::r#async::TickSubscription;
+use crate::market_data::historical::{self, BarSize, Duration, HistoricalData, TickBidAsk, TickLast, TickMidpoint, WhatToShow};
+use crate::Error;
+
+type Shared<T> = Arc<OnceCell<Result<T, Error>>>;
+
+/// Sends historical data requests within the pacing limits of TWS.
+///
+/// Requests wait until they fit within the limits of the [PacingConfig]. Identical bar and head timestamp requests made
+/// while one is in flight share its response, and requests rejected for a pacing violation are retried with backoff.
+/// Share one scheduler, for example in an `Arc`, between all tasks requesting historical data from a client.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::historical::{BarSize, HistoricalScheduler, PacingConfig, ToDuration, WhatToShow};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///     let scheduler = HistoricalScheduler::new(&client, PacingConfig::default());
+///
+///     for symbol in ["AAPL", "MSFT", "NVDA"] {
+///         let contract = Contract::stock(symbol);
+///         let bars = scheduler
+///             .historical_data(&contract, None, 1.days(), BarSize::Min, Some(WhatToShow::Trades), true)
+///             .await
+///             .expect("historical data request failed");
+///         println!("{symbol}: {} bars", bars.bars.len());
+///     }
+/// }
+/// ```
+pub struct HistoricalScheduler {
+    client: Client,
+    config: PacingConfig,
+    pacer: Mutex<Pacer>,
+    bars: Mutex<HashMap<String, Shared<HistoricalData>>>,
+    head_timestamps: Mutex<HashMap<String, Shared<OffsetDateTime>>>,
+}
+
+impl HistoricalScheduler {
+    /// Creates a scheduler for requests sent by the client.
+    pub fn new(client: &Client, config: PacingConfig) -> Self {
+        Self {
+            client: client.clone(),
+            config,
+            pacer: Mutex::new(Pacer::new(config)),
+            bars: Mutex::new(HashMap::new()),
+            head_timestamps: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Requests historical bars within the pacing limits. See [Client::historical_data].
+    pub async fn historical_data(
+        &self,
+        contract: &Contract,
+        end_date: Option<OffsetDateTime>,
+        duration: Duration,
+        bar_size: BarSize,
+        what_to_show: Option<WhatToShow>,
+        use_rth: bool,
+    ) -> Result<HistoricalData, Error> {
+        let key = RequestKey::new(
+            "bars",
+            contract,
+            what_to_show.unwrap_or(WhatToShow::Trades),
+            (end_date, duration, bar_size, use_rth),
+        );
+        self.shared(&self.bars, &key, || {
+            historical::r#async::historical_data(&self.client, contract, end_date, duration, bar_size, what_to_show, use_rth)
+        })
+        .await
+    }
+
+    /// Requests the timestamp of the earliest available data within the pacing limits. See [Client::head_timestamp].
+    pub async fn head_timestamp(&self, contract: &Contract, what_to_show: WhatToShow, use_rth: bool) -> Result<OffsetDateTime, Error> {
+        let key = RequestKey::new("head_timestamp", contract, what_to_show, use_rth);
+        self.shared(&self.head_timestamps, &key, || {
+            historical::r#async::head_timestamp(&self.client, contract, what_to_show, use_rth)
+        })
+        .await
+    }
+
+    /// Requests historical bid and ask ticks within the pacing limits. See [Client::historical_ticks_bid_ask].
+    pub async fn historical_ticks_bid_ask(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+        ignore_size: bool,
+    ) -> Result<TickSubscription<TickBidAsk>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::BidAsk,
+            (start, end, number_of_ticks, use_rth, ignore_size),
+        ))
+        .await;
+        historical::r#async::historical_ticks_bid_ask(&self.client, contract, start, end, number_of_ticks, use_rth, ignore_size).await
+    }
+
+    /// Requests historical midpoint ticks within the pacing limits. See [Client::historical_ticks_mid_point].
+    pub async fn historical_ticks_mid_point(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+    ) -> Result<TickSubscription<TickMidpoint>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::MidPoint,
+            (start, end, number_of_ticks, use_rth),
+        ))
+        .await;
+        historical::r#async::historical_ticks_mid_point(&self.client, contract, start, end, number_of_ticks, use_rth).await
+    }
+
+    /// Requests historical trade ticks within the pacing limits. See [Client::historical_ticks_trade].
+    pub async fn historical_ticks_trade(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+    ) -> Result<TickSubscription<TickLast>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::Trades,
+            (start, end, number_of_ticks, use_rth),
+        ))
+        .await;
+        historical::r#async::historical_ticks_trade(&self.client, contract, start, end, number_of_ticks, use_rth).await
+    }
+
+    // Sends the request once for all callers waiting on an identical request.
+    async fn shared<T: Clone, F: Future<Output = Result<T, Error>>>(
+        &self,
+        in_flight: &Mutex<HashMap<String, Shared<T>>>,
+        key: &RequestKey,
+        request: impl Fn() -> F,
+    ) -> Result<T, Error> {
+        let cell = in_flight.lock()?.entry(key.request().to_string()).or_default().clone();
+
+        let result = cell.get_or_init(|| self.send_with_retry(key, request)).await.clone();
+
+        let mut in_flight = in_flight.lock()?;
+        if in_flight.get(key.request()).is_some_and(|current| Arc::ptr_eq(current, &cell)) {
+            in_flight.remove(key.request());
+        }
+
+        result
+    }
+
+    async fn send_with_retry<T, F: Future<Output = Result<T, Error>>>(&self, key: &RequestKey, request: impl Fn() -> F) -> Result<T, Error> {
+        let mut retry = 0;
+        loop {
+            self.acquire(key).await;
+
+            match request().await {
+                Err(ref err) if is_pacing_violation(err) && retry < self.config.max_retries => {
+                    let delay = self.config.backoff(retry);
+                    warn!("historical data pacing violation, retrying in {delay:?}");
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Waits until the request fits within the pacing limits.
+    async fn acquire(&self, key: &RequestKey) {
+        loop {
+            let wait = match self.pacer.lock() {
+                Ok(mut pacer) => pacer.try_acquire(key, Instant::now()),
+                Err(_) => None,
+            };
+
+            match wait {
+                Some(delay) => {
+                    debug!("pacing historical data request for {delay:?}");
+                    tokio::time::sleep(delay).await;
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::RwLock;
+    use std::time::Duration as StdDuration;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn scheduler(config: PacingConfig) -> HistoricalScheduler {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+        HistoricalScheduler::new(&Client::stubbed(message_bus, server_versions::SIZE_RULES), config)
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_pacing_violation() {
+        let config = PacingConfig::default()
+            .identical_interval(StdDuration::ZERO)
+            .retry_delay(StdDuration::from_millis(1));
+        let scheduler = scheduler(config);
+        let key = RequestKey::new("bars", &Contract::stock("MSFT"), WhatToShow::Trades, ());
+
+        let attempts = AtomicUsize::new(0);
+        let result = scheduler
+            .send_with_retry(&key, || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(Error::Message(
+                        162,
+                        "Historical Market Data Service error message:Historical data request pacing violation".into(),
+                    )),
+                    _ => Ok(42),
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_in_flight_are_shared() {
+        let scheduler = scheduler(PacingConfig::default());
+        let key = RequestKey::new("head_timestamp", &Contract::stock("MSFT"), WhatToShow::Trades, true);
+        let sent = AtomicUsize::new(0);
+
+        let request = || {
+            scheduler.shared(&scheduler.head_timestamps, &key, || async {
+                sent.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(StdDuration::from_millis(50)).await;
+                Ok(OffsetDateTime::UNIX_EPOCH)
+            })
+        };
+        let results = futures::future::join_all((0..4).map(|_| request())).await;
+
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Ok(timestamp) if *timestamp == OffsetDateTime::UNIX_EPOCH)));
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert!(scheduler.head_timestamps.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! Pacing of historical data requests.
+//!
+//! TWS rejects historical data requests that break its pacing rules with error 162:
+//!
+//! * no identical requests within 15 seconds,
+//! * no more than 6 requests for the same contract, exchange and data type within 2 seconds,
+//! * no more than 60 requests within 10 minutes.
+//!
+//! A `HistoricalScheduler` holds requests back until they fit within these limits. Identical requests that are in
+//! flight at the same time are sent once and share the response, and requests rejected for a pacing violation are
+//! retried with exponential backoff.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::contracts::Contract;
+use crate::messages::IncomingMessages;
+use crate::Error;
+
+use super::WhatToShow;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// Error code TWS returns for historical data errors, including pacing violations.
+const HISTORICAL_DATA_ERROR: i32 = 162;
+
+/// Pacing limits and retry policy of a `HistoricalScheduler`. Defaults to the limits enforced by TWS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacingConfig {
+    identical_interval: Duration,
+    burst_limit: usize,
+    burst_window: Duration,
+    request_limit: usize,
+    request_window: Duration,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            identical_interval: Duration::from_secs(15),
+            burst_limit: 6,
+            burst_window: Duration::from_secs(2),
+            request_limit: 60,
+            request_window: Duration::from_secs(600),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl PacingConfig {
+    /// Sets the minimum time between identical requests. Defaults to 15 seconds.
+    pub fn identical_interval(mut self, interval: Duration) -> Self {
+        self.identical_interval = interval;
+        self
+    }
+
+    /// Sets how many requests for the same contract, exchange and data type may be sent within `window`.
+    /// Defaults to 6 within 2 seconds.
+    pub fn burst(mut self, limit: usize, window: Duration) -> Self {
+        self.burst_limit = limit.max(1);
+        self.burst_window = window;
+        self
+    }
+
+    /// Sets how many requests may be sent within `window`. Defaults to 60 within 10 minutes.
+    pub fn requests(mut self, limit: usize, window: Duration) -> Self {
+        self.request_limit = limit.max(1);
+        self.request_window = window;
+        self
+    }
+
+    /// Sets how many times a request rejected for a pacing violation is retried. Defaults to 3.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry. The delay doubles with each retry. Defaults to 10 seconds.
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Returns the delay before the given retry, starting from 0.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.retry_delay.saturating_mul(2u32.saturating_pow(retry))
+    }
+}
+
+/// Identifies a request for pacing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RequestKey {
+    // Every parameter of the request.
+    request: String,
+    // Contract, exchange and data type.
+    instrument: String,
+}
+
+impl RequestKey {
+    pub(crate) fn new(kind: &str, contract: &Contract, what_to_show: WhatToShow, parameters: impl std::fmt::Debug) -> Self {
+        let instrument = format!(
+            "{}|{}|{}|{}|{}",
+            contract.contract_id, contract.symbol, contract.security_type, contract.exchange, what_to_show
+        );
+        let request = format!("{kind}|{contract:?}|{what_to_show}|{parameters:?}");
+        Self { request, instrument }
+    }
+
+    /// Key of every parameter, used to share identical requests in flight.
+    pub(crate) fn request(&self) -> &str {
+        &self.request
+    }
+}
+
+/// Tracks recently sent requests against the pacing limits.
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    config: PacingConfig,
+    sent: VecDeque<(Instant, RequestKey)>,
+}
+
+impl Pacer {
+    pub(crate) fn new(config: PacingConfig) -> Self {
+        Self {
+            config,
+            sent: VecDeque::new(),
+        }
+    }
+
+    /// Records the request as sent at `now` if it fits within the limits. Otherwise returns how long to wait.
+    pub(crate) fn try_acquire(&mut self, key: &RequestKey, now: Instant) -> Option<Duration> {
+        let config = &self.config;
+
+        let retention = config.identical_interval.max(config.burst_window).max(config.request_window);
+        while let Some((sent_at, _)) = self.sent.front() {
+            if now.saturating_duration_since(*sent_at) < retention {
+                break;
+            }
+            self.sent.pop_front();
+        }
+
+        // Time until the entry leaves a window, if it is still inside it.
+        let remaining = |sent_at: Instant, window: Duration| window.checked_sub(now.saturating_duration_since(sent_at)).filter(|d| !d.is_zero());
+
+        let mut wait = Duration::ZERO;
+
+        if let Some((sent_at, _)) = self.sent.iter().rev().find(|(_, sent)| sent.request == key.request) {
+            wait = wait.max(remaining(*sent_at, config.identical_interval).unwrap_or_default());
+        }
+
+        let burst: Vec<Instant> = self
+            .sent
+            .iter()
+            .filter(|(sent_at, sent)| sent.instrument == key.instrument && remaining(*sent_at, config.burst_window).is_some())
+            .map(|(sent_at, _)| *sent_at)
+            .collect();
+        if burst.len() >= config.burst_limit {
+            let oldest = burst[burst.len() - config.burst_limit];
+            wait = wait.max(remaining(oldest, config.burst_window).unwrap_or_default());
+        }
+
+        let requests: Vec<Instant> = self
+            .sent
+            .iter()
+            .filter(|(sent_at, _)| remaining(*sent_at, config.request_window).is_some())
+            .map(|(sent_at, _)| *sent_at)
+            .collect();
+        if requests.len() >= config.request_limit {
+            let oldest = requests[requests.len() - config.request_limit];
+            wait = wait.max(remaining(oldest, config.request_window).unwrap_or_default());
+        }
+
+        if wait.is_zero() {
+            self.sent.push_back((now, key.clone()));
+            None
+        } else {
+            Some(wait)
+        }
+    }
+}
+
+/// Returns true if TWS rejected the request for breaking the pacing rules.
+pub(crate) fn is_pacing_violation(error: &Error) -> bool {
+    match error {
+        Error::Message(code, message) => *code == HISTORICAL_DATA_ERROR && message.to_lowercase().contains("pacing violation"),
+        Error::UnexpectedResponse(message) if message.message_type() == IncomingMessages::Error => is_pacing_violation(&Error::from(message.clone())),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(symbol: &str, what_to_show: WhatToShow, parameters: &str) -> RequestKey {
+        RequestKey::new("bars", &Contract::stock(symbol), what_to_show, parameters)
+    }
+
+    #[test]
+    fn test_identical_requests_are_spaced() {
+        let mut pacer = Pacer::new(PacingConfig::default());
+        let start = Instant::now();
+        let request = key("AAPL", WhatToShow::Trades, "1 D");
+
+        assert_eq!(pacer.try_acquire(&request, start), None);
+        assert_eq!(pacer.try_acquire(&request, start + Duration::from_secs(5)), Some(Duration::from_secs(10)));
+        assert_eq!(pacer.try_acquire(&request, start + Duration::from_secs(15)), None);
+
+        // A different duration is not identical.
+        assert_eq!(
+            pacer.try_acquire(&key("AAPL", WhatToShow::Trades, "2 D"), start + Duration::from_secs(16)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_burst_limit_per_instrument() {
+        let mut pacer = Pacer::new(PacingConfig::default());
+        let start = Instant::now();
+
+        for i in 0..6 {
+            assert_eq!(pacer.try_acquire(&key("AAPL", WhatToShow::Trades, &i.to_string()), start), None);
+        }
+
+        let at = start + Duration::from_millis(500);
+        assert_eq!(
+            pacer.try_acquire(&key("AAPL", WhatToShow::Trades, "6"), at),
+            Some(Duration::from_millis(1500))
+        );
+        // Other data types and contracts are not affected.
+        assert_eq!(pacer.try_acquire(&key("AAPL", WhatToShow::Bid, "6"), at), None);
+        assert_eq!(pacer.try_acquire(&key("MSFT", WhatToShow::Trades, "6"), at), None);
+        assert_eq!(
+            pacer.try_acquire(&key("AAPL", WhatToShow::Trades, "6"), start + Duration::from_secs(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_request_limit() {
+        let mut pacer = Pacer::new(PacingConfig::default().requests(3, Duration::from_secs(60)));
+        let start = Instant::now();
+
+        for symbol in ["A", "B", "C"] {
+            assert_eq!(pacer.try_acquire(&key(symbol, WhatToShow::Trades, ""), start), None);
+        }
+
+        let at = start + Duration::from_secs(20);
+        assert_eq!(pacer.try_acquire(&key("D", WhatToShow::Trades, ""), at), Some(Duration::from_secs(40)));
+        assert_eq!(
+            pacer.try_acquire(&key("D", WhatToShow::Trades, ""), start + Duration::from_secs(60)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let config = PacingConfig::default().retry_delay(Duration::from_secs(1));
+
+        assert_eq!(config.backoff(0), Duration::from_secs(1));
+        assert_eq!(config.backoff(2), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_pacing_violation() {
+        let violation = Error::Message(
+            162,
+            "Historical Market Data Service error message:Historical data request pacing violation".into(),
+        );
+        let permissions = Error::Message(162, "Historical Market Data Service error message:No market data permissions.".into());
+
+        assert!(is_pacing_violation(&violation));
+        assert!(!is_pacing_violation(&permissions));
+        assert!(!is_pacing_violation(&Error::Message(200, "pacing violation".into())));
+    }
+}
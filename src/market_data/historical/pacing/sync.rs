@@ -0,0 +1,292 @@
+//! Historical data pacing for the blocking client.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+use log::{debug, warn};
+use time::OffsetDateTime;
+
+use super::{is_pacing_violation, Pacer, PacingConfig, RequestKey};
+use crate::client::sync::Client;
+use crate::contracts::Contract;
+use crate::market_data::historical::sync::TickSubscription;
+use crate::market_data::historical::{self, BarSize, Duration, HistoricalData, TickBidAsk, TickLast, TickMidpoint, WhatToShow};
+use crate::Error;
+
+type Shared<T> = Arc<OnceLock<Result<T, Error>>>;
+
+/// Sends historical data requests within the pacing limits of TWS.
+///
+/// Requests wait until they fit within the limits of the [PacingConfig]. Identical bar and head timestamp requests made
+/// while one is in flight share its response, and requests rejected for a pacing violation are retried with backoff.
+/// Share one scheduler between all threads requesting historical data from a client.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::blocking::HistoricalScheduler;
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::historical::{BarSize, PacingConfig, ToDuration, WhatToShow};
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+/// let scheduler = HistoricalScheduler::new(&client, PacingConfig::default());
+///
+/// for symbol in ["AAPL", "MSFT", "NVDA"] {
+///     let contract = Contract::stock(symbol);
+///     let bars = scheduler
+///         .historical_data(&contract, None, 1.days(), BarSize::Min, WhatToShow::Trades, true)
+///         .expect("historical data request failed");
+///     println!("{symbol}: {} bars", bars.bars.len());
+/// }
+/// ```
+pub struct HistoricalScheduler<'a> {
+    client: &'a Client,
+    config: PacingConfig,
+    pacer: Mutex<Pacer>,
+    bars: Mutex<HashMap<String, Shared<HistoricalData>>>,
+    head_timestamps: Mutex<HashMap<String, Shared<OffsetDateTime>>>,
+}
+
+impl<'a> HistoricalScheduler<'a> {
+    /// Creates a scheduler for requests sent by the client.
+    pub fn new(client: &'a Client, config: PacingConfig) -> Self {
+        Self {
+            client,
+            config,
+            pacer: Mutex::new(Pacer::new(config)),
+            bars: Mutex::new(HashMap::new()),
+            head_timestamps: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Requests historical bars within the pacing limits. See [Client::historical_data].
+    pub fn historical_data(
+        &self,
+        contract: &Contract,
+        interval_end: Option<OffsetDateTime>,
+        duration: Duration,
+        bar_size: BarSize,
+        what_to_show: WhatToShow,
+        use_rth: bool,
+    ) -> Result<HistoricalData, Error> {
+        let key = RequestKey::new("bars", contract, what_to_show, (interval_end, duration, bar_size, use_rth));
+        self.shared(&self.bars, &key, || {
+            historical::sync::historical_data(self.client, contract, interval_end, duration, bar_size, Some(what_to_show), use_rth)
+        })
+    }
+
+    /// Requests the timestamp of the earliest available data within the pacing limits. See [Client::head_timestamp].
+    pub fn head_timestamp(&self, contract: &Contract, what_to_show: WhatToShow, use_rth: bool) -> Result<OffsetDateTime, Error> {
+        let key = RequestKey::new("head_timestamp", contract, what_to_show, use_rth);
+        self.shared(&self.head_timestamps, &key, || {
+            historical::sync::head_timestamp(self.client, contract, what_to_show, use_rth)
+        })
+    }
+
+    /// Requests historical bid and ask ticks within the pacing limits. See [Client::historical_ticks_bid_ask].
+    pub fn historical_ticks_bid_ask(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+        ignore_size: bool,
+    ) -> Result<TickSubscription<TickBidAsk>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::BidAsk,
+            (start, end, number_of_ticks, use_rth, ignore_size),
+        ));
+        historical::sync::historical_ticks_bid_ask(self.client, contract, start, end, number_of_ticks, use_rth, ignore_size)
+    }
+
+    /// Requests historical midpoint ticks within the pacing limits. See [Client::historical_ticks_mid_point].
+    pub fn historical_ticks_mid_point(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+    ) -> Result<TickSubscription<TickMidpoint>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::MidPoint,
+            (start, end, number_of_ticks, use_rth),
+        ));
+        historical::sync::historical_ticks_mid_point(self.client, contract, start, end, number_of_ticks, use_rth)
+    }
+
+    /// Requests historical trade ticks within the pacing limits. See [Client::historical_ticks_trade].
+    pub fn historical_ticks_trade(
+        &self,
+        contract: &Contract,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+        number_of_ticks: i32,
+        use_rth: bool,
+    ) -> Result<TickSubscription<TickLast>, Error> {
+        self.acquire(&RequestKey::new(
+            "ticks",
+            contract,
+            WhatToShow::Trades,
+            (start, end, number_of_ticks, use_rth),
+        ));
+        historical::sync::historical_ticks_trade(self.client, contract, start, end, number_of_ticks, use_rth)
+    }
+
+    // Sends the request once for all callers waiting on an identical request.
+    fn shared<T: Clone>(
+        &self,
+        in_flight: &Mutex<HashMap<String, Shared<T>>>,
+        key: &RequestKey,
+        request: impl Fn() -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let cell = in_flight.lock()?.entry(key.request().to_string()).or_default().clone();
+
+        let result = cell.get_or_init(|| self.send_with_retry(key, request)).clone();
+
+        let mut in_flight = in_flight.lock()?;
+        if in_flight.get(key.request()).is_some_and(|current| Arc::ptr_eq(current, &cell)) {
+            in_flight.remove(key.request());
+        }
+
+        result
+    }
+
+    fn send_with_retry<T>(&self, key: &RequestKey, request: impl Fn() -> Result<T, Error>) -> Result<T, Error> {
+        let mut retry = 0;
+        loop {
+            self.acquire(key);
+
+            match request() {
+                Err(ref err) if is_pacing_violation(err) && retry < self.config.max_retries => {
+                    let delay = self.config.backoff(retry);
+                    warn!("historical data pacing violation, retrying in {delay:?}");
+                    thread::sleep(delay);
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Blocks until the request fits within the pacing limits.
+    fn acquire(&self, key: &RequestKey) {
+        loop {
+            let wait = match self.pacer.lock() {
+                Ok(mut pacer) => pacer.try_acquire(key, Instant::now()),
+                Err(_) => None,
+            };
+
+            match wait {
+                Some(delay) => {
+                    debug!("pacing historical data request for {delay:?}");
+                    thread::sleep(delay);
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::RwLock;
+    use std::time::Duration as StdDuration;
+
+    use super::*;
+    use crate::market_data::historical::ToDuration;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn violation() -> Error {
+        Error::Message(
+            162,
+            "Historical Market Data Service error message:Historical data request pacing violation".into(),
+        )
+    }
+
+    fn stubbed_client(response_messages: Vec<String>) -> (Client, Arc<MessageBusStub>) {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages,
+        });
+        (Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES), message_bus)
+    }
+
+    #[test]
+    fn test_historical_data() {
+        let (client, message_bus) = stubbed_client(vec![
+            "17|9000|20230413  16:31:22|20230415  16:31:22|2|20230413|182.9400|186.5000|180.9400|185.9000|948837.22|184.869|324891|20230414|183.8800|186.2800|182.0100|185.0000|810998.27|183.9865|277547|".to_owned(),
+        ]);
+        let scheduler = HistoricalScheduler::new(&client, PacingConfig::default());
+
+        let bars = scheduler
+            .historical_data(&Contract::stock("MSFT"), None, 2.days(), BarSize::Day, WhatToShow::Trades, true)
+            .expect("historical data request failed");
+
+        assert_eq!(bars.bars.len(), 2);
+        assert_eq!(message_bus.request_messages().len(), 1);
+    }
+
+    #[test]
+    fn test_retry_after_pacing_violation() {
+        let (client, _) = stubbed_client(vec![]);
+        let config = PacingConfig::default()
+            .identical_interval(StdDuration::ZERO)
+            .retry_delay(StdDuration::from_millis(1));
+        let scheduler = HistoricalScheduler::new(&client, config);
+        let key = RequestKey::new("bars", &Contract::stock("MSFT"), WhatToShow::Trades, ());
+
+        let attempts = AtomicUsize::new(0);
+        let result = scheduler.send_with_retry(&key, || match attempts.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(violation()),
+            _ => Ok(42),
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), Error> = scheduler.send_with_retry(&key, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(violation())
+        });
+
+        assert!(matches!(result, Err(ref err) if is_pacing_violation(err)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_identical_requests_in_flight_are_shared() {
+        let (client, _) = stubbed_client(vec![]);
+        let scheduler = HistoricalScheduler::new(&client, PacingConfig::default());
+        let key = RequestKey::new("head_timestamp", &Contract::stock("MSFT"), WhatToShow::Trades, true);
+        let sent = AtomicUsize::new(0);
+        let timestamp = OffsetDateTime::UNIX_EPOCH;
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let result = scheduler.shared(&scheduler.head_timestamps, &key, || {
+                        sent.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(StdDuration::from_millis(200));
+                        Ok(timestamp)
+                    });
+                    assert_eq!(result.unwrap(), timestamp);
+                });
+            }
+        });
+
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert!(scheduler.head_timestamps.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+//! Historical data ranges for the async client.
+
+use std::collections::VecDeque;
+
+use log::debug;
+use time::OffsetDateTime;
+
+use super::{clamp_start, is_no_data, plan_chunks, Chunk, RangeProgress, Stitcher};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::historical::pacing::r#async::HistoricalScheduler;
+use crate::market_data::historical::pacing::PacingConfig;
+use crate::market_data::historical::{Bar, BarSize, WhatToShow};
+use crate::Error;
+
+/// Bars over a range, requested one chunk at a time.
+///
+/// [HistoricalRange::next_chunk] returns the bars of each chunk, oldest first. Use [HistoricalRange::collect_bars] to
+/// wait for the whole range instead.
+pub struct HistoricalRange {
+    scheduler: HistoricalScheduler,
+    contract: Contract,
+    bar_size: BarSize,
+    what_to_show: Option<WhatToShow>,
+    use_rth: bool,
+    chunks: VecDeque<Chunk>,
+    stitcher: Stitcher,
+    progress: RangeProgress,
+}
+
+impl HistoricalRange {
+    pub(crate) async fn new(
+        client: &Client,
+        contract: &Contract,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        bar_size: BarSize,
+        what_to_show: Option<WhatToShow>,
+        use_rth: bool,
+    ) -> Result<Self, Error> {
+        let scheduler = HistoricalScheduler::new(client, PacingConfig::default());
+
+        let earliest = scheduler
+            .head_timestamp(contract, what_to_show.unwrap_or(WhatToShow::Trades), use_rth)
+            .await?;
+
+        Ok(Self::planned(
+            scheduler,
+            contract,
+            clamp_start(start, earliest, bar_size),
+            end,
+            bar_size,
+            what_to_show,
+            use_rth,
+        ))
+    }
+
+    fn planned(
+        scheduler: HistoricalScheduler,
+        contract: &Contract,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        bar_size: BarSize,
+        what_to_show: Option<WhatToShow>,
+        use_rth: bool,
+    ) -> Self {
+        let chunks: VecDeque<Chunk> = plan_chunks(start, end, bar_size).into();
+        debug!("requesting {} chunks of {bar_size} bars from {start} to {end}", chunks.len());
+
+        Self {
+            scheduler,
+            contract: contract.clone(),
+            bar_size,
+            what_to_show,
+            use_rth,
+            progress: RangeProgress {
+                chunks_completed: 0,
+                chunks_total: chunks.len(),
+                bars: 0,
+                start,
+                end,
+            },
+            chunks,
+            stitcher: Stitcher::new(start, end),
+        }
+    }
+
+    /// Returns how much of the range has been received.
+    pub fn progress(&self) -> RangeProgress {
+        self.progress
+    }
+
+    /// Requests the next chunk and returns its bars. Returns `None` once the range is complete.
+    pub async fn next_chunk(&mut self) -> Option<Result<Vec<Bar>, Error>> {
+        let chunk = self.chunks.pop_front()?;
+
+        let bars = match self
+            .scheduler
+            .historical_data(
+                &self.contract,
+                Some(chunk.end),
+                chunk.duration,
+                self.bar_size,
+                self.what_to_show,
+                self.use_rth,
+            )
+            .await
+        {
+            Ok(data) => data.bars,
+            Err(ref err) if is_no_data(err) => Vec::new(),
+            Err(err) => {
+                self.chunks.clear();
+                return Some(Err(err));
+            }
+        };
+
+        let bars = self.stitcher.stitch(bars);
+        self.progress.chunks_completed += 1;
+        self.progress.bars += bars.len();
+
+        Some(Ok(bars))
+    }
+
+    /// Requests every remaining chunk and returns all bars of the range.
+    pub async fn collect_bars(self) -> Result<Vec<Bar>, Error> {
+        self.collect_bars_with_progress(|_| {}).await
+    }
+
+    /// Requests every remaining chunk, calling `on_progress` after each one, and returns all bars of the range.
+    pub async fn collect_bars_with_progress(mut self, mut on_progress: impl FnMut(&RangeProgress)) -> Result<Vec<Bar>, Error> {
+        let mut bars = Vec::new();
+        while let Some(chunk) = self.next_chunk().await {
+            bars.extend(chunk?);
+            on_progress(&self.progress);
+        }
+        Ok(bars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[tokio::test]
+    async fn test_range_requests_chunks() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "17|9000|20230413  16:31:22|20230415  16:31:22|2|20230413|182.9400|186.5000|180.9400|185.9000|948837.22|184.869|324891|20230414|183.8800|186.2800|182.0100|185.0000|810998.27|183.9865|277547|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let range = HistoricalRange::planned(
+            HistoricalScheduler::new(&client, PacingConfig::default()),
+            &Contract::stock("MSFT"),
+            datetime!(2023-04-13 0:00 UTC),
+            datetime!(2023-04-15 0:00 UTC),
+            BarSize::Day,
+            Some(WhatToShow::Trades),
+            true,
+        );
+
+        let bars = range.collect_bars().await.expect("range request failed");
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(message_bus.request_messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_range_clamped_to_head_timestamp() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec!["88|9000|1681133400|".to_owned()],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        let range = client
+            .historical_data_range(
+                &Contract::stock("MSFT"),
+                datetime!(2020-01-01 0:00 UTC),
+                datetime!(2023-04-15 0:00 UTC),
+                BarSize::Day,
+                None,
+                true,
+            )
+            .await
+            .expect("range request failed");
+
+        assert_eq!(range.progress().start, datetime!(2023-04-10 0:00 UTC));
+    }
+}
@@ -0,0 +1,249 @@
+//! Historical bars over ranges longer than a single request allows.
+//!
+//! TWS caps the duration of a historical data request by bar size. `Client::historical_data_range` clamps the start of
+//! the range to the earliest available data, splits the range into chunks no longer than the cap for the bar size,
+//! requests them oldest first within the pacing limits and stitches the bars into one ordered series without
+//! duplicates.
+
+use time::{OffsetDateTime, Time, UtcOffset};
+
+use crate::Error;
+
+use super::{Bar, BarSize, Duration};
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// Message of the error TWS returns for a request without any bars, such as one covering a weekend.
+const NO_DATA: &str = "HMDS query returned no data";
+
+/// Progress of a range request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeProgress {
+    /// Chunks received so far.
+    pub chunks_completed: usize,
+    /// Chunks the range was split into.
+    pub chunks_total: usize,
+    /// Bars received so far, after removing duplicates.
+    pub bars: usize,
+    /// Start of the range, after clamping to the earliest available data.
+    pub start: OffsetDateTime,
+    /// End of the range.
+    pub end: OffsetDateTime,
+}
+
+impl RangeProgress {
+    /// Returns true once every chunk has been received.
+    pub fn is_complete(&self) -> bool {
+        self.chunks_completed == self.chunks_total
+    }
+}
+
+/// One request of a range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Chunk {
+    pub(crate) end: OffsetDateTime,
+    pub(crate) duration: Duration,
+}
+
+/// Returns the start of the range clamped to the earliest available data.
+///
+/// Daily and longer bars are dated at midnight UTC, while the earliest data has a time of day, so for those bar sizes
+/// the clamped start is moved to the start of its day to keep the first bar.
+pub(crate) fn clamp_start(start: OffsetDateTime, earliest: OffsetDateTime, bar_size: BarSize) -> OffsetDateTime {
+    if start >= earliest {
+        return start;
+    }
+
+    match bar_size {
+        BarSize::Day | BarSize::Week | BarSize::Month => earliest.to_offset(UtcOffset::UTC).replace_time(Time::MIDNIGHT),
+        _ => earliest,
+    }
+}
+
+/// Longest span of a single request for the bar size, and whether it is requested in seconds rather than days.
+fn chunk_limit(bar_size: BarSize) -> (time::Duration, bool) {
+    match bar_size {
+        BarSize::Sec => (time::Duration::minutes(30), true),
+        BarSize::Sec5 => (time::Duration::hours(1), true),
+        BarSize::Sec15 => (time::Duration::hours(4), true),
+        BarSize::Sec30 => (time::Duration::hours(8), true),
+        BarSize::Min | BarSize::Min2 | BarSize::Min3 => (time::Duration::days(7), false),
+        BarSize::Min5 | BarSize::Min15 | BarSize::Min20 | BarSize::Min30 => (time::Duration::days(30), false),
+        BarSize::Hour | BarSize::Hour2 | BarSize::Hour3 | BarSize::Hour4 | BarSize::Hour8 => (time::Duration::days(30), false),
+        BarSize::Day | BarSize::Week | BarSize::Month => (time::Duration::days(365), false),
+    }
+}
+
+/// Splits the range into requests no longer than allowed for the bar size, oldest first.
+pub(crate) fn plan_chunks(start: OffsetDateTime, end: OffsetDateTime, bar_size: BarSize) -> Vec<Chunk> {
+    let (limit, in_seconds) = chunk_limit(bar_size);
+
+    let mut chunks = Vec::new();
+    let mut chunk_end = end;
+    while chunk_end > start {
+        let span = (chunk_end - start).min(limit);
+
+        let duration = if in_seconds {
+            Duration::seconds(span.whole_seconds().max(1) as i32)
+        } else {
+            // Rounded up, so the oldest chunk reaches the start.
+            let days = (span.whole_seconds() + 86_399) / 86_400;
+            Duration::days(days.max(1) as i32)
+        };
+
+        chunks.push(Chunk { end: chunk_end, duration });
+        chunk_end -= span;
+    }
+
+    chunks.reverse();
+    chunks
+}
+
+/// Joins the bars of consecutive chunks into one ordered series within the range.
+#[derive(Debug)]
+pub(crate) struct Stitcher {
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    last: Option<OffsetDateTime>,
+}
+
+impl Stitcher {
+    pub(crate) fn new(start: OffsetDateTime, end: OffsetDateTime) -> Self {
+        Self { start, end, last: None }
+    }
+
+    /// Returns the bars of the next chunk that fall inside the range and follow the bars already returned.
+    pub(crate) fn stitch(&mut self, mut bars: Vec<Bar>) -> Vec<Bar> {
+        bars.sort_by_key(|bar| bar.date);
+        bars.dedup_by_key(|bar| bar.date);
+        bars.retain(|bar| bar.date >= self.start && bar.date < self.end && self.last.is_none_or(|last| bar.date > last));
+
+        if let Some(bar) = bars.last() {
+            self.last = Some(bar.date);
+        }
+        bars
+    }
+}
+
+/// Returns true if TWS had no bars for the request.
+pub(crate) fn is_no_data(error: &Error) -> bool {
+    matches!(error, Error::Message(_, message) if message.contains(NO_DATA))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn bar(date: OffsetDateTime) -> Bar {
+        Bar {
+            date,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: 1.0,
+            wap: 1.0,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn test_plan_chunks_by_bar_size() {
+        let start = datetime!(2024-01-01 0:00 UTC);
+        let end = datetime!(2024-01-20 12:00 UTC);
+
+        let chunks = plan_chunks(start, end, BarSize::Min);
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk {
+                    end: datetime!(2024-01-06 12:00 UTC),
+                    duration: Duration::days(6),
+                },
+                Chunk {
+                    end: datetime!(2024-01-13 12:00 UTC),
+                    duration: Duration::days(7),
+                },
+                Chunk {
+                    end,
+                    duration: Duration::days(7),
+                },
+            ]
+        );
+
+        let chunks = plan_chunks(start, datetime!(2024-01-01 1:10 UTC), BarSize::Sec);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].duration, Duration::seconds(600));
+        assert_eq!(chunks[2].duration, Duration::seconds(1800));
+
+        assert_eq!(plan_chunks(start, end, BarSize::Day).len(), 1);
+        assert!(plan_chunks(end, start, BarSize::Day).is_empty());
+    }
+
+    #[test]
+    fn test_clamp_start() {
+        let earliest = datetime!(2024-01-02 14:30 UTC);
+
+        assert_eq!(
+            clamp_start(datetime!(2024-01-03 0:00 UTC), earliest, BarSize::Day),
+            datetime!(2024-01-03 0:00 UTC)
+        );
+        assert_eq!(clamp_start(datetime!(2023-06-01 0:00 UTC), earliest, BarSize::Hour), earliest);
+        assert_eq!(
+            clamp_start(datetime!(2023-06-01 0:00 UTC), earliest, BarSize::Day),
+            datetime!(2024-01-02 0:00 UTC)
+        );
+        assert_eq!(
+            clamp_start(datetime!(2023-06-01 0:00 UTC), earliest, BarSize::Month),
+            datetime!(2024-01-02 0:00 UTC)
+        );
+
+        // The first daily bar, dated at midnight, is kept.
+        let mut stitcher = Stitcher::new(
+            clamp_start(datetime!(2023-06-01 0:00 UTC), earliest, BarSize::Day),
+            datetime!(2024-01-05 0:00 UTC),
+        );
+        let bars = stitcher.stitch(vec![bar(datetime!(2024-01-02 0:00 UTC)), bar(datetime!(2024-01-03 0:00 UTC))]);
+        assert_eq!(bars.len(), 2);
+    }
+
+    #[test]
+    fn test_stitch_removes_overlap_and_clamps_to_range() {
+        let mut stitcher = Stitcher::new(datetime!(2024-01-02 0:00 UTC), datetime!(2024-01-05 0:00 UTC));
+
+        let first = stitcher.stitch(vec![
+            bar(datetime!(2024-01-03 0:00 UTC)),
+            bar(datetime!(2024-01-01 0:00 UTC)),
+            bar(datetime!(2024-01-02 0:00 UTC)),
+        ]);
+        assert_eq!(
+            first.iter().map(|bar| bar.date).collect::<Vec<_>>(),
+            vec![datetime!(2024-01-02 0:00 UTC), datetime!(2024-01-03 0:00 UTC)]
+        );
+
+        let second = stitcher.stitch(vec![
+            bar(datetime!(2024-01-03 0:00 UTC)),
+            bar(datetime!(2024-01-04 0:00 UTC)),
+            bar(datetime!(2024-01-05 0:00 UTC)),
+        ]);
+        assert_eq!(
+            second.iter().map(|bar| bar.date).collect::<Vec<_>>(),
+            vec![datetime!(2024-01-04 0:00 UTC)]
+        );
+    }
+
+    #[test]
+    fn test_no_data_error() {
+        assert!(is_no_data(&Error::Message(
+            162,
+            "Historical Market Data Service error message:HMDS query returned no data: AAPL@SMART Trades".into()
+        )));
+        assert!(!is_no_data(&Error::Message(162, "pacing violation".into())));
+    }
+}
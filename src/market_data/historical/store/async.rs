@@ -0,0 +1,248 @@
+//! On-disk cache of historical bars for the async client.
+//!
+//! Cache files are small and read with blocking I/O.
+
+use std::fs;
+use std::path::PathBuf;
+
+use log::debug;
+use time::OffsetDateTime;
+
+use super::{adjusted_duration, adjusted_start, clear, merge, missing, remove, CacheKey, CachedBars};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::historical::range::is_no_data;
+use crate::market_data::historical::{Bar, BarSize, WhatToShow};
+use crate::Error;
+
+/// Serves historical bars from an on-disk cache, requesting only the bars not cached yet.
+///
+/// Contracts are identified by their contract id, which must be set.
+///
+/// # Examples
+///
+/// ```no_run
+/// use time::macros::datetime;
+///
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::historical::{BarSize, HistoricalStore, WhatToShow};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///     let store = HistoricalStore::open(&client, "bars").expect("could not open store");
+///
+///     let contract = Contract {
+///         contract_id: 265598,
+///         ..Contract::stock("AAPL")
+///     };
+///     let bars = store
+///         .historical_data(&contract, datetime!(2024-01-01 0:00 UTC), datetime!(2025-01-01 0:00 UTC), BarSize::Hour, Some(WhatToShow::Trades), true)
+///         .await
+///         .expect("historical data request failed");
+///     println!("{} bars", bars.len());
+/// }
+/// ```
+pub struct HistoricalStore {
+    client: Client,
+    dir: PathBuf,
+}
+
+impl HistoricalStore {
+    /// Opens the store in `dir`, creating the directory if needed.
+    pub fn open(client: &Client, dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { client: client.clone(), dir })
+    }
+
+    /// Returns the bars from `start` up to `end`, requesting the parts that are not cached and caching them. Trades
+    /// are requested if `what_to_show` is `None`.
+    pub async fn historical_data(
+        &self,
+        contract: &Contract,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        bar_size: BarSize,
+        what_to_show: Option<WhatToShow>,
+        use_rth: bool,
+    ) -> Result<Vec<Bar>, Error> {
+        let what_to_show = what_to_show.unwrap_or(WhatToShow::Trades);
+        let path = CacheKey::new(contract, bar_size, what_to_show, use_rth)?.path(&self.dir);
+        let now = OffsetDateTime::now_utc();
+        let end = end.min(now);
+
+        let mut cached = CachedBars::read(&path)?;
+        let mut unsettled = Vec::new();
+        let mut changed = false;
+
+        if what_to_show == WhatToShow::AdjustedLast {
+            if !missing(cached.as_ref(), start, end).is_empty() {
+                let mut from = adjusted_start(cached.as_ref(), start);
+                let mut bars = self.adjusted_bars(contract, from, now, bar_size, use_rth).await?;
+
+                if cached.as_ref().is_some_and(|cached| cached.adjustments_changed(&bars)) {
+                    debug!("adjustments changed, discarding cached bars for {}", path.display());
+                    cached = None;
+                    if from > start {
+                        from = start;
+                        bars = self.adjusted_bars(contract, from, now, bar_size, use_rth).await?;
+                    }
+                }
+
+                unsettled = merge(&mut cached, from, now, bars, bar_size, now);
+                changed = true;
+            }
+        } else {
+            for (from, to) in missing(cached.as_ref(), start, end) {
+                debug!("requesting {bar_size} bars from {from} to {to} for {}", path.display());
+                let bars = self
+                    .client
+                    .historical_data_range(contract, from, to, bar_size, Some(what_to_show), use_rth)
+                    .await?
+                    .collect_bars()
+                    .await?;
+                unsettled.extend(merge(&mut cached, from, to, bars, bar_size, now));
+                changed = true;
+            }
+        }
+
+        let Some(cached) = cached else {
+            return Ok(Vec::new());
+        };
+        if changed {
+            cached.write(&path)?;
+        }
+
+        let mut bars = cached.slice(start, end);
+        bars.extend(unsettled.into_iter().filter(|bar| bar.date >= start && bar.date < end));
+        Ok(bars)
+    }
+
+    async fn adjusted_bars(
+        &self,
+        contract: &Contract,
+        start: OffsetDateTime,
+        now: OffsetDateTime,
+        bar_size: BarSize,
+        use_rth: bool,
+    ) -> Result<Vec<Bar>, Error> {
+        match self
+            .client
+            .historical_data(
+                contract,
+                None,
+                adjusted_duration(start, now),
+                bar_size,
+                Some(WhatToShow::AdjustedLast),
+                use_rth,
+            )
+            .await
+        {
+            Ok(data) => Ok(data.bars),
+            Err(ref err) if is_no_data(err) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Removes the cached bars of the contract, bar size, bar type and trading hours.
+    pub fn invalidate(&self, contract: &Contract, bar_size: BarSize, what_to_show: WhatToShow, use_rth: bool) -> Result<(), Error> {
+        remove(&CacheKey::new(contract, bar_size, what_to_show, use_rth)?.path(&self.dir))
+    }
+
+    /// Removes all cached bars.
+    pub fn clear(&self) -> Result<(), Error> {
+        clear(&self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn contract() -> Contract {
+        Contract {
+            contract_id: 272093,
+            ..Contract::stock("MSFT")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_range_served_from_disk() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = HistoricalStore::open(&client, dir.path()).unwrap();
+
+        let bar = Bar {
+            date: datetime!(2023-04-13 0:00 UTC),
+            open: 182.94,
+            high: 186.5,
+            low: 180.94,
+            close: 185.9,
+            volume: 948837.22,
+            wap: 184.869,
+            count: 324891,
+        };
+        let path = CacheKey::new(&contract(), BarSize::Day, WhatToShow::Trades, true)
+            .unwrap()
+            .path(dir.path());
+        CachedBars {
+            start: datetime!(2023-04-01 0:00 UTC),
+            end: datetime!(2023-05-01 0:00 UTC),
+            bars: vec![bar],
+        }
+        .write(&path)
+        .unwrap();
+
+        let bars = store
+            .historical_data(
+                &contract(),
+                datetime!(2023-04-10 0:00 UTC),
+                datetime!(2023-04-20 0:00 UTC),
+                BarSize::Day,
+                Some(WhatToShow::Trades),
+                true,
+            )
+            .await
+            .expect("historical data request failed");
+
+        assert_eq!(bars, vec![bar]);
+        assert!(message_bus.request_messages().is_empty());
+
+        store.invalidate(&contract(), BarSize::Day, WhatToShow::Trades, true).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_contract_id_required() {
+        let message_bus = Arc::new(MessageBusStub::default());
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = HistoricalStore::open(&client, dir.path()).unwrap();
+
+        let result = store
+            .historical_data(
+                &Contract::stock("MSFT"),
+                datetime!(2023-04-10 0:00 UTC),
+                datetime!(2023-04-20 0:00 UTC),
+                BarSize::Day,
+                None,
+                true,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+    }
+}
@@ -0,0 +1,393 @@
+//! On-disk cache of historical bars.
+//!
+//! A `HistoricalStore` keeps the bars of each contract, bar size, bar type and trading hours in its own file. A request
+//! for a range that is already cached is served from disk. Otherwise only the missing edges before and after the cached
+//! range are requested from TWS, and the file is extended to cover the whole request. Bars whose period has not
+//! closed yet are returned but not cached.
+//!
+//! [WhatToShow::AdjustedLast] bars are adjusted for splits and dividends, so a new corporate action rewrites the whole
+//! history. TWS only returns them up to the current time, so the store requests them from the last cached bar onward
+//! and compares the bars that overlap the cache. If any differ the cached file is discarded and the requested range is
+//! downloaded again.
+//!
+//! A file is laid out as, all integers big-endian:
+//! `b"IBHB" | u8 version | i64 start | i64 end | records`, where `start` and `end` bound the cached range in unix
+//! seconds and each record is `i64 date | f64 open | f64 high | f64 low | f64 close | f64 volume | f64 wap | i32 count`.
+//!
+//! Files are replaced atomically, but a directory should only be used by one store at a time.
+
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use time::OffsetDateTime;
+
+use super::{Bar, BarSize, Duration, WhatToShow};
+use crate::contracts::Contract;
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+const MAGIC: &[u8; 4] = b"IBHB";
+const VERSION: u8 = 1;
+const EXTENSION: &str = "bars";
+
+/// Identifies the file bars are cached in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CacheKey {
+    contract_id: i32,
+    bar_size: BarSize,
+    what_to_show: WhatToShow,
+    use_rth: bool,
+}
+
+impl CacheKey {
+    pub(crate) fn new(contract: &Contract, bar_size: BarSize, what_to_show: WhatToShow, use_rth: bool) -> Result<Self, Error> {
+        if contract.contract_id <= 0 {
+            return Err(Error::InvalidArgument("contract_id is required to cache historical data.".into()));
+        }
+
+        Ok(Self {
+            contract_id: contract.contract_id,
+            bar_size,
+            what_to_show,
+            use_rth,
+        })
+    }
+
+    pub(crate) fn path(&self, dir: &Path) -> PathBuf {
+        let hours = if self.use_rth { "rth" } else { "all" };
+        dir.join(format!(
+            "{}-{:?}-{}-{hours}.{EXTENSION}",
+            self.contract_id, self.bar_size, self.what_to_show
+        ))
+    }
+}
+
+/// Bars cached for one key, covering `start` up to `end`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CachedBars {
+    pub(crate) start: OffsetDateTime,
+    pub(crate) end: OffsetDateTime,
+    pub(crate) bars: Vec<Bar>,
+}
+
+impl CachedBars {
+    /// Reads the cached bars, or `None` if nothing is cached yet.
+    pub(crate) fn read(path: &Path) -> Result<Option<Self>, Error> {
+        match fs::read(path) {
+            Ok(data) => Ok(Some(Self::decode(&data)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Replaces the cached bars.
+    pub(crate) fn write(&self, path: &Path) -> Result<(), Error> {
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, self.encode()?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::with_capacity(21 + self.bars.len() * 60);
+        data.extend_from_slice(MAGIC);
+        data.write_u8(VERSION)?;
+        data.write_i64::<BigEndian>(self.start.unix_timestamp())?;
+        data.write_i64::<BigEndian>(self.end.unix_timestamp())?;
+
+        for bar in &self.bars {
+            data.write_i64::<BigEndian>(bar.date.unix_timestamp())?;
+            for value in [bar.open, bar.high, bar.low, bar.close, bar.volume, bar.wap] {
+                data.write_f64::<BigEndian>(value)?;
+            }
+            data.write_i32::<BigEndian>(bar.count)?;
+        }
+
+        Ok(data)
+    }
+
+    fn decode(data: &[u8]) -> Result<Self, Error> {
+        let mut reader = Cursor::new(data);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        let version = reader.read_u8()?;
+        if &magic != MAGIC || version != VERSION {
+            return Err(Error::Simple(format!("unsupported historical bar cache file, version {version}")));
+        }
+
+        let start = timestamp(reader.read_i64::<BigEndian>()?)?;
+        let end = timestamp(reader.read_i64::<BigEndian>()?)?;
+
+        let mut bars = Vec::new();
+        while (reader.position() as usize) < data.len() {
+            bars.push(Bar {
+                date: timestamp(reader.read_i64::<BigEndian>()?)?,
+                open: reader.read_f64::<BigEndian>()?,
+                high: reader.read_f64::<BigEndian>()?,
+                low: reader.read_f64::<BigEndian>()?,
+                close: reader.read_f64::<BigEndian>()?,
+                volume: reader.read_f64::<BigEndian>()?,
+                wap: reader.read_f64::<BigEndian>()?,
+                count: reader.read_i32::<BigEndian>()?,
+            });
+        }
+
+        Ok(Self { start, end, bars })
+    }
+
+    /// Returns the cached bars from `start` up to `end`.
+    pub(crate) fn slice(&self, start: OffsetDateTime, end: OffsetDateTime) -> Vec<Bar> {
+        self.bars.iter().filter(|bar| bar.date >= start && bar.date < end).copied().collect()
+    }
+
+    /// Returns true if a bar overlapping the cache differs from the cached one, as happens when adjusted bars are
+    /// rewritten after a split or dividend.
+    pub(crate) fn adjustments_changed(&self, fresh: &[Bar]) -> bool {
+        fresh.iter().any(|bar| {
+            let Ok(i) = self.bars.binary_search_by_key(&bar.date, |cached| cached.date) else {
+                return false;
+            };
+            let cached = &self.bars[i];
+            differs(cached.open, bar.open) || differs(cached.close, bar.close) || differs(cached.volume, bar.volume)
+        })
+    }
+}
+
+fn differs(a: f64, b: f64) -> bool {
+    (a - b).abs() > 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+fn timestamp(seconds: i64) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::from_unix_timestamp(seconds).map_err(|err| Error::Simple(format!("invalid timestamp in historical bar cache: {err}")))
+}
+
+/// Returns the parts of `start` up to `end` not covered by the cache. They border the cached range, so merging them
+/// keeps it contiguous.
+pub(crate) fn missing(cached: Option<&CachedBars>, start: OffsetDateTime, end: OffsetDateTime) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    let Some(cached) = cached else {
+        return if start < end { vec![(start, end)] } else { Vec::new() };
+    };
+
+    let mut missing = Vec::new();
+    if start < cached.start {
+        missing.push((start, cached.start));
+    }
+    if end > cached.end {
+        missing.push((cached.end, end));
+    }
+    missing
+}
+
+/// Adds bars fetched for `start` up to `end` to the cache, replacing any cached over the same range. Returns the bars
+/// whose period has not closed at `now`, which are left out of the cache.
+pub(crate) fn merge(
+    cached: &mut Option<CachedBars>,
+    start: OffsetDateTime,
+    mut end: OffsetDateTime,
+    mut bars: Vec<Bar>,
+    bar_size: BarSize,
+    now: OffsetDateTime,
+) -> Vec<Bar> {
+    bars.sort_by_key(|bar| bar.date);
+    bars.retain(|bar| bar.date >= start);
+
+    let mut unsettled = Vec::new();
+    while bars.last().is_some_and(|bar| bar.date + bar_span(bar_size) > now) {
+        if let Some(bar) = bars.pop() {
+            end = end.min(bar.date);
+            unsettled.insert(0, bar);
+        }
+    }
+
+    match cached {
+        Some(cached) => {
+            cached.bars.retain(|bar| bar.date < start || bar.date >= end);
+            cached.bars.extend(bars);
+            cached.bars.sort_by_key(|bar| bar.date);
+            cached.start = cached.start.min(start);
+            cached.end = cached.end.max(end);
+        }
+        None => *cached = Some(CachedBars { start, end, bars }),
+    }
+
+    unsettled
+}
+
+/// Time covered by one bar. Months are taken as 31 days, so a monthly bar is settled a little late.
+pub(crate) fn bar_span(bar_size: BarSize) -> time::Duration {
+    match bar_size {
+        BarSize::Sec => time::Duration::seconds(1),
+        BarSize::Sec5 => time::Duration::seconds(5),
+        BarSize::Sec15 => time::Duration::seconds(15),
+        BarSize::Sec30 => time::Duration::seconds(30),
+        BarSize::Min => time::Duration::minutes(1),
+        BarSize::Min2 => time::Duration::minutes(2),
+        BarSize::Min3 => time::Duration::minutes(3),
+        BarSize::Min5 => time::Duration::minutes(5),
+        BarSize::Min15 => time::Duration::minutes(15),
+        BarSize::Min20 => time::Duration::minutes(20),
+        BarSize::Min30 => time::Duration::minutes(30),
+        BarSize::Hour => time::Duration::hours(1),
+        BarSize::Hour2 => time::Duration::hours(2),
+        BarSize::Hour3 => time::Duration::hours(3),
+        BarSize::Hour4 => time::Duration::hours(4),
+        BarSize::Hour8 => time::Duration::hours(8),
+        BarSize::Day => time::Duration::days(1),
+        BarSize::Week => time::Duration::weeks(1),
+        BarSize::Month => time::Duration::days(31),
+    }
+}
+
+/// Duration of an adjusted bar request reaching back from `now` to `start`. Requests over a year are made in years.
+pub(crate) fn adjusted_duration(start: OffsetDateTime, now: OffsetDateTime) -> Duration {
+    let days = ((now - start).whole_seconds() + 86_399) / 86_400;
+    if days > 365 {
+        Duration::years(((days + 364) / 365) as i32)
+    } else {
+        Duration::days(days.max(1) as i32)
+    }
+}
+
+/// Returns the start of the adjusted bar request extending the cache, overlapping its last bar so that changed
+/// adjustments are noticed.
+pub(crate) fn adjusted_start(cached: Option<&CachedBars>, start: OffsetDateTime) -> OffsetDateTime {
+    match cached {
+        Some(cached) if start >= cached.start => cached.bars.last().map_or(cached.end, |bar| bar.date.min(cached.end)),
+        _ => start,
+    }
+}
+
+/// Removes every cache file in the directory.
+pub(crate) fn clear(dir: &Path) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == EXTENSION) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes one cache file, if present.
+pub(crate) fn remove(path: &Path) -> Result<(), Error> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn bar(date: OffsetDateTime, close: f64) -> Bar {
+        Bar {
+            date,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100.0,
+            wap: close,
+            count: 10,
+        }
+    }
+
+    #[test]
+    fn test_cache_file_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = CacheKey::new(
+            &Contract {
+                contract_id: 265598,
+                ..Contract::stock("AAPL")
+            },
+            BarSize::Day,
+            WhatToShow::Trades,
+            true,
+        )
+        .unwrap()
+        .path(dir.path());
+        assert_eq!(path.file_name().unwrap(), "265598-Day-TRADES-rth.bars");
+
+        assert_eq!(CachedBars::read(&path).unwrap(), None);
+
+        let cached = CachedBars {
+            start: datetime!(2024-01-01 0:00 UTC),
+            end: datetime!(2024-01-03 0:00 UTC),
+            bars: vec![bar(datetime!(2024-01-01 0:00 UTC), 185.5), bar(datetime!(2024-01-02 0:00 UTC), 186.25)],
+        };
+        cached.write(&path).unwrap();
+
+        assert_eq!(CachedBars::read(&path).unwrap(), Some(cached));
+    }
+
+    #[test]
+    fn test_missing_edges() {
+        let cached = CachedBars {
+            start: datetime!(2024-01-10 0:00 UTC),
+            end: datetime!(2024-01-20 0:00 UTC),
+            bars: vec![],
+        };
+
+        assert_eq!(
+            missing(Some(&cached), datetime!(2024-01-05 0:00 UTC), datetime!(2024-01-25 0:00 UTC)),
+            vec![
+                (datetime!(2024-01-05 0:00 UTC), datetime!(2024-01-10 0:00 UTC)),
+                (datetime!(2024-01-20 0:00 UTC), datetime!(2024-01-25 0:00 UTC)),
+            ]
+        );
+        assert!(missing(Some(&cached), datetime!(2024-01-12 0:00 UTC), datetime!(2024-01-18 0:00 UTC)).is_empty());
+        assert_eq!(missing(None, cached.start, cached.end), vec![(cached.start, cached.end)]);
+    }
+
+    #[test]
+    fn test_merge_leaves_out_unsettled_bars() {
+        let now = datetime!(2024-01-03 15:00 UTC);
+        let mut cached = Some(CachedBars {
+            start: datetime!(2024-01-01 0:00 UTC),
+            end: datetime!(2024-01-02 0:00 UTC),
+            bars: vec![bar(datetime!(2024-01-01 0:00 UTC), 185.0)],
+        });
+
+        let fetched = vec![bar(datetime!(2024-01-03 0:00 UTC), 187.0), bar(datetime!(2024-01-02 0:00 UTC), 186.0)];
+        let unsettled = merge(&mut cached, datetime!(2024-01-02 0:00 UTC), now, fetched, BarSize::Day, now);
+
+        assert_eq!(unsettled, vec![bar(datetime!(2024-01-03 0:00 UTC), 187.0)]);
+
+        let cached = cached.unwrap();
+        assert_eq!(cached.end, datetime!(2024-01-03 0:00 UTC));
+        assert_eq!(cached.bars.len(), 2);
+        assert_eq!(cached.bars[1].close, 186.0);
+    }
+
+    #[test]
+    fn test_adjustments_changed() {
+        let cached = CachedBars {
+            start: datetime!(2024-01-01 0:00 UTC),
+            end: datetime!(2024-01-03 0:00 UTC),
+            bars: vec![bar(datetime!(2024-01-01 0:00 UTC), 185.0), bar(datetime!(2024-01-02 0:00 UTC), 186.0)],
+        };
+
+        assert!(!cached.adjustments_changed(&[bar(datetime!(2024-01-02 0:00 UTC), 186.0), bar(datetime!(2024-01-03 0:00 UTC), 90.0)]));
+        assert!(cached.adjustments_changed(&[bar(datetime!(2024-01-02 0:00 UTC), 93.0)]));
+
+        assert_eq!(
+            adjusted_start(Some(&cached), datetime!(2024-01-01 0:00 UTC)),
+            datetime!(2024-01-02 0:00 UTC)
+        );
+        assert_eq!(
+            adjusted_duration(datetime!(2022-06-01 0:00 UTC), datetime!(2024-01-02 0:00 UTC)),
+            Duration::years(2)
+        );
+    }
+}
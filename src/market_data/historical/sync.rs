@@ -5,12 +5,13 @@ use std::sync::Mutex;
 use log::{debug, warn};
 use time::OffsetDateTime;
 
-use crate::client::ClientRequestBuilders;
+use crate::client::builders::sync::ClientRequestBuilders;
+use crate::client::sync::Client;
 use crate::contracts::Contract;
 use crate::messages::IncomingMessages;
 use crate::protocol::{check_version, Features};
 use crate::transport::{InternalSubscription, Response};
-use crate::{Client, Error, MAX_RETRIES};
+use crate::{Error, MAX_RETRIES};
 
 use super::common::{decoders, encoders};
 use super::{BarSize, Duration, HistogramEntry, HistoricalData, Schedule, TickBidAsk, TickDecoder, TickLast, TickMidpoint, WhatToShow};
@@ -253,15 +254,15 @@ impl<T: TickDecoder<T>> TickSubscription<T> {
         }
     }
 
-    pub fn iter(&self) -> TickSubscriptionIter<T> {
+    pub fn iter(&self) -> TickSubscriptionIter<'_, T> {
         TickSubscriptionIter { subscription: self }
     }
 
-    pub fn try_iter(&self) -> TickSubscriptionTryIter<T> {
+    pub fn try_iter(&self) -> TickSubscriptionTryIter<'_, T> {
         TickSubscriptionTryIter { subscription: self }
     }
 
-    pub fn timeout_iter(&self, duration: std::time::Duration) -> TickSubscriptionTimeoutIter<T> {
+    pub fn timeout_iter(&self, duration: std::time::Duration) -> TickSubscriptionTimeoutIter<'_, T> {
         TickSubscriptionTimeoutIter {
             subscription: self,
             timeout: duration,
@@ -419,9 +420,9 @@ mod tests {
     use crate::contracts::Contract;
     use crate::market_data::historical::ToDuration;
     use crate::messages::OutgoingMessages;
+    use crate::server_versions;
     use crate::stubs::MessageBusStub;
     use crate::ToField;
-    use crate::{server_versions, Client};
     use std::sync::{Arc, RwLock};
     use time::macros::{date, datetime};
     use time::OffsetDateTime;
@@ -454,7 +455,7 @@ mod tests {
             OutgoingMessages::RequestHeadTimestamp.to_field(),
             "message.message_type"
         );
-        assert_eq!(head_timestamp_request[1], "9000", "message.request_id");
+        assert_eq!(&head_timestamp_request[1], "9000", "message.request_id");
         assert_eq!(head_timestamp_request[2], contract.contract_id.to_field(), "message.contract_id");
         assert_eq!(head_timestamp_request[3], contract.symbol.to_field(), "message.symbol");
         assert_eq!(head_timestamp_request[4], contract.security_type.to_field(), "message.security_type");
@@ -478,7 +479,7 @@ mod tests {
         assert_eq!(head_timestamp_request[14], contract.include_expired.to_field(), "message.include_expired");
         assert_eq!(head_timestamp_request[15], use_rth.to_field(), "message.use_rth");
         assert_eq!(head_timestamp_request[16], what_to_show.to_field(), "message.what_to_show");
-        assert_eq!(head_timestamp_request[17], "2", "message.date_format");
+        assert_eq!(&head_timestamp_request[17], "2", "message.date_format");
     }
 
     #[test]
@@ -560,7 +561,7 @@ mod tests {
             OutgoingMessages::RequestHistoricalData.to_field(),
             "message.message_type"
         );
-        assert_eq!(head_timestamp_request[1], "9000", "message.request_id");
+        assert_eq!(&head_timestamp_request[1], "9000", "message.request_id");
         assert_eq!(head_timestamp_request[2], contract.contract_id.to_field(), "message.contract_id");
         assert_eq!(head_timestamp_request[3], contract.symbol.to_field(), "message.symbol");
         assert_eq!(head_timestamp_request[4], contract.security_type.to_field(), "message.security_type");
@@ -587,9 +588,9 @@ mod tests {
         assert_eq!(head_timestamp_request[17], duration.to_field(), "message.duration");
         assert_eq!(head_timestamp_request[18], use_rth.to_field(), "message.use_rth");
         assert_eq!(head_timestamp_request[19], what_to_show.to_field(), "message.what_to_show");
-        assert_eq!(head_timestamp_request[20], "2", "message.date_format");
-        assert_eq!(head_timestamp_request[21], "0", "message.keep_up_to_data");
-        assert_eq!(head_timestamp_request[22], "", "message.chart_options");
+        assert_eq!(&head_timestamp_request[20], "2", "message.date_format");
+        assert_eq!(&head_timestamp_request[21], "0", "message.keep_up_to_data");
+        assert_eq!(&head_timestamp_request[22], "", "message.chart_options");
     }
 
     #[test]
@@ -648,7 +649,7 @@ mod tests {
             OutgoingMessages::RequestHistoricalData.to_field(),
             "message.message_type"
         );
-        assert_eq!(historical_schedule_request[1], "9000", "message.request_id");
+        assert_eq!(&historical_schedule_request[1], "9000", "message.request_id");
         assert_eq!(historical_schedule_request[15], end_date.to_field(), "message.end_date");
         assert_eq!(historical_schedule_request[16], BarSize::Day.to_field(), "message.bar_size");
         assert_eq!(historical_schedule_request[17], duration.to_field(), "message.duration");
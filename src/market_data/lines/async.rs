@@ -0,0 +1,365 @@
+//! Line budget for the async client.
+
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use super::{LineBudget, LineKind, Lines, Priority};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{Bar, BarSize, BidAsk, MarketDepths, MidPoint, TickTypes, Trade, WhatToShow};
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+// Signals a subscription that its line was taken.
+#[derive(Default)]
+struct Eviction {
+    evicted: AtomicBool,
+    notify: Notify,
+}
+
+impl Eviction {
+    fn evict(&self) {
+        self.evicted.store(true, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+
+    fn is_evicted(&self) -> bool {
+        self.evicted.load(Ordering::Relaxed)
+    }
+}
+
+struct Shared {
+    lines: Mutex<Lines<Arc<Eviction>>>,
+    released: Notify,
+}
+
+/// Opens market data subscriptions within a [LineBudget].
+///
+/// Each request takes a line of its kind, held until the returned [ManagedSubscription] is dropped. When every line is
+/// in use, the request evicts the newest subscription of a lower priority, which is cancelled the next time it is
+/// polled or dropped. Otherwise it fails with [Error::LinesExhausted], after waiting for a line if the budget is set to
+/// [WhenExhausted::Wait](super::WhenExhausted::Wait).
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::lines::{LineBudget, LineManager, Priority};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///     let lines = LineManager::new(&client, LineBudget::default().market_data(2));
+///
+///     let watchlist = lines.market_data(&Contract::stock("MSFT"), &[], false, false, Priority::Low).await.expect("request failed");
+///     let mut position = lines.market_data(&Contract::stock("AAPL"), &[], false, false, Priority::High).await.expect("request failed");
+///
+///     while let Some(tick) = position.next().await {
+///         println!("{tick:?}");
+///     }
+///     println!("watchlist evicted: {}", watchlist.is_evicted());
+/// }
+/// ```
+pub struct LineManager {
+    client: Client,
+    shared: Arc<Shared>,
+}
+
+impl LineManager {
+    /// Creates a line manager for a client.
+    pub fn new(client: &Client, budget: LineBudget) -> Self {
+        Self {
+            client: client.clone(),
+            shared: Arc::new(Shared {
+                lines: Mutex::new(Lines::new(budget)),
+                released: Notify::new(),
+            }),
+        }
+    }
+
+    /// Returns the budget.
+    pub fn budget(&self) -> LineBudget {
+        *self.shared.lines.lock().unwrap().budget()
+    }
+
+    /// Returns the number of lines of a kind in use.
+    pub fn in_use(&self, kind: LineKind) -> usize {
+        self.shared.lines.lock().unwrap().in_use(kind)
+    }
+
+    /// Returns the number of lines of a kind available.
+    pub fn available(&self, kind: LineKind) -> usize {
+        let lines = self.shared.lines.lock().unwrap();
+        lines.budget().limit(kind).saturating_sub(lines.in_use(kind))
+    }
+
+    /// Requests market data within the budget. See [Client::market_data].
+    pub async fn market_data(
+        &self,
+        contract: &Contract,
+        generic_ticks: &[&str],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<TickTypes>, Error> {
+        let line = self.acquire(LineKind::MarketData, priority).await?;
+        Ok(line.attach(self.client.market_data(contract, generic_ticks, snapshot, regulatory_snapshot).await?))
+    }
+
+    /// Requests market depth within the budget. See [Client::market_depth].
+    pub async fn market_depth(
+        &self,
+        contract: &Contract,
+        number_of_rows: i32,
+        is_smart_depth: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<MarketDepths>, Error> {
+        let line = self.acquire(LineKind::MarketDepth, priority).await?;
+        Ok(line.attach(self.client.market_depth(contract, number_of_rows, is_smart_depth).await?))
+    }
+
+    /// Requests tick-by-tick trades within the budget. See [Client::tick_by_tick_last].
+    pub async fn tick_by_tick_last(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<Trade>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority).await?;
+        Ok(line.attach(self.client.tick_by_tick_last(contract, number_of_ticks, ignore_size).await?))
+    }
+
+    /// Requests tick-by-tick trades, including those outside the last price rules, within the budget. See
+    /// [Client::tick_by_tick_all_last].
+    pub async fn tick_by_tick_all_last(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<Trade>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority).await?;
+        Ok(line.attach(self.client.tick_by_tick_all_last(contract, number_of_ticks, ignore_size).await?))
+    }
+
+    /// Requests tick-by-tick quotes within the budget. See [Client::tick_by_tick_bid_ask].
+    pub async fn tick_by_tick_bid_ask(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<BidAsk>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority).await?;
+        Ok(line.attach(self.client.tick_by_tick_bid_ask(contract, number_of_ticks, ignore_size).await?))
+    }
+
+    /// Requests tick-by-tick midpoints within the budget. See [Client::tick_by_tick_midpoint].
+    pub async fn tick_by_tick_midpoint(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<MidPoint>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority).await?;
+        Ok(line.attach(self.client.tick_by_tick_midpoint(contract, number_of_ticks, ignore_size).await?))
+    }
+
+    /// Requests realtime bars within the budget. See [Client::realtime_bars].
+    pub async fn realtime_bars(
+        &self,
+        contract: &Contract,
+        bar_size: BarSize,
+        what_to_show: WhatToShow,
+        use_rth: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<Bar>, Error> {
+        let line = self.acquire(LineKind::RealtimeBars, priority).await?;
+        Ok(line.attach(self.client.realtime_bars(contract, bar_size, what_to_show, use_rth).await?))
+    }
+
+    async fn acquire(&self, kind: LineKind, priority: Priority) -> Result<Line, Error> {
+        let deadline = self.budget().wait().map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let released = {
+                let mut lines = self.shared.lines.lock().unwrap();
+                if let Some((id, evicted)) = lines.try_acquire(kind, priority) {
+                    drop(lines);
+                    if let Some(evicted) = evicted {
+                        debug!("evicting {kind} subscription for a {priority:?} priority request");
+                        evicted.evict();
+                        self.shared.released.notify_waiters();
+                    }
+                    return Ok(Line {
+                        id,
+                        kind,
+                        priority,
+                        shared: self.shared.clone(),
+                        eviction: Arc::new(Eviction::default()),
+                    });
+                }
+
+                match deadline {
+                    // Created while the lines are locked, so a release before the wait starts is not missed.
+                    Some(deadline) if Instant::now() < deadline => self.shared.released.notified(),
+                    _ => return Err(lines.budget().exhausted(kind)),
+                }
+            };
+
+            if let Some(deadline) = deadline {
+                let _ = tokio::time::timeout_at(deadline, released).await;
+            }
+        }
+    }
+}
+
+// A line taken from the budget, released when dropped.
+struct Line {
+    id: u64,
+    kind: LineKind,
+    priority: Priority,
+    shared: Arc<Shared>,
+    eviction: Arc<Eviction>,
+}
+
+impl Line {
+    fn attach<T>(self, subscription: Subscription<T>) -> ManagedSubscription<T> {
+        self.shared.lines.lock().unwrap().attach(self.id, self.eviction.clone());
+        ManagedSubscription { subscription, line: self }
+    }
+}
+
+impl Drop for Line {
+    fn drop(&mut self) {
+        if self.shared.lines.lock().unwrap().release(self.id) {
+            self.shared.released.notify_waiters();
+        }
+    }
+}
+
+/// A subscription holding a line of a [LineManager] until dropped.
+///
+/// Dereferences to the [Subscription]. An evicted subscription is cancelled the next time [ManagedSubscription::next]
+/// is called, which then returns `None`.
+pub struct ManagedSubscription<T> {
+    // Declared first to cancel the subscription before the line is released.
+    subscription: Subscription<T>,
+    line: Line,
+}
+
+impl<T: 'static> ManagedSubscription<T> {
+    /// Waits for the next value. Returns `None` once the subscription ends or its line is evicted.
+    pub async fn next(&mut self) -> Option<Result<T, Error>> {
+        if !self.line.eviction.is_evicted() {
+            tokio::select! {
+                biased;
+                _ = self.line.eviction.notify.notified() => {}
+                item = self.subscription.next() => return item,
+            }
+        }
+        self.subscription.cancel().await;
+        None
+    }
+}
+
+impl<T> ManagedSubscription<T> {
+    /// Returns the kind of line held.
+    pub fn kind(&self) -> LineKind {
+        self.line.kind
+    }
+
+    /// Returns the priority of the subscription.
+    pub fn priority(&self) -> Priority {
+        self.line.priority
+    }
+
+    /// Returns true if the line was taken by a request of a higher priority.
+    pub fn is_evicted(&self) -> bool {
+        self.line.eviction.is_evicted()
+    }
+}
+
+impl<T> Deref for ManagedSubscription<T> {
+    type Target = Subscription<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.subscription
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::market_data::lines::WhenExhausted;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client() -> Client {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+        Client::stubbed(message_bus, server_versions::SIZE_RULES)
+    }
+
+    #[tokio::test]
+    async fn test_line_manager() {
+        let client = client();
+        let lines = LineManager::new(&client, LineBudget::default().market_data(2));
+        let contract = Contract::stock("AAPL");
+
+        let mut watchlist = lines.market_data(&contract, &[], false, false, Priority::Low).await.unwrap();
+        let portfolio = lines.market_data(&contract, &[], false, false, Priority::Normal).await.unwrap();
+        assert_eq!(lines.available(LineKind::MarketData), 0);
+
+        let error = lines.market_data(&contract, &[], false, false, Priority::Low).await.err().unwrap();
+        assert!(matches!(error, Error::LinesExhausted(LineKind::MarketData, 2)), "{error}");
+
+        let position = lines.market_data(&contract, &[], false, false, Priority::High).await.unwrap();
+        assert!(watchlist.is_evicted());
+        assert!(watchlist.next().await.is_none(), "evicted subscription ends");
+        assert!(!portfolio.is_evicted());
+        assert_eq!(position.kind(), LineKind::MarketData);
+
+        drop(watchlist);
+        assert_eq!(lines.in_use(LineKind::MarketData), 2, "evicted line already released");
+
+        drop(portfolio);
+        assert_eq!(lines.available(LineKind::MarketData), 1);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_line() {
+        let client = client();
+        let budget = LineBudget::default()
+            .market_depth(1)
+            .when_exhausted(WhenExhausted::Wait(Duration::from_secs(5)));
+        let lines = LineManager::new(&client, budget);
+        let contract = Contract::stock("AAPL");
+
+        let depth = lines.market_depth(&contract, 5, false, Priority::Normal).await.unwrap();
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(depth);
+        };
+        let (waiting, _) = tokio::join!(lines.market_depth(&contract, 5, false, Priority::Normal), release);
+        assert_eq!(waiting.unwrap().kind(), LineKind::MarketDepth);
+
+        let budget = budget.when_exhausted(WhenExhausted::Wait(Duration::from_millis(20)));
+        let lines = LineManager::new(&client, budget);
+        let _depth = lines.market_depth(&contract, 5, false, Priority::Normal).await.unwrap();
+        assert!(lines.market_depth(&contract, 5, false, Priority::Normal).await.is_err());
+    }
+}
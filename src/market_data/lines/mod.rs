@@ -0,0 +1,280 @@
+//! Budget of market data lines.
+//!
+//! TWS limits how many market data lines, market depth and tick-by-tick subscriptions an account holds at once. Requests
+//! beyond the limit start normally and then fail with error 101 or 322. A `LineManager` counts the subscriptions it
+//! opens against a [LineBudget], and refuses or holds back requests that would exceed it.
+//!
+//! Each subscription holds its line until it is dropped. When the budget of a kind is in use, a request evicts the newest
+//! subscription of a lower [Priority], so quotes of a watchlist yield their lines to the contracts of open positions.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use sync::{LineManager, ManagedSubscription};
+
+#[cfg(feature = "async")]
+pub use r#async::{LineManager, ManagedSubscription};
+
+/// Kind of subscription counted against a budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    /// `market_data` subscriptions, including snapshots.
+    MarketData,
+    /// `market_depth` subscriptions.
+    MarketDepth,
+    /// `tick_by_tick_*` subscriptions.
+    TickByTick,
+    /// `realtime_bars` subscriptions.
+    RealtimeBars,
+}
+
+impl fmt::Display for LineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::MarketData => "market data",
+            Self::MarketDepth => "market depth",
+            Self::TickByTick => "tick-by-tick",
+            Self::RealtimeBars => "realtime bars",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Priority of a subscription. A request may evict subscriptions of a lower priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// What a request does when its budget is in use and no subscription can be evicted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhenExhausted {
+    /// Fails with [Error::LinesExhausted].
+    #[default]
+    Reject,
+    /// Waits up to the given time for a line to be released, then fails with [Error::LinesExhausted].
+    Wait(Duration),
+}
+
+/// Number of subscriptions of each kind a `LineManager` may open.
+///
+/// Defaults to 100 market data lines, 3 market depth, 5 tick-by-tick and 50 realtime bars subscriptions, the limits of
+/// an account with the standard allowance of market data lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineBudget {
+    market_data: usize,
+    market_depth: usize,
+    tick_by_tick: usize,
+    realtime_bars: usize,
+    when_exhausted: WhenExhausted,
+}
+
+impl Default for LineBudget {
+    fn default() -> Self {
+        Self {
+            market_data: 100,
+            market_depth: 3,
+            tick_by_tick: 5,
+            realtime_bars: 50,
+            when_exhausted: WhenExhausted::Reject,
+        }
+    }
+}
+
+impl LineBudget {
+    /// Sets the number of market data lines. Defaults to 100.
+    pub fn market_data(mut self, lines: usize) -> Self {
+        self.market_data = lines;
+        self
+    }
+
+    /// Sets the number of market depth subscriptions. Defaults to 3.
+    pub fn market_depth(mut self, subscriptions: usize) -> Self {
+        self.market_depth = subscriptions;
+        self
+    }
+
+    /// Sets the number of tick-by-tick subscriptions. Defaults to 5.
+    pub fn tick_by_tick(mut self, subscriptions: usize) -> Self {
+        self.tick_by_tick = subscriptions;
+        self
+    }
+
+    /// Sets the number of realtime bars subscriptions. Defaults to 50.
+    pub fn realtime_bars(mut self, subscriptions: usize) -> Self {
+        self.realtime_bars = subscriptions;
+        self
+    }
+
+    /// Sets what a request does when its budget is in use. Defaults to [WhenExhausted::Reject].
+    pub fn when_exhausted(mut self, when_exhausted: WhenExhausted) -> Self {
+        self.when_exhausted = when_exhausted;
+        self
+    }
+
+    /// Returns the number of subscriptions of a kind.
+    pub fn limit(&self, kind: LineKind) -> usize {
+        match kind {
+            LineKind::MarketData => self.market_data,
+            LineKind::MarketDepth => self.market_depth,
+            LineKind::TickByTick => self.tick_by_tick,
+            LineKind::RealtimeBars => self.realtime_bars,
+        }
+    }
+
+    pub(crate) fn wait(&self) -> Option<Duration> {
+        match self.when_exhausted {
+            WhenExhausted::Reject => None,
+            WhenExhausted::Wait(timeout) => Some(timeout),
+        }
+    }
+
+    pub(crate) fn exhausted(&self, kind: LineKind) -> Error {
+        Error::LinesExhausted(kind, self.limit(kind))
+    }
+}
+
+#[derive(Debug)]
+struct Line<H> {
+    id: u64,
+    kind: LineKind,
+    priority: Priority,
+    // Set once the subscription holding the line is open. Lines without a handle cannot be evicted.
+    handle: Option<H>,
+}
+
+/// Lines in use, with a handle to evict the subscription holding each.
+#[derive(Debug)]
+pub(crate) struct Lines<H> {
+    budget: LineBudget,
+    lines: Vec<Line<H>>,
+    next_id: u64,
+}
+
+impl<H> Lines<H> {
+    pub(crate) fn new(budget: LineBudget) -> Self {
+        Self {
+            budget,
+            lines: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub(crate) fn budget(&self) -> &LineBudget {
+        &self.budget
+    }
+
+    pub(crate) fn in_use(&self, kind: LineKind) -> usize {
+        self.lines.iter().filter(|line| line.kind == kind).count()
+    }
+
+    /// Takes a line if one is free or can be evicted. Returns its id and the handle of the evicted subscription.
+    pub(crate) fn try_acquire(&mut self, kind: LineKind, priority: Priority) -> Option<(u64, Option<H>)> {
+        let mut evicted = None;
+        if self.in_use(kind) >= self.budget.limit(kind) {
+            // The newest subscription of the lowest priority below the request.
+            let victim = self
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.kind == kind && line.priority < priority && line.handle.is_some())
+                .min_by_key(|(_, line)| (line.priority, std::cmp::Reverse(line.id)))
+                .map(|(index, _)| index)?;
+            evicted = self.lines.remove(victim).handle;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.lines.push(Line {
+            id,
+            kind,
+            priority,
+            handle: None,
+        });
+        Some((id, evicted))
+    }
+
+    /// Sets the handle used to evict the subscription holding a line.
+    pub(crate) fn attach(&mut self, id: u64, handle: H) {
+        if let Some(line) = self.lines.iter_mut().find(|line| line.id == id) {
+            line.handle = Some(handle);
+        }
+    }
+
+    /// Releases a line. Returns false if it was already evicted.
+    pub(crate) fn release(&mut self, id: u64) -> bool {
+        let count = self.lines.len();
+        self.lines.retain(|line| line.id != id);
+        self.lines.len() < count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget() {
+        let mut lines: Lines<()> = Lines::new(LineBudget::default().market_depth(2));
+
+        let (first, _) = lines.try_acquire(LineKind::MarketDepth, Priority::Normal).unwrap();
+        lines.try_acquire(LineKind::MarketDepth, Priority::Normal).unwrap();
+        assert!(lines.try_acquire(LineKind::MarketDepth, Priority::Normal).is_none());
+        assert!(
+            lines.try_acquire(LineKind::MarketData, Priority::Normal).is_some(),
+            "kinds are counted apart"
+        );
+        assert_eq!(lines.in_use(LineKind::MarketDepth), 2);
+
+        assert!(lines.release(first));
+        assert!(!lines.release(first));
+        assert!(lines.try_acquire(LineKind::MarketDepth, Priority::Normal).is_some());
+    }
+
+    #[test]
+    fn test_priority_eviction() {
+        let mut lines = Lines::new(LineBudget::default().market_data(3));
+
+        let mut acquire = |priority, handle: &'static str| {
+            let (id, evicted) = lines.try_acquire(LineKind::MarketData, priority)?;
+            lines.attach(id, handle);
+            Some(evicted)
+        };
+
+        assert_eq!(acquire(Priority::Low, "watchlist 1"), Some(None));
+        assert_eq!(acquire(Priority::Low, "watchlist 2"), Some(None));
+        assert_eq!(acquire(Priority::Normal, "portfolio"), Some(None));
+
+        assert_eq!(
+            acquire(Priority::High, "position 1"),
+            Some(Some("watchlist 2")),
+            "newest of the lowest priority"
+        );
+        assert_eq!(acquire(Priority::High, "position 2"), Some(Some("watchlist 1")));
+        assert_eq!(acquire(Priority::High, "position 3"), Some(Some("portfolio")));
+        assert_eq!(acquire(Priority::High, "position 4"), None, "equal priorities are not evicted");
+    }
+
+    #[test]
+    fn test_pending_lines_are_not_evicted() {
+        let mut lines: Lines<()> = Lines::new(LineBudget::default().tick_by_tick(1));
+
+        lines.try_acquire(LineKind::TickByTick, Priority::Low).unwrap();
+        assert!(lines.try_acquire(LineKind::TickByTick, Priority::High).is_none());
+
+        let error = lines.budget().exhausted(LineKind::TickByTick);
+        assert_eq!(error.to_string(), "no tick-by-tick lines available, all 1 in use");
+    }
+}
@@ -0,0 +1,363 @@
+//! Line budget for the blocking client.
+
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::Instant;
+
+use log::debug;
+
+use super::{LineBudget, LineKind, Lines, Priority};
+use crate::client::sync::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{Bar, BarSize, BidAsk, MarketDepths, MidPoint, TickTypes, Trade, WhatToShow};
+use crate::subscriptions::sync::Subscription;
+use crate::subscriptions::StreamDecoder;
+use crate::Error;
+
+trait Evict: Send + Sync {
+    fn evict(&self);
+}
+
+#[allow(private_bounds)]
+impl<T: StreamDecoder<T> + Send + Sync + 'static> Evict for Subscription<'_, T> {
+    fn evict(&self) {
+        self.cancel();
+    }
+}
+
+struct Evictor<'a> {
+    subscription: Weak<dyn Evict + 'a>,
+    evicted: Arc<AtomicBool>,
+}
+
+impl Evictor<'_> {
+    fn evict(self) {
+        self.evicted.store(true, Ordering::Relaxed);
+        if let Some(subscription) = self.subscription.upgrade() {
+            subscription.evict();
+        }
+    }
+}
+
+struct Shared<'a> {
+    lines: Mutex<Lines<Evictor<'a>>>,
+    released: Condvar,
+}
+
+/// Opens market data subscriptions within a [LineBudget].
+///
+/// Each request takes a line of its kind, held until the returned [ManagedSubscription] is dropped. When every line is
+/// in use, the request evicts the newest subscription of a lower priority, which is cancelled and ends. Otherwise it
+/// fails with [Error::LinesExhausted], after waiting for a line if the budget is set to
+/// [WhenExhausted::Wait](super::WhenExhausted::Wait).
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::blocking::LineManager;
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::lines::{LineBudget, Priority};
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+/// let lines = LineManager::new(&client, LineBudget::default().market_data(2));
+///
+/// let watchlist = lines.market_data(&Contract::stock("MSFT"), &[], false, false, Priority::Low).expect("request failed");
+/// let position = lines.market_data(&Contract::stock("AAPL"), &[], false, false, Priority::High).expect("request failed");
+///
+/// for tick in position.iter().take(10) {
+///     println!("{tick:?}");
+/// }
+/// println!("watchlist evicted: {}", watchlist.is_evicted());
+/// ```
+pub struct LineManager<'a> {
+    client: &'a Client,
+    shared: Arc<Shared<'a>>,
+}
+
+impl<'a> LineManager<'a> {
+    /// Creates a line manager for a client.
+    pub fn new(client: &'a Client, budget: LineBudget) -> Self {
+        Self {
+            client,
+            shared: Arc::new(Shared {
+                lines: Mutex::new(Lines::new(budget)),
+                released: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Returns the budget.
+    pub fn budget(&self) -> LineBudget {
+        *self.shared.lines.lock().unwrap().budget()
+    }
+
+    /// Returns the number of lines of a kind in use.
+    pub fn in_use(&self, kind: LineKind) -> usize {
+        self.shared.lines.lock().unwrap().in_use(kind)
+    }
+
+    /// Returns the number of lines of a kind available.
+    pub fn available(&self, kind: LineKind) -> usize {
+        let lines = self.shared.lines.lock().unwrap();
+        lines.budget().limit(kind).saturating_sub(lines.in_use(kind))
+    }
+
+    /// Requests market data within the budget. See [Client::market_data].
+    pub fn market_data(
+        &self,
+        contract: &Contract,
+        generic_ticks: &[&str],
+        snapshot: bool,
+        regulatory_snapshot: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, TickTypes>, Error> {
+        let line = self.acquire(LineKind::MarketData, priority)?;
+        Ok(line.attach(self.client.market_data(contract, generic_ticks, snapshot, regulatory_snapshot)?))
+    }
+
+    /// Requests market depth within the budget. See [Client::market_depth].
+    pub fn market_depth(
+        &self,
+        contract: &Contract,
+        number_of_rows: i32,
+        is_smart_depth: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, MarketDepths>, Error> {
+        let line = self.acquire(LineKind::MarketDepth, priority)?;
+        Ok(line.attach(self.client.market_depth(contract, number_of_rows, is_smart_depth)?))
+    }
+
+    /// Requests tick-by-tick trades within the budget. See [Client::tick_by_tick_last].
+    pub fn tick_by_tick_last(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, Trade>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority)?;
+        Ok(line.attach(self.client.tick_by_tick_last(contract, number_of_ticks, ignore_size)?))
+    }
+
+    /// Requests tick-by-tick trades, including those outside the last price rules, within the budget. See
+    /// [Client::tick_by_tick_all_last].
+    pub fn tick_by_tick_all_last(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, Trade>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority)?;
+        Ok(line.attach(self.client.tick_by_tick_all_last(contract, number_of_ticks, ignore_size)?))
+    }
+
+    /// Requests tick-by-tick quotes within the budget. See [Client::tick_by_tick_bid_ask].
+    pub fn tick_by_tick_bid_ask(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, BidAsk>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority)?;
+        Ok(line.attach(self.client.tick_by_tick_bid_ask(contract, number_of_ticks, ignore_size)?))
+    }
+
+    /// Requests tick-by-tick midpoints within the budget. See [Client::tick_by_tick_midpoint].
+    pub fn tick_by_tick_midpoint(
+        &self,
+        contract: &Contract,
+        number_of_ticks: i32,
+        ignore_size: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, MidPoint>, Error> {
+        let line = self.acquire(LineKind::TickByTick, priority)?;
+        Ok(line.attach(self.client.tick_by_tick_midpoint(contract, number_of_ticks, ignore_size)?))
+    }
+
+    /// Requests realtime bars within the budget. See [Client::realtime_bars].
+    pub fn realtime_bars(
+        &self,
+        contract: &Contract,
+        bar_size: BarSize,
+        what_to_show: WhatToShow,
+        use_rth: bool,
+        priority: Priority,
+    ) -> Result<ManagedSubscription<'a, Bar>, Error> {
+        let line = self.acquire(LineKind::RealtimeBars, priority)?;
+        Ok(line.attach(self.client.realtime_bars(contract, bar_size, what_to_show, use_rth)?))
+    }
+
+    fn acquire(&self, kind: LineKind, priority: Priority) -> Result<Line<'a>, Error> {
+        let mut lines = self.shared.lines.lock().unwrap();
+        let deadline = lines.budget().wait().map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some((id, evicted)) = lines.try_acquire(kind, priority) {
+                drop(lines);
+                if let Some(evicted) = evicted {
+                    debug!("evicting {kind} subscription for a {priority:?} priority request");
+                    evicted.evict();
+                    self.shared.released.notify_all();
+                }
+                return Ok(Line {
+                    id,
+                    kind,
+                    priority,
+                    shared: self.shared.clone(),
+                    evicted: Arc::new(AtomicBool::new(false)),
+                });
+            }
+
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            match remaining {
+                Some(remaining) if !remaining.is_zero() => {
+                    lines = self.shared.released.wait_timeout(lines, remaining).unwrap().0;
+                }
+                _ => return Err(lines.budget().exhausted(kind)),
+            }
+        }
+    }
+}
+
+// A line taken from the budget, released when dropped.
+struct Line<'a> {
+    id: u64,
+    kind: LineKind,
+    priority: Priority,
+    shared: Arc<Shared<'a>>,
+    evicted: Arc<AtomicBool>,
+}
+
+impl<'a> Line<'a> {
+    #[allow(private_bounds)]
+    fn attach<T: StreamDecoder<T> + Send + Sync + 'static>(self, subscription: Subscription<'a, T>) -> ManagedSubscription<'a, T> {
+        let subscription = Arc::new(subscription);
+        let handle: Arc<dyn Evict + 'a> = subscription.clone();
+        self.shared.lines.lock().unwrap().attach(
+            self.id,
+            Evictor {
+                subscription: Arc::downgrade(&handle),
+                evicted: self.evicted.clone(),
+            },
+        );
+        ManagedSubscription { subscription, line: self }
+    }
+}
+
+impl Drop for Line<'_> {
+    fn drop(&mut self) {
+        if self.shared.lines.lock().unwrap().release(self.id) {
+            self.shared.released.notify_all();
+        }
+    }
+}
+
+/// A subscription holding a line of a [LineManager] until dropped.
+///
+/// Dereferences to the [Subscription]. An evicted subscription is cancelled, so iterating it ends.
+#[allow(private_bounds)]
+pub struct ManagedSubscription<'a, T: StreamDecoder<T> + 'static> {
+    // Declared first to cancel the subscription before the line is released.
+    subscription: Arc<Subscription<'a, T>>,
+    line: Line<'a>,
+}
+
+#[allow(private_bounds)]
+impl<T: StreamDecoder<T> + 'static> ManagedSubscription<'_, T> {
+    /// Returns the kind of line held.
+    pub fn kind(&self) -> LineKind {
+        self.line.kind
+    }
+
+    /// Returns the priority of the subscription.
+    pub fn priority(&self) -> Priority {
+        self.line.priority
+    }
+
+    /// Returns true if the line was taken by a request of a higher priority.
+    pub fn is_evicted(&self) -> bool {
+        self.line.evicted.load(Ordering::Relaxed)
+    }
+}
+
+#[allow(private_bounds)]
+impl<'a, T: StreamDecoder<T> + 'static> Deref for ManagedSubscription<'a, T> {
+    type Target = Subscription<'a, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.subscription
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::market_data::lines::WhenExhausted;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client() -> Client {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+        Client::stubbed(message_bus, server_versions::SIZE_RULES)
+    }
+
+    #[test]
+    fn test_line_manager() {
+        let client = client();
+        let lines = LineManager::new(&client, LineBudget::default().market_data(2));
+        let contract = Contract::stock("AAPL");
+
+        let watchlist = lines.market_data(&contract, &[], false, false, Priority::Low).unwrap();
+        let portfolio = lines.market_data(&contract, &[], false, false, Priority::Normal).unwrap();
+        assert_eq!(lines.available(LineKind::MarketData), 0);
+
+        let error = lines.market_data(&contract, &[], false, false, Priority::Low).err().unwrap();
+        assert!(matches!(error, Error::LinesExhausted(LineKind::MarketData, 2)), "{error}");
+
+        let position = lines.market_data(&contract, &[], false, false, Priority::High).unwrap();
+        assert!(watchlist.is_evicted());
+        assert!(watchlist.next().is_none(), "evicted subscription ends");
+        assert!(!portfolio.is_evicted());
+        assert_eq!(position.kind(), LineKind::MarketData);
+
+        drop(watchlist);
+        assert_eq!(lines.in_use(LineKind::MarketData), 2, "evicted line already released");
+
+        drop(portfolio);
+        assert_eq!(lines.available(LineKind::MarketData), 1);
+        assert_eq!(lines.in_use(LineKind::TickByTick), 0);
+    }
+
+    #[test]
+    fn test_wait_for_line() {
+        let client = client();
+        let budget = LineBudget::default()
+            .market_depth(1)
+            .when_exhausted(WhenExhausted::Wait(Duration::from_secs(5)));
+        let lines = LineManager::new(&client, budget);
+        let contract = Contract::stock("AAPL");
+
+        let depth = lines.market_depth(&contract, 5, false, Priority::Normal).unwrap();
+        std::thread::scope(|scope| {
+            let waiting = scope.spawn(|| lines.market_depth(&contract, 5, false, Priority::Normal).map(|depth| depth.kind()));
+            std::thread::sleep(Duration::from_millis(50));
+            drop(depth);
+            assert_eq!(waiting.join().unwrap().unwrap(), LineKind::MarketDepth);
+        });
+
+        let budget = budget.when_exhausted(WhenExhausted::Wait(Duration::from_millis(20)));
+        let lines = LineManager::new(&client, budget);
+        let _depth = lines.market_depth(&contract, 5, false, Priority::Normal).unwrap();
+        assert!(lines.market_depth(&contract, 5, false, Priority::Normal).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Bar aggregation for the async client.
+
+use std::collections::VecDeque;
+
+use super::{BarAggregator, Sample};
+use crate::market_data::realtime::Bar;
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+/// Bars built by a [BarAggregator] from a realtime bar or tick-by-tick subscription.
+///
+/// The bar being built is returned when the subscription ends.
+///
+/// # Examples
+///
+/// ```no_run
+/// use time::Duration;
+///
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::{AggregatedBars, BarAggregator, BarSpec};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let contract = Contract::stock("AAPL");
+///     let subscription = client.tick_by_tick_last(&contract, 0, false).await.expect("request failed");
+///
+///     let aggregator = BarAggregator::new(BarSpec::Volume(10_000.0)).expect("invalid bar spec");
+///     let mut bars = AggregatedBars::new(subscription, aggregator);
+///     while let Some(bar) = bars.next().await {
+///         println!("{:?}", bar.expect("tick-by-tick error"));
+///     }
+/// }
+/// ```
+pub struct AggregatedBars<T> {
+    subscription: Subscription<T>,
+    aggregator: BarAggregator,
+    pending: VecDeque<Bar>,
+    ended: bool,
+}
+
+impl<T> AggregatedBars<T>
+where
+    T: 'static,
+    for<'b> &'b T: Into<Sample>,
+{
+    /// Builds bars from the items of `subscription`.
+    pub fn new(subscription: Subscription<T>, aggregator: BarAggregator) -> Self {
+        Self {
+            subscription,
+            aggregator,
+            pending: VecDeque::new(),
+            ended: false,
+        }
+    }
+
+    /// Returns the aggregator, holding the bar being built.
+    pub fn aggregator(&self) -> &BarAggregator {
+        &self.aggregator
+    }
+
+    /// Waits for the next closed bar. Returns `None` once the subscription ends and the last bar was returned.
+    pub async fn next(&mut self) -> Option<Result<Bar, Error>> {
+        while self.pending.is_empty() {
+            if self.ended {
+                return None;
+            }
+            match self.subscription.next().await {
+                Some(Ok(item)) => self.pending.extend(self.aggregator.push(&item)),
+                Some(Err(err)) => return Some(Err(err)),
+                None => {
+                    self.ended = true;
+                    self.pending.extend(self.aggregator.flush());
+                }
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+
+    /// Cancels the subscription.
+    pub async fn cancel(&self) {
+        self.subscription.cancel().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::BarSpec;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+    use crate::Client;
+
+    #[tokio::test]
+    async fn test_aggregated_trades() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "99|9001|1|1678740829|3895.25|7|2|NASDAQ||".to_owned(),
+                "99|9001|1|1678740830|3895.50|5|0|NASDAQ||".to_owned(),
+                "99|9001|1|1678740831|3895.00|3|0|NASDAQ||".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus, server_versions::TICK_BY_TICK);
+
+        let subscription = client
+            .tick_by_tick_last(&Contract::stock("AAPL"), 0, false)
+            .await
+            .expect("tick-by-tick request failed");
+        let mut bars = AggregatedBars::new(subscription, BarAggregator::new(BarSpec::Volume(10.0)).unwrap());
+
+        let bar = bars.next().await.expect("no bar").expect("tick-by-tick error");
+        assert_eq!(bar.date, datetime!(2023-03-13 20:53:49 UTC));
+        assert_eq!(bar.volume, 12.0);
+        assert_eq!(bar.close, 3895.50);
+        assert_eq!(bar.count, 2);
+    }
+}
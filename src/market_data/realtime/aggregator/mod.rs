@@ -0,0 +1,443 @@
+//! Builds bars of any size from realtime bars or tick-by-tick data.
+//!
+//! IB only streams 5 second bars. A [BarAggregator] combines them, or tick-by-tick trades, midpoints and quotes,
+//! into time, tick, volume or dollar bars. Bars can be limited to the trading sessions of a
+//! [historical::Schedule](crate::market_data::historical::Schedule) or of
+//! [ContractDetails::trading_hours](crate::contracts::ContractDetails::trading_hours).
+
+use log::debug;
+use time::{Duration, OffsetDateTime};
+
+use super::{Bar, BidAsk, MidPoint, Trade};
+use crate::contracts::schedule::TradingSchedule;
+use crate::contracts::ContractDetails;
+use crate::market_data::historical::{Schedule, Session};
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// When a [BarAggregator] closes a bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarSpec {
+    /// Bars covering a fixed span of time, aligned to the session start or, without sessions, to the Unix epoch.
+    Time(Duration),
+    /// Bars closing once they hold the given number of trades.
+    Ticks(u32),
+    /// Bars closing once the given volume has traded.
+    Volume(f64),
+    /// Bars closing once the given value, volume times price, has traded.
+    Dollar(f64),
+}
+
+/// A piece of market data added to a bar.
+///
+/// Created from realtime [Bar]s and tick-by-tick [Trade]s, [MidPoint]s and [BidAsk]s. Quotes are added at the midpoint
+/// of the bid and ask, with no volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// When the sample starts.
+    pub time: OffsetDateTime,
+    /// When the sample ends. Ticks end when they start, realtime bars 5 seconds later.
+    pub end: OffsetDateTime,
+    /// Opening price.
+    pub open: f64,
+    /// Highest price.
+    pub high: f64,
+    /// Lowest price.
+    pub low: f64,
+    /// Closing price.
+    pub close: f64,
+    /// Volume traded.
+    pub volume: f64,
+    /// Volume weighted average price.
+    pub wap: f64,
+    /// Number of trades.
+    pub count: i32,
+}
+
+impl Sample {
+    fn tick(time: OffsetDateTime, price: f64, volume: f64) -> Self {
+        Self {
+            time,
+            end: time,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            wap: price,
+            count: 1,
+        }
+    }
+}
+
+impl From<&Bar> for Sample {
+    fn from(bar: &Bar) -> Self {
+        Self {
+            time: bar.date,
+            end: bar.date + Duration::seconds(5),
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            wap: bar.wap,
+            count: bar.count,
+        }
+    }
+}
+
+impl From<&Trade> for Sample {
+    fn from(trade: &Trade) -> Self {
+        Self::tick(trade.time, trade.price, trade.size)
+    }
+}
+
+impl From<&MidPoint> for Sample {
+    fn from(mid_point: &MidPoint) -> Self {
+        Self::tick(mid_point.time, mid_point.mid_point, 0.0)
+    }
+}
+
+impl From<&BidAsk> for Sample {
+    fn from(bid_ask: &BidAsk) -> Self {
+        Self::tick(bid_ask.time, (bid_ask.bid_price + bid_ask.ask_price) / 2.0, 0.0)
+    }
+}
+
+/// Combines [Sample]s into bars.
+///
+/// Time bars close once a sample reaches the end of the bar, or a sample for a later bar arrives. Tick, volume and
+/// dollar bars close once they reach their threshold, without splitting the sample that crosses it. With sessions,
+/// no bar spans two sessions and samples outside the sessions are ignored. The bar's `wap` is the volume weighted
+/// average price, or the close when no volume traded.
+///
+/// # Examples
+///
+/// ```
+/// use time::Duration;
+///
+/// use ibapi::market_data::realtime::{BarAggregator, BarSpec};
+///
+/// let aggregator = BarAggregator::new(BarSpec::Time(Duration::minutes(1))).expect("invalid bar spec");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    spec: BarSpec,
+    sessions: Vec<Session>,
+    current: Option<Building>,
+}
+
+#[derive(Debug, Clone)]
+struct Building {
+    bar: Bar,
+    session: Option<usize>,
+    value: f64,
+}
+
+impl Building {
+    fn new(sample: &Sample, date: OffsetDateTime, session: Option<usize>) -> Self {
+        Self {
+            bar: Bar {
+                date,
+                open: sample.open,
+                high: sample.high,
+                low: sample.low,
+                close: sample.close,
+                volume: sample.volume,
+                wap: sample.wap,
+                count: sample.count,
+            },
+            session,
+            value: sample.wap * sample.volume,
+        }
+    }
+
+    fn add(&mut self, sample: &Sample) {
+        self.bar.high = self.bar.high.max(sample.high);
+        self.bar.low = self.bar.low.min(sample.low);
+        self.bar.close = sample.close;
+        self.bar.volume += sample.volume;
+        self.bar.count += sample.count;
+        self.value += sample.wap * sample.volume;
+    }
+
+    fn finish(mut self) -> Bar {
+        self.bar.wap = if self.bar.volume > 0.0 {
+            self.value / self.bar.volume
+        } else {
+            self.bar.close
+        };
+        self.bar
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    session: Option<usize>,
+    start: OffsetDateTime,
+    end: Option<OffsetDateTime>,
+}
+
+impl BarAggregator {
+    /// Creates an aggregator building bars of `spec`. Returns an error if the size or threshold is not positive.
+    pub fn new(spec: BarSpec) -> Result<Self, Error> {
+        let valid = match spec {
+            BarSpec::Time(duration) => duration.is_positive(),
+            BarSpec::Ticks(ticks) => ticks > 0,
+            BarSpec::Volume(threshold) | BarSpec::Dollar(threshold) => threshold > 0.0,
+        };
+        if !valid {
+            return Err(Error::InvalidArgument(format!("bar spec must be positive: {spec:?}")));
+        }
+
+        Ok(Self {
+            spec,
+            sessions: Vec::new(),
+            current: None,
+        })
+    }
+
+    /// Limits bars to the given trading sessions.
+    pub fn with_sessions(mut self, sessions: impl IntoIterator<Item = Session>) -> Self {
+        self.sessions = sessions.into_iter().collect();
+        self.sessions.sort_by_key(|session| session.start);
+        self
+    }
+
+    /// Limits bars to the sessions of a schedule from `historical_schedules`.
+    pub fn with_schedule(self, schedule: &Schedule) -> Self {
+        self.with_sessions(schedule.sessions.iter().copied())
+    }
+
+    /// Limits bars to the trading hours of a contract, or its liquid hours if `use_rth` is set.
+    pub fn with_trading_hours(self, details: &ContractDetails, use_rth: bool) -> Result<Self, Error> {
+        let schedule = if use_rth {
+            TradingSchedule::liquid_hours(details)?
+        } else {
+            TradingSchedule::trading_hours(details)?
+        };
+        Ok(self.with_sessions(schedule.sessions().iter().copied()))
+    }
+
+    /// Returns the bar being built.
+    pub fn current(&self) -> Option<Bar> {
+        self.current.clone().map(Building::finish)
+    }
+
+    /// Adds a sample and returns the bars it closed.
+    pub fn push(&mut self, sample: impl Into<Sample>) -> Vec<Bar> {
+        let sample = sample.into();
+        let Some(window) = self.window(sample.time) else {
+            debug!("ignoring sample outside trading sessions at {}", sample.time);
+            return Vec::new();
+        };
+
+        let mut bars = Vec::new();
+        match self.spec {
+            BarSpec::Time(duration) => {
+                let (start, end) = bucket(&window, sample.time, duration);
+                match &mut self.current {
+                    Some(current) if current.bar.date == start => current.add(&sample),
+                    Some(current) if current.bar.date > start => {
+                        debug!("ignoring sample at {} for a closed bar", sample.time);
+                        return bars;
+                    }
+                    _ => {
+                        bars.extend(self.flush());
+                        self.current = Some(Building::new(&sample, start, window.session));
+                    }
+                }
+
+                if sample.end >= end {
+                    bars.extend(self.flush());
+                }
+            }
+            spec => {
+                match &mut self.current {
+                    Some(current) if current.session == window.session => current.add(&sample),
+                    _ => {
+                        bars.extend(self.flush());
+                        self.current = Some(Building::new(&sample, sample.time, window.session));
+                    }
+                }
+
+                let reached = self.current.as_ref().is_some_and(|current| match spec {
+                    BarSpec::Ticks(ticks) => current.bar.count >= ticks as i32,
+                    BarSpec::Volume(threshold) => current.bar.volume >= threshold,
+                    BarSpec::Dollar(threshold) => current.value >= threshold,
+                    BarSpec::Time(_) => false,
+                });
+                if reached {
+                    bars.extend(self.flush());
+                }
+            }
+        }
+        bars
+    }
+
+    /// Closes the bar being built and returns it.
+    pub fn flush(&mut self) -> Option<Bar> {
+        self.current.take().map(Building::finish)
+    }
+
+    fn window(&self, time: OffsetDateTime) -> Option<Window> {
+        if self.sessions.is_empty() {
+            return Some(Window {
+                session: None,
+                start: OffsetDateTime::UNIX_EPOCH,
+                end: None,
+            });
+        }
+
+        self.sessions
+            .iter()
+            .position(|session| session.start <= time && time < session.end)
+            .map(|i| Window {
+                session: Some(i),
+                start: self.sessions[i].start,
+                end: Some(self.sessions[i].end),
+            })
+    }
+}
+
+// Returns the start and end of the time bar holding `time`.
+fn bucket(window: &Window, time: OffsetDateTime, duration: Duration) -> (OffsetDateTime, OffsetDateTime) {
+    let span = duration.whole_nanoseconds();
+    let offset = (time - window.start).whole_nanoseconds().div_euclid(span) * span;
+    let start = window.start + Duration::nanoseconds_i128(offset);
+    let end = start + duration;
+    (start, window.end.map_or(end, |limit| end.min(limit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    fn trade(time: OffsetDateTime, price: f64, size: f64) -> Sample {
+        Sample::tick(time, price, size)
+    }
+
+    fn five_second_bar(date: OffsetDateTime, close: f64, volume: f64) -> Bar {
+        Bar {
+            date,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            wap: close,
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn test_time_bars_from_realtime_bars() {
+        let mut aggregator = BarAggregator::new(BarSpec::Time(Duration::minutes(1))).unwrap();
+
+        let start = datetime!(2024-03-01 14:30:00 UTC);
+        let mut bars = Vec::new();
+        for i in 0..12 {
+            let bar = five_second_bar(start + Duration::seconds(5 * i), 100.0 + i as f64, 10.0);
+            bars.extend(aggregator.push(&bar));
+        }
+
+        assert_eq!(bars.len(), 1, "the last 5 second bar closes the minute");
+        let bar = &bars[0];
+        assert_eq!(bar.date, start);
+        assert_eq!(bar.open, 100.0);
+        assert_eq!(bar.high, 111.0);
+        assert_eq!(bar.low, 100.0);
+        assert_eq!(bar.close, 111.0);
+        assert_eq!(bar.volume, 120.0);
+        assert_eq!(bar.wap, 105.5);
+        assert_eq!(bar.count, 12);
+        assert!(aggregator.current().is_none());
+    }
+
+    #[test]
+    fn test_time_bars_from_ticks() {
+        let mut aggregator = BarAggregator::new(BarSpec::Time(Duration::minutes(15))).unwrap();
+
+        assert!(aggregator.push(trade(datetime!(2024-03-01 14:31:00 UTC), 10.0, 1.0)).is_empty());
+        assert!(aggregator.push(trade(datetime!(2024-03-01 14:44:59 UTC), 12.0, 3.0)).is_empty());
+
+        let bars = aggregator.push(trade(datetime!(2024-03-01 15:20:00 UTC), 11.0, 1.0));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].date, datetime!(2024-03-01 14:30:00 UTC));
+        assert_eq!(bars[0].close, 12.0);
+        assert_eq!(bars[0].wap, 11.5);
+
+        assert!(
+            aggregator.push(trade(datetime!(2024-03-01 14:50:00 UTC), 9.0, 1.0)).is_empty(),
+            "late tick ignored"
+        );
+        assert_eq!(aggregator.flush().map(|bar| bar.date), Some(datetime!(2024-03-01 15:15:00 UTC)));
+    }
+
+    #[test]
+    fn test_threshold_bars() {
+        let mut ticks = BarAggregator::new(BarSpec::Ticks(2)).unwrap();
+        let time = datetime!(2024-03-01 14:30:00 UTC);
+        assert!(ticks.push(trade(time, 10.0, 5.0)).is_empty());
+        assert_eq!(ticks.push(trade(time, 11.0, 5.0)).len(), 1);
+
+        let mut volume = BarAggregator::new(BarSpec::Volume(100.0)).unwrap();
+        assert!(volume.push(trade(time, 10.0, 60.0)).is_empty());
+        let bars = volume.push(trade(time + Duration::seconds(1), 10.5, 50.0));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].volume, 110.0);
+
+        let mut dollar = BarAggregator::new(BarSpec::Dollar(1000.0)).unwrap();
+        assert!(dollar.push(trade(time, 10.0, 50.0)).is_empty());
+        assert_eq!(dollar.push(trade(time, 10.0, 50.0)).len(), 1);
+
+        assert!(BarAggregator::new(BarSpec::Volume(0.0)).is_err());
+        assert!(BarAggregator::new(BarSpec::Time(Duration::ZERO)).is_err());
+    }
+
+    #[test]
+    fn test_sessions_bound_bars() {
+        let details = ContractDetails {
+            liquid_hours: vec![
+                "20240301:0930-20240301:1600".to_owned(),
+                "20240302:CLOSED".to_owned(),
+                "20240304:0930-20240304:1600".to_owned(),
+            ],
+            time_zone_id: "US/Eastern".to_owned(),
+            ..ContractDetails::default()
+        };
+        let sessions = TradingSchedule::liquid_hours(&details).unwrap().sessions().to_vec();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].reference, date!(2024 - 03 - 01));
+        assert_eq!(sessions[0].start, datetime!(2024-03-01 14:30:00 UTC));
+        assert_eq!(sessions[0].end, datetime!(2024-03-01 21:00:00 UTC));
+
+        let mut aggregator = BarAggregator::new(BarSpec::Time(Duration::hours(4)))
+            .unwrap()
+            .with_trading_hours(&details, true)
+            .unwrap();
+
+        assert!(aggregator.push(trade(datetime!(2024-03-01 14:00:00 UTC), 10.0, 1.0)).is_empty());
+        assert!(aggregator.current().is_none(), "pre-market tick ignored");
+
+        aggregator.push(trade(datetime!(2024-03-01 18:40:00 UTC), 10.0, 1.0));
+        assert_eq!(aggregator.current().map(|bar| bar.date), Some(datetime!(2024-03-01 18:30:00 UTC)));
+
+        let bars = aggregator.push(trade(datetime!(2024-03-04 14:35:00 UTC), 11.0, 1.0));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(aggregator.current().map(|bar| bar.date), Some(datetime!(2024-03-04 14:30:00 UTC)));
+
+        let mut volume = BarAggregator::new(BarSpec::Volume(100.0)).unwrap().with_sessions(sessions);
+        volume.push(trade(datetime!(2024-03-01 20:59:00 UTC), 10.0, 10.0));
+        let bars = volume.push(trade(datetime!(2024-03-04 14:30:00 UTC), 10.0, 10.0));
+        assert_eq!(bars.len(), 1, "volume bar closed at the session end");
+        assert_eq!(bars[0].volume, 10.0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Bar aggregation for the blocking client.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::{BarAggregator, Sample};
+use crate::market_data::realtime::Bar;
+use crate::subscriptions::sync::Subscription;
+use crate::subscriptions::StreamDecoder;
+use crate::Error;
+
+/// Bars built by a [BarAggregator] from a realtime bar or tick-by-tick subscription.
+///
+/// The bar being built is returned when the subscription ends.
+///
+/// # Examples
+///
+/// ```no_run
+/// use time::Duration;
+///
+/// use ibapi::blocking::AggregatedBars;
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::{BarAggregator, BarSize, BarSpec, WhatToShow};
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let contract = Contract::stock("AAPL");
+/// let subscription = client.realtime_bars(&contract, BarSize::Sec5, WhatToShow::Trades, false).expect("request failed");
+///
+/// let aggregator = BarAggregator::new(BarSpec::Time(Duration::minutes(1))).expect("invalid bar spec");
+/// for bar in AggregatedBars::new(subscription, aggregator) {
+///     println!("{bar:?}");
+/// }
+/// ```
+#[allow(private_bounds)]
+pub struct AggregatedBars<'a, T: StreamDecoder<T> + 'static> {
+    subscription: Subscription<'a, T>,
+    aggregator: BarAggregator,
+    pending: VecDeque<Bar>,
+    ended: bool,
+}
+
+#[allow(private_bounds)]
+impl<'a, T> AggregatedBars<'a, T>
+where
+    T: StreamDecoder<T> + 'static,
+    for<'b> &'b T: Into<Sample>,
+{
+    /// Builds bars from the items of `subscription`.
+    pub fn new(subscription: Subscription<'a, T>, aggregator: BarAggregator) -> Self {
+        Self {
+            subscription,
+            aggregator,
+            pending: VecDeque::new(),
+            ended: false,
+        }
+    }
+
+    /// Returns the aggregator, holding the bar being built.
+    pub fn aggregator(&self) -> &BarAggregator {
+        &self.aggregator
+    }
+
+    /// Adds the items already received and returns the next closed bar, if any.
+    pub fn try_next(&mut self) -> Option<Bar> {
+        while self.pending.is_empty() {
+            let item = self.subscription.try_next()?;
+            self.pending.extend(self.aggregator.push(&item));
+        }
+        self.pending.pop_front()
+    }
+
+    /// Waits up to `timeout` for the next closed bar.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Bar> {
+        let deadline = Instant::now() + timeout;
+        while self.pending.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let item = self.subscription.next_timeout(remaining)?;
+            self.pending.extend(self.aggregator.push(&item));
+        }
+        self.pending.pop_front()
+    }
+
+    /// Returns the error that ended the subscription, if any.
+    pub fn error(&self) -> Option<Error> {
+        self.subscription.error()
+    }
+
+    /// Cancels the subscription.
+    pub fn cancel(&self) {
+        self.subscription.cancel();
+    }
+}
+
+#[allow(private_bounds)]
+impl<T> Iterator for AggregatedBars<'_, T>
+where
+    T: StreamDecoder<T> + 'static,
+    for<'b> &'b T: Into<Sample>,
+{
+    type Item = Bar;
+
+    /// Blocks until a bar closes. Returns `None` once the subscription ends and the last bar was returned.
+    fn next(&mut self) -> Option<Bar> {
+        while self.pending.is_empty() {
+            if self.ended {
+                return None;
+            }
+            match self.subscription.next() {
+                Some(item) => self.pending.extend(self.aggregator.push(&item)),
+                None => {
+                    self.ended = true;
+                    self.pending.extend(self.aggregator.flush());
+                }
+            }
+        }
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::client::sync::Client;
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::{BarSize, BarSpec, WhatToShow};
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[test]
+    fn test_aggregated_realtime_bars() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "50|3|9001|1678323335|4028.75|4029.00|4028.25|4028.50|2|4026.75|1|".to_owned(),
+                "50|3|9001|1678323340|4028.50|4030.00|4028.25|4029.75|4|4029.25|2|".to_owned(),
+                "50|3|9001|1678323345|4029.75|4029.75|4027.50|4028.00|2|4028.00|1|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        let subscription = client
+            .realtime_bars(&Contract::stock("ES"), BarSize::Sec5, WhatToShow::Trades, false)
+            .expect("realtime bars request failed");
+        let aggregator = BarAggregator::new(BarSpec::Time(time::Duration::seconds(10))).unwrap();
+        let mut bars = AggregatedBars::new(subscription, aggregator);
+
+        let bar = bars.next().expect("no bar");
+        assert_eq!(bar.date, datetime!(2023-03-09 0:55:30 UTC));
+        assert_eq!(bar.open, 4028.75);
+        assert_eq!(bar.close, 4028.50);
+
+        let bar = bars.next().expect("no bar");
+        assert_eq!(bar.date, datetime!(2023-03-09 0:55:40 UTC));
+        assert_eq!(bar.high, 4030.00);
+        assert_eq!(bar.low, 4027.50);
+        assert_eq!(bar.volume, 6.0);
+        assert_eq!(bar.count, 3);
+    }
+}
@@ -2,9 +2,9 @@ use log::debug;
 
 use crate::client::ClientRequestBuilders;
 use crate::contracts::{Contract, TagValue};
-use crate::messages::{IncomingMessages, Notice, OutgoingMessages, ResponseMessage};
+use crate::messages::OutgoingMessages;
 use crate::protocol::{check_version, Features};
-use crate::subscriptions::{ResponseContext, StreamDecoder, Subscription};
+use crate::subscriptions::Subscription;
 use crate::{Client, Error};
 
 use super::common::{decoders, encoders};
@@ -12,149 +12,6 @@ use super::{Bar, BarSize, BidAsk, DepthMarketDataDescription, MarketDepths, MidP
 
 // === DataStream implementations ===
 
-impl StreamDecoder<BidAsk> for BidAsk {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => decoders::decode_bid_ask_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(
-        _server_version: i32,
-        request_id: Option<i32>,
-        _context: Option<&ResponseContext>,
-    ) -> Result<crate::messages::RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
-        encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
-impl StreamDecoder<MidPoint> for MidPoint {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => decoders::decode_mid_point_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(
-        _server_version: i32,
-        request_id: Option<i32>,
-        _context: Option<&ResponseContext>,
-    ) -> Result<crate::messages::RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
-        encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
-impl StreamDecoder<Bar> for Bar {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::RealTimeBars];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        decoders::decode_realtime_bar(message)
-    }
-
-    fn cancel_message(
-        _server_version: i32,
-        request_id: Option<i32>,
-        _context: Option<&ResponseContext>,
-    ) -> Result<crate::messages::RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        encoders::encode_cancel_realtime_bars(request_id)
-    }
-}
-
-impl StreamDecoder<Trade> for Trade {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => decoders::decode_trade_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(
-        _server_version: i32,
-        request_id: Option<i32>,
-        _context: Option<&ResponseContext>,
-    ) -> Result<crate::messages::RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
-        encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
-impl StreamDecoder<MarketDepths> for MarketDepths {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] =
-        &[IncomingMessages::MarketDepth, IncomingMessages::MarketDepthL2, IncomingMessages::Error];
-
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        use crate::messages;
-        match message.message_type() {
-            IncomingMessages::MarketDepth => Ok(MarketDepths::MarketDepth(decoders::decode_market_depth(message)?)),
-            IncomingMessages::MarketDepthL2 => Ok(MarketDepths::MarketDepthL2(decoders::decode_market_depth_l2(server_version, message)?)),
-            IncomingMessages::Error => {
-                let code = message.peek_int(messages::CODE_INDEX).unwrap();
-                if (2100..2200).contains(&code) {
-                    Ok(MarketDepths::Notice(Notice::from(message)))
-                } else {
-                    Err(Error::from(message.clone()))
-                }
-            }
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(
-        server_version: i32,
-        request_id: Option<i32>,
-        context: Option<&ResponseContext>,
-    ) -> Result<crate::messages::RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel market depth");
-        encoders::encode_cancel_market_depth(server_version, request_id, context.map(|c| c.is_smart_depth).unwrap_or(false))
-    }
-}
-
-impl StreamDecoder<TickTypes> for TickTypes {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[
-        IncomingMessages::TickPrice,
-        IncomingMessages::TickSize,
-        IncomingMessages::TickString,
-        IncomingMessages::TickEFP,
-        IncomingMessages::TickGeneric,
-        IncomingMessages::TickOptionComputation,
-        IncomingMessages::TickSnapshotEnd,
-        IncomingMessages::Error,
-        IncomingMessages::TickReqParams,
-    ];
-
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickPrice => Ok(decoders::decode_tick_price(server_version, message)?),
-            IncomingMessages::TickSize => Ok(TickTypes::Size(decoders::decode_tick_size(message)?)),
-            IncomingMessages::TickString => Ok(TickTypes::String(decoders::decode_tick_string(message)?)),
-            IncomingMessages::TickEFP => Ok(TickTypes::EFP(decoders::decode_tick_efp(message)?)),
-            IncomingMessages::TickGeneric => Ok(TickTypes::Generic(decoders::decode_tick_generic(message)?)),
-            IncomingMessages::TickOptionComputation => Ok(TickTypes::OptionComputation(decoders::decode_tick_option_computation(
-                server_version,
-                message,
-            )?)),
-            IncomingMessages::TickReqParams => Ok(TickTypes::RequestParameters(decoders::decode_tick_request_parameters(message)?)),
-            IncomingMessages::TickSnapshotEnd => Ok(TickTypes::SnapshotEnd),
-            IncomingMessages::Error => Ok(TickTypes::Notice(Notice::from(message))),
-            _ => Err(Error::NotImplemented),
-        }
-    }
-}
-
 // === Public API Functions ===
 
 /// Requests realtime bars.
@@ -303,6 +160,18 @@ pub async fn market_data(
     generic_ticks: &[&str],
     snapshot: bool,
     regulatory_snapshot: bool,
+) -> Result<Subscription<TickTypes>, Error> {
+    let _market_data_type = client.market_data_type_lock.read().await;
+    send_market_data_request(client, contract, generic_ticks, snapshot, regulatory_snapshot).await
+}
+
+// Sends the request without waiting for a fallback subscription switching the market data type.
+pub(crate) async fn send_market_data_request(
+    client: &Client,
+    contract: &Contract,
+    generic_ticks: &[&str],
+    snapshot: bool,
+    regulatory_snapshot: bool,
 ) -> Result<Subscription<TickTypes>, Error> {
     let builder = client.request();
     let request = encoders::encode_request_market_data(
@@ -421,10 +290,10 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestRealTimeBars.to_field(), "Wrong message type");
-        assert_eq!(request.fields[1], "8", "Wrong version");
-        assert_eq!(request.fields[16], what_to_show.to_field(), "Wrong what to show value");
-        assert_eq!(request.fields[17], use_rth.to_field(), "Wrong use RTH flag");
+        assert_eq!(&request[0], OutgoingMessages::RequestRealTimeBars.to_field(), "Wrong message type");
+        assert_eq!(&request[1], "8", "Wrong version");
+        assert_eq!(&request[16], what_to_show.to_field(), "Wrong what to show value");
+        assert_eq!(&request[17], use_rth.to_field(), "Wrong use RTH flag");
     }
 
     #[tokio::test]
@@ -481,12 +350,8 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(
-            request.fields[0],
-            OutgoingMessages::RequestTickByTickData.to_field(),
-            "Wrong message type"
-        );
-        assert_eq!(request.fields[14], "AllLast", "Wrong tick type");
+        assert_eq!(&request[0], OutgoingMessages::RequestTickByTickData.to_field(), "Wrong message type");
+        assert_eq!(&request[14], "AllLast", "Wrong tick type");
     }
 
     #[tokio::test]
@@ -530,7 +395,7 @@ mod tests {
         // Verify request message uses "Last" instead of "AllLast"
         let request_messages = message_bus.request_messages.read().unwrap();
         let request = &request_messages[0];
-        assert_eq!(request.fields[14], "Last", "Wrong tick type");
+        assert_eq!(&request[14], "Last", "Wrong tick type");
     }
 
     #[tokio::test]
@@ -575,7 +440,7 @@ mod tests {
         // Verify request message
         let request_messages = message_bus.request_messages.read().unwrap();
         let request = &request_messages[0];
-        assert_eq!(request.fields[14], "BidAsk", "Wrong tick type");
+        assert_eq!(&request[14], "BidAsk", "Wrong tick type");
     }
 
     #[tokio::test]
@@ -635,12 +500,8 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(
-            request.fields[0],
-            OutgoingMessages::RequestTickByTickData.to_field(),
-            "Wrong message type"
-        );
-        assert_eq!(request.fields[14], "MidPoint", "Wrong tick type");
+        assert_eq!(&request[0], OutgoingMessages::RequestTickByTickData.to_field(), "Wrong message type");
+        assert_eq!(&request[14], "MidPoint", "Wrong tick type");
     }
 
     #[tokio::test]
@@ -704,10 +565,10 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestMarketDepth.to_field(), "Wrong message type");
-        assert_eq!(request.fields[1], "5", "Wrong version");
-        assert_eq!(request.fields[14], number_of_rows.to_field(), "Wrong number of rows");
-        assert_eq!(request.fields[15], is_smart_depth.to_field(), "Wrong smart depth flag");
+        assert_eq!(&request[0], OutgoingMessages::RequestMarketDepth.to_field(), "Wrong message type");
+        assert_eq!(&request[1], "5", "Wrong version");
+        assert_eq!(&request[14], number_of_rows.to_field(), "Wrong number of rows");
+        assert_eq!(&request[15], is_smart_depth.to_field(), "Wrong smart depth flag");
     }
 
     #[tokio::test]
@@ -745,11 +606,7 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(
-            request.fields[0],
-            OutgoingMessages::RequestMktDepthExchanges.to_field(),
-            "Wrong message type"
-        );
+        assert_eq!(&request[0], OutgoingMessages::RequestMktDepthExchanges.to_field(), "Wrong message type");
     }
 
     #[tokio::test]
@@ -851,7 +708,7 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
+        assert_eq!(&request[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
     }
 
     #[tokio::test]
@@ -881,7 +738,7 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
+        assert_eq!(&request[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
     }
 
     #[tokio::test]
@@ -913,8 +770,8 @@ mod tests {
         assert_eq!(request_messages.len(), 1, "Should send one request message");
 
         let request = &request_messages[0];
-        assert_eq!(request.fields[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
-        assert_eq!(request.fields[17], regulatory_snapshot.to_field(), "Wrong regulatory snapshot flag");
+        assert_eq!(&request[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
+        assert_eq!(&request[17], regulatory_snapshot.to_field(), "Wrong regulatory snapshot flag");
     }
 
     #[tokio::test]
@@ -1,5 +1,6 @@
 use crate::contracts::decode_option_computation;
 use crate::contracts::OptionComputation;
+use crate::market_data::MarketDataType;
 use crate::Error;
 use crate::{messages::ResponseMessage, server_versions};
 
@@ -239,6 +240,19 @@ pub(crate) fn decode_tick_request_parameters(message: &mut ResponseMessage) -> R
     })
 }
 
+pub(crate) fn decode_market_data_type(message: &mut ResponseMessage) -> Result<MarketDataType, Error> {
+    message.skip(); // message type
+    message.skip(); // message version
+    message.skip(); // message request id
+    match message.next_int()? {
+        1 => Ok(MarketDataType::Live),
+        2 => Ok(MarketDataType::Frozen),
+        3 => Ok(MarketDataType::Delayed),
+        4 => Ok(MarketDataType::DelayedFrozen),
+        other => Err(Error::Simple(format!("unexpected market data type: {other}"))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -631,6 +645,15 @@ mod tests {
             assert_eq!(tick.value, 20.5, "Wrong value");
         }
 
+        #[test]
+        fn test_decode_market_data_type() {
+            let mut message = ResponseMessage::from("58\01\09000\03\0");
+            assert_eq!(decode_market_data_type(&mut message).unwrap(), MarketDataType::Delayed);
+
+            let mut message = ResponseMessage::from("58\01\09000\09\0");
+            assert!(decode_market_data_type(&mut message).is_err());
+        }
+
         #[test]
         fn test_decode_tick_generic_types() {
             let test_cases = vec![
@@ -262,7 +262,7 @@ mod tests {
             assert_eq!(message[13], contract.trading_class, "Wrong trading class");
 
             // Verify tick parameters
-            assert_eq!(message[14], tick_type, "Wrong tick type");
+            assert_eq!(&message[14], tick_type, "Wrong tick type");
 
             // Version specific fields
             if server_version >= server_versions::TICK_BY_TICK_IGNORE_SIZE {
@@ -305,7 +305,7 @@ mod tests {
             let message = encode_cancel_realtime_bars(request_id).expect("Failed to encode cancel realtime bars");
 
             assert_eq!(message[0], OutgoingMessages::CancelRealTimeBars.to_field(), "Wrong message type");
-            assert_eq!(message[1], "1", "Wrong version");
+            assert_eq!(&message[1], "1", "Wrong version");
             assert_eq!(message[2], request_id.to_string(), "Wrong request ID");
             assert_eq!(message.len(), 3, "Unexpected message length");
         }
@@ -332,7 +332,7 @@ mod tests {
 
             // Verify message structure
             assert_eq!(message[0], OutgoingMessages::RequestRealTimeBars.to_field(), "Wrong message type");
-            assert_eq!(message[1], "8", "Wrong version");
+            assert_eq!(&message[1], "8", "Wrong version");
             assert_eq!(message[2], request_id.to_field(), "Wrong request ID");
 
             // Verify contract fields
@@ -350,10 +350,10 @@ mod tests {
             assert_eq!(message[14], contract.trading_class, "Wrong trading class");
 
             // Verify bar parameters
-            assert_eq!(message[15], "0", "Wrong bar size");
+            assert_eq!(&message[15], "0", "Wrong bar size");
             assert_eq!(message[16], what_to_show.to_field(), "Wrong what to show value");
             assert_eq!(message[17], use_rth.to_field(), "Wrong use RTH flag");
-            assert_eq!(message[18], "", "Wrong options field");
+            assert_eq!(&message[18], "", "Wrong options field");
         }
 
         #[test]
@@ -379,7 +379,7 @@ mod tests {
             let message = encode_request_realtime_bars(server_version, request_id, &contract, &bar_size, &what_to_show, use_rth, options)
                 .expect("Failed to encode realtime bars request");
 
-            assert_eq!(message[18], "aggregateGroup=1;", "Wrong options encoding");
+            assert_eq!(&message[18], "aggregateGroup=1;", "Wrong options encoding");
         }
     }
 
@@ -401,14 +401,14 @@ mod tests {
 
             // Verify basic message structure
             assert_eq!(message[0], OutgoingMessages::RequestMarketData.to_field(), "Wrong message type");
-            assert_eq!(message[1], "11", "Wrong version");
+            assert_eq!(&message[1], "11", "Wrong version");
             assert_eq!(message[2], request_id.to_field(), "Wrong request ID");
 
             // Verify contract fields
             assert_eq!(message[3], contract.contract_id.to_field(), "Wrong contract ID");
 
             // Verify generic ticks
-            assert_eq!(message[16], "100,101,104", "Wrong generic ticks");
+            assert_eq!(&message[16], "100,101,104", "Wrong generic ticks");
 
             // Verify snapshot flags
             assert_eq!(message[17], snapshot.to_field(), "Wrong snapshot flag");
@@ -421,7 +421,7 @@ mod tests {
             let message = encode_cancel_market_data(request_id).expect("Failed to encode cancel market data");
 
             assert_eq!(message[0], OutgoingMessages::CancelMarketData.to_field(), "Wrong message type");
-            assert_eq!(message[1], "1", "Wrong version");
+            assert_eq!(&message[1], "1", "Wrong version");
             assert_eq!(message[2], request_id.to_string(), "Wrong request ID");
             assert_eq!(message.len(), 3, "Unexpected message length");
         }
@@ -466,7 +466,7 @@ mod tests {
             let message = encode_cancel_market_depth(server_version, request_id, is_smart_depth).expect("Failed to encode cancel realtime bars");
 
             assert_eq!(message[0], OutgoingMessages::CancelMarketDepth.to_field(), "Wrong message type");
-            assert_eq!(message[1], "1", "Wrong version");
+            assert_eq!(&message[1], "1", "Wrong version");
             assert_eq!(message[2], request_id.to_field(), "Wrong request ID");
             assert_eq!(message[3], is_smart_depth.to_field(), "Wrong smart depth flag");
             assert_eq!(message.len(), 4, "Unexpected message length");
@@ -1,3 +1,4 @@
 pub(crate) mod decoders;
 pub(crate) mod encoders;
+pub(super) mod stream_decoders;
 pub mod tick_types;
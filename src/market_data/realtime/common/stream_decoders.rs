@@ -0,0 +1,144 @@
+//! Common StreamDecoder implementations for realtime market data module
+//!
+//! This module contains the StreamDecoder trait implementations that are shared
+//! between sync and async versions, avoiding code duplication.
+
+use crate::market_data::realtime::*;
+use crate::messages::{self, IncomingMessages, Notice, RequestMessage, ResponseMessage};
+use crate::subscriptions::{ResponseContext, StreamDecoder};
+use crate::Error;
+
+use super::{decoders, encoders};
+
+impl StreamDecoder<BidAsk> for BidAsk {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
+
+    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::TickByTick => decoders::decode_bid_ask_tick(message),
+            IncomingMessages::Error => Err(Error::from(message.clone())),
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        encoders::encode_cancel_tick_by_tick(request_id)
+    }
+}
+
+impl StreamDecoder<MidPoint> for MidPoint {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
+
+    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::TickByTick => decoders::decode_mid_point_tick(message),
+            IncomingMessages::Error => Err(Error::from(message.clone())),
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel mid point ticks");
+        encoders::encode_cancel_tick_by_tick(request_id)
+    }
+}
+
+impl StreamDecoder<Bar> for Bar {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::RealTimeBars];
+
+    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        decoders::decode_realtime_bar(message)
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        encoders::encode_cancel_realtime_bars(request_id)
+    }
+}
+
+impl StreamDecoder<Trade> for Trade {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::TickByTick];
+
+    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::TickByTick => decoders::decode_trade_tick(message),
+            IncomingMessages::Error => Err(Error::from(message.clone())),
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        encoders::encode_cancel_tick_by_tick(request_id)
+    }
+}
+
+impl StreamDecoder<MarketDepths> for MarketDepths {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] =
+        &[IncomingMessages::MarketDepth, IncomingMessages::MarketDepthL2, IncomingMessages::Error];
+
+    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::MarketDepth => Ok(MarketDepths::MarketDepth(decoders::decode_market_depth(message)?)),
+            IncomingMessages::MarketDepthL2 => Ok(MarketDepths::MarketDepthL2(decoders::decode_market_depth_l2(server_version, message)?)),
+            IncomingMessages::Error => {
+                let code = message.peek_int(messages::CODE_INDEX).unwrap();
+                if (2100..2200).contains(&code) {
+                    Ok(MarketDepths::Notice(Notice::from(message)))
+                } else {
+                    Err(Error::from(message.clone()))
+                }
+            }
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(server_version: i32, request_id: Option<i32>, context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        encoders::encode_cancel_market_depth(server_version, request_id, context.map(|c| c.is_smart_depth).unwrap_or(false))
+    }
+}
+
+impl StreamDecoder<TickTypes> for TickTypes {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[
+        IncomingMessages::TickPrice,
+        IncomingMessages::TickSize,
+        IncomingMessages::TickString,
+        IncomingMessages::TickEFP,
+        IncomingMessages::TickGeneric,
+        IncomingMessages::TickOptionComputation,
+        IncomingMessages::TickSnapshotEnd,
+        IncomingMessages::Error,
+        IncomingMessages::TickReqParams,
+        IncomingMessages::MarketDataType,
+    ];
+
+    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::TickPrice => Ok(decoders::decode_tick_price(server_version, message)?),
+            IncomingMessages::TickSize => Ok(TickTypes::Size(decoders::decode_tick_size(message)?)),
+            IncomingMessages::TickString => Ok(TickTypes::String(decoders::decode_tick_string(message)?)),
+            IncomingMessages::TickEFP => Ok(TickTypes::EFP(decoders::decode_tick_efp(message)?)),
+            IncomingMessages::TickGeneric => Ok(TickTypes::Generic(decoders::decode_tick_generic(message)?)),
+            IncomingMessages::TickOptionComputation => Ok(TickTypes::OptionComputation(decoders::decode_tick_option_computation(
+                server_version,
+                message,
+            )?)),
+            IncomingMessages::TickReqParams => Ok(TickTypes::RequestParameters(decoders::decode_tick_request_parameters(message)?)),
+            IncomingMessages::MarketDataType => Ok(TickTypes::MarketDataType(decoders::decode_market_data_type(message)?)),
+            IncomingMessages::TickSnapshotEnd => Ok(TickTypes::SnapshotEnd),
+            IncomingMessages::Error => Ok(TickTypes::Notice(Notice::from(message))),
+            _ => Err(Error::NotImplemented),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        encoders::encode_cancel_market_data(request_id)
+    }
+
+    fn is_snapshot_end(&self) -> bool {
+        matches!(self, TickTypes::SnapshotEnd)
+    }
+}
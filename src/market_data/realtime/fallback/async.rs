@@ -0,0 +1,183 @@
+//! Market data fallback for the async client.
+
+use log::debug;
+
+use super::{step, MarketDataFallback, MarketDataRequest, MarketDataTick, Step};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{self, TickTypes};
+use crate::market_data::MarketDataType;
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+/// Streaming market data that falls back to delayed data when the live subscription is missing.
+///
+/// When TWS reports that the account is not subscribed to live data for the contract, the live request is cancelled
+/// and the contract requested again as [MarketDataFallback] data. The market data type is switched back to live
+/// afterwards. Market data requests from other tasks wait while the type is switched, but market data type changes
+/// made by calling `switch_market_data_type` directly are not coordinated.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::MarketDataFallback;
+/// use ibapi::market_data::MarketDataType;
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let contract = Contract::stock("AAPL");
+///     let mut subscription = client
+///         .market_data_with_fallback(&contract, &[], false, false, MarketDataFallback::Delayed)
+///         .await
+///         .expect("market data request failed");
+///
+///     while let Some(Ok(tick)) = subscription.next().await {
+///         let stale = if tick.market_data_type == MarketDataType::Live { "" } else { " (delayed)" };
+///         println!("{:?}{stale}", tick.tick);
+///     }
+/// }
+/// ```
+pub struct FallbackSubscription {
+    client: Client,
+    request: MarketDataRequest,
+    fallback: MarketDataFallback,
+    market_data_type: MarketDataType,
+    subscription: Subscription<TickTypes>,
+}
+
+impl FallbackSubscription {
+    pub(crate) async fn new(client: &Client, request: MarketDataRequest, fallback: MarketDataFallback) -> Result<Self, Error> {
+        let subscription = request_market_data(client, &request).await?;
+        Ok(Self {
+            client: client.clone(),
+            request,
+            fallback,
+            market_data_type: MarketDataType::Live,
+            subscription,
+        })
+    }
+
+    /// Returns the type of market data streamed.
+    pub fn market_data_type(&self) -> MarketDataType {
+        self.market_data_type
+    }
+
+    /// Returns the contract requested.
+    pub fn contract(&self) -> &Contract {
+        &self.request.contract
+    }
+
+    /// Waits for the next tick. Returns `None` once the subscription ends.
+    ///
+    /// Returns the error if the contract could not be requested again as fallback data.
+    pub async fn next(&mut self) -> Option<Result<MarketDataTick, Error>> {
+        loop {
+            let tick = match self.subscription.next().await? {
+                Ok(tick) => tick,
+                Err(err) => return Some(Err(err)),
+            };
+
+            match step(self.market_data_type, tick) {
+                Step::Tick(tick) => return Some(Ok(tick)),
+                Step::Switched(market_data_type) => self.market_data_type = market_data_type,
+                Step::Fallback => {
+                    if let Err(err) = self.fall_back().await {
+                        return Some(Err(err));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cancels the market data subscription.
+    pub async fn cancel(&self) {
+        self.subscription.cancel().await;
+    }
+
+    // Requests the contract again as fallback data. On error the live subscription stays cancelled and the stream ends.
+    async fn fall_back(&mut self) -> Result<(), Error> {
+        let market_data_type = self.fallback.market_data_type();
+        debug!(
+            "no live market data for {}, falling back to {market_data_type:?}",
+            self.request.contract.symbol
+        );
+
+        self.subscription.cancel().await;
+
+        // Other market data requests wait until the type is switched back.
+        let lock = self.client.market_data_type_lock.clone();
+        let _switching = lock.write().await;
+        self.client.switch_market_data_type(market_data_type).await?;
+        let subscription = send_market_data_request(&self.client, &self.request).await;
+        let restored = self.client.switch_market_data_type(MarketDataType::Live).await;
+
+        self.subscription = subscription?;
+        self.market_data_type = market_data_type;
+        restored
+    }
+}
+
+async fn request_market_data(client: &Client, request: &MarketDataRequest) -> Result<Subscription<TickTypes>, Error> {
+    client
+        .market_data(&request.contract, &request.generic_ticks(), request.snapshot, request.regulatory_snapshot)
+        .await
+}
+
+// Sends the request while the market data type lock is held.
+async fn send_market_data_request(client: &Client, request: &MarketDataRequest) -> Result<Subscription<TickTypes>, Error> {
+    realtime::r#async::send_market_data_request(
+        client,
+        &request.contract,
+        &request.generic_ticks(),
+        request.snapshot,
+        request.regulatory_snapshot,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[tokio::test]
+    async fn test_fallback_to_delayed() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "4|2|9001|10089|Requested market data requires additional subscription for API.|".to_owned(),
+                "1|2|9001|66|185.50|100|7|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let mut subscription = client
+            .market_data_with_fallback(&Contract::stock("AAPL"), &[], false, false, MarketDataFallback::DelayedFrozen)
+            .await
+            .expect("market data request failed");
+
+        // The stub sends every response to each request, so the fallback request receives the notice too.
+        let tick = subscription.next().await.expect("no tick").expect("market data error");
+        assert_eq!(tick.market_data_type, MarketDataType::DelayedFrozen);
+        assert!(matches!(tick.tick, TickTypes::Notice(_)), "{tick:?}");
+
+        let tick = subscription.next().await.expect("no tick").expect("market data error");
+        assert_eq!(tick.market_data_type, MarketDataType::DelayedFrozen);
+        assert!(matches!(tick.tick, TickTypes::PriceSize(_)), "{tick:?}");
+
+        let types: Vec<_> = message_bus
+            .request_messages()
+            .iter()
+            .filter(|message| message.encode_simple().starts_with("59|"))
+            .map(|message| message.encode_simple())
+            .collect();
+        assert_eq!(types, vec!["59|1|4|", "59|1|1|"]);
+    }
+}
@@ -0,0 +1,153 @@
+//! Market data that falls back to delayed data when the account lacks a live subscription.
+//!
+//! TWS applies the market data type set by `switch_market_data_type` to the requests sent after it. A fallback
+//! subscription requests live data and, when TWS reports the live subscription is missing, requests the contract again
+//! as delayed or delayed-frozen data and continues the stream. Each tick is tagged with the [MarketDataType] it came
+//! from, as last reported by TWS for the request.
+//!
+//! Market data requests wait while a fallback subscription switches the market data type, so other requests are not
+//! sent as delayed data by mistake.
+
+use crate::market_data::realtime::TickTypes;
+use crate::market_data::MarketDataType;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// Notice codes for market data the account is not subscribed to.
+const NOT_SUBSCRIBED: [i32; 2] = [354, 10089];
+/// Notice code sent when delayed market data is shown instead of live.
+const DISPLAYING_DELAYED: i32 = 10167;
+
+/// Market data requested when the live subscription is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarketDataFallback {
+    /// Delayed market data, 15 to 20 minutes behind.
+    #[default]
+    Delayed,
+    /// Delayed market data, or the last delayed values while the market is closed.
+    DelayedFrozen,
+}
+
+impl MarketDataFallback {
+    /// Returns the market data type requested.
+    pub fn market_data_type(&self) -> MarketDataType {
+        match self {
+            Self::Delayed => MarketDataType::Delayed,
+            Self::DelayedFrozen => MarketDataType::DelayedFrozen,
+        }
+    }
+}
+
+/// A market data tick tagged with the type of data it came from.
+#[derive(Debug)]
+pub struct MarketDataTick {
+    /// [MarketDataType::Live], or the fallback once the stream switched to it.
+    pub market_data_type: MarketDataType,
+    /// The tick.
+    pub tick: TickTypes,
+}
+
+/// Arguments of a market data request, kept to request the contract again.
+#[derive(Debug, Clone)]
+pub(crate) struct MarketDataRequest {
+    pub(crate) contract: crate::contracts::Contract,
+    pub(crate) generic_ticks: Vec<String>,
+    pub(crate) snapshot: bool,
+    pub(crate) regulatory_snapshot: bool,
+}
+
+impl MarketDataRequest {
+    pub(crate) fn generic_ticks(&self) -> Vec<&str> {
+        self.generic_ticks.iter().map(String::as_str).collect()
+    }
+}
+
+/// What a fallback subscription does with a tick.
+#[derive(Debug)]
+pub(crate) enum Step {
+    /// Passes the tick on.
+    Tick(MarketDataTick),
+    /// Requests the contract again as fallback data.
+    Fallback,
+    /// TWS sends another type of data, so later ticks are tagged with it.
+    Switched(MarketDataType),
+}
+
+/// Decides what to do with a tick received while streaming `current` data.
+pub(crate) fn step(current: MarketDataType, tick: TickTypes) -> Step {
+    if let TickTypes::MarketDataType(market_data_type) = tick {
+        return Step::Switched(market_data_type);
+    }
+
+    if current == MarketDataType::Live {
+        if let TickTypes::Notice(notice) = &tick {
+            if NOT_SUBSCRIBED.contains(&notice.code) {
+                return Step::Fallback;
+            }
+            // TWS follows the notice with the type of delayed data shown, which corrects the tag if it is frozen.
+            if notice.code == DISPLAYING_DELAYED {
+                return Step::Switched(MarketDataType::Delayed);
+            }
+        }
+    }
+
+    Step::Tick(MarketDataTick {
+        market_data_type: current,
+        tick,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::Notice;
+
+    fn notice(code: i32) -> TickTypes {
+        TickTypes::Notice(Notice {
+            code,
+            message: "Requested market data is not subscribed.".into(),
+        })
+    }
+
+    #[test]
+    fn test_step() {
+        assert!(matches!(step(MarketDataType::Live, notice(10089)), Step::Fallback));
+        assert!(matches!(step(MarketDataType::Live, notice(354)), Step::Fallback));
+        assert!(matches!(
+            step(MarketDataType::Live, notice(10167)),
+            Step::Switched(MarketDataType::Delayed)
+        ));
+        assert!(matches!(
+            step(MarketDataType::Delayed, TickTypes::MarketDataType(MarketDataType::DelayedFrozen)),
+            Step::Switched(MarketDataType::DelayedFrozen)
+        ));
+        assert!(matches!(
+            step(MarketDataType::Live, notice(2104)),
+            Step::Tick(MarketDataTick {
+                market_data_type: MarketDataType::Live,
+                tick: TickTypes::Notice(_)
+            })
+        ));
+
+        // Once on fallback data, a missing subscription is passed on.
+        assert!(matches!(
+            step(MarketDataType::Delayed, notice(10089)),
+            Step::Tick(MarketDataTick {
+                market_data_type: MarketDataType::Delayed,
+                ..
+            })
+        ));
+        assert!(matches!(
+            step(MarketDataType::Delayed, TickTypes::SnapshotEnd),
+            Step::Tick(MarketDataTick {
+                tick: TickTypes::SnapshotEnd,
+                ..
+            })
+        ));
+        assert_eq!(MarketDataFallback::DelayedFrozen.market_data_type(), MarketDataType::DelayedFrozen);
+    }
+}
@@ -0,0 +1,243 @@
+//! Market data fallback for the blocking client.
+
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+use super::{step, MarketDataFallback, MarketDataRequest, MarketDataTick, Step};
+use crate::client::sync::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{self, TickTypes};
+use crate::market_data::MarketDataType;
+use crate::subscriptions::sync::Subscription;
+use crate::Error;
+
+/// Streaming market data that falls back to delayed data when the live subscription is missing.
+///
+/// When TWS reports that the account is not subscribed to live data for the contract, the live request is cancelled
+/// and the contract requested again as [MarketDataFallback] data. The market data type is switched back to live
+/// afterwards. Market data requests from other threads wait while the type is switched, but market data type changes
+/// made by calling `switch_market_data_type` directly are not coordinated.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::MarketDataFallback;
+/// use ibapi::market_data::MarketDataType;
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let contract = Contract::stock("AAPL");
+/// let subscription = client
+///     .market_data_with_fallback(&contract, &[], false, false, MarketDataFallback::Delayed)
+///     .expect("market data request failed");
+///
+/// for tick in subscription {
+///     let stale = if tick.market_data_type == MarketDataType::Live { "" } else { " (delayed)" };
+///     println!("{:?}{stale}", tick.tick);
+/// }
+/// ```
+pub struct FallbackSubscription<'a> {
+    client: &'a Client,
+    request: MarketDataRequest,
+    fallback: MarketDataFallback,
+    market_data_type: MarketDataType,
+    subscription: Subscription<'a, TickTypes>,
+    error: Option<Error>,
+}
+
+impl<'a> FallbackSubscription<'a> {
+    pub(crate) fn new(client: &'a Client, request: MarketDataRequest, fallback: MarketDataFallback) -> Result<Self, Error> {
+        let subscription = request_market_data(client, &request)?;
+        Ok(Self {
+            client,
+            request,
+            fallback,
+            market_data_type: MarketDataType::Live,
+            subscription,
+            error: None,
+        })
+    }
+
+    /// Returns the type of market data streamed.
+    pub fn market_data_type(&self) -> MarketDataType {
+        self.market_data_type
+    }
+
+    /// Returns the contract requested.
+    pub fn contract(&self) -> &Contract {
+        &self.request.contract
+    }
+
+    /// Returns the next tick if one was received, without waiting.
+    pub fn try_next(&mut self) -> Option<MarketDataTick> {
+        loop {
+            let tick = self.subscription.try_next()?;
+            if let Some(tick) = self.apply(tick) {
+                return Some(tick);
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for the next tick.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<MarketDataTick> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let tick = self.subscription.next_timeout(remaining)?;
+            if let Some(tick) = self.apply(tick) {
+                return Some(tick);
+            }
+        }
+    }
+
+    /// Returns the error that ended the subscription, if any.
+    pub fn error(&self) -> Option<Error> {
+        self.error.clone().or_else(|| self.subscription.error())
+    }
+
+    /// Cancels the market data subscription.
+    pub fn cancel(&self) {
+        self.subscription.cancel();
+    }
+
+    fn apply(&mut self, tick: TickTypes) -> Option<MarketDataTick> {
+        match step(self.market_data_type, tick) {
+            Step::Tick(tick) => Some(tick),
+            Step::Switched(market_data_type) => {
+                self.market_data_type = market_data_type;
+                None
+            }
+            Step::Fallback => {
+                if let Err(error) = self.fall_back() {
+                    self.error = Some(error);
+                }
+                None
+            }
+        }
+    }
+
+    // Requests the contract again as fallback data. On error the live subscription stays cancelled and the stream ends.
+    fn fall_back(&mut self) -> Result<(), Error> {
+        let market_data_type = self.fallback.market_data_type();
+        debug!(
+            "no live market data for {}, falling back to {market_data_type:?}",
+            self.request.contract.symbol
+        );
+
+        self.subscription.cancel();
+
+        // Other market data requests wait until the type is switched back.
+        let client = self.client;
+        let _switching = client.market_data_type_lock.write()?;
+        client.switch_market_data_type(market_data_type)?;
+        let subscription = send_market_data_request(client, &self.request);
+        let restored = client.switch_market_data_type(MarketDataType::Live);
+
+        self.subscription = subscription?;
+        self.market_data_type = market_data_type;
+        restored
+    }
+}
+
+impl Iterator for FallbackSubscription<'_> {
+    type Item = MarketDataTick;
+
+    /// Blocks until the next tick. Returns `None` once the subscription ends; check [FallbackSubscription::error] for
+    /// the cause.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tick = self.subscription.next()?;
+            if let Some(tick) = self.apply(tick) {
+                return Some(tick);
+            }
+        }
+    }
+}
+
+fn request_market_data<'a>(client: &'a Client, request: &MarketDataRequest) -> Result<Subscription<'a, TickTypes>, Error> {
+    realtime::sync::market_data(
+        client,
+        &request.contract,
+        &request.generic_ticks(),
+        request.snapshot,
+        request.regulatory_snapshot,
+    )
+}
+
+// Sends the request while the market data type lock is held.
+fn send_market_data_request<'a>(client: &'a Client, request: &MarketDataRequest) -> Result<Subscription<'a, TickTypes>, Error> {
+    realtime::sync::send_market_data_request(
+        client,
+        &request.contract,
+        &request.generic_ticks(),
+        request.snapshot,
+        request.regulatory_snapshot,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[test]
+    fn test_fallback_to_delayed() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "4|2|9001|10089|Requested market data requires additional subscription for API.|".to_owned(),
+                "1|2|9001|66|185.50|100|7|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let mut subscription = client
+            .market_data_with_fallback(&Contract::stock("AAPL"), &[], false, false, MarketDataFallback::Delayed)
+            .expect("market data request failed");
+
+        // The stub sends every response to each request, so the delayed request receives the notice too.
+        let tick = subscription.next().expect("no tick");
+        assert_eq!(tick.market_data_type, MarketDataType::Delayed);
+        assert!(matches!(tick.tick, TickTypes::Notice(_)), "{tick:?}");
+
+        let tick = subscription.next().expect("no tick");
+        assert_eq!(tick.market_data_type, MarketDataType::Delayed);
+        assert!(matches!(tick.tick, TickTypes::PriceSize(_)), "{tick:?}");
+        assert_eq!(subscription.market_data_type(), MarketDataType::Delayed);
+
+        let requests: Vec<_> = message_bus.request_messages().iter().map(|message| message.encode_simple()).collect();
+        assert_eq!(requests[1], "2|1|9000|", "live request cancelled");
+        assert_eq!(requests[2], "59|1|3|");
+        assert!(requests[3].starts_with("1|"), "{}", requests[3]);
+        assert_eq!(requests[4], "59|1|1|");
+    }
+
+    #[test]
+    fn test_delayed_data_displayed() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "4|2|9001|10167|Requested market data is not subscribed. Displaying delayed market data.|".to_owned(),
+                "58|1|9001|4|".to_owned(),
+                "1|2|9001|66|185.50|100|7|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let mut subscription = client
+            .market_data_with_fallback(&Contract::stock("AAPL"), &[], false, false, MarketDataFallback::Delayed)
+            .expect("market data request failed");
+
+        // Tagged with the type TWS reports, not the configured fallback.
+        let tick = subscription.next().expect("no tick");
+        assert_eq!(tick.market_data_type, MarketDataType::DelayedFrozen);
+        assert!(matches!(tick.tick, TickTypes::PriceSize(_)), "{tick:?}");
+        assert_eq!(message_bus.request_messages().len(), 1, "not requested again");
+    }
+}
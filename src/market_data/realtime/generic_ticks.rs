@@ -0,0 +1,205 @@
+//! Generic ticks requested with market data.
+
+use std::fmt;
+
+use super::TickType;
+
+/// Additional data requested with market data, sent to TWS as its generic tick id.
+///
+/// See the [available tick types](https://interactivebrokers.github.io/tws-api/tick_types.html).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GenericTick {
+    /// Call and put option volume, id 100.
+    OptionVolume,
+    /// Call and put option open interest, id 101.
+    OptionOpenInterest,
+    /// 30 day historical volatility of the underlying, id 104.
+    HistoricalVolatility,
+    /// Average option volume, id 105.
+    AverageOptionVolume,
+    /// 30 day implied volatility of the options, id 106.
+    OptionImpliedVolatility,
+    /// Index future premium, id 162.
+    IndexFuturePremium,
+    /// 13, 26 and 52 week highs and lows and the average volume, id 165.
+    MiscellaneousStats,
+    /// Mark price used in margin calculations, id 221.
+    MarkPrice,
+    /// Auction volume, price and imbalance, id 225.
+    AuctionValues,
+    /// Last trade details including the VWAP, id 233.
+    RtVolume,
+    /// Shortable indicator and shortable shares, id 236.
+    Shortable,
+    /// Fundamental ratios, id 258.
+    Fundamentals,
+    /// Trade count of the day, id 293.
+    TradeCount,
+    /// Trades per minute, id 294.
+    TradeRate,
+    /// Volume per minute, id 295.
+    VolumeRate,
+    /// Last regular trading hours trade, id 318.
+    LastRthTrade,
+    /// Last trade details excluding unreportable trades, id 375.
+    RtTradeVolume,
+    /// Real time historical volatility, id 411.
+    RealtimeHistoricalVolatility,
+    /// Past and expected dividends, id 456.
+    Dividends,
+    /// Bond factor multiplier, id 460.
+    BondFactorMultiplier,
+    /// ETF net asset value bid and ask, id 576.
+    EtfNavBidAsk,
+    /// ETF net asset value last, id 577.
+    EtfNavLast,
+    /// ETF net asset value close and prior close, id 578.
+    EtfNavClose,
+    /// Futures open interest, id 588.
+    FuturesOpenInterest,
+    /// 3, 5 and 10 minute short term volume, id 595.
+    ShortTermVolume,
+    /// ETF net asset value high and low, id 614.
+    EtfNavHighLow,
+    /// Creditman slow mark price, id 619.
+    CreditmanSlowMarkPrice,
+    /// ETF frozen net asset value last, id 623.
+    EtfFrozenNavLast,
+    /// Headlines from the news providers, id 292.
+    News(Vec<String>),
+    /// Turns off the top of book data, `mdoff`. Used with [GenericTick::News] to only receive headlines.
+    MarketDataOff,
+}
+
+impl GenericTick {
+    /// Returns the generic tick id, or `None` for [GenericTick::MarketDataOff].
+    pub fn id(&self) -> Option<u16> {
+        let id = match self {
+            Self::OptionVolume => 100,
+            Self::OptionOpenInterest => 101,
+            Self::HistoricalVolatility => 104,
+            Self::AverageOptionVolume => 105,
+            Self::OptionImpliedVolatility => 106,
+            Self::IndexFuturePremium => 162,
+            Self::MiscellaneousStats => 165,
+            Self::MarkPrice => 221,
+            Self::AuctionValues => 225,
+            Self::RtVolume => 233,
+            Self::Shortable => 236,
+            Self::Fundamentals => 258,
+            Self::News(_) => 292,
+            Self::TradeCount => 293,
+            Self::TradeRate => 294,
+            Self::VolumeRate => 295,
+            Self::LastRthTrade => 318,
+            Self::RtTradeVolume => 375,
+            Self::RealtimeHistoricalVolatility => 411,
+            Self::Dividends => 456,
+            Self::BondFactorMultiplier => 460,
+            Self::EtfNavBidAsk => 576,
+            Self::EtfNavLast => 577,
+            Self::EtfNavClose => 578,
+            Self::FuturesOpenInterest => 588,
+            Self::ShortTermVolume => 595,
+            Self::EtfNavHighLow => 614,
+            Self::CreditmanSlowMarkPrice => 619,
+            Self::EtfFrozenNavLast => 623,
+            Self::MarketDataOff => return None,
+        };
+        Some(id)
+    }
+
+    /// Returns the tick types TWS sends for the generic tick.
+    pub fn tick_types(&self) -> &'static [TickType] {
+        match self {
+            Self::OptionVolume => &[TickType::OptionCallVolume, TickType::OptionPutVolume],
+            Self::OptionOpenInterest => &[TickType::OptionCallOpenInterest, TickType::OptionPutOpenInterest],
+            Self::HistoricalVolatility => &[TickType::OptionHistoricalVol],
+            Self::AverageOptionVolume => &[TickType::AvgOptVolume],
+            Self::OptionImpliedVolatility => &[TickType::OptionImpliedVol],
+            Self::IndexFuturePremium => &[TickType::IndexFuturePremium],
+            Self::MiscellaneousStats => &[
+                TickType::Low13Week,
+                TickType::High13Week,
+                TickType::Low26Week,
+                TickType::High26Week,
+                TickType::Low52Week,
+                TickType::High52Week,
+                TickType::AvgVolume,
+            ],
+            Self::MarkPrice => &[TickType::MarkPrice],
+            Self::AuctionValues => &[
+                TickType::AuctionVolume,
+                TickType::AuctionPrice,
+                TickType::AuctionImbalance,
+                TickType::RegulatoryImbalance,
+            ],
+            Self::RtVolume => &[TickType::RtVolume],
+            Self::Shortable => &[TickType::Shortable, TickType::ShortableShares],
+            Self::Fundamentals => &[TickType::FundamentalRatios],
+            Self::News(_) => &[TickType::NewsTick],
+            Self::TradeCount => &[TickType::TradeCount],
+            Self::TradeRate => &[TickType::TradeRate],
+            Self::VolumeRate => &[TickType::VolumeRate],
+            Self::LastRthTrade => &[TickType::LastRthTrade],
+            Self::RtTradeVolume => &[TickType::RtTrdVolume],
+            Self::RealtimeHistoricalVolatility => &[TickType::RtHistoricalVol],
+            Self::Dividends => &[TickType::IbDividends],
+            Self::BondFactorMultiplier => &[TickType::BondFactorMultiplier],
+            Self::EtfNavBidAsk => &[TickType::EtfNavBid, TickType::EtfNavAsk],
+            Self::EtfNavLast => &[TickType::EtfNavLast],
+            Self::EtfNavClose => &[TickType::EtfNavClose, TickType::EtfNavPriorClose],
+            Self::FuturesOpenInterest => &[TickType::FuturesOpenInterest],
+            Self::ShortTermVolume => &[
+                TickType::ShortTermVolume3Min,
+                TickType::ShortTermVolume5Min,
+                TickType::ShortTermVolume10Min,
+            ],
+            Self::EtfNavHighLow => &[TickType::EtfNavHigh, TickType::EtfNavLow],
+            Self::CreditmanSlowMarkPrice => &[TickType::CreditmanSlowMarkPrice],
+            Self::EtfFrozenNavLast => &[TickType::EtfFrozenNavLast],
+            Self::MarketDataOff => &[],
+        }
+    }
+
+    /// Returns the generic tick list sent to TWS, such as `"233,236"`.
+    pub fn encode(ticks: &[GenericTick]) -> String {
+        ticks.iter().map(GenericTick::to_string).collect::<Vec<_>>().join(",")
+    }
+}
+
+impl fmt::Display for GenericTick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MarketDataOff => write!(f, "mdoff"),
+            Self::News(providers) if !providers.is_empty() => {
+                let providers: Vec<String> = providers.iter().map(|provider| format!("292:{provider}")).collect();
+                write!(f, "{}", providers.join(","))
+            }
+            tick => write!(f, "{}", tick.id().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_generic_ticks() {
+        assert_eq!(GenericTick::encode(&[GenericTick::RtVolume, GenericTick::Shortable]), "233,236");
+        assert_eq!(
+            GenericTick::encode(&[GenericTick::MarketDataOff, GenericTick::News(vec!["BRFG".into(), "DJNL".into()])]),
+            "mdoff,292:BRFG,292:DJNL"
+        );
+        assert_eq!(GenericTick::News(vec![]).to_string(), "292");
+        assert_eq!(GenericTick::encode(&[]), "");
+    }
+
+    #[test]
+    fn test_generic_tick_types() {
+        assert_eq!(GenericTick::Shortable.tick_types(), &[TickType::Shortable, TickType::ShortableShares]);
+        assert_eq!(GenericTick::MiscellaneousStats.tick_types().len(), 7);
+        assert!(GenericTick::MarketDataOff.tick_types().is_empty());
+    }
+}
@@ -3,14 +3,9 @@ use time::OffsetDateTime;
 
 use crate::ToField;
 
-#[cfg(feature = "sync")]
-use crate::client::{ResponseContext, StreamDecoder};
 use crate::contracts::OptionComputation;
+use crate::market_data::MarketDataType;
 use crate::messages::Notice;
-#[cfg(feature = "sync")]
-use crate::messages::{self, IncomingMessages, RequestMessage, ResponseMessage};
-#[cfg(feature = "sync")]
-use crate::Error;
 
 // Common modules
 pub(crate) mod common;
@@ -22,14 +17,49 @@ pub mod sync;
 #[cfg(feature = "async")]
 pub mod r#async;
 
+pub mod aggregator;
+pub mod fallback;
+mod generic_ticks;
+pub mod order_book;
+pub mod tick_mux;
+pub mod ticker;
+
 // Re-export tick types
 pub use crate::contracts::tick_types::TickType;
+pub use aggregator::{BarAggregator, BarSpec, Sample};
+pub use fallback::{MarketDataFallback, MarketDataTick};
+pub use generic_ticks::GenericTick;
+pub use order_book::{BookChange, BookEvent, BookIssue, BookLevel, BookOperation, BookSide, BookSnapshot, OrderBook};
+pub use tick_mux::{TickByTick, TickByTickType, TickMuxEvent};
+pub use ticker::{Dividends, Greeks, TickValue, Ticker, TickerField};
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use aggregator::sync::AggregatedBars;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use fallback::sync::FallbackSubscription;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use order_book::sync::OrderBookSubscription;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use tick_mux::sync::TickMux;
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use ticker::sync::TickerSubscription;
+
+#[cfg(feature = "async")]
+pub use aggregator::r#async::AggregatedBars;
+#[cfg(feature = "async")]
+pub use fallback::r#async::FallbackSubscription;
+#[cfg(feature = "async")]
+pub use order_book::r#async::OrderBookSubscription;
+#[cfg(feature = "async")]
+pub use tick_mux::r#async::TickMux;
+#[cfg(feature = "async")]
+pub use ticker::r#async::TickerSubscription;
 
 // === Models ===
 
 /// Bar size for real-time bars.
 ///
-/// Note: Currently only 5-second bars are supported for real-time data.
+/// Note: Currently only 5-second bars are supported for real-time data. Use a [BarAggregator] to build larger bars.
 #[derive(Clone, Debug, Copy, Serialize, Deserialize, PartialEq)]
 pub enum BarSize {
     // Sec,
@@ -48,7 +78,7 @@ pub enum BarSize {
 }
 
 /// Represents `BidAsk` tick by tick realtime tick.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BidAsk {
     /// The spread's date and time (either as a yyyymmss hh:mm:ss formatted string or as system time according to the request). Time zone is the TWS time zone chosen on login.
     pub time: OffsetDateTime,
@@ -64,26 +94,8 @@ pub struct BidAsk {
     pub bid_ask_attribute: BidAskAttribute,
 }
 
-#[cfg(feature = "sync")]
-impl StreamDecoder<BidAsk> for BidAsk {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => common::decoders::decode_bid_ask_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        common::encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
 /// Attributes for bid/ask tick data.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BidAskAttribute {
     /// Indicates if the bid price is past the daily low.
     pub bid_past_low: bool,
@@ -92,7 +104,7 @@ pub struct BidAskAttribute {
 }
 
 /// Represents `MidPoint` tick by tick realtime tick.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct MidPoint {
     /// The trade's date and time (either as a yyyymmss hh:mm:ss formatted string or as system time according to the request). Time zone is the TWS time zone chosen on login.
     pub time: OffsetDateTime,
@@ -100,24 +112,6 @@ pub struct MidPoint {
     pub mid_point: f64,
 }
 
-#[cfg(feature = "sync")]
-impl StreamDecoder<MidPoint> for MidPoint {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => common::decoders::decode_mid_point_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel mid point ticks");
-        common::encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
 /// Represents a real-time bar with OHLCV data
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Bar {
@@ -139,22 +133,8 @@ pub struct Bar {
     pub count: i32,
 }
 
-#[cfg(feature = "sync")]
-impl StreamDecoder<Bar> for Bar {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::RealTimeBars];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        common::decoders::decode_realtime_bar(message)
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        common::encoders::encode_cancel_realtime_bars(request_id)
-    }
-}
-
 /// Represents `Last` or `AllLast` tick-by-tick real-time tick.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Trade {
     /// Tick type: `Last` or `AllLast`
     pub tick_type: String,
@@ -172,26 +152,8 @@ pub struct Trade {
     pub special_conditions: String,
 }
 
-#[cfg(feature = "sync")]
-impl StreamDecoder<Trade> for Trade {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::TickByTick];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickByTick => common::decoders::decode_trade_tick(message),
-            IncomingMessages::Error => Err(Error::from(message.clone())),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        common::encoders::encode_cancel_tick_by_tick(request_id)
-    }
-}
-
 /// Attributes for trade tick data.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TradeAttribute {
     /// Indicates if the trade occurred past the limit price.
     pub past_limit: bool,
@@ -271,35 +233,6 @@ pub struct MarketDepthL2 {
     pub smart_depth: bool,
 }
 
-#[cfg(feature = "sync")]
-impl StreamDecoder<MarketDepths> for MarketDepths {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::MarketDepth, IncomingMessages::MarketDepthL2, IncomingMessages::Error];
-
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::MarketDepth => Ok(MarketDepths::MarketDepth(common::decoders::decode_market_depth(message)?)),
-            IncomingMessages::MarketDepthL2 => Ok(MarketDepths::MarketDepthL2(common::decoders::decode_market_depth_l2(
-                server_version,
-                message,
-            )?)),
-            IncomingMessages::Error => {
-                let code = message.peek_int(messages::CODE_INDEX).unwrap();
-                if (2100..2200).contains(&code) {
-                    Ok(MarketDepths::Notice(Notice::from(message)))
-                } else {
-                    Err(Error::from(message.clone()))
-                }
-            }
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(server_version: i32, request_id: Option<i32>, context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        common::encoders::encode_cancel_market_depth(server_version, request_id, context.map(|c| c.is_smart_depth).unwrap_or(false))
-    }
-}
-
 /// Stores depth market data description.
 #[derive(Debug, Default)]
 pub struct DepthMarketDataDescription {
@@ -328,48 +261,8 @@ pub enum TickTypes {
     Notice(Notice),
     RequestParameters(TickRequestParameters),
     PriceSize(TickPriceSize),
-}
-
-#[cfg(feature = "sync")]
-impl StreamDecoder<TickTypes> for TickTypes {
-    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[
-        IncomingMessages::TickPrice,
-        IncomingMessages::TickSize,
-        IncomingMessages::TickString,
-        IncomingMessages::TickEFP,
-        IncomingMessages::TickGeneric,
-        IncomingMessages::TickOptionComputation,
-        IncomingMessages::TickSnapshotEnd,
-        IncomingMessages::Error,
-        IncomingMessages::TickReqParams,
-    ];
-
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Self, Error> {
-        match message.message_type() {
-            IncomingMessages::TickPrice => Ok(common::decoders::decode_tick_price(server_version, message)?),
-            IncomingMessages::TickSize => Ok(TickTypes::Size(common::decoders::decode_tick_size(message)?)),
-            IncomingMessages::TickString => Ok(TickTypes::String(common::decoders::decode_tick_string(message)?)),
-            IncomingMessages::TickEFP => Ok(TickTypes::EFP(common::decoders::decode_tick_efp(message)?)),
-            IncomingMessages::TickGeneric => Ok(TickTypes::Generic(common::decoders::decode_tick_generic(message)?)),
-            IncomingMessages::TickOptionComputation => Ok(TickTypes::OptionComputation(common::decoders::decode_tick_option_computation(
-                server_version,
-                message,
-            )?)),
-            IncomingMessages::TickReqParams => Ok(TickTypes::RequestParameters(common::decoders::decode_tick_request_parameters(message)?)),
-            IncomingMessages::TickSnapshotEnd => Ok(TickTypes::SnapshotEnd),
-            IncomingMessages::Error => Ok(TickTypes::Notice(Notice::from(message))),
-            _ => Err(Error::NotImplemented),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        common::encoders::encode_cancel_market_data(request_id)
-    }
-
-    fn is_snapshot_end(&self) -> bool {
-        matches!(self, TickTypes::SnapshotEnd)
-    }
+    /// Type of market data TWS sends for the request, reported when it is not live data.
+    MarketDataType(MarketDataType),
 }
 
 /// Price tick data.
@@ -471,7 +364,7 @@ pub struct TickRequestParameters {
 // === Implementation ===
 
 // Re-export functions based on active feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::*;
 
 #[cfg(feature = "async")]
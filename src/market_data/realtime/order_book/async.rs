@@ -0,0 +1,117 @@
+//! Order book for the async client.
+
+use log::warn;
+
+use super::{BookEvent, OrderBook};
+use crate::market_data::realtime::MarketDepths;
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+/// Market depth applied to an [OrderBook].
+///
+/// Each call to [OrderBookSubscription::next] applies a depth update and returns the change, or the issue if the update
+/// did not fit the book.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::BookEvent;
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let contract = Contract::stock("AAPL");
+///     let mut subscription = client.order_book(&contract, 10, true).await.expect("market depth request failed");
+///
+///     while let Some(event) = subscription.next().await {
+///         if let BookEvent::Invalid(issue) = event.expect("market depth error") {
+///             println!("book out of sync: {issue:?}");
+///         }
+///         let book = subscription.book();
+///         println!("microprice {:?}, imbalance {:?}", book.microprice(), book.imbalance(5));
+///     }
+/// }
+/// ```
+pub struct OrderBookSubscription {
+    subscription: Subscription<MarketDepths>,
+    book: OrderBook,
+}
+
+impl OrderBookSubscription {
+    pub(crate) fn new(subscription: Subscription<MarketDepths>, number_of_rows: usize) -> Self {
+        Self {
+            subscription,
+            book: OrderBook::new(number_of_rows),
+        }
+    }
+
+    /// Returns the order book.
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Waits for an update and returns its event. Returns `None` once the subscription ends.
+    pub async fn next(&mut self) -> Option<Result<BookEvent, Error>> {
+        loop {
+            let depth = match self.subscription.next().await? {
+                Ok(depth) => depth,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if let MarketDepths::Notice(notice) = &depth {
+                warn!("market depth notice {}: {}", notice.code, notice.message);
+            }
+
+            if let Some(event) = BookEvent::from_result(self.book.apply(&depth)) {
+                return Some(Ok(event));
+            }
+        }
+    }
+
+    /// Cancels the market depth subscription.
+    pub async fn cancel(&self) {
+        self.subscription.cancel().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::order_book::{BookEvent, BookIssue, BookSide};
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+    use crate::Client;
+
+    #[tokio::test]
+    async fn test_order_book_subscription() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec!["12|1|9001|0|0|1|185.50|100|".to_owned(), "12|1|9001|3|1|1|185.40|200|".to_owned()],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SMART_DEPTH);
+
+        let mut subscription = client
+            .order_book(&Contract::stock("AAPL"), 5, false)
+            .await
+            .expect("market depth request failed");
+
+        let event = subscription.next().await.expect("no update").expect("market depth error");
+        assert!(matches!(event, BookEvent::Changed(_)));
+
+        let event = subscription.next().await.expect("no update").expect("market depth error");
+        assert_eq!(
+            event,
+            BookEvent::Invalid(BookIssue::OutOfRange {
+                side: BookSide::Bid,
+                position: 3,
+                rows: 1
+            })
+        );
+        assert_eq!(subscription.book().issues(), 1);
+    }
+}
@@ -0,0 +1,455 @@
+//! Order book maintained from market depth updates.
+//!
+//! Market depth arrives as row operations: insert a row at a position, shifting the rows below it down, update the row
+//! at a position, or delete it, shifting the rows below it up. An [OrderBook] applies the operations to a bid and an
+//! ask ladder, best price first. With smart depth each row also names the exchange or market maker holding it, so the
+//! same price can appear on several rows.
+//!
+//! Operations that do not fit the ladder, such as an update past its last row, are reported as a [BookIssue]. They are
+//! applied as closely as possible, but the book may no longer match the one in TWS. Requesting depth again rebuilds it.
+
+use super::{MarketDepth, MarketDepthL2, MarketDepths};
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// Side of the order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+impl BookSide {
+    fn from_wire(side: i32) -> Option<Self> {
+        match side {
+            0 => Some(Self::Ask),
+            1 => Some(Self::Bid),
+            _ => None,
+        }
+    }
+}
+
+/// Row operation of a market depth update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl BookOperation {
+    fn from_wire(operation: i32) -> Option<Self> {
+        match operation {
+            0 => Some(Self::Insert),
+            1 => Some(Self::Update),
+            2 => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// Row of an order book ladder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookLevel {
+    /// Price of the row.
+    pub price: f64,
+    /// Size offered at the price.
+    pub size: f64,
+    /// Exchange or market maker holding the row, for Level II depth.
+    pub market_maker: Option<String>,
+}
+
+/// Change applied to an [OrderBook].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookChange {
+    /// Side that changed.
+    pub side: BookSide,
+    /// Row that changed.
+    pub position: usize,
+    /// Operation applied to the row.
+    pub operation: BookOperation,
+}
+
+/// Market depth update that does not fit the order book.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookIssue {
+    /// The side is neither 0, ask, nor 1, bid. The update was ignored.
+    UnknownSide(i32),
+    /// The operation is not 0, 1 or 2. The update was ignored.
+    UnknownOperation(i32),
+    /// The position is negative. The update was ignored.
+    NegativePosition(i32),
+    /// An insert past the last row would leave a gap. The row was appended instead.
+    Gap { side: BookSide, position: usize, rows: usize },
+    /// An update or delete of a row that does not exist. Updates are appended, deletes ignored.
+    OutOfRange { side: BookSide, position: usize, rows: usize },
+    /// The row's price is out of order with its neighbours. The row was applied as given.
+    OutOfOrder { side: BookSide, position: usize },
+}
+
+/// Event produced by an order book subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookEvent {
+    /// A depth update was applied.
+    Changed(BookChange),
+    /// A depth update did not fit the book.
+    Invalid(BookIssue),
+}
+
+impl BookEvent {
+    // Event for the result of OrderBook::apply, if the update was not a notice.
+    pub(crate) fn from_result(result: Result<Option<BookChange>, BookIssue>) -> Option<Self> {
+        match result {
+            Ok(change) => change.map(BookEvent::Changed),
+            Err(issue) => Some(BookEvent::Invalid(issue)),
+        }
+    }
+}
+
+/// Best bid and ask rows of an [OrderBook].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BookSnapshot {
+    /// Bid rows, highest price first.
+    pub bids: Vec<BookLevel>,
+    /// Ask rows, lowest price first.
+    pub asks: Vec<BookLevel>,
+}
+
+/// Bid and ask ladders built from market depth updates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OrderBook {
+    bids: Vec<BookLevel>,
+    asks: Vec<BookLevel>,
+    max_rows: Option<usize>,
+    issues: usize,
+}
+
+impl OrderBook {
+    /// Creates an empty book. Rows past `max_rows` are dropped, as TWS does for the number of rows requested.
+    pub fn new(max_rows: usize) -> Self {
+        Self {
+            max_rows: Some(max_rows),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the bid rows, highest price first.
+    pub fn bids(&self) -> &[BookLevel] {
+        &self.bids
+    }
+
+    /// Returns the ask rows, lowest price first.
+    pub fn asks(&self) -> &[BookLevel] {
+        &self.asks
+    }
+
+    /// Returns the number of updates that did not fit the book.
+    pub fn issues(&self) -> usize {
+        self.issues
+    }
+
+    /// Returns the best bid row.
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids.first()
+    }
+
+    /// Returns the best ask row.
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks.first()
+    }
+
+    /// Returns the midpoint of the best bid and ask.
+    pub fn midpoint(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some((bid.price + ask.price) / 2.0)
+    }
+
+    /// Returns the best ask minus the best bid.
+    pub fn spread(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some(ask.price - bid.price)
+    }
+
+    /// Returns the midpoint weighted by the size on the opposite side, which leans towards the side more likely to
+    /// trade through.
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let total = bid.size + ask.size;
+        if total <= 0.0 {
+            return None;
+        }
+        Some((bid.price * ask.size + ask.price * bid.size) / total)
+    }
+
+    /// Returns the top `rows` rows of each side.
+    pub fn top(&self, rows: usize) -> BookSnapshot {
+        BookSnapshot {
+            bids: self.bids.iter().take(rows).cloned().collect(),
+            asks: self.asks.iter().take(rows).cloned().collect(),
+        }
+    }
+
+    /// Returns the price and the running total of size for the top `rows` rows of a side.
+    pub fn cumulative_depth(&self, side: BookSide, rows: usize) -> Vec<(f64, f64)> {
+        let mut total = 0.0;
+        self.ladder(side)
+            .iter()
+            .take(rows)
+            .map(|level| {
+                total += level.size;
+                (level.price, total)
+            })
+            .collect()
+    }
+
+    /// Returns `(bid size - ask size) / (bid size + ask size)` over the top `rows` rows, from -1 when only asks are
+    /// offered to 1 when only bids are.
+    pub fn imbalance(&self, rows: usize) -> Option<f64> {
+        let bid: f64 = self.bids.iter().take(rows).map(|level| level.size).sum();
+        let ask: f64 = self.asks.iter().take(rows).map(|level| level.size).sum();
+        let total = bid + ask;
+        (total > 0.0).then(|| (bid - ask) / total)
+    }
+
+    /// Applies a market depth update. Notices leave the book unchanged and return `Ok(None)`.
+    pub fn apply(&mut self, depth: &MarketDepths) -> Result<Option<BookChange>, BookIssue> {
+        let result = match depth {
+            MarketDepths::MarketDepth(MarketDepth {
+                position,
+                operation,
+                side,
+                price,
+                size,
+            }) => self.apply_row(*position, *operation, *side, *price, *size, None),
+            MarketDepths::MarketDepthL2(MarketDepthL2 {
+                position,
+                market_maker,
+                operation,
+                side,
+                price,
+                size,
+                ..
+            }) => self.apply_row(*position, *operation, *side, *price, *size, Some(market_maker.clone())),
+            MarketDepths::Notice(_) => return Ok(None),
+        };
+
+        if result.is_err() {
+            self.issues += 1;
+        }
+        result.map(Some)
+    }
+
+    fn apply_row(
+        &mut self,
+        position: i32,
+        operation: i32,
+        side: i32,
+        price: f64,
+        size: f64,
+        market_maker: Option<String>,
+    ) -> Result<BookChange, BookIssue> {
+        let side = BookSide::from_wire(side).ok_or(BookIssue::UnknownSide(side))?;
+        let operation = BookOperation::from_wire(operation).ok_or(BookIssue::UnknownOperation(operation))?;
+        let position = usize::try_from(position).map_err(|_| BookIssue::NegativePosition(position))?;
+
+        let max_rows = self.max_rows;
+        let ladder = self.ladder_mut(side);
+        let rows = ladder.len();
+        let level = BookLevel { price, size, market_maker };
+
+        let mut issue = None;
+        let applied = match operation {
+            BookOperation::Insert => {
+                let at = if position > rows {
+                    issue = Some(BookIssue::Gap { side, position, rows });
+                    rows
+                } else {
+                    position
+                };
+                ladder.insert(at, level);
+                if let Some(max_rows) = max_rows {
+                    ladder.truncate(max_rows);
+                }
+                Some(at)
+            }
+            BookOperation::Update => {
+                if let Some(row) = ladder.get_mut(position) {
+                    *row = level;
+                    Some(position)
+                } else {
+                    issue = Some(BookIssue::OutOfRange { side, position, rows });
+                    ladder.push(level);
+                    Some(rows)
+                }
+            }
+            BookOperation::Delete => {
+                if position < rows {
+                    ladder.remove(position);
+                } else {
+                    issue = Some(BookIssue::OutOfRange { side, position, rows });
+                }
+                None
+            }
+        };
+
+        // A row inserted past the rows requested was dropped.
+        if let Some(at) = applied.filter(|at| *at < ladder.len()) {
+            if issue.is_none() && !in_order(ladder, side, at) {
+                issue = Some(BookIssue::OutOfOrder { side, position: at });
+            }
+        }
+
+        match issue {
+            Some(issue) => Err(issue),
+            None => Ok(BookChange { side, position, operation }),
+        }
+    }
+
+    fn ladder(&self, side: BookSide) -> &[BookLevel] {
+        match side {
+            BookSide::Bid => &self.bids,
+            BookSide::Ask => &self.asks,
+        }
+    }
+
+    fn ladder_mut(&mut self, side: BookSide) -> &mut Vec<BookLevel> {
+        match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        }
+    }
+}
+
+// Bids are ordered from the highest price and asks from the lowest. Rows at the same price are in order.
+fn in_order(ladder: &[BookLevel], side: BookSide, at: usize) -> bool {
+    let better_or_equal = |a: &BookLevel, b: &BookLevel| match side {
+        BookSide::Bid => a.price >= b.price,
+        BookSide::Ask => a.price <= b.price,
+    };
+
+    let price = &ladder[at];
+    let after_previous = at == 0 || better_or_equal(&ladder[at - 1], price);
+    let before_next = ladder.get(at + 1).is_none_or(|next| better_or_equal(price, next));
+    after_previous && before_next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(position: i32, operation: i32, side: i32, price: f64, size: f64) -> MarketDepths {
+        MarketDepths::MarketDepth(MarketDepth {
+            position,
+            operation,
+            side,
+            price,
+            size,
+        })
+    }
+
+    #[test]
+    fn test_book_applies_row_operations() {
+        let mut book = OrderBook::new(5);
+
+        book.apply(&depth(0, 0, 1, 185.50, 100.0)).unwrap();
+        book.apply(&depth(1, 0, 1, 185.40, 200.0)).unwrap();
+        book.apply(&depth(0, 0, 1, 185.55, 50.0)).unwrap();
+        book.apply(&depth(0, 0, 0, 185.60, 300.0)).unwrap();
+        book.apply(&depth(1, 0, 0, 185.70, 100.0)).unwrap();
+
+        let prices: Vec<f64> = book.bids().iter().map(|level| level.price).collect();
+        assert_eq!(prices, vec![185.55, 185.50, 185.40]);
+
+        let change = book.apply(&depth(1, 1, 1, 185.50, 150.0)).unwrap();
+        assert_eq!(
+            change,
+            Some(BookChange {
+                side: BookSide::Bid,
+                position: 1,
+                operation: BookOperation::Update
+            })
+        );
+        assert_eq!(book.bids()[1].size, 150.0);
+
+        book.apply(&depth(0, 2, 1, 185.55, 50.0)).unwrap();
+        assert_eq!(book.best_bid().map(|level| level.price), Some(185.50));
+
+        assert_eq!(book.spread().map(|spread| (spread * 100.0).round() / 100.0), Some(0.1));
+        assert_eq!(book.cumulative_depth(BookSide::Ask, 2), vec![(185.60, 300.0), (185.70, 400.0)]);
+        assert_eq!(book.imbalance(1), Some((150.0 - 300.0) / 450.0));
+        assert_eq!(book.microprice(), Some((185.50 * 300.0 + 185.60 * 150.0) / 450.0));
+        assert_eq!(book.top(1).asks.len(), 1);
+        assert_eq!(book.issues(), 0);
+    }
+
+    #[test]
+    fn test_book_truncates_to_max_rows() {
+        let mut book = OrderBook::new(2);
+
+        book.apply(&depth(0, 0, 0, 10.2, 1.0)).unwrap();
+        book.apply(&depth(1, 0, 0, 10.3, 1.0)).unwrap();
+        book.apply(&depth(0, 0, 0, 10.1, 1.0)).unwrap();
+        book.apply(&depth(2, 0, 0, 10.4, 1.0)).unwrap();
+
+        let prices: Vec<f64> = book.asks().iter().map(|level| level.price).collect();
+        assert_eq!(prices, vec![10.1, 10.2]);
+    }
+
+    #[test]
+    fn test_book_flags_invalid_updates() {
+        let mut book = OrderBook::new(5);
+
+        assert_eq!(book.apply(&depth(0, 3, 1, 10.0, 1.0)), Err(BookIssue::UnknownOperation(3)));
+        assert_eq!(book.apply(&depth(0, 0, 2, 10.0, 1.0)), Err(BookIssue::UnknownSide(2)));
+        assert_eq!(
+            book.apply(&depth(2, 0, 1, 10.0, 1.0)),
+            Err(BookIssue::Gap {
+                side: BookSide::Bid,
+                position: 2,
+                rows: 0
+            })
+        );
+        assert_eq!(
+            book.apply(&depth(4, 2, 1, 10.0, 1.0)),
+            Err(BookIssue::OutOfRange {
+                side: BookSide::Bid,
+                position: 4,
+                rows: 1
+            })
+        );
+        assert_eq!(
+            book.apply(&depth(1, 0, 1, 10.5, 1.0)),
+            Err(BookIssue::OutOfOrder {
+                side: BookSide::Bid,
+                position: 1
+            })
+        );
+        assert_eq!(book.issues(), 5);
+    }
+
+    #[test]
+    fn test_book_keeps_market_maker_rows() {
+        let mut book = OrderBook::new(5);
+
+        for (position, market_maker) in ["ISLAND", "ARCA"].iter().enumerate() {
+            book.apply(&MarketDepths::MarketDepthL2(MarketDepthL2 {
+                position: position as i32,
+                market_maker: market_maker.to_string(),
+                operation: 0,
+                side: 1,
+                price: 185.5,
+                size: 100.0,
+                smart_depth: true,
+            }))
+            .unwrap();
+        }
+
+        let market_makers: Vec<_> = book.bids().iter().map(|level| level.market_maker.as_deref()).collect();
+        assert_eq!(market_makers, vec![Some("ISLAND"), Some("ARCA")]);
+        assert_eq!(book.cumulative_depth(BookSide::Bid, 5), vec![(185.5, 100.0), (185.5, 200.0)]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Order book for the blocking client.
+
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+use super::{BookEvent, OrderBook};
+use crate::market_data::realtime::MarketDepths;
+use crate::subscriptions::sync::Subscription;
+use crate::Error;
+
+/// Market depth applied to an [OrderBook].
+///
+/// Iterating the subscription applies each depth update and returns the change, or the issue if the update did not fit
+/// the book.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::BookEvent;
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let contract = Contract::stock("AAPL");
+/// let mut subscription = client.order_book(&contract, 10, true).expect("market depth request failed");
+///
+/// while let Some(event) = subscription.next() {
+///     if let BookEvent::Invalid(issue) = event {
+///         println!("book out of sync: {issue:?}");
+///     }
+///     let book = subscription.book();
+///     println!("microprice {:?}, imbalance {:?}", book.microprice(), book.imbalance(5));
+/// }
+/// ```
+pub struct OrderBookSubscription<'a> {
+    subscription: Subscription<'a, MarketDepths>,
+    book: OrderBook,
+}
+
+impl<'a> OrderBookSubscription<'a> {
+    pub(crate) fn new(subscription: Subscription<'a, MarketDepths>, number_of_rows: usize) -> Self {
+        Self {
+            subscription,
+            book: OrderBook::new(number_of_rows),
+        }
+    }
+
+    /// Returns the order book.
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Applies the updates already received and returns their events.
+    pub fn try_next(&mut self) -> Vec<BookEvent> {
+        let mut events = Vec::new();
+        while let Some(depth) = self.subscription.try_next() {
+            events.extend(self.apply(depth));
+        }
+        events
+    }
+
+    /// Waits up to `timeout` for an update and returns its event.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<BookEvent> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let depth = self.subscription.next_timeout(remaining)?;
+            if let Some(event) = self.apply(depth) {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Returns the error that ended the subscription, if any.
+    pub fn error(&self) -> Option<Error> {
+        self.subscription.error()
+    }
+
+    /// Cancels the market depth subscription.
+    pub fn cancel(&self) {
+        self.subscription.cancel();
+    }
+
+    fn apply(&mut self, depth: MarketDepths) -> Option<BookEvent> {
+        if let MarketDepths::Notice(notice) = &depth {
+            warn!("market depth notice {}: {}", notice.code, notice.message);
+        }
+        BookEvent::from_result(self.book.apply(&depth))
+    }
+}
+
+impl Iterator for OrderBookSubscription<'_> {
+    type Item = BookEvent;
+
+    /// Blocks until an update arrives and returns its event. Returns `None` once the subscription ends; check
+    /// [OrderBookSubscription::error] for the cause.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.subscription.next()?;
+            if let Some(event) = self.apply(depth) {
+                return Some(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use crate::client::sync::Client;
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::order_book::{BookChange, BookEvent, BookOperation, BookSide};
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[test]
+    fn test_order_book_subscription() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "13|1|9001|0|ISLAND|0|1|185.50|100|1|".to_owned(),
+                "13|1|9001|0|ARCA|0|0|185.60|200|1|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SMART_DEPTH);
+
+        let mut subscription = client.order_book(&Contract::stock("AAPL"), 5, true).expect("market depth request failed");
+
+        assert_eq!(
+            subscription.next(),
+            Some(BookEvent::Changed(BookChange {
+                side: BookSide::Bid,
+                position: 0,
+                operation: BookOperation::Insert
+            }))
+        );
+        assert!(matches!(subscription.next(), Some(BookEvent::Changed(_))));
+
+        let book = subscription.book();
+        assert_eq!(book.best_bid().and_then(|level| level.market_maker.as_deref()), Some("ISLAND"));
+        assert_eq!(book.best_ask().map(|level| level.size), Some(200.0));
+    }
+}
@@ -1,11 +1,14 @@
 use log::debug;
 
-use crate::client::{ClientRequestBuilders, ResponseContext, Subscription};
+use crate::client::builders::sync::ClientRequestBuilders;
+use crate::client::sync::Client;
 use crate::contracts::Contract;
 use crate::messages::OutgoingMessages;
 use crate::orders::TagValue;
 use crate::protocol::{check_version, Features};
-use crate::{Client, Error};
+use crate::subscriptions::sync::Subscription;
+use crate::subscriptions::ResponseContext;
+use crate::Error;
 
 use super::common::{decoders, encoders};
 use super::{Bar, BarSize, BidAsk, DepthMarketDataDescription, MarketDepths, MidPoint, TickTypes, Trade, WhatToShow};
@@ -165,6 +168,18 @@ pub fn market_data<'a>(
     generic_ticks: &[&str],
     snapshot: bool,
     regulatory_snapshot: bool,
+) -> Result<Subscription<'a, TickTypes>, Error> {
+    let _market_data_type = client.market_data_type_lock.read()?;
+    send_market_data_request(client, contract, generic_ticks, snapshot, regulatory_snapshot)
+}
+
+// Sends the request without waiting for a fallback subscription switching the market data type.
+pub(crate) fn send_market_data_request<'a>(
+    client: &'a Client,
+    contract: &Contract,
+    generic_ticks: &[&str],
+    snapshot: bool,
+    regulatory_snapshot: bool,
 ) -> Result<Subscription<'a, TickTypes>, Error> {
     let builder = client.request();
     let request = encoders::encode_request_market_data(
@@ -278,7 +293,7 @@ mod tests {
 
         let request = &request_messages[0];
         assert_eq!(request[0], OutgoingMessages::RequestRealTimeBars.to_field(), "Wrong message type");
-        assert_eq!(request[1], "8", "Wrong version");
+        assert_eq!(&request[1], "8", "Wrong version");
         assert_eq!(request[16], what_to_show.to_field(), "Wrong what to show value");
         assert_eq!(request[17], use_rth.to_field(), "Wrong use RTH flag");
     }
@@ -332,7 +347,7 @@ mod tests {
 
         let request = &request_messages[0];
         assert_eq!(request[0], OutgoingMessages::RequestTickByTickData.to_field(), "Wrong message type");
-        assert_eq!(request[14], "AllLast", "Wrong tick type");
+        assert_eq!(&request[14], "AllLast", "Wrong tick type");
     }
 
     #[test]
@@ -391,7 +406,7 @@ mod tests {
 
         let request = &request_messages[0];
         assert_eq!(request[0], OutgoingMessages::RequestMarketDepth.to_field(), "Wrong message type");
-        assert_eq!(request[1], "5", "Wrong version");
+        assert_eq!(&request[1], "5", "Wrong version");
         assert_eq!(request[14], number_of_rows.to_field(), "Wrong number of rows");
         assert_eq!(request[15], is_smart_depth.to_field(), "Wrong smart depth flag");
     }
@@ -472,7 +487,7 @@ mod tests {
         // Verify request message
         let request_messages = client.message_bus.request_messages();
         let request = &request_messages[0];
-        assert_eq!(request[14], "BidAsk", "Wrong tick type");
+        assert_eq!(&request[14], "BidAsk", "Wrong tick type");
     }
 
     #[test]
@@ -527,7 +542,7 @@ mod tests {
 
         let request = &request_messages[0];
         assert_eq!(request[0], OutgoingMessages::RequestTickByTickData.to_field(), "Wrong message type");
-        assert_eq!(request[14], "MidPoint", "Wrong tick type");
+        assert_eq!(&request[14], "MidPoint", "Wrong tick type");
     }
 
     #[test]
@@ -775,6 +790,6 @@ mod tests {
         // Verify request message uses "Last" instead of "AllLast"
         let request_messages = client.message_bus.request_messages();
         let request = &request_messages[0];
-        assert_eq!(request[14], "Last", "Wrong tick type");
+        assert_eq!(&request[14], "Last", "Wrong tick type");
     }
 }
@@ -0,0 +1,331 @@
+//! Tick mux for the async client.
+
+use futures::future::select_all;
+use log::debug;
+
+use super::{check_limit, unresolved, TickByTick, TickByTickType, TickMuxEvent, DEFAULT_MAX_SUBSCRIPTIONS};
+use crate::client::r#async::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{BidAsk, MidPoint, Trade};
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+enum TickSubscription {
+    Trades(Subscription<Trade>),
+    BidAsk(Subscription<BidAsk>),
+    MidPoint(Subscription<MidPoint>),
+}
+
+impl TickSubscription {
+    async fn new(client: &Client, contract: &Contract, tick_type: TickByTickType, ignore_size: bool) -> Result<Self, Error> {
+        Ok(match tick_type {
+            TickByTickType::Last => Self::Trades(client.tick_by_tick_last(contract, 0, ignore_size).await?),
+            TickByTickType::AllLast => Self::Trades(client.tick_by_tick_all_last(contract, 0, ignore_size).await?),
+            TickByTickType::BidAsk => Self::BidAsk(client.tick_by_tick_bid_ask(contract, 0, ignore_size).await?),
+            TickByTickType::MidPoint => Self::MidPoint(client.tick_by_tick_midpoint(contract, 0, ignore_size).await?),
+        })
+    }
+
+    async fn next(&mut self, tick_type: TickByTickType) -> Option<Result<TickByTick, Error>> {
+        match self {
+            Self::Trades(subscription) => Some(subscription.next().await?.map(|trade| match tick_type {
+                TickByTickType::AllLast => TickByTick::AllLast(trade),
+                _ => TickByTick::Last(trade),
+            })),
+            Self::BidAsk(subscription) => Some(subscription.next().await?.map(TickByTick::BidAsk)),
+            Self::MidPoint(subscription) => Some(subscription.next().await?.map(TickByTick::MidPoint)),
+        }
+    }
+
+    async fn cancel(&self) {
+        match self {
+            Self::Trades(subscription) => subscription.cancel().await,
+            Self::BidAsk(subscription) => subscription.cancel().await,
+            Self::MidPoint(subscription) => subscription.cancel().await,
+        }
+    }
+}
+
+struct Stream {
+    contract_id: i32,
+    tick_type: TickByTickType,
+    subscription: TickSubscription,
+}
+
+/// Tick-by-tick data of many contracts merged into one stream.
+///
+/// Contracts can be added and removed between calls to [TickMux::next]. Each contract uses one tick-by-tick
+/// subscription per tick type, and [TickMux::add] refuses contracts that would exceed
+/// [TickMux::max_subscriptions].
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::{TickByTick, TickByTickType, TickMuxEvent};
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let mut mux = client.tick_mux(&[TickByTickType::Last]).max_subscriptions(10);
+///     for symbol in ["AAPL", "MSFT", "NVDA"] {
+///         mux.add(&Contract::stock(symbol)).await.expect("tick-by-tick request failed");
+///     }
+///
+///     while let Some(event) = mux.next().await {
+///         match event {
+///             TickMuxEvent::Tick { contract_id, tick: TickByTick::Last(trade) } => {
+///                 let symbol = &mux.contract(contract_id).unwrap().symbol;
+///                 println!("{symbol}: {} x {}", trade.price, trade.size);
+///             }
+///             TickMuxEvent::Failed { contract_id, error, .. } => {
+///                 println!("{contract_id} failed: {error}");
+///                 mux.remove(contract_id).await;
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+pub struct TickMux {
+    client: Client,
+    tick_types: Vec<TickByTickType>,
+    ignore_size: bool,
+    max_subscriptions: usize,
+    contracts: Vec<Contract>,
+    streams: Vec<Stream>,
+}
+
+impl TickMux {
+    pub(crate) fn new(client: &Client, tick_types: &[TickByTickType]) -> Self {
+        let mut unique = Vec::with_capacity(tick_types.len());
+        for &tick_type in tick_types {
+            if !unique.contains(&tick_type) {
+                unique.push(tick_type);
+            }
+        }
+
+        Self {
+            client: client.clone(),
+            tick_types: unique,
+            ignore_size: false,
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+            contracts: Vec::new(),
+            streams: Vec::new(),
+        }
+    }
+
+    /// Requests ticks without sizes, so bid/ask ticks are only sent when prices change.
+    pub fn ignore_size(mut self, ignore_size: bool) -> Self {
+        self.ignore_size = ignore_size;
+        self
+    }
+
+    /// Sets the number of tick-by-tick subscriptions the mux may open. Defaults to 5, the least TWS allows.
+    pub fn max_subscriptions(mut self, max_subscriptions: usize) -> Self {
+        self.max_subscriptions = max_subscriptions;
+        self
+    }
+
+    /// Subscribes to the ticks of a contract and returns its contract id.
+    ///
+    /// The contract id is looked up if not set. Adding a contract again returns its id without subscribing again.
+    pub async fn add(&mut self, contract: &Contract) -> Result<i32, Error> {
+        let contract = if contract.contract_id > 0 {
+            contract.clone()
+        } else {
+            let details = self.client.contract_details(contract).await?;
+            details
+                .into_iter()
+                .next()
+                .map(|details| details.contract)
+                .ok_or_else(|| unresolved(contract))?
+        };
+
+        if self.contract(contract.contract_id).is_some() {
+            return Ok(contract.contract_id);
+        }
+        check_limit(self.streams.len(), self.tick_types.len(), self.max_subscriptions)?;
+
+        // Subscriptions already opened for the contract are cancelled when dropped on error.
+        let mut streams = Vec::with_capacity(self.tick_types.len());
+        for &tick_type in &self.tick_types {
+            streams.push(Stream {
+                contract_id: contract.contract_id,
+                tick_type,
+                subscription: TickSubscription::new(&self.client, &contract, tick_type, self.ignore_size).await?,
+            });
+        }
+
+        debug!("added {} ({}) to tick mux", contract.symbol, contract.contract_id);
+        let contract_id = contract.contract_id;
+        self.streams.extend(streams);
+        self.contracts.push(contract);
+        Ok(contract_id)
+    }
+
+    /// Subscribes to the ticks of several contracts and returns their contract ids.
+    ///
+    /// Stops at the first contract that could not be added. The contracts before it stay added.
+    pub async fn add_all(&mut self, contracts: &[Contract]) -> Result<Vec<i32>, Error> {
+        let mut contract_ids = Vec::with_capacity(contracts.len());
+        for contract in contracts {
+            contract_ids.push(self.add(contract).await?);
+        }
+        Ok(contract_ids)
+    }
+
+    /// Cancels the ticks of a contract. Returns false if the contract was not added.
+    pub async fn remove(&mut self, contract_id: i32) -> bool {
+        let Some(position) = self.contracts.iter().position(|contract| contract.contract_id == contract_id) else {
+            return false;
+        };
+        self.contracts.remove(position);
+
+        for stream in self.streams.iter().filter(|stream| stream.contract_id == contract_id) {
+            stream.subscription.cancel().await;
+        }
+        self.streams.retain(|stream| stream.contract_id != contract_id);
+        true
+    }
+
+    /// Returns the contracts added and not removed.
+    pub fn contracts(&self) -> &[Contract] {
+        &self.contracts
+    }
+
+    /// Returns the contract with a contract id.
+    pub fn contract(&self, contract_id: i32) -> Option<&Contract> {
+        self.contracts.iter().find(|contract| contract.contract_id == contract_id)
+    }
+
+    /// Returns the number of open tick-by-tick subscriptions.
+    pub fn subscriptions(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Waits for an event. Returns `None` once no subscriptions remain.
+    pub async fn next(&mut self) -> Option<TickMuxEvent> {
+        loop {
+            let (index, tick) = {
+                let pending: Vec<_> = self
+                    .streams
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(index, stream)| Box::pin(async move { (index, stream.subscription.next(stream.tick_type).await) }))
+                    .collect();
+                if pending.is_empty() {
+                    return None;
+                }
+                select_all(pending).await.0
+            };
+
+            let stream = &self.streams[index];
+            match tick {
+                Some(Ok(tick)) => {
+                    return Some(TickMuxEvent::Tick {
+                        contract_id: stream.contract_id,
+                        tick,
+                    })
+                }
+                Some(Err(error)) => {
+                    let stream = self.streams.remove(index);
+                    return Some(TickMuxEvent::Failed {
+                        contract_id: stream.contract_id,
+                        tick_type: stream.tick_type,
+                        error,
+                    });
+                }
+                None => {
+                    let stream = self.streams.remove(index);
+                    debug!("{} ticks of {} ended", stream.tick_type, stream.contract_id);
+                }
+            }
+        }
+    }
+
+    /// Cancels the ticks of all contracts.
+    pub async fn cancel(&mut self) {
+        for stream in &self.streams {
+            stream.subscription.cancel().await;
+        }
+        self.streams.clear();
+        self.contracts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client(response_messages: &[&str]) -> Client {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: response_messages.iter().map(|message| message.to_string()).collect(),
+        });
+        Client::stubbed(message_bus, server_versions::TICK_BY_TICK)
+    }
+
+    fn stock(symbol: &str, contract_id: i32) -> Contract {
+        Contract {
+            contract_id,
+            ..Contract::stock(symbol)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tick_mux() {
+        let client = client(&["99|9001|4|1678740829|3895.375|", "99|9001|4|1678740830|3895.425|"]);
+
+        let mut mux = client.tick_mux(&[TickByTickType::MidPoint]).max_subscriptions(2);
+        assert_eq!(
+            mux.add_all(&[stock("AAPL", 265598), stock("MSFT", 272093)]).await.unwrap(),
+            vec![265598, 272093]
+        );
+        assert!(mux.add(&stock("NVDA", 4815747)).await.is_err(), "limit reached");
+
+        let mut ticks = Vec::new();
+        while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(200), mux.next()).await {
+            match event {
+                TickMuxEvent::Tick {
+                    contract_id,
+                    tick: TickByTick::MidPoint(mid_point),
+                } => ticks.push((contract_id, mid_point.mid_point)),
+                event => panic!("unexpected event: {event:?}"),
+            }
+        }
+        ticks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            ticks,
+            vec![(265598, 3895.375), (265598, 3895.425), (272093, 3895.375), (272093, 3895.425)]
+        );
+
+        assert!(mux.remove(265598).await);
+        assert!(mux.add(&stock("NVDA", 4815747)).await.is_ok(), "slot freed by remove");
+        assert_eq!(mux.contracts().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tick_mux_failure() {
+        let client = client(&["4|2|9001|10190|Max number of tick-by-tick requests has been reached.|"]);
+
+        let mut mux = client.tick_mux(&[TickByTickType::BidAsk]);
+        mux.add(&stock("AAPL", 265598)).await.unwrap();
+
+        match tokio::time::timeout(Duration::from_secs(1), mux.next()).await {
+            Ok(Some(TickMuxEvent::Failed { contract_id, error, .. })) => {
+                assert_eq!(contract_id, 265598);
+                assert!(error.to_string().contains("10190"), "{error}");
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+        assert_eq!(mux.subscriptions(), 0);
+    }
+}
@@ -0,0 +1,129 @@
+//! Tick-by-tick data of many contracts merged into one stream.
+//!
+//! Each `tick_by_tick_*` request streams one tick type of one contract. A `TickMux` holds these subscriptions for a set
+//! of contracts, which can change while it runs, and returns their ticks as [TickMuxEvent]s tagged with the contract id.
+//! An error ends only the subscription that failed.
+//!
+//! TWS limits how many tick-by-tick subscriptions can be open at once, depending on the market data lines of the
+//! account. The mux counts one subscription per contract and tick type, and refuses contracts beyond its limit.
+
+use std::fmt;
+
+use super::{BidAsk, MidPoint, Trade};
+use crate::contracts::Contract;
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+// Tick-by-tick subscriptions open at once on accounts with the minimum of market data lines.
+pub(crate) const DEFAULT_MAX_SUBSCRIPTIONS: usize = 5;
+
+/// Tick type of a tick-by-tick subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickByTickType {
+    /// Trades, see `tick_by_tick_last`.
+    Last,
+    /// Trades including those outside the last price rules, see `tick_by_tick_all_last`.
+    AllLast,
+    /// Quotes, see `tick_by_tick_bid_ask`.
+    BidAsk,
+    /// Midpoints, see `tick_by_tick_midpoint`.
+    MidPoint,
+}
+
+impl fmt::Display for TickByTickType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Last => "Last",
+            Self::AllLast => "AllLast",
+            Self::BidAsk => "BidAsk",
+            Self::MidPoint => "MidPoint",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A tick-by-tick tick.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TickByTick {
+    Last(Trade),
+    AllLast(Trade),
+    BidAsk(BidAsk),
+    MidPoint(MidPoint),
+}
+
+impl TickByTick {
+    /// Returns the tick type of the tick.
+    pub fn tick_type(&self) -> TickByTickType {
+        match self {
+            Self::Last(_) => TickByTickType::Last,
+            Self::AllLast(_) => TickByTickType::AllLast,
+            Self::BidAsk(_) => TickByTickType::BidAsk,
+            Self::MidPoint(_) => TickByTickType::MidPoint,
+        }
+    }
+}
+
+/// Event of a tick mux.
+#[derive(Debug, Clone)]
+pub enum TickMuxEvent {
+    /// A tick of a contract.
+    Tick { contract_id: i32, tick: TickByTick },
+    /// A subscription of a contract ended with an error. The other subscriptions continue.
+    Failed {
+        contract_id: i32,
+        tick_type: TickByTickType,
+        error: Error,
+    },
+}
+
+impl TickMuxEvent {
+    /// Returns the id of the contract of the event.
+    pub fn contract_id(&self) -> i32 {
+        match self {
+            Self::Tick { contract_id, .. } | Self::Failed { contract_id, .. } => *contract_id,
+        }
+    }
+}
+
+// Checks that `additional` subscriptions fit within `max_subscriptions`.
+pub(crate) fn check_limit(open: usize, additional: usize, max_subscriptions: usize) -> Result<(), Error> {
+    if open + additional > max_subscriptions {
+        return Err(Error::InvalidArgument(format!(
+            "{additional} tick-by-tick subscriptions would exceed the limit of {max_subscriptions}, {open} are open"
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn unresolved(contract: &Contract) -> Error {
+    Error::InvalidArgument(format!("no contract found for {}", contract.symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_limit() {
+        assert!(check_limit(0, 5, 5).is_ok());
+        assert!(check_limit(3, 2, 5).is_ok());
+
+        let error = check_limit(4, 2, 5).unwrap_err();
+        assert!(error.to_string().contains("limit of 5"), "{error}");
+    }
+
+    #[test]
+    fn test_tick_type() {
+        let tick = TickByTick::MidPoint(MidPoint {
+            time: time::OffsetDateTime::UNIX_EPOCH,
+            mid_point: 101.25,
+        });
+        assert_eq!(tick.tick_type(), TickByTickType::MidPoint);
+        assert_eq!(TickByTickType::AllLast.to_string(), "AllLast");
+    }
+}
@@ -0,0 +1,378 @@
+//! Tick mux for the blocking client.
+
+use std::time::Duration;
+
+use crossbeam::channel::Receiver;
+use log::debug;
+
+use super::{check_limit, unresolved, TickByTick, TickByTickType, TickMuxEvent, DEFAULT_MAX_SUBSCRIPTIONS};
+use crate::client::sync::Client;
+use crate::contracts::Contract;
+use crate::market_data::realtime::{BidAsk, MidPoint, Trade};
+use crate::subscriptions::sync::{select, Subscription};
+use crate::transport::Response;
+use crate::Error;
+
+enum TickSubscription<'a> {
+    Trades(Subscription<'a, Trade>),
+    BidAsk(Subscription<'a, BidAsk>),
+    MidPoint(Subscription<'a, MidPoint>),
+}
+
+impl<'a> TickSubscription<'a> {
+    fn new(client: &'a Client, contract: &Contract, tick_type: TickByTickType, ignore_size: bool) -> Result<Self, Error> {
+        Ok(match tick_type {
+            TickByTickType::Last => Self::Trades(client.tick_by_tick_last(contract, 0, ignore_size)?),
+            TickByTickType::AllLast => Self::Trades(client.tick_by_tick_all_last(contract, 0, ignore_size)?),
+            TickByTickType::BidAsk => Self::BidAsk(client.tick_by_tick_bid_ask(contract, 0, ignore_size)?),
+            TickByTickType::MidPoint => Self::MidPoint(client.tick_by_tick_midpoint(contract, 0, ignore_size)?),
+        })
+    }
+
+    fn receiver(&self) -> Option<&Receiver<Response>> {
+        match self {
+            Self::Trades(subscription) => subscription.receiver(),
+            Self::BidAsk(subscription) => subscription.receiver(),
+            Self::MidPoint(subscription) => subscription.receiver(),
+        }
+    }
+
+    fn process(&self, tick_type: TickByTickType, response: Response) -> Option<TickByTick> {
+        match self {
+            Self::Trades(subscription) => subscription.process(response).map(|trade| match tick_type {
+                TickByTickType::AllLast => TickByTick::AllLast(trade),
+                _ => TickByTick::Last(trade),
+            }),
+            Self::BidAsk(subscription) => subscription.process(response).map(TickByTick::BidAsk),
+            Self::MidPoint(subscription) => subscription.process(response).map(TickByTick::MidPoint),
+        }
+    }
+
+    fn error(&self) -> Option<Error> {
+        match self {
+            Self::Trades(subscription) => subscription.error(),
+            Self::BidAsk(subscription) => subscription.error(),
+            Self::MidPoint(subscription) => subscription.error(),
+        }
+    }
+
+    fn cancel(&self) {
+        match self {
+            Self::Trades(subscription) => subscription.cancel(),
+            Self::BidAsk(subscription) => subscription.cancel(),
+            Self::MidPoint(subscription) => subscription.cancel(),
+        }
+    }
+}
+
+struct Stream<'a> {
+    contract_id: i32,
+    tick_type: TickByTickType,
+    subscription: TickSubscription<'a>,
+}
+
+/// Tick-by-tick data of many contracts merged into one stream.
+///
+/// Contracts can be added and removed between calls to [TickMux::next]. Each contract uses one tick-by-tick
+/// subscription per tick type, and [TickMux::add] refuses contracts that would exceed
+/// [TickMux::max_subscriptions].
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::{TickByTick, TickByTickType, TickMuxEvent};
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let mut mux = client.tick_mux(&[TickByTickType::Last]).max_subscriptions(10);
+/// for symbol in ["AAPL", "MSFT", "NVDA"] {
+///     mux.add(&Contract::stock(symbol)).expect("tick-by-tick request failed");
+/// }
+///
+/// while let Some(event) = mux.next() {
+///     match event {
+///         TickMuxEvent::Tick { contract_id, tick: TickByTick::Last(trade) } => {
+///             let symbol = &mux.contract(contract_id).unwrap().symbol;
+///             println!("{symbol}: {} x {}", trade.price, trade.size);
+///         }
+///         TickMuxEvent::Failed { contract_id, error, .. } => {
+///             println!("{contract_id} failed: {error}");
+///             mux.remove(contract_id);
+///         }
+///         _ => {}
+///     }
+/// }
+/// ```
+pub struct TickMux<'a> {
+    client: &'a Client,
+    tick_types: Vec<TickByTickType>,
+    ignore_size: bool,
+    max_subscriptions: usize,
+    contracts: Vec<Contract>,
+    streams: Vec<Stream<'a>>,
+}
+
+impl<'a> TickMux<'a> {
+    pub(crate) fn new(client: &'a Client, tick_types: &[TickByTickType]) -> Self {
+        let mut unique = Vec::with_capacity(tick_types.len());
+        for &tick_type in tick_types {
+            if !unique.contains(&tick_type) {
+                unique.push(tick_type);
+            }
+        }
+
+        Self {
+            client,
+            tick_types: unique,
+            ignore_size: false,
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+            contracts: Vec::new(),
+            streams: Vec::new(),
+        }
+    }
+
+    /// Requests ticks without sizes, so bid/ask ticks are only sent when prices change.
+    pub fn ignore_size(mut self, ignore_size: bool) -> Self {
+        self.ignore_size = ignore_size;
+        self
+    }
+
+    /// Sets the number of tick-by-tick subscriptions the mux may open. Defaults to 5, the least TWS allows.
+    pub fn max_subscriptions(mut self, max_subscriptions: usize) -> Self {
+        self.max_subscriptions = max_subscriptions;
+        self
+    }
+
+    /// Subscribes to the ticks of a contract and returns its contract id.
+    ///
+    /// The contract id is looked up if not set. Adding a contract again returns its id without subscribing again.
+    pub fn add(&mut self, contract: &Contract) -> Result<i32, Error> {
+        let contract = if contract.contract_id > 0 {
+            contract.clone()
+        } else {
+            let details = self.client.contract_details(contract)?;
+            details
+                .into_iter()
+                .next()
+                .map(|details| details.contract)
+                .ok_or_else(|| unresolved(contract))?
+        };
+
+        if self.contract(contract.contract_id).is_some() {
+            return Ok(contract.contract_id);
+        }
+        check_limit(self.streams.len(), self.tick_types.len(), self.max_subscriptions)?;
+
+        // Subscriptions already opened for the contract are cancelled when dropped on error.
+        let mut streams = Vec::with_capacity(self.tick_types.len());
+        for &tick_type in &self.tick_types {
+            streams.push(Stream {
+                contract_id: contract.contract_id,
+                tick_type,
+                subscription: TickSubscription::new(self.client, &contract, tick_type, self.ignore_size)?,
+            });
+        }
+
+        debug!("added {} ({}) to tick mux", contract.symbol, contract.contract_id);
+        let contract_id = contract.contract_id;
+        self.streams.extend(streams);
+        self.contracts.push(contract);
+        Ok(contract_id)
+    }
+
+    /// Subscribes to the ticks of several contracts and returns their contract ids.
+    ///
+    /// Stops at the first contract that could not be added. The contracts before it stay added.
+    pub fn add_all(&mut self, contracts: &[Contract]) -> Result<Vec<i32>, Error> {
+        contracts.iter().map(|contract| self.add(contract)).collect()
+    }
+
+    /// Cancels the ticks of a contract. Returns false if the contract was not added.
+    pub fn remove(&mut self, contract_id: i32) -> bool {
+        let Some(position) = self.contracts.iter().position(|contract| contract.contract_id == contract_id) else {
+            return false;
+        };
+        self.contracts.remove(position);
+
+        for stream in self.streams.iter().filter(|stream| stream.contract_id == contract_id) {
+            stream.subscription.cancel();
+        }
+        self.streams.retain(|stream| stream.contract_id != contract_id);
+        true
+    }
+
+    /// Returns the contracts added and not removed.
+    pub fn contracts(&self) -> &[Contract] {
+        &self.contracts
+    }
+
+    /// Returns the contract with a contract id.
+    pub fn contract(&self, contract_id: i32) -> Option<&Contract> {
+        self.contracts.iter().find(|contract| contract.contract_id == contract_id)
+    }
+
+    /// Returns the number of open tick-by-tick subscriptions.
+    pub fn subscriptions(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Waits up to `timeout` for an event.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<TickMuxEvent> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if let Some(event) = self.receive(Some(remaining))? {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Returns an event already received, without waiting.
+    pub fn try_next(&mut self) -> Option<TickMuxEvent> {
+        self.next_timeout(Duration::ZERO)
+    }
+
+    /// Cancels the ticks of all contracts.
+    pub fn cancel(&mut self) {
+        for stream in &self.streams {
+            stream.subscription.cancel();
+        }
+        self.streams.clear();
+        self.contracts.clear();
+    }
+
+    // Returns `None` if no response arrives in time, or `Some(None)` for a response without an event.
+    fn receive(&mut self, timeout: Option<Duration>) -> Option<Option<TickMuxEvent>> {
+        let (indexes, receivers): (Vec<usize>, Vec<_>) = self
+            .streams
+            .iter()
+            .enumerate()
+            .filter_map(|(index, stream)| Some((index, stream.subscription.receiver()?)))
+            .unzip();
+
+        let (selected, response) = select(&receivers, timeout)?;
+        let index = indexes[selected];
+
+        let Some(response) = response else {
+            let stream = self.streams.remove(index);
+            debug!("{} ticks of {} disconnected", stream.tick_type, stream.contract_id);
+            return Some(None);
+        };
+
+        let stream = &self.streams[index];
+        if let Some(tick) = stream.subscription.process(stream.tick_type, response) {
+            return Some(Some(TickMuxEvent::Tick {
+                contract_id: stream.contract_id,
+                tick,
+            }));
+        }
+
+        let error = stream.subscription.error()?;
+        let stream = self.streams.remove(index);
+        Some(Some(TickMuxEvent::Failed {
+            contract_id: stream.contract_id,
+            tick_type: stream.tick_type,
+            error,
+        }))
+    }
+}
+
+impl Iterator for TickMux<'_> {
+    type Item = TickMuxEvent;
+
+    /// Blocks until an event arrives. Returns `None` once no subscriptions remain.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.streams.is_empty() {
+                return None;
+            }
+            if let Some(event) = self.receive(None)? {
+                return Some(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn client(response_messages: &[&str]) -> Client {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: response_messages.iter().map(|message| message.to_string()).collect(),
+        });
+        Client::stubbed(message_bus, server_versions::TICK_BY_TICK)
+    }
+
+    fn stock(symbol: &str, contract_id: i32) -> Contract {
+        Contract {
+            contract_id,
+            ..Contract::stock(symbol)
+        }
+    }
+
+    #[test]
+    fn test_tick_mux() {
+        let client = client(&["99|9001|4|1678740829|3895.375|", "99|9001|4|1678740830|3895.425|"]);
+
+        let mut mux = client.tick_mux(&[TickByTickType::MidPoint]).max_subscriptions(2);
+        assert_eq!(
+            mux.add_all(&[stock("AAPL", 265598), stock("MSFT", 272093)]).unwrap(),
+            vec![265598, 272093]
+        );
+        assert_eq!(mux.add(&stock("AAPL", 265598)).unwrap(), 265598, "already added");
+        assert!(mux.add(&stock("NVDA", 4815747)).is_err(), "limit reached");
+
+        let mut ticks = Vec::new();
+        while let Some(event) = mux.next_timeout(Duration::from_millis(100)) {
+            match event {
+                TickMuxEvent::Tick {
+                    contract_id,
+                    tick: TickByTick::MidPoint(mid_point),
+                } => ticks.push((contract_id, mid_point.mid_point)),
+                event => panic!("unexpected event: {event:?}"),
+            }
+        }
+        ticks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            ticks,
+            vec![(265598, 3895.375), (265598, 3895.425), (272093, 3895.375), (272093, 3895.425)]
+        );
+
+        assert!(mux.remove(265598));
+        assert!(!mux.remove(265598));
+        assert_eq!(mux.contracts().len(), 1);
+        assert!(mux.add(&stock("NVDA", 4815747)).is_ok(), "slot freed by remove");
+    }
+
+    #[test]
+    fn test_tick_mux_failure() {
+        let client = client(&["4|2|9001|10190|Max number of tick-by-tick requests has been reached.|"]);
+
+        let mut mux = client.tick_mux(&[TickByTickType::BidAsk]);
+        mux.add(&stock("AAPL", 265598)).unwrap();
+
+        match mux.next() {
+            Some(TickMuxEvent::Failed {
+                contract_id,
+                tick_type,
+                error,
+            }) => {
+                assert_eq!(contract_id, 265598);
+                assert_eq!(tick_type, TickByTickType::BidAsk);
+                assert!(error.to_string().contains("10190"), "{error}");
+            }
+            event => panic!("unexpected event: {event:?}"),
+        }
+        assert_eq!(mux.subscriptions(), 0);
+        assert_eq!(mux.contracts().len(), 1, "the contract stays until removed");
+        assert!(mux.next().is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Ticker for the async client.
+
+use log::warn;
+use time::OffsetDateTime;
+
+use super::{Ticker, TickerField};
+use crate::market_data::realtime::TickTypes;
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+/// Streaming market data applied to a [Ticker].
+///
+/// Each call to [TickerSubscription::next] waits for a tick that changes the ticker and returns the fields it changed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::GenericTick;
+/// use ibapi::Client;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::connect("127.0.0.1:4002", 100).await.expect("connection failed");
+///
+///     let contract = Contract::stock("AAPL");
+///     let mut subscription = client.ticker(&contract, &[GenericTick::RtVolume]).await.expect("market data request failed");
+///
+///     while let Some(changed) = subscription.next().await {
+///         let changed = changed.expect("market data error");
+///         let ticker = subscription.ticker();
+///         println!("{changed:?}: bid {:?} ask {:?} vwap {:?}", ticker.bid, ticker.ask, ticker.vwap);
+///     }
+/// }
+/// ```
+pub struct TickerSubscription {
+    subscription: Subscription<TickTypes>,
+    ticker: Ticker,
+}
+
+impl TickerSubscription {
+    pub(crate) fn new(subscription: Subscription<TickTypes>) -> Self {
+        Self {
+            subscription,
+            ticker: Ticker::default(),
+        }
+    }
+
+    /// Returns the latest market data.
+    pub fn ticker(&self) -> &Ticker {
+        &self.ticker
+    }
+
+    /// Waits for a tick that changes the ticker and returns the changed fields. Returns `None` once the subscription
+    /// ends.
+    pub async fn next(&mut self) -> Option<Result<Vec<TickerField>, Error>> {
+        loop {
+            let tick = match self.subscription.next().await? {
+                Ok(tick) => tick,
+                Err(err) => return Some(Err(err)),
+            };
+
+            if let TickTypes::Notice(notice) = &tick {
+                warn!("market data notice {}: {}", notice.code, notice.message);
+            }
+
+            let changed = self.ticker.update(&tick, OffsetDateTime::now_utc());
+            if !changed.is_empty() {
+                return Some(Ok(changed));
+            }
+        }
+    }
+
+    /// Cancels the market data subscription.
+    pub async fn cancel(&self) {
+        self.subscription.cancel().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::ticker::TickerField;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+    use crate::Client;
+
+    #[tokio::test]
+    async fn test_ticker_subscription() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec!["1|2|9001|1|185.50|100|7|".to_owned(), "45|2|9001|49|1|".to_owned()],
+        });
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        let mut subscription = client.ticker(&Contract::stock("AAPL"), &[]).await.expect("market data request failed");
+
+        let changed = subscription.next().await.expect("no tick").expect("market data error");
+        assert_eq!(changed, vec![TickerField::Bid, TickerField::BidSize]);
+
+        let changed = subscription.next().await.expect("no tick").expect("market data error");
+        assert_eq!(changed, vec![TickerField::Halted]);
+
+        assert_eq!(subscription.ticker().halted.map(|halted| halted.value), Some(true));
+    }
+}
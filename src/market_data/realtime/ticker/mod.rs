@@ -0,0 +1,448 @@
+//! Consolidated quote maintained from a market data subscription.
+//!
+//! A market data subscription delivers each value as its own tick. A [Ticker] keeps the latest value of each field,
+//! with the time it was updated. `Client::ticker` requests streaming market data and returns a `TickerSubscription`,
+//! which applies the ticks to its ticker and reports the fields each tick changed.
+//!
+//! Delayed ticks update the same fields as their real time counterparts.
+
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+use super::{TickType, TickTypes};
+use crate::contracts::OptionComputation;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+/// A value and the time it was last updated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickValue<T> {
+    /// Latest value.
+    pub value: T,
+    /// Local time the value was received.
+    pub updated: OffsetDateTime,
+}
+
+/// Option model values computed by TWS.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Greeks {
+    /// Implied volatility.
+    pub implied_volatility: Option<f64>,
+    /// Option delta.
+    pub delta: Option<f64>,
+    /// Option gamma.
+    pub gamma: Option<f64>,
+    /// Option vega.
+    pub vega: Option<f64>,
+    /// Option theta.
+    pub theta: Option<f64>,
+    /// Option price.
+    pub option_price: Option<f64>,
+    /// Price of the underlying.
+    pub underlying_price: Option<f64>,
+    /// Present value of the dividends expected on the underlying.
+    pub present_value_dividend: Option<f64>,
+}
+
+impl From<&OptionComputation> for Greeks {
+    fn from(computation: &OptionComputation) -> Self {
+        Self {
+            implied_volatility: computation.implied_volatility,
+            delta: computation.delta,
+            gamma: computation.gamma,
+            vega: computation.vega,
+            theta: computation.theta,
+            option_price: computation.option_price,
+            underlying_price: computation.underlying_price,
+            present_value_dividend: computation.present_value_dividend,
+        }
+    }
+}
+
+/// Dividends reported by the IB Dividends tick, generic tick 456.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dividends {
+    /// Sum of the dividends paid over the past 12 months.
+    pub past_12_months: Option<f64>,
+    /// Sum of the dividends expected over the next 12 months.
+    pub next_12_months: Option<f64>,
+    /// Date of the next dividend.
+    pub next_date: Option<Date>,
+    /// Amount of the next dividend.
+    pub next_amount: Option<f64>,
+}
+
+impl Dividends {
+    // Parses "past 12 months,next 12 months,next date,next amount", such as "0.83,0.92,20130219,0.23".
+    fn parse(value: &str) -> Self {
+        let mut parts = value.split(',').map(str::trim);
+        let mut amount = || parts.next().and_then(|part| part.parse().ok());
+
+        let past_12_months = amount();
+        let next_12_months = amount();
+        let next_date = parts
+            .next()
+            .and_then(|part| Date::parse(part, format_description!("[year][month][day]")).ok());
+        let next_amount = parts.next().and_then(|part| part.parse().ok());
+
+        Self {
+            past_12_months,
+            next_12_months,
+            next_date,
+            next_amount,
+        }
+    }
+}
+
+/// Field of a [Ticker], reported when a tick changes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickerField {
+    /// The bid price.
+    Bid,
+    /// The ask price.
+    Ask,
+    /// The last trade price.
+    Last,
+    /// The size at the bid.
+    BidSize,
+    /// The size at the ask.
+    AskSize,
+    /// The size of the last trade.
+    LastSize,
+    /// The opening price of the day.
+    Open,
+    /// The highest price of the day.
+    High,
+    /// The lowest price of the day.
+    Low,
+    /// The previous day's closing price.
+    Close,
+    /// The volume traded during the day.
+    Volume,
+    /// The volume weighted average price of the day.
+    Vwap,
+    /// The time of the last trade.
+    LastTimestamp,
+    /// Whether trading is halted.
+    Halted,
+    /// The shortable indicator.
+    Shortable,
+    /// The number of shares available to short.
+    ShortableShares,
+    /// The dividends.
+    Dividends,
+    /// The lowest price of the last 13 weeks.
+    Low13Week,
+    /// The highest price of the last 13 weeks.
+    High13Week,
+    /// The lowest price of the last 26 weeks.
+    Low26Week,
+    /// The highest price of the last 26 weeks.
+    High26Week,
+    /// The lowest price of the last 52 weeks.
+    Low52Week,
+    /// The highest price of the last 52 weeks.
+    High52Week,
+    /// The average daily volume.
+    AverageVolume,
+    /// The historical volatility of the underlying.
+    HistoricalVolatility,
+    /// The implied volatility of the options.
+    ImpliedVolatility,
+    /// The mark price.
+    MarkPrice,
+    /// The option model values at the bid.
+    BidGreeks,
+    /// The option model values at the ask.
+    AskGreeks,
+    /// The option model values at the last price.
+    LastGreeks,
+    /// The option model values of the TWS model.
+    ModelGreeks,
+}
+
+/// Latest market data of a contract.
+///
+/// A field is `None` until the first tick for it arrives.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Ticker {
+    /// Highest price a buyer is willing to pay.
+    pub bid: Option<TickValue<f64>>,
+    /// Lowest price a seller is willing to accept.
+    pub ask: Option<TickValue<f64>>,
+    /// Price of the last trade.
+    pub last: Option<TickValue<f64>>,
+    /// Size offered at the bid.
+    pub bid_size: Option<TickValue<f64>>,
+    /// Size offered at the ask.
+    pub ask_size: Option<TickValue<f64>>,
+    /// Size of the last trade.
+    pub last_size: Option<TickValue<f64>>,
+    /// Opening price of the day.
+    pub open: Option<TickValue<f64>>,
+    /// Highest price of the day.
+    pub high: Option<TickValue<f64>>,
+    /// Lowest price of the day.
+    pub low: Option<TickValue<f64>>,
+    /// Previous day's closing price.
+    pub close: Option<TickValue<f64>>,
+    /// Volume traded during the day.
+    pub volume: Option<TickValue<f64>>,
+    /// Volume weighted average price of the day, from the RT Volume tick, generic tick 233.
+    pub vwap: Option<TickValue<f64>>,
+    /// Time of the last trade.
+    pub last_timestamp: Option<TickValue<OffsetDateTime>>,
+    /// Whether trading is halted, from generic tick 49.
+    pub halted: Option<TickValue<bool>>,
+    /// Shortable indicator, from generic tick 236. Above 2.5 at least 1000 shares are available to short.
+    pub shortable: Option<TickValue<f64>>,
+    /// Number of shares available to short, from generic tick 236.
+    pub shortable_shares: Option<TickValue<f64>>,
+    /// Dividends, from generic tick 456.
+    pub dividends: Option<TickValue<Dividends>>,
+    /// Lowest price of the last 13 weeks, from generic tick 165.
+    pub low_13_week: Option<TickValue<f64>>,
+    /// Highest price of the last 13 weeks, from generic tick 165.
+    pub high_13_week: Option<TickValue<f64>>,
+    /// Lowest price of the last 26 weeks, from generic tick 165.
+    pub low_26_week: Option<TickValue<f64>>,
+    /// Highest price of the last 26 weeks, from generic tick 165.
+    pub high_26_week: Option<TickValue<f64>>,
+    /// Lowest price of the last 52 weeks, from generic tick 165.
+    pub low_52_week: Option<TickValue<f64>>,
+    /// Highest price of the last 52 weeks, from generic tick 165.
+    pub high_52_week: Option<TickValue<f64>>,
+    /// Average daily volume over 90 days, from generic tick 165.
+    pub average_volume: Option<TickValue<f64>>,
+    /// Historical volatility of the underlying, from generic tick 104.
+    pub historical_volatility: Option<TickValue<f64>>,
+    /// Implied volatility of the options, from generic tick 106.
+    pub implied_volatility: Option<TickValue<f64>>,
+    /// Mark price, from generic tick 221.
+    pub mark_price: Option<TickValue<f64>>,
+    /// Option model values at the bid.
+    pub bid_greeks: Option<TickValue<Greeks>>,
+    /// Option model values at the ask.
+    pub ask_greeks: Option<TickValue<Greeks>>,
+    /// Option model values at the last price.
+    pub last_greeks: Option<TickValue<Greeks>>,
+    /// Option model values of the TWS model.
+    pub model_greeks: Option<TickValue<Greeks>>,
+}
+
+impl Ticker {
+    /// Returns the midpoint of the bid and ask, if both are known.
+    pub fn midpoint(&self) -> Option<f64> {
+        match (self.bid, self.ask) {
+            (Some(bid), Some(ask)) => Some((bid.value + ask.value) / 2.0),
+            _ => None,
+        }
+    }
+
+    /// Applies a tick received at `now` and returns the fields it changed.
+    pub fn update(&mut self, tick: &TickTypes, now: OffsetDateTime) -> Vec<TickerField> {
+        let mut changed = Vec::new();
+
+        match tick {
+            TickTypes::Price(tick) => self.set_number(&tick.tick_type, tick.price, now, &mut changed),
+            TickTypes::Size(tick) => self.set_number(&tick.tick_type, tick.size, now, &mut changed),
+            TickTypes::PriceSize(tick) => {
+                self.set_number(&tick.price_tick_type, tick.price, now, &mut changed);
+                self.set_number(&tick.size_tick_type, tick.size, now, &mut changed);
+            }
+            TickTypes::Generic(tick) => self.set_number(&tick.tick_type, tick.value, now, &mut changed),
+            TickTypes::String(tick) => self.set_string(&tick.tick_type, &tick.value, now, &mut changed),
+            TickTypes::OptionComputation(computation) => {
+                let (field, name) = match computation.field {
+                    TickType::BidOption | TickType::DelayedBidOption => (&mut self.bid_greeks, TickerField::BidGreeks),
+                    TickType::AskOption | TickType::DelayedAskOption => (&mut self.ask_greeks, TickerField::AskGreeks),
+                    TickType::LastOption | TickType::DelayedLastOption => (&mut self.last_greeks, TickerField::LastGreeks),
+                    TickType::ModelOption | TickType::DelayedModelOption => (&mut self.model_greeks, TickerField::ModelGreeks),
+                    _ => return changed,
+                };
+                set(field, Greeks::from(computation), now, name, &mut changed);
+            }
+            TickTypes::EFP(_) | TickTypes::SnapshotEnd | TickTypes::Notice(_) | TickTypes::RequestParameters(_) | TickTypes::MarketDataType(_) => {}
+        }
+
+        changed
+    }
+
+    fn set_number(&mut self, tick_type: &TickType, value: f64, now: OffsetDateTime, changed: &mut Vec<TickerField>) {
+        if *tick_type == TickType::Halted {
+            set(&mut self.halted, value > 0.0, now, TickerField::Halted, changed);
+            return;
+        }
+
+        let (field, name) = match tick_type {
+            TickType::Bid | TickType::DelayedBid => (&mut self.bid, TickerField::Bid),
+            TickType::Ask | TickType::DelayedAsk => (&mut self.ask, TickerField::Ask),
+            TickType::Last | TickType::DelayedLast => (&mut self.last, TickerField::Last),
+            TickType::BidSize | TickType::DelayedBidSize => (&mut self.bid_size, TickerField::BidSize),
+            TickType::AskSize | TickType::DelayedAskSize => (&mut self.ask_size, TickerField::AskSize),
+            TickType::LastSize | TickType::DelayedLastSize => (&mut self.last_size, TickerField::LastSize),
+            TickType::Open | TickType::DelayedOpen => (&mut self.open, TickerField::Open),
+            TickType::High | TickType::DelayedHigh => (&mut self.high, TickerField::High),
+            TickType::Low | TickType::DelayedLow => (&mut self.low, TickerField::Low),
+            TickType::Close | TickType::DelayedClose => (&mut self.close, TickerField::Close),
+            TickType::Volume | TickType::DelayedVolume => (&mut self.volume, TickerField::Volume),
+            TickType::Shortable => (&mut self.shortable, TickerField::Shortable),
+            TickType::ShortableShares => (&mut self.shortable_shares, TickerField::ShortableShares),
+            TickType::Low13Week => (&mut self.low_13_week, TickerField::Low13Week),
+            TickType::High13Week => (&mut self.high_13_week, TickerField::High13Week),
+            TickType::Low26Week => (&mut self.low_26_week, TickerField::Low26Week),
+            TickType::High26Week => (&mut self.high_26_week, TickerField::High26Week),
+            TickType::Low52Week => (&mut self.low_52_week, TickerField::Low52Week),
+            TickType::High52Week => (&mut self.high_52_week, TickerField::High52Week),
+            TickType::AvgVolume => (&mut self.average_volume, TickerField::AverageVolume),
+            TickType::OptionHistoricalVol => (&mut self.historical_volatility, TickerField::HistoricalVolatility),
+            TickType::OptionImpliedVol => (&mut self.implied_volatility, TickerField::ImpliedVolatility),
+            TickType::MarkPrice => (&mut self.mark_price, TickerField::MarkPrice),
+            _ => return,
+        };
+
+        set(field, value, now, name, changed);
+    }
+
+    fn set_string(&mut self, tick_type: &TickType, value: &str, now: OffsetDateTime, changed: &mut Vec<TickerField>) {
+        match tick_type {
+            TickType::LastTimestamp | TickType::DelayedLastTimestamp => {
+                let timestamp = value.parse().ok().and_then(|seconds| OffsetDateTime::from_unix_timestamp(seconds).ok());
+                if let Some(timestamp) = timestamp {
+                    set(&mut self.last_timestamp, timestamp, now, TickerField::LastTimestamp, changed);
+                }
+            }
+            TickType::RtVolume | TickType::RtTrdVolume => {
+                // "price;size;time;total volume;vwap;single trade"
+                if let Some(vwap) = value.split(';').nth(4).and_then(|vwap| vwap.parse().ok()) {
+                    set(&mut self.vwap, vwap, now, TickerField::Vwap, changed);
+                }
+            }
+            TickType::IbDividends => set(&mut self.dividends, Dividends::parse(value), now, TickerField::Dividends, changed),
+            _ => {}
+        }
+    }
+}
+
+fn set<T: PartialEq>(field: &mut Option<TickValue<T>>, value: T, now: OffsetDateTime, name: TickerField, changed: &mut Vec<TickerField>) {
+    if field.as_ref().is_none_or(|current| current.value != value) {
+        changed.push(name);
+    }
+    *field = Some(TickValue { value, updated: now });
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::market_data::realtime::{TickGeneric, TickPrice, TickPriceSize, TickString};
+
+    #[test]
+    fn test_ticker_tracks_prices_and_sizes() {
+        let mut ticker = Ticker::default();
+        let now = datetime!(2025-04-15 14:30 UTC);
+
+        let tick = TickTypes::PriceSize(TickPriceSize {
+            price_tick_type: TickType::Bid,
+            price: 185.5,
+            size_tick_type: TickType::BidSize,
+            size: 100.0,
+            ..Default::default()
+        });
+        assert_eq!(ticker.update(&tick, now), vec![TickerField::Bid, TickerField::BidSize]);
+
+        let tick = TickTypes::Price(TickPrice {
+            tick_type: TickType::DelayedAsk,
+            price: 185.7,
+            ..Default::default()
+        });
+        assert_eq!(ticker.update(&tick, now), vec![TickerField::Ask]);
+
+        assert_eq!(ticker.bid, Some(TickValue { value: 185.5, updated: now }));
+        assert_eq!(ticker.bid_size.map(|size| size.value), Some(100.0));
+        assert_eq!(ticker.midpoint(), Some(185.6));
+
+        // An unchanged value refreshes the timestamp without reporting a change.
+        let later = now + time::Duration::seconds(1);
+        let tick = TickTypes::Price(TickPrice {
+            tick_type: TickType::Bid,
+            price: 185.5,
+            ..Default::default()
+        });
+        assert!(ticker.update(&tick, later).is_empty());
+        assert_eq!(ticker.bid.map(|bid| bid.updated), Some(later));
+    }
+
+    #[test]
+    fn test_ticker_parses_generic_and_string_ticks() {
+        let mut ticker = Ticker::default();
+        let now = datetime!(2025-04-15 14:30 UTC);
+
+        let ticks = [
+            TickTypes::Generic(TickGeneric {
+                tick_type: TickType::Halted,
+                value: 1.0,
+            }),
+            TickTypes::Generic(TickGeneric {
+                tick_type: TickType::ShortableShares,
+                value: 25000.0,
+            }),
+            TickTypes::String(TickString {
+                tick_type: TickType::RtVolume,
+                value: "701.28;1;1348075471534;67854;701.46918464;true".into(),
+            }),
+            TickTypes::String(TickString {
+                tick_type: TickType::IbDividends,
+                value: "0.83,0.92,20130219,0.23".into(),
+            }),
+            TickTypes::String(TickString {
+                tick_type: TickType::LastTimestamp,
+                value: "1744727400".into(),
+            }),
+        ];
+        for tick in &ticks {
+            ticker.update(tick, now);
+        }
+
+        assert_eq!(ticker.halted.map(|halted| halted.value), Some(true));
+        assert_eq!(ticker.shortable_shares.map(|shares| shares.value), Some(25000.0));
+        assert_eq!(ticker.vwap.map(|vwap| vwap.value), Some(701.46918464));
+        assert_eq!(
+            ticker.dividends.map(|dividends| dividends.value),
+            Some(Dividends {
+                past_12_months: Some(0.83),
+                next_12_months: Some(0.92),
+                next_date: Some(date!(2013 - 02 - 19)),
+                next_amount: Some(0.23),
+            })
+        );
+        assert_eq!(
+            ticker.last_timestamp.map(|timestamp| timestamp.value),
+            Some(datetime!(2025-04-15 14:30 UTC))
+        );
+    }
+
+    #[test]
+    fn test_ticker_tracks_greeks() {
+        let mut ticker = Ticker::default();
+        let now = datetime!(2025-04-15 14:30 UTC);
+
+        let tick = TickTypes::OptionComputation(OptionComputation {
+            field: TickType::ModelOption,
+            implied_volatility: Some(0.25),
+            delta: Some(0.5),
+            ..Default::default()
+        });
+
+        assert_eq!(ticker.update(&tick, now), vec![TickerField::ModelGreeks]);
+
+        let greeks = ticker.model_greeks.expect("model greeks").value;
+        assert_eq!(greeks.delta, Some(0.5));
+        assert_eq!(greeks.implied_volatility, Some(0.25));
+    }
+}
@@ -0,0 +1,152 @@
+//! Ticker for the blocking client.
+
+use std::time::{Duration, Instant};
+
+use log::warn;
+use time::OffsetDateTime;
+
+use super::{Ticker, TickerField};
+use crate::market_data::realtime::TickTypes;
+use crate::subscriptions::sync::Subscription;
+use crate::Error;
+
+/// Streaming market data applied to a [Ticker].
+///
+/// Each call to `next` waits for a tick that changes the ticker and returns the fields it changed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::GenericTick;
+/// use ibapi::blocking::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let contract = Contract::stock("AAPL");
+/// let mut subscription = client.ticker(&contract, &[GenericTick::RtVolume]).expect("market data request failed");
+///
+/// while let Some(changed) = subscription.next() {
+///     let ticker = subscription.ticker();
+///     println!("{changed:?}: bid {:?} ask {:?} vwap {:?}", ticker.bid, ticker.ask, ticker.vwap);
+/// }
+/// ```
+pub struct TickerSubscription<'a> {
+    subscription: Subscription<'a, TickTypes>,
+    ticker: Ticker,
+}
+
+impl<'a> TickerSubscription<'a> {
+    pub(crate) fn new(subscription: Subscription<'a, TickTypes>) -> Self {
+        Self {
+            subscription,
+            ticker: Ticker::default(),
+        }
+    }
+
+    /// Returns the latest market data.
+    pub fn ticker(&self) -> &Ticker {
+        &self.ticker
+    }
+
+    /// Applies the ticks already received and returns the fields they changed, or `None` if nothing changed.
+    pub fn try_next(&mut self) -> Option<Vec<TickerField>> {
+        let mut changed: Vec<TickerField> = Vec::new();
+        while let Some(tick) = self.subscription.try_next() {
+            for field in self.apply(tick).unwrap_or_default() {
+                if !changed.contains(&field) {
+                    changed.push(field);
+                }
+            }
+        }
+        (!changed.is_empty()).then_some(changed)
+    }
+
+    /// Waits up to `timeout` for a tick that changes the ticker and returns the changed fields.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Vec<TickerField>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let tick = self.subscription.next_timeout(remaining)?;
+            if let Some(changed) = self.apply(tick) {
+                return Some(changed);
+            }
+        }
+    }
+
+    /// Returns the error that ended the subscription, if any.
+    pub fn error(&self) -> Option<Error> {
+        self.subscription.error()
+    }
+
+    /// Cancels the market data subscription.
+    pub fn cancel(&self) {
+        self.subscription.cancel();
+    }
+
+    fn apply(&mut self, tick: TickTypes) -> Option<Vec<TickerField>> {
+        if let TickTypes::Notice(notice) = &tick {
+            warn!("market data notice {}: {}", notice.code, notice.message);
+        }
+
+        let changed = self.ticker.update(&tick, OffsetDateTime::now_utc());
+        (!changed.is_empty()).then_some(changed)
+    }
+}
+
+impl Iterator for TickerSubscription<'_> {
+    type Item = Vec<TickerField>;
+
+    /// Blocks until a tick changes the ticker and returns the changed fields. Returns `None` once the subscription
+    /// ends; check [TickerSubscription::error] for the cause.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tick = self.subscription.next()?;
+            if let Some(changed) = self.apply(tick) {
+                return Some(changed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use crate::client::sync::Client;
+    use crate::contracts::Contract;
+    use crate::market_data::realtime::ticker::TickerField;
+    use crate::market_data::realtime::GenericTick;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[test]
+    fn test_ticker_subscription() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![
+                "1|2|9001|1|185.50|100|7|".to_owned(),
+                "2|2|9001|0|150|".to_owned(),
+                "45|2|9001|49|0|".to_owned(),
+            ],
+        });
+        let client = Client::stubbed(message_bus.clone(), server_versions::SIZE_RULES);
+
+        let mut subscription = client
+            .ticker(&Contract::stock("AAPL"), &[GenericTick::RtVolume])
+            .expect("market data request failed");
+
+        assert_eq!(subscription.next(), Some(vec![TickerField::Bid, TickerField::BidSize]));
+        assert_eq!(subscription.next(), Some(vec![TickerField::BidSize]));
+        assert_eq!(subscription.next(), Some(vec![TickerField::Halted]));
+
+        let ticker = subscription.ticker();
+        assert_eq!(ticker.bid.map(|bid| bid.value), Some(185.5));
+        assert_eq!(ticker.bid_size.map(|size| size.value), Some(150.0));
+        assert_eq!(ticker.halted.map(|halted| halted.value), Some(false));
+
+        let request = &message_bus.request_messages()[0];
+        assert_eq!(request.field(0), Some("1"));
+        assert!(request.fields().any(|field| field == "233"));
+    }
+}
@@ -8,6 +8,7 @@ use std::fmt::Display;
 use std::io::Write;
 use std::ops::Index;
 use std::str::{self, FromStr};
+use std::sync::Arc;
 
 use byteorder::{BigEndian, WriteBytesExt};
 
@@ -327,6 +328,7 @@ pub fn request_id_index(kind: IncomingMessages) -> Option<usize> {
         IncomingMessages::HistoricalTick => Some(1),
         IncomingMessages::HistoricalTickBidAsk => Some(1),
         IncomingMessages::HistoricalTickLast => Some(1),
+        IncomingMessages::MarketDataType => Some(2),
         IncomingMessages::MarketDepth => Some(2),
         IncomingMessages::MarketDepthL2 => Some(2),
         IncomingMessages::NewsArticle => Some(1),
@@ -447,6 +449,10 @@ impl ToField for OutgoingMessages {
     fn to_field(&self) -> String {
         (*self as i32).to_string()
     }
+
+    fn write_field(&self, out: &mut String) {
+        (*self as i32).write_field(out);
+    }
 }
 
 impl std::fmt::Display for OutgoingMessages {
@@ -556,9 +562,19 @@ pub fn encode_length(message: &str) -> Vec<u8> {
     packet
 }
 
-#[derive(Default, Debug, Clone)]
+/// Frames a message body with its big-endian length prefix, appending the packet to a reusable buffer.
+pub(crate) fn write_length_prefixed(body: &[u8], packet: &mut Vec<u8>) {
+    packet.reserve(body.len() + 4);
+    packet.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    packet.extend_from_slice(body);
+}
+
+/// Message sent to TWS.
+///
+/// Fields are written straight into a single buffer, each terminated by a NUL as on the wire.
+#[derive(Default, Clone)]
 pub struct RequestMessage {
-    pub(crate) fields: Vec<String>,
+    data: String,
 }
 
 impl RequestMessage {
@@ -567,71 +583,112 @@ impl RequestMessage {
     }
 
     pub(crate) fn push_field<T: ToField>(&mut self, val: &T) -> &RequestMessage {
-        let field = val.to_field();
-        self.fields.push(field);
+        val.write_field(&mut self.data);
+        self.data.push('\0');
         self
     }
 
     pub fn encode(&self) -> String {
-        let mut data = self.fields.join("\0");
-        data.push('\0');
-        data
+        self.data.clone()
+    }
+
+    /// Fields as sent on the wire, each terminated by a NUL.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.data
+    }
+
+    pub(crate) fn fields(&self) -> impl Iterator<Item = &str> {
+        self.data.split_terminator('\0')
+    }
+
+    pub(crate) fn field(&self, i: usize) -> Option<&str> {
+        self.fields().nth(i)
     }
 
     #[cfg(test)]
     pub(crate) fn len(&self) -> usize {
-        self.fields.len()
+        self.fields().count()
     }
 
     #[cfg(test)]
     pub(crate) fn encode_simple(&self) -> String {
-        let mut data = self.fields.join("|");
-        data.push('|');
-        data
+        self.data.replace('\0', "|")
     }
     #[cfg(test)]
     pub fn from(fields: &str) -> RequestMessage {
-        RequestMessage {
-            fields: fields.split_terminator('\x00').map(|x| x.to_string()).collect(),
-        }
+        Self::from_fields(fields.split_terminator('\x00'))
     }
     #[cfg(test)]
     pub fn from_simple(fields: &str) -> RequestMessage {
-        RequestMessage {
-            fields: fields.split_terminator('|').map(|x| x.to_string()).collect(),
+        Self::from_fields(fields.split_terminator('|'))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_fields<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> RequestMessage {
+        let mut message = RequestMessage::new();
+        for field in fields {
+            message.push_field(&field.as_ref());
         }
+        message
     }
 }
 
 impl Index<usize> for RequestMessage {
-    type Output = String;
+    type Output = str;
 
     fn index(&self, i: usize) -> &Self::Output {
-        &self.fields[i]
+        match self.field(i) {
+            Some(field) => field,
+            None => panic!("field index {i} out of range for message with {} fields", self.fields().count()),
+        }
+    }
+}
+
+impl std::fmt::Debug for RequestMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestMessage")
+            .field("fields", &self.fields().collect::<Vec<_>>())
+            .finish()
     }
 }
 
-#[derive(Clone, Default, Debug)]
+/// Message received from TWS.
+///
+/// The frame is kept as a single shared buffer with the end offset of each field, so parsing a frame makes two
+/// allocations regardless of its number of fields and cloning a message makes none. Decoders parse borrowed slices
+/// of the frame and only allocate for the strings they return.
+#[derive(Clone, Default)]
 pub struct ResponseMessage {
     pub i: usize,
-    pub fields: Vec<String>,
+    data: Arc<str>,
+    ends: Arc<[u32]>,
 }
 
 impl ResponseMessage {
     pub fn len(&self) -> usize {
-        self.fields.len()
+        self.ends.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.fields.is_empty()
+        self.ends.is_empty()
+    }
+
+    /// Returns the field at index `i`, if present.
+    pub fn field(&self, i: usize) -> Option<&str> {
+        let end = *self.ends.get(i)? as usize;
+        let start = if i == 0 { 0 } else { self.ends[i - 1] as usize + 1 };
+        Some(&self.data[start..end])
+    }
+
+    /// Iterates over the fields of the message.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).filter_map(|i| self.field(i))
     }
 
     pub fn message_type(&self) -> IncomingMessages {
-        if self.fields.is_empty() {
-            IncomingMessages::NotValid
-        } else {
-            let message_id = i32::from_str(&self.fields[0]).unwrap_or(-1);
-            IncomingMessages::from(message_id)
+        match self.field(0) {
+            Some(message_id) => IncomingMessages::from(i32::from_str(message_id).unwrap_or(-1)),
+            None => IncomingMessages::NotValid,
         }
     }
 
@@ -662,11 +719,10 @@ impl ResponseMessage {
     }
 
     pub fn peek_int(&self, i: usize) -> Result<i32, Error> {
-        if self.i >= self.fields.len() {
+        let Some(field) = self.field(i) else {
             return Err(Error::Simple("expected int and found end of message".into()));
-        }
+        };
 
-        let field = &self.fields[i];
         match field.parse() {
             Ok(val) => Ok(val),
             Err(err) => Err(Error::Parse(i, field.into(), err.to_string())),
@@ -674,17 +730,36 @@ impl ResponseMessage {
     }
 
     pub fn peek_string(&self, i: usize) -> String {
-        self.fields[i].to_owned()
+        self.peek_str(i).to_owned()
     }
 
-    pub fn next_int(&mut self) -> Result<i32, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected int and found end of message".into()));
+    /// Returns the field at index `i` without copying it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message has no field at index `i`.
+    pub fn peek_str(&self, i: usize) -> &str {
+        match self.field(i) {
+            Some(field) => field,
+            None => panic!("field index {i} out of range for message with {} fields", self.len()),
         }
+    }
 
-        let field = &self.fields[self.i];
+    // Advances the cursor, returning the index of the field it was on.
+    fn advance(&mut self, expected: &str) -> Result<usize, Error> {
+        let i = self.i;
+        if i >= self.len() {
+            return Err(Error::Simple(format!("expected {expected} and found end of message")));
+        }
         self.i += 1;
 
+        Ok(i)
+    }
+
+    pub fn next_int(&mut self) -> Result<i32, Error> {
+        let i = self.advance("int")?;
+        let field = self.peek_str(i);
+
         match field.parse() {
             Ok(val) => Ok(val),
             Err(err) => Err(Error::Parse(self.i, field.into(), err.to_string())),
@@ -692,12 +767,8 @@ impl ResponseMessage {
     }
 
     pub fn next_optional_int(&mut self) -> Result<Option<i32>, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional int and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("optional int")?;
+        let field = self.peek_str(i);
 
         if field.is_empty() || field == UNSET_INTEGER {
             return Ok(None);
@@ -710,23 +781,15 @@ impl ResponseMessage {
     }
 
     pub fn next_bool(&mut self) -> Result<bool, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected bool and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("bool")?;
+        let field = self.peek_str(i);
 
         Ok(field == "1")
     }
 
     pub fn next_long(&mut self) -> Result<i64, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected long and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("long")?;
+        let field = self.peek_str(i);
 
         match field.parse() {
             Ok(val) => Ok(val),
@@ -735,12 +798,8 @@ impl ResponseMessage {
     }
 
     pub fn next_optional_long(&mut self) -> Result<Option<i64>, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional long and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("optional long")?;
+        let field = self.peek_str(i);
 
         if field.is_empty() || field == UNSET_LONG {
             return Ok(None);
@@ -753,12 +812,8 @@ impl ResponseMessage {
     }
 
     pub fn next_date_time(&mut self) -> Result<OffsetDateTime, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected datetime and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("datetime")?;
+        let field = self.peek_str(i);
 
         if field.is_empty() {
             return Err(Error::Simple("expected timestamp and found empty string".into()));
@@ -773,22 +828,18 @@ impl ResponseMessage {
     }
 
     pub fn next_string(&mut self) -> Result<String, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected string and found end of message".into()));
-        }
+        self.next_str().map(String::from)
+    }
 
-        let field = &self.fields[self.i];
-        self.i += 1;
-        Ok(String::from(field))
+    /// Returns the next field without copying it.
+    pub fn next_str(&mut self) -> Result<&str, Error> {
+        let i = self.advance("string")?;
+        Ok(self.peek_str(i))
     }
 
     pub fn next_double(&mut self) -> Result<f64, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected double and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("double")?;
+        let field = self.peek_str(i);
 
         if field.is_empty() || field == "0" || field == "0.0" {
             return Ok(0.0);
@@ -801,12 +852,8 @@ impl ResponseMessage {
     }
 
     pub fn next_optional_double(&mut self) -> Result<Option<f64>, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional double and found end of message".into()));
-        }
-
-        let field = &self.fields[self.i];
-        self.i += 1;
+        let i = self.advance("optional double")?;
+        let field = self.peek_str(i);
 
         if field.is_empty() || field == UNSET_DOUBLE {
             return Ok(None);
@@ -823,16 +870,35 @@ impl ResponseMessage {
     }
 
     pub fn from(fields: &str) -> ResponseMessage {
-        ResponseMessage {
-            i: 0,
-            fields: fields.split_terminator('\x00').map(|x| x.to_string()).collect(),
-        }
+        Self::parse(fields, b'\0')
     }
     #[cfg(test)]
     pub fn from_simple(fields: &str) -> ResponseMessage {
+        Self::parse(fields, b'|')
+    }
+
+    // Indexes the fields of a frame. Each field is terminated by the separator, which is optional after the last field.
+    fn parse(frame: &str, separator: u8) -> ResponseMessage {
+        let bytes = frame.as_bytes();
+        let separators = bytes.iter().filter(|&&byte| byte == separator).count();
+        let count = if bytes.last().is_none_or(|&byte| byte == separator) {
+            separators
+        } else {
+            separators + 1
+        };
+
+        let mut positions = bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, &byte)| byte == separator)
+            .map(|(position, _)| position as u32);
+        // Iterating over a range lets the offsets be collected with a single allocation.
+        let ends = (0..count).map(|_| positions.next().unwrap_or(bytes.len() as u32)).collect();
+
         ResponseMessage {
-            i: 0,
-            fields: fields.split_terminator('|').map(|x| x.to_string()).collect(),
+            data: Arc::from(frame),
+            ends,
+            ..Default::default()
         }
     }
 
@@ -841,16 +907,26 @@ impl ResponseMessage {
     }
 
     pub fn encode(&self) -> String {
-        let mut data = self.fields.join("\0");
-        data.push('\0');
+        let mut data = String::with_capacity(self.data.len() + 1);
+        for field in self.fields() {
+            data.push_str(field);
+            data.push('\0');
+        }
         data
     }
 
     #[cfg(test)]
     pub fn encode_simple(&self) -> String {
-        let mut data = self.fields.join("|");
-        data.push('|');
-        data
+        self.encode().replace('\0', "|")
+    }
+}
+
+impl std::fmt::Debug for ResponseMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseMessage")
+            .field("i", &self.i)
+            .field("fields", &self.fields().collect::<Vec<_>>())
+            .finish()
     }
 }
 
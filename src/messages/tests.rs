@@ -11,7 +11,7 @@ fn test_message_encodes_bool() {
     message.push_field(&false);
     message.push_field(&true);
 
-    assert_eq!(2, message.fields.len());
+    assert_eq!(2, message.len());
     assert_eq!("0\01\0", message.encode());
 }
 
@@ -23,7 +23,7 @@ fn test_message_encodes_i32() {
     message.push_field(&Some(2));
     message.push_field(&Option::<i32>::None);
 
-    assert_eq!(3, message.fields.len());
+    assert_eq!(3, message.len());
     assert_eq!("1\02\0\0", message.encode());
 }
 
@@ -35,7 +35,7 @@ fn test_message_encodes_f64() {
     message.push_field(&Some(3.0));
     message.push_field(&Option::<f64>::None);
 
-    assert_eq!(3, message.fields.len());
+    assert_eq!(3, message.len());
     // assert_eq!("2.0\03.0\0\0", message.encode());
 }
 
@@ -46,7 +46,7 @@ fn test_message_encodes_string() {
     message.push_field(&"interactive");
     message.push_field(&"brokers");
 
-    assert_eq!(2, message.fields.len());
+    assert_eq!(2, message.len());
     assert_eq!("interactive\0brokers\0", message.encode());
 }
 
@@ -66,7 +66,7 @@ fn test_message_encodes_rule_80_a() {
     message.push_field(&Some(Rule80A::AgentOtherMemberPT));
     message.push_field(&Option::<Rule80A>::None);
 
-    assert_eq!(10, message.fields.len());
+    assert_eq!(10, message.len());
     assert_eq!("I\0A\0W\0J\0U\0M\0K\0Y\0N\0\0", message.encode());
 }
 
@@ -82,7 +82,7 @@ fn test_message_encodes_order_condition() {
     message.push_field(&OrderCondition::Volume);
     message.push_field(&OrderCondition::PercentChange);
 
-    assert_eq!(6, message.fields.len());
+    assert_eq!(6, message.len());
     assert_eq!("1\03\04\05\06\07\0", message.encode());
 }
 
@@ -96,7 +96,7 @@ fn test_message_encodes_action() {
     message.push_field(&Action::SellShort);
     message.push_field(&Action::SellLong);
 
-    assert_eq!(4, message.fields.len());
+    assert_eq!(4, message.len());
     assert_eq!("BUY\0SELL\0SSHORT\0SLONG\0", message.encode());
 }
 
@@ -120,7 +120,7 @@ fn test_message_encodes_security_type() {
     message.push_field(&SecurityType::CFD);
     message.push_field(&SecurityType::Other("??".to_owned()));
 
-    assert_eq!(15, message.fields.len());
+    assert_eq!(15, message.len());
     assert_eq!(
         "STK\0OPT\0FUT\0IND\0FOP\0CASH\0BAG\0WAR\0BOND\0CMDTY\0NEWS\0FUND\0CRYPTO\0CFD\0??\0",
         message.encode()
@@ -136,7 +136,7 @@ fn test_message_encodes_outgoing_message() {
     message.push_field(&OutgoingMessages::PlaceOrder);
     message.push_field(&OutgoingMessages::RequestUserInfo);
 
-    assert_eq!(4, message.fields.len());
+    assert_eq!(4, message.len());
     assert_eq!("1\02\03\0104\0", message.encode());
 }
 
@@ -149,7 +149,7 @@ fn test_message_encodes_order_open_close() {
     message.push_field(&OrderOpenClose::Open);
     message.push_field(&OrderOpenClose::Close);
 
-    assert_eq!(3, message.fields.len());
+    assert_eq!(3, message.len());
     assert_eq!("\0O\0C\0", message.encode());
 }
 
@@ -162,7 +162,7 @@ fn test_message_encodes_combo_leg_open_close() {
     message.push_field(&ComboLegOpenClose::Close);
     message.push_field(&ComboLegOpenClose::Unknown);
 
-    assert_eq!(4, message.fields.len());
+    assert_eq!(4, message.len());
     assert_eq!("0\01\02\03\0", message.encode());
 }
 
@@ -0,0 +1,212 @@
+//! Pluggable metrics for the transport and subscriptions.
+//!
+//! Install an implementation of [Metrics] with `Client::set_metrics` to receive counters, gauges and histograms
+//! from the dispatcher, the connection and the subscription channels. Every method has an empty default, so an
+//! implementation only needs to handle the kinds of metric it exports.
+//!
+//! | Metric | Kind | Labels | Emitted when |
+//! |--------|------|--------|--------------|
+//! | [MESSAGES_RECEIVED] | counter | `message_type` | a message is dispatched |
+//! | [DECODE_DURATION] | histogram (seconds) | `message_type` | a frame read from the socket is decoded |
+//! | [REQUESTS_SENT] | counter | `message_type` | a request is written to the socket |
+//! | [CANCELLATIONS] | counter | `message_type` | a subscription is cancelled |
+//! | [ORPHANED_RESPONSES] | counter | `message_type` | a response has no subscriber |
+//! | [SUBSCRIPTION_BACKLOG] | gauge | `message_type` | a message is queued for a subscriber, with the queue length |
+//! | [ACTIVE_SUBSCRIPTIONS] | gauge | `kind` (`request` or `order`) | a subscription is opened or released |
+//! | [CONNECTION_RESETS] | counter | | the connection to TWS is lost |
+//! | [RECONNECT_ATTEMPTS] | counter | | a reconnection is attempted |
+//! | [RECONNECTS] | counter | `outcome` (`success` or `failure`) | reconnection succeeds or gives up |
+//!
+//! Incoming message types are labelled with their [IncomingMessages] name and requests with their
+//! [OutgoingMessages] name.
+//!
+//! The `prometheus` feature provides [prometheus::PrometheusMetrics], which renders the metrics in the Prometheus
+//! text exposition format.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//!
+//! use ibapi::metrics::{Label, Metrics, MESSAGES_RECEIVED};
+//!
+//! #[derive(Default)]
+//! struct MessageCounter(AtomicU64);
+//!
+//! impl Metrics for MessageCounter {
+//!     fn increment_counter(&self, name: &'static str, _labels: &[Label], value: u64) {
+//!         if name == MESSAGES_RECEIVED {
+//!             self.0.fetch_add(value, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::messages::{IncomingMessages, OutgoingMessages, RequestMessage};
+
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+
+/// Messages received from TWS.
+pub const MESSAGES_RECEIVED: &str = "ibapi_messages_received_total";
+/// Time spent decoding a frame into a message, in seconds.
+pub const DECODE_DURATION: &str = "ibapi_decode_duration_seconds";
+/// Requests sent to TWS.
+pub const REQUESTS_SENT: &str = "ibapi_requests_sent_total";
+/// Subscriptions cancelled.
+pub const CANCELLATIONS: &str = "ibapi_cancellations_total";
+/// Responses that no subscriber was waiting for.
+pub const ORPHANED_RESPONSES: &str = "ibapi_orphaned_responses_total";
+/// Messages waiting in the channel of the subscriber that last received a message of the type.
+pub const SUBSCRIPTION_BACKLOG: &str = "ibapi_subscription_backlog";
+/// Subscriptions with a channel routed by request or order id.
+pub const ACTIVE_SUBSCRIPTIONS: &str = "ibapi_active_subscriptions";
+/// Connections to TWS lost.
+pub const CONNECTION_RESETS: &str = "ibapi_connection_resets_total";
+/// Reconnection attempts.
+pub const RECONNECT_ATTEMPTS: &str = "ibapi_reconnect_attempts_total";
+/// Completed reconnections, by outcome.
+pub const RECONNECTS: &str = "ibapi_reconnects_total";
+
+/// A metric label as a name and value pair.
+pub type Label<'a> = (&'static str, &'a str);
+
+/// Receives metrics emitted by the client.
+///
+/// Methods are called from the dispatcher and from the threads or tasks sending requests, so implementations should
+/// be cheap and must not block.
+pub trait Metrics: Send + Sync {
+    /// Adds `value` to a counter.
+    fn increment_counter(&self, _name: &'static str, _labels: &[Label], _value: u64) {}
+
+    /// Sets a gauge to `value`.
+    fn set_gauge(&self, _name: &'static str, _labels: &[Label], _value: f64) {}
+
+    /// Records an observation of a histogram.
+    fn record_histogram(&self, _name: &'static str, _labels: &[Label], _value: f64) {}
+}
+
+/// Emits metrics to the installed [Metrics], if any.
+#[derive(Default)]
+pub(crate) struct MetricsRecorder {
+    enabled: AtomicBool,
+    metrics: RwLock<Option<Arc<dyn Metrics>>>,
+}
+
+impl fmt::Debug for MetricsRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetricsRecorder").field("enabled", &self.is_enabled()).finish()
+    }
+}
+
+impl MetricsRecorder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs the metrics implementation, replacing any previous one.
+    pub(crate) fn install(&self, metrics: Arc<dyn Metrics>) {
+        if let Ok(mut installed) = self.metrics.write() {
+            *installed = Some(metrics);
+            self.enabled.store(true, Ordering::Release);
+        }
+    }
+
+    /// Returns true when metrics are installed. Used to skip measurements nobody receives.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    fn installed(&self) -> Option<Arc<dyn Metrics>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        self.metrics.read().ok().and_then(|metrics| metrics.clone())
+    }
+
+    pub(crate) fn message_received(&self, message_type: IncomingMessages) {
+        if let Some(metrics) = self.installed() {
+            let message_type = format!("{message_type:?}");
+            metrics.increment_counter(MESSAGES_RECEIVED, &[("message_type", &message_type)], 1);
+        }
+    }
+
+    pub(crate) fn message_decoded(&self, message_type: IncomingMessages, elapsed: Duration) {
+        if let Some(metrics) = self.installed() {
+            let message_type = format!("{message_type:?}");
+            metrics.record_histogram(DECODE_DURATION, &[("message_type", &message_type)], elapsed.as_secs_f64());
+        }
+    }
+
+    pub(crate) fn request_sent(&self, message: &RequestMessage) {
+        if let Some(metrics) = self.installed() {
+            let message_type = request_type(message);
+            metrics.increment_counter(REQUESTS_SENT, &[("message_type", &message_type)], 1);
+        }
+    }
+
+    pub(crate) fn subscription_cancelled(&self, message: &RequestMessage) {
+        if let Some(metrics) = self.installed() {
+            let message_type = request_type(message);
+            metrics.increment_counter(CANCELLATIONS, &[("message_type", &message_type)], 1);
+        }
+    }
+
+    pub(crate) fn response_orphaned(&self, message_type: IncomingMessages) {
+        if let Some(metrics) = self.installed() {
+            let message_type = format!("{message_type:?}");
+            metrics.increment_counter(ORPHANED_RESPONSES, &[("message_type", &message_type)], 1);
+        }
+    }
+
+    pub(crate) fn backlog(&self, message_type: IncomingMessages, queued: usize) {
+        if let Some(metrics) = self.installed() {
+            let message_type = format!("{message_type:?}");
+            metrics.set_gauge(SUBSCRIPTION_BACKLOG, &[("message_type", &message_type)], queued as f64);
+        }
+    }
+
+    pub(crate) fn active_subscriptions(&self, kind: &'static str, count: usize) {
+        if let Some(metrics) = self.installed() {
+            metrics.set_gauge(ACTIVE_SUBSCRIPTIONS, &[("kind", kind)], count as f64);
+        }
+    }
+
+    pub(crate) fn connection_reset(&self) {
+        if let Some(metrics) = self.installed() {
+            metrics.increment_counter(CONNECTION_RESETS, &[], 1);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    pub(crate) fn reconnect_attempted(&self) {
+        if let Some(metrics) = self.installed() {
+            metrics.increment_counter(RECONNECT_ATTEMPTS, &[], 1);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    pub(crate) fn reconnected(&self, success: bool) {
+        if let Some(metrics) = self.installed() {
+            let outcome = if success { "success" } else { "failure" };
+            metrics.increment_counter(RECONNECTS, &[("outcome", outcome)], 1);
+        }
+    }
+}
+
+// Name of the request type, or its raw id when unknown.
+fn request_type(message: &RequestMessage) -> String {
+    let field = message.field(0).unwrap_or_default();
+    match field.parse::<OutgoingMessages>() {
+        Ok(message_type) => format!("{message_type:?}"),
+        Err(_) => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
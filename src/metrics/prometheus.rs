@@ -0,0 +1,263 @@
+//! [Metrics] adapter rendering the Prometheus text exposition format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use super::{Label, Metrics};
+
+/// Histogram buckets, in seconds, used by [PrometheusMetrics::new]. Sized for message decoding.
+pub const DEFAULT_BUCKETS: &[f64] = &[
+    0.000_001,
+    0.000_002_5,
+    0.000_005,
+    0.000_01,
+    0.000_025,
+    0.000_05,
+    0.000_1,
+    0.000_25,
+    0.000_5,
+    0.001,
+    0.01,
+    0.1,
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+enum Value {
+    Counter(u64),
+    Gauge(f64),
+    Histogram { buckets: Vec<u64>, sum: f64, count: u64 },
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Counter(_) => "counter",
+            Value::Gauge(_) => "gauge",
+            Value::Histogram { .. } => "histogram",
+        }
+    }
+}
+
+/// Collects metrics in memory and renders them for a Prometheus scrape.
+///
+/// Install it on the client with `Client::set_metrics` and serve [PrometheusMetrics::render] from the metrics
+/// endpoint of your HTTP server.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::metrics::prometheus::PrometheusMetrics;
+/// use ibapi::metrics::{Metrics, REQUESTS_SENT};
+///
+/// let metrics = PrometheusMetrics::new();
+/// metrics.increment_counter(REQUESTS_SENT, &[("message_type", "RequestMarketData")], 1);
+///
+/// assert!(metrics
+///     .render()
+///     .contains("ibapi_requests_sent_total{message_type=\"RequestMarketData\"} 1"));
+/// ```
+#[derive(Debug)]
+pub struct PrometheusMetrics {
+    buckets: Vec<f64>,
+    families: Mutex<BTreeMap<&'static str, BTreeMap<Labels, Value>>>,
+}
+
+impl Default for PrometheusMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrometheusMetrics {
+    /// Creates an empty collector with the [DEFAULT_BUCKETS].
+    pub fn new() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS)
+    }
+
+    /// Creates an empty collector with the given histogram bucket upper bounds.
+    pub fn with_buckets(buckets: &[f64]) -> Self {
+        let mut buckets = buckets.to_vec();
+        buckets.sort_by(f64::total_cmp);
+
+        Self {
+            buckets,
+            families: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn update(&self, name: &'static str, labels: &[Label], new: impl FnOnce() -> Value, update: impl FnOnce(&mut Value)) {
+        let Ok(mut families) = self.families.lock() else {
+            return;
+        };
+
+        let labels: Labels = labels.iter().map(|(name, value)| (*name, value.to_string())).collect();
+        let value = families.entry(name).or_default().entry(labels).or_insert_with(new);
+        update(value);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let Ok(families) = self.families.lock() else {
+            return out;
+        };
+
+        for (name, series) in families.iter() {
+            let Some(kind) = series.values().next().map(Value::kind) else {
+                continue;
+            };
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+
+            for (labels, value) in series {
+                match value {
+                    Value::Counter(value) => {
+                        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+                    }
+                    Value::Gauge(value) => {
+                        let _ = writeln!(out, "{name}{} {value}", format_labels(labels, None));
+                    }
+                    Value::Histogram { buckets, sum, count } => {
+                        for (bound, observed) in self.buckets.iter().zip(buckets) {
+                            let le = bound.to_string();
+                            let _ = writeln!(out, "{name}_bucket{} {observed}", format_labels(labels, Some(&le)));
+                        }
+                        let _ = writeln!(out, "{name}_bucket{} {count}", format_labels(labels, Some("+Inf")));
+                        let _ = writeln!(out, "{name}_sum{} {sum}", format_labels(labels, None));
+                        let _ = writeln!(out, "{name}_count{} {count}", format_labels(labels, None));
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn increment_counter(&self, name: &'static str, labels: &[Label], value: u64) {
+        self.update(
+            name,
+            labels,
+            || Value::Counter(0),
+            |current| {
+                if let Value::Counter(current) = current {
+                    *current += value;
+                }
+            },
+        );
+    }
+
+    fn set_gauge(&self, name: &'static str, labels: &[Label], value: f64) {
+        self.update(
+            name,
+            labels,
+            || Value::Gauge(0.0),
+            |current| {
+                if let Value::Gauge(current) = current {
+                    *current = value;
+                }
+            },
+        );
+    }
+
+    fn record_histogram(&self, name: &'static str, labels: &[Label], value: f64) {
+        let bucket_count = self.buckets.len();
+        self.update(
+            name,
+            labels,
+            || Value::Histogram {
+                buckets: vec![0; bucket_count],
+                sum: 0.0,
+                count: 0,
+            },
+            |current| {
+                if let Value::Histogram { buckets, sum, count } = current {
+                    // Buckets are cumulative: an observation counts towards every bucket whose bound it fits under.
+                    for (observed, bound) in buckets.iter_mut().zip(&self.buckets) {
+                        if value <= *bound {
+                            *observed += 1;
+                        }
+                    }
+                    *sum += value;
+                    *count += 1;
+                }
+            },
+        );
+    }
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels.iter().map(|(name, value)| format!("{name}=\"{}\"", escape(value))).collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{ACTIVE_SUBSCRIPTIONS, DECODE_DURATION, MESSAGES_RECEIVED, RECONNECT_ATTEMPTS};
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let metrics = PrometheusMetrics::new();
+
+        metrics.increment_counter(MESSAGES_RECEIVED, &[("message_type", "TickPrice")], 1);
+        metrics.increment_counter(MESSAGES_RECEIVED, &[("message_type", "TickPrice")], 2);
+        metrics.increment_counter(MESSAGES_RECEIVED, &[("message_type", "TickSize")], 1);
+        metrics.increment_counter(RECONNECT_ATTEMPTS, &[], 1);
+        metrics.set_gauge(ACTIVE_SUBSCRIPTIONS, &[("kind", "request")], 4.0);
+        metrics.set_gauge(ACTIVE_SUBSCRIPTIONS, &[("kind", "request")], 3.0);
+
+        let expected = "\
+# TYPE ibapi_active_subscriptions gauge
+ibapi_active_subscriptions{kind=\"request\"} 3
+# TYPE ibapi_messages_received_total counter
+ibapi_messages_received_total{message_type=\"TickPrice\"} 3
+ibapi_messages_received_total{message_type=\"TickSize\"} 1
+# TYPE ibapi_reconnect_attempts_total counter
+ibapi_reconnect_attempts_total 1
+";
+        assert_eq!(metrics.render(), expected);
+    }
+
+    #[test]
+    fn test_render_histogram() {
+        let metrics = PrometheusMetrics::with_buckets(&[0.01, 0.001]);
+
+        metrics.record_histogram(DECODE_DURATION, &[("message_type", "TickPrice")], 0.0005);
+        metrics.record_histogram(DECODE_DURATION, &[("message_type", "TickPrice")], 0.005);
+        metrics.record_histogram(DECODE_DURATION, &[("message_type", "TickPrice")], 0.5);
+
+        let expected = "\
+# TYPE ibapi_decode_duration_seconds histogram
+ibapi_decode_duration_seconds_bucket{message_type=\"TickPrice\",le=\"0.001\"} 1
+ibapi_decode_duration_seconds_bucket{message_type=\"TickPrice\",le=\"0.01\"} 2
+ibapi_decode_duration_seconds_bucket{message_type=\"TickPrice\",le=\"+Inf\"} 3
+ibapi_decode_duration_seconds_sum{message_type=\"TickPrice\"} 0.5055
+ibapi_decode_duration_seconds_count{message_type=\"TickPrice\"} 3
+";
+        assert_eq!(metrics.render(), expected);
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let metrics = PrometheusMetrics::new();
+        metrics.increment_counter(MESSAGES_RECEIVED, &[("message_type", "a\"b\\c\nd")], 1);
+
+        assert!(metrics.render().contains(r#"{message_type="a\"b\\c\nd"}"#));
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Mutex;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Counter(&'static str, Vec<(&'static str, String)>, u64),
+    Gauge(&'static str, Vec<(&'static str, String)>, f64),
+    Histogram(&'static str, Vec<(&'static str, String)>),
+}
+
+#[derive(Default)]
+struct RecordingMetrics {
+    events: Mutex<Vec<Event>>,
+}
+
+fn owned(labels: &[Label]) -> Vec<(&'static str, String)> {
+    labels.iter().map(|(name, value)| (*name, value.to_string())).collect()
+}
+
+impl Metrics for RecordingMetrics {
+    fn increment_counter(&self, name: &'static str, labels: &[Label], value: u64) {
+        self.events.lock().unwrap().push(Event::Counter(name, owned(labels), value));
+    }
+
+    fn set_gauge(&self, name: &'static str, labels: &[Label], value: f64) {
+        self.events.lock().unwrap().push(Event::Gauge(name, owned(labels), value));
+    }
+
+    fn record_histogram(&self, name: &'static str, labels: &[Label], _value: f64) {
+        self.events.lock().unwrap().push(Event::Histogram(name, owned(labels)));
+    }
+}
+
+fn message_type(name: &str) -> Vec<(&'static str, String)> {
+    vec![("message_type", name.to_string())]
+}
+
+#[test]
+fn test_recorder_without_metrics() {
+    let recorder = MetricsRecorder::new();
+
+    assert!(!recorder.is_enabled());
+    recorder.message_received(IncomingMessages::TickPrice);
+    recorder.connection_reset();
+}
+
+#[test]
+fn test_recorder_emits_metrics() {
+    let recorder = MetricsRecorder::new();
+    let metrics = Arc::new(RecordingMetrics::default());
+    recorder.install(metrics.clone());
+
+    assert!(recorder.is_enabled());
+
+    recorder.message_received(IncomingMessages::TickPrice);
+    recorder.message_decoded(IncomingMessages::TickSize, Duration::from_micros(3));
+    recorder.request_sent(&RequestMessage::from_simple("1|11|9000|"));
+    recorder.subscription_cancelled(&RequestMessage::from_simple("2|2|9000|"));
+    recorder.response_orphaned(IncomingMessages::OrderStatus);
+    recorder.backlog(IncomingMessages::TickPrice, 12);
+    recorder.active_subscriptions("request", 2);
+    recorder.connection_reset();
+
+    let events = metrics.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            Event::Counter(MESSAGES_RECEIVED, message_type("TickPrice"), 1),
+            Event::Histogram(DECODE_DURATION, message_type("TickSize")),
+            Event::Counter(REQUESTS_SENT, message_type("RequestMarketData"), 1),
+            Event::Counter(CANCELLATIONS, message_type("CancelMarketData"), 1),
+            Event::Counter(ORPHANED_RESPONSES, message_type("OrderStatus"), 1),
+            Event::Gauge(SUBSCRIPTION_BACKLOG, message_type("TickPrice"), 12.0),
+            Event::Gauge(ACTIVE_SUBSCRIPTIONS, vec![("kind", "request".to_string())], 2.0),
+            Event::Counter(CONNECTION_RESETS, vec![], 1),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_request_type_uses_raw_id() {
+    assert_eq!(request_type(&RequestMessage::from_simple("9999|1|")), "9999");
+    assert_eq!(request_type(&RequestMessage::from_simple("71|2|100|")), "StartApi");
+}
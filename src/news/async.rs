@@ -4,54 +4,11 @@ use super::common::{decoders, encoders};
 use super::*;
 use crate::contracts::Contract;
 use crate::market_data::realtime;
-use crate::messages::{IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
-use crate::subscriptions::{ResponseContext, StreamDecoder, Subscription};
+use crate::messages::OutgoingMessages;
+use crate::subscriptions::{ResponseContext, Subscription};
 use crate::{server_versions, Client, Error};
 use std::sync::Arc;
 
-impl StreamDecoder<NewsBulletin> for NewsBulletin {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::NewsBulletins];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<NewsBulletin, Error> {
-        match message.message_type() {
-            IncomingMessages::NewsBulletins => Ok(decoders::decode_news_bulletin(message.clone())?),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, _request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        encoders::encode_cancel_news_bulletin()
-    }
-}
-
-impl StreamDecoder<NewsArticle> for NewsArticle {
-    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[
-        IncomingMessages::HistoricalNews,
-        IncomingMessages::HistoricalNewsEnd,
-        IncomingMessages::TickNews,
-    ];
-
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<NewsArticle, Error> {
-        match message.message_type() {
-            IncomingMessages::HistoricalNews => Ok(decoders::decode_historical_news(None, message.clone())?),
-            IncomingMessages::HistoricalNewsEnd => Err(Error::EndOfStream),
-            IncomingMessages::TickNews => Ok(decoders::decode_tick_news(message.clone())?),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        // News articles can come from market data subscriptions, so use the appropriate cancel
-        if context.and_then(|c| c.request_type) == Some(OutgoingMessages::RequestMarketData) {
-            let request_id = request_id.expect("Request ID required to encode cancel market data");
-            realtime::common::encoders::encode_cancel_market_data(request_id)
-        } else {
-            // Historical news requests don't need cancellation (they end with HistoricalNewsEnd)
-            Err(Error::NotImplemented)
-        }
-    }
-}
-
 /// Requests news providers which the user has subscribed to.
 pub(crate) async fn news_providers(client: &Client) -> Result<Vec<NewsProvider>, Error> {
     client.check_server_version(server_versions::REQ_NEWS_PROVIDERS, "It does not support news providers requests.")?;
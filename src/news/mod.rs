@@ -12,7 +12,7 @@ mod common;
 
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -91,8 +91,5 @@ pub struct NewsArticleBody {
 }
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
-pub(crate) use sync::{broad_tape_news, contract_news, historical_news, news_article, news_bulletins, news_providers};
-
 #[cfg(feature = "async")]
 pub(crate) use r#async::{broad_tape_news, contract_news, historical_news, news_article, news_bulletins, news_providers};
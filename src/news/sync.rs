@@ -2,40 +2,17 @@
 
 use super::common::{decoders, encoders};
 use super::*;
-use crate::client::{ResponseContext, SharesChannel, StreamDecoder, Subscription};
+use crate::client::sync::Client;
 use crate::contracts::Contract;
 use crate::market_data::realtime;
-use crate::messages::{IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
-use crate::{server_versions, Client, Error};
+use crate::messages::OutgoingMessages;
+use crate::subscriptions::sync::{SharesChannel, Subscription};
+use crate::{server_versions, Error};
 
 impl SharesChannel for Vec<NewsProvider> {}
 
-impl StreamDecoder<NewsBulletin> for NewsBulletin {
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<NewsBulletin, Error> {
-        match message.message_type() {
-            IncomingMessages::NewsBulletins => Ok(decoders::decode_news_bulletin(message.clone())?),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, _request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        encoders::encode_cancel_news_bulletin()
-    }
-}
-
 impl SharesChannel for Subscription<'_, NewsBulletin> {}
 
-impl StreamDecoder<NewsArticle> for NewsArticle {
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<NewsArticle, Error> {
-        match message.message_type() {
-            IncomingMessages::HistoricalNews => Ok(decoders::decode_historical_news(None, message.clone())?),
-            IncomingMessages::HistoricalNewsEnd => Err(Error::EndOfStream),
-            IncomingMessages::TickNews => Ok(decoders::decode_tick_news(message.clone())?),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
 /// Requests news providers which the user has subscribed to.
 pub(crate) fn news_providers(client: &Client) -> Result<Vec<NewsProvider>, Error> {
     client.check_server_version(server_versions::REQ_NEWS_PROVIDERS, "It does not support news providers requests.")?;
@@ -52,7 +29,7 @@ pub(crate) fn news_providers(client: &Client) -> Result<Vec<NewsProvider>, Error
 }
 
 /// Subscribes to IB's News Bulletins.
-pub(crate) fn news_bulletins(client: &Client, all_messages: bool) -> Result<Subscription<NewsBulletin>, Error> {
+pub(crate) fn news_bulletins(client: &Client, all_messages: bool) -> Result<Subscription<'_, NewsBulletin>, Error> {
     let request = encoders::encode_request_news_bulletins(all_messages)?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestNewsBulletins, request)?;
 
@@ -134,7 +111,7 @@ mod tests {
     use super::*;
     use crate::contracts::Contract;
     use crate::news::ArticleType;
-    use crate::{server_versions, stubs::MessageBusStub, Client};
+    use crate::{server_versions, stubs::MessageBusStub};
     use std::sync::{Arc, RwLock};
     use time::macros::datetime;
 
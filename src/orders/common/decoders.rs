@@ -127,7 +127,7 @@ impl OrderDecoder {
     }
 
     fn read_perm_id(&mut self) -> Result<(), Error> {
-        self.order.perm_id = self.message.next_int()?;
+        self.order.perm_id = self.message.next_long()?;
         Ok(())
     }
 
@@ -742,7 +742,7 @@ pub(crate) fn decode_order_status(server_version: i32, message: &mut ResponseMes
         filled: message.next_double()?,
         remaining: message.next_double()?,
         average_fill_price: message.next_double()?,
-        perm_id: message.next_int()?,
+        perm_id: message.next_long()?,
         parent_id: message.next_int()?,
         last_fill_price: message.next_double()?,
         client_id: message.next_int()?,
@@ -789,7 +789,7 @@ pub(crate) fn decode_execution_data(server_version: i32, message: &mut ResponseM
     execution.side = message.next_string()?;
     execution.shares = message.next_double()?;
     execution.price = message.next_double()?;
-    execution.perm_id = message.next_int()?;
+    execution.perm_id = message.next_long()?;
     execution.client_id = message.next_int()?;
     execution.liquidation = message.next_int()?;
     execution.cumulative_quantity = message.next_double()?;
@@ -1,4 +1,5 @@
 pub(super) mod decoders;
 pub(crate) mod encoders;
 pub mod order_builder;
+pub(super) mod stream_decoders;
 pub(super) mod verify;
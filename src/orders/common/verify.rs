@@ -1,9 +1,28 @@
 use crate::contracts::Contract;
 use crate::orders::Order;
-use crate::{server_versions, Client, Error};
+use crate::{server_versions, Error};
+
+// Implemented by the sync and async clients so order verification is shared between them.
+pub(crate) trait ServerVersionCheck {
+    fn check_server_version(&self, version: i32, message: &str) -> Result<(), Error>;
+}
+
+#[cfg(feature = "sync")]
+impl ServerVersionCheck for crate::client::sync::Client {
+    fn check_server_version(&self, version: i32, message: &str) -> Result<(), Error> {
+        crate::client::sync::Client::check_server_version(self, version, message)
+    }
+}
+
+#[cfg(feature = "async")]
+impl ServerVersionCheck for crate::client::r#async::Client {
+    fn check_server_version(&self, version: i32, message: &str) -> Result<(), Error> {
+        crate::client::r#async::Client::check_server_version(self, version, message)
+    }
+}
 
 // Verifies that Order is properly formed.
-pub(crate) fn verify_order(client: &Client, order: &Order, _order_id: i32) -> Result<(), Error> {
+pub(crate) fn verify_order(client: &impl ServerVersionCheck, order: &Order, _order_id: i32) -> Result<(), Error> {
     let is_bag_order: bool = false; // StringsAreEqual(Constants.BagSecType, contract.SecType)
 
     if order.scale_init_level_size.is_some() || order.scale_price_increment.is_some() {
@@ -173,7 +192,7 @@ pub(crate) fn verify_order(client: &Client, order: &Order, _order_id: i32) -> Re
 }
 
 // Verifies that Contract is properly formed.
-pub(crate) fn verify_order_contract(client: &Client, contract: &Contract, _order_id: i32) -> Result<(), Error> {
+pub(crate) fn verify_order_contract(client: &impl ServerVersionCheck, contract: &Contract, _order_id: i32) -> Result<(), Error> {
     if contract
         .combo_legs
         .iter()
@@ -63,7 +63,7 @@ pub struct Order {
     /// The API client id which placed the order.
     pub client_id: i32,
     /// The Host order identifier.
-    pub perm_id: i32,
+    pub perm_id: i64,
     /// Identifies the side.
     /// Generally available values are BUY and SELL.
     /// Additionally, SSHORT and SLONG are available in some institutional-accounts only.
@@ -1002,7 +1002,7 @@ pub struct Execution {
     /// The order's execution price excluding commissions.
     pub price: f64,
     /// The TWS order identifier. The PermId can be 0 for trades originating outside IB.
-    pub perm_id: i32,
+    pub perm_id: i64,
     /// Identifies whether an execution occurred because of an IB-initiated liquidation.
     pub liquidation: i32,
     /// Cumulative quantity.
@@ -1092,7 +1092,7 @@ pub struct OrderStatus {
     /// Average filling price.
     pub average_fill_price: f64,
     /// The order's permId used by the TWS to identify orders.
-    pub perm_id: i32,
+    pub perm_id: i64,
     /// Parent's id. Used for bracket and auto trailing stop orders.
     pub parent_id: i32,
     /// Price at which the last positions were filled.
@@ -1173,13 +1173,13 @@ pub enum ExerciseOptions {
 
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::{
     all_open_orders, auto_open_orders, cancel_order, completed_orders, executions, exercise_options, global_cancel, next_valid_order_id, open_orders,
     order_update_stream, place_order, submit_order,
@@ -1,87 +1,12 @@
-use super::common::{decoders, encoders, verify};
+use super::common::{encoders, verify};
 use super::{CancelOrder, ExecutionFilter, Executions, ExerciseAction, ExerciseOptions, OrderUpdate, Orders, PlaceOrder};
-use crate::client::{StreamDecoder, Subscription};
+use crate::client::sync::Client;
 use crate::contracts::Contract;
-use crate::messages::{IncomingMessages, Notice, OutgoingMessages, ResponseMessage};
-use crate::{server_versions, Client, Error};
+use crate::messages::OutgoingMessages;
+use crate::subscriptions::sync::Subscription;
+use crate::{server_versions, Error};
 use time::OffsetDateTime;
 
-impl StreamDecoder<PlaceOrder> for PlaceOrder {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<PlaceOrder, Error> {
-        match message.message_type() {
-            IncomingMessages::OpenOrder => Ok(PlaceOrder::OpenOrder(decoders::decode_open_order(server_version, message.clone())?)),
-            IncomingMessages::OrderStatus => Ok(PlaceOrder::OrderStatus(decoders::decode_order_status(server_version, message)?)),
-            IncomingMessages::ExecutionData => Ok(PlaceOrder::ExecutionData(decoders::decode_execution_data(server_version, message)?)),
-            IncomingMessages::CommissionsReport => Ok(PlaceOrder::CommissionReport(decoders::decode_commission_report(server_version, message)?)),
-            IncomingMessages::Error => Ok(PlaceOrder::Message(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
-impl StreamDecoder<OrderUpdate> for OrderUpdate {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<OrderUpdate, Error> {
-        match message.message_type() {
-            IncomingMessages::OpenOrder => Ok(OrderUpdate::OpenOrder(decoders::decode_open_order(server_version, message.clone())?)),
-            IncomingMessages::OrderStatus => Ok(OrderUpdate::OrderStatus(decoders::decode_order_status(server_version, message)?)),
-            IncomingMessages::ExecutionData => Ok(OrderUpdate::ExecutionData(decoders::decode_execution_data(server_version, message)?)),
-            IncomingMessages::CommissionsReport => Ok(OrderUpdate::CommissionReport(decoders::decode_commission_report(
-                server_version,
-                message,
-            )?)),
-            IncomingMessages::Error => Ok(OrderUpdate::Message(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
-impl StreamDecoder<CancelOrder> for CancelOrder {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<CancelOrder, Error> {
-        match message.message_type() {
-            IncomingMessages::OrderStatus => Ok(CancelOrder::OrderStatus(decoders::decode_order_status(server_version, message)?)),
-            IncomingMessages::Error => Ok(CancelOrder::Notice(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
-impl StreamDecoder<Orders> for Orders {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Orders, Error> {
-        match message.message_type() {
-            IncomingMessages::CompletedOrder => Ok(Orders::OrderData(decoders::decode_completed_order(server_version, message.clone())?)),
-            IncomingMessages::CommissionsReport => Ok(Orders::OrderData(decoders::decode_open_order(server_version, message.clone())?)),
-            IncomingMessages::OpenOrder => Ok(Orders::OrderData(decoders::decode_open_order(server_version, message.clone())?)),
-            IncomingMessages::OrderStatus => Ok(Orders::OrderStatus(decoders::decode_order_status(server_version, message)?)),
-            IncomingMessages::OpenOrderEnd | IncomingMessages::CompletedOrdersEnd => Err(Error::EndOfStream),
-            IncomingMessages::Error => Ok(Orders::Notice(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
-impl StreamDecoder<Executions> for Executions {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<Executions, Error> {
-        match message.message_type() {
-            IncomingMessages::ExecutionData => Ok(Executions::ExecutionData(decoders::decode_execution_data(server_version, message)?)),
-            IncomingMessages::CommissionsReport => Ok(Executions::CommissionReport(decoders::decode_commission_report(server_version, message)?)),
-            IncomingMessages::ExecutionDataEnd => Err(Error::EndOfStream),
-            IncomingMessages::Error => Ok(Executions::Notice(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
-impl StreamDecoder<ExerciseOptions> for ExerciseOptions {
-    fn decode(server_version: i32, message: &mut ResponseMessage) -> Result<ExerciseOptions, Error> {
-        match message.message_type() {
-            IncomingMessages::OpenOrder => Ok(ExerciseOptions::OpenOrder(decoders::decode_open_order(server_version, message.clone())?)),
-            IncomingMessages::OrderStatus => Ok(ExerciseOptions::OrderStatus(decoders::decode_order_status(server_version, message)?)),
-            IncomingMessages::Error => Ok(ExerciseOptions::Notice(Notice::from(message))),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-}
-
 /// Subscribes to order update events. Only one subscription can be active at a time.
 ///
 /// This function returns a subscription that will receive updates of activity for all orders placed by the client.
@@ -176,7 +101,7 @@ pub fn next_valid_order_id(client: &Client) -> Result<i32, Error> {
 }
 
 // Requests completed [Order]s.
-pub fn completed_orders(client: &Client, api_only: bool) -> Result<Subscription<Orders>, Error> {
+pub fn completed_orders(client: &Client, api_only: bool) -> Result<Subscription<'_, Orders>, Error> {
     client.check_server_version(server_versions::COMPLETED_ORDERS, "It does not support completed orders requests.")?;
 
     let request = encoders::encode_completed_orders(api_only)?;
@@ -191,7 +116,7 @@ pub fn completed_orders(client: &Client, api_only: bool) -> Result<Subscription<
 /// # Arguments
 /// * `client` - [Client] used to communicate with server.
 ///
-pub fn open_orders(client: &Client) -> Result<Subscription<Orders>, Error> {
+pub fn open_orders(client: &Client) -> Result<Subscription<'_, Orders>, Error> {
     let request = encoders::encode_open_orders()?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestOpenOrders, request)?;
 
@@ -200,7 +125,7 @@ pub fn open_orders(client: &Client) -> Result<Subscription<Orders>, Error> {
 
 // Requests all *current* open orders in associated accounts at the current moment.
 // Open orders are returned once; this function does not initiate a subscription.
-pub fn all_open_orders(client: &Client) -> Result<Subscription<Orders>, Error> {
+pub fn all_open_orders(client: &Client) -> Result<Subscription<'_, Orders>, Error> {
     let request = encoders::encode_all_open_orders()?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestAllOpenOrders, request)?;
 
@@ -208,7 +133,7 @@ pub fn all_open_orders(client: &Client) -> Result<Subscription<Orders>, Error> {
 }
 
 // Requests status updates about future orders placed from TWS. Can only be used with client ID 0.
-pub fn auto_open_orders(client: &Client, auto_bind: bool) -> Result<Subscription<Orders>, Error> {
+pub fn auto_open_orders(client: &Client, auto_bind: bool) -> Result<Subscription<'_, Orders>, Error> {
     let request = encoders::encode_auto_open_orders(auto_bind)?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestAutoOpenOrders, request)?;
 
@@ -223,7 +148,7 @@ pub fn auto_open_orders(client: &Client, auto_bind: bool) -> Result<Subscription
 //
 // # Arguments
 // * `filter` - filter criteria used to determine which execution reports are returned
-pub fn executions(client: &Client, filter: ExecutionFilter) -> Result<Subscription<Executions>, Error> {
+pub fn executions(client: &Client, filter: ExecutionFilter) -> Result<Subscription<'_, Executions>, Error> {
     let request_id = client.next_request_id();
 
     let request = encoders::encode_executions(client.server_version, request_id, &filter)?;
@@ -563,7 +488,7 @@ mod tests {
         let message_bus = Arc::new(MessageBusStub {
             request_messages: RwLock::new(vec![]),
             response_messages: vec![
-                "3|41|Cancelled|0|100|0|71270927|0|0|100||0||".to_owned(),
+                "3|41|Cancelled|0|100|0|3271270927|0|0|100||0||".to_owned(),
                 "4|2|41|202|Order Canceled - reason:||".to_owned(),
             ],
         });
@@ -587,7 +512,7 @@ mod tests {
             assert_eq!(order_status.filled, 0.0, "order_status.filled");
             assert_eq!(order_status.remaining, 100.0, "order_status.remaining");
             assert_eq!(order_status.average_fill_price, 0.0, "order_status.average_fill_price");
-            assert_eq!(order_status.perm_id, 71270927, "order_status.perm_id");
+            assert_eq!(order_status.perm_id, 3271270927, "order_status.perm_id");
             assert_eq!(order_status.parent_id, 0, "order_status.parent_id");
             assert_eq!(order_status.last_fill_price, 0.0, "order_status.last_fill_price");
             assert_eq!(order_status.client_id, 100, "order_status.client_id");
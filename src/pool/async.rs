@@ -0,0 +1,229 @@
+//! Connection pool for the async client.
+
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use super::{PoolOptions, Route, Router};
+use crate::client::r#async::Client;
+use crate::contracts::{Contract, ContractDetails};
+use crate::market_data::historical::{self, HistogramEntry};
+use crate::metrics::Metrics;
+use crate::orders::{CancelOrder, ExecutionFilter, Executions, Order, OrderUpdate, Orders, PlaceOrder};
+use crate::subscriptions::Subscription;
+use crate::Error;
+
+/// Connections to TWS or Gateway with distinct client ids, with requests routed across them.
+///
+/// Methods named after [Client] methods forward to the member selected by their [Route]. Use [ClientPool::client]
+/// for requests without a forwarding method.
+///
+/// Members do not reconnect, as the async client has no reconnection. A member whose connection is lost stays
+/// disconnected.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
+/// use ibapi::pool::{ClientPool, PoolOptions, Route};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let options = PoolOptions::new([0, 1, 2]).orders_client_id(1);
+///     let pool = ClientPool::connect("127.0.0.1:4002", options).await.expect("connection failed");
+///
+///     // Spread across the members in turn.
+///     let contract = Contract::stock("AAPL");
+///     let bars = pool
+///         .historical_data(&contract, None, 5.days(), BarSize::Hour, Some(WhatToShow::Trades), true)
+///         .await
+///         .expect("historical data request failed");
+///     println!("{} bars", bars.bars.len());
+///
+///     // Any other request, on the member chosen by route.
+///     let client = pool.client(Route::Historical).expect("no member");
+///     println!("server time: {:?}", client.server_time().await);
+/// }
+/// ```
+pub struct ClientPool {
+    clients: Vec<Client>,
+    router: Router,
+}
+
+impl ClientPool {
+    /// Opens one connection for each client id in the options.
+    pub async fn connect(address: &str, options: PoolOptions) -> Result<ClientPool, Error> {
+        let router = Router::new(&options)?;
+
+        let mut clients = Vec::with_capacity(options.client_ids().len());
+        for client_id in options.client_ids() {
+            clients.push(Client::connect(address, *client_id).await?);
+        }
+
+        Ok(ClientPool { clients, router })
+    }
+
+    /// Returns the member that handles requests for the route.
+    pub fn client(&self, route: Route) -> Result<&Client, Error> {
+        Ok(&self.clients[self.router.route(route)?])
+    }
+
+    /// Returns all members, in the order of their client ids in the options.
+    pub fn clients(&self) -> &[Client] {
+        &self.clients
+    }
+
+    /// Installs the metrics on every member.
+    pub fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        for client in &self.clients {
+            client.set_metrics(Arc::clone(&metrics));
+        }
+    }
+
+    // Orders
+
+    /// Returns the next order id of the order entry member. See [Client::next_order_id].
+    pub fn next_order_id(&self) -> Result<i32, Error> {
+        Ok(self.client(Route::Orders)?.next_order_id())
+    }
+
+    /// See [Client::next_valid_order_id]. Routed to the order entry member.
+    pub async fn next_valid_order_id(&self) -> Result<i32, Error> {
+        self.client(Route::Orders)?.next_valid_order_id().await
+    }
+
+    /// See [Client::place_order]. Routed to the order entry member.
+    pub async fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<PlaceOrder>, Error> {
+        self.client(Route::Orders)?.place_order(order_id, contract, order).await
+    }
+
+    /// See [Client::submit_order]. Routed to the order entry member.
+    pub async fn submit_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<(), Error> {
+        self.client(Route::Orders)?.submit_order(order_id, contract, order).await
+    }
+
+    /// See [Client::cancel_order]. Routed to the order entry member.
+    pub async fn cancel_order(&self, order_id: i32, manual_order_cancel_time: &str) -> Result<Subscription<CancelOrder>, Error> {
+        self.client(Route::Orders)?.cancel_order(order_id, manual_order_cancel_time).await
+    }
+
+    /// See [Client::global_cancel]. Routed to the order entry member.
+    pub async fn global_cancel(&self) -> Result<(), Error> {
+        self.client(Route::Orders)?.global_cancel().await
+    }
+
+    /// See [Client::order_update_stream]. Routed to the order entry member.
+    pub async fn order_update_stream(&self) -> Result<Subscription<OrderUpdate>, Error> {
+        self.client(Route::Orders)?.order_update_stream().await
+    }
+
+    /// See [Client::open_orders]. Routed to the order entry member.
+    pub async fn open_orders(&self) -> Result<Subscription<Orders>, Error> {
+        self.client(Route::Orders)?.open_orders().await
+    }
+
+    /// See [Client::all_open_orders]. Routed to the order entry member.
+    pub async fn all_open_orders(&self) -> Result<Subscription<Orders>, Error> {
+        self.client(Route::Orders)?.all_open_orders().await
+    }
+
+    /// See [Client::completed_orders]. Routed to the order entry member.
+    pub async fn completed_orders(&self, api_only: bool) -> Result<Subscription<Orders>, Error> {
+        self.client(Route::Orders)?.completed_orders(api_only).await
+    }
+
+    /// See [Client::executions]. Routed to the order entry member.
+    pub async fn executions(&self, filter: ExecutionFilter) -> Result<Subscription<Executions>, Error> {
+        self.client(Route::Orders)?.executions(filter).await
+    }
+
+    /// See [Client::auto_open_orders]. Routed to the member with client id 0.
+    pub async fn auto_open_orders(&self, auto_bind: bool) -> Result<Subscription<Orders>, Error> {
+        self.client(Route::OrderBinding)?.auto_open_orders(auto_bind).await
+    }
+
+    // Historical data and contracts
+
+    /// See [Client::head_timestamp]. Routed to each member in turn.
+    pub async fn head_timestamp(&self, contract: &Contract, what_to_show: historical::WhatToShow, use_rth: bool) -> Result<OffsetDateTime, Error> {
+        self.client(Route::Historical)?.head_timestamp(contract, what_to_show, use_rth).await
+    }
+
+    /// See [Client::historical_data]. Routed to each member in turn.
+    pub async fn historical_data(
+        &self,
+        contract: &Contract,
+        end_date: Option<OffsetDateTime>,
+        duration: historical::Duration,
+        bar_size: historical::BarSize,
+        what_to_show: Option<historical::WhatToShow>,
+        use_rth: bool,
+    ) -> Result<historical::HistoricalData, Error> {
+        self.client(Route::Historical)?
+            .historical_data(contract, end_date, duration, bar_size, what_to_show, use_rth)
+            .await
+    }
+
+    /// See [Client::historical_schedule]. Routed to each member in turn.
+    pub async fn historical_schedule(
+        &self,
+        contract: &Contract,
+        end_date: Option<OffsetDateTime>,
+        duration: historical::Duration,
+    ) -> Result<historical::Schedule, Error> {
+        self.client(Route::Historical)?.historical_schedule(contract, end_date, duration).await
+    }
+
+    /// See [Client::histogram_data]. Routed to each member in turn.
+    pub async fn histogram_data(&self, contract: &Contract, use_rth: bool, period: historical::BarSize) -> Result<Vec<HistogramEntry>, Error> {
+        self.client(Route::Historical)?.histogram_data(contract, use_rth, period).await
+    }
+
+    /// See [Client::contract_details]. Routed to each member in turn.
+    pub async fn contract_details(&self, contract: &Contract) -> Result<Vec<ContractDetails>, Error> {
+        self.client(Route::Historical)?.contract_details(contract).await
+    }
+
+    /// See [Client::server_time]. Routed to each member in turn.
+    pub async fn server_time(&self) -> Result<OffsetDateTime, Error> {
+        self.client(Route::Historical)?.server_time().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    #[tokio::test]
+    async fn test_requests_routed_round_robin() {
+        let options = PoolOptions::new([1, 2]);
+        let buses: Vec<Arc<MessageBusStub>> = (0..2)
+            .map(|_| {
+                Arc::new(MessageBusStub {
+                    request_messages: RwLock::new(vec![]),
+                    response_messages: vec!["49|1|1678323335|".to_owned()],
+                })
+            })
+            .collect();
+        let clients = buses
+            .iter()
+            .map(|bus| Client::stubbed(bus.clone(), server_versions::SIZE_RULES))
+            .collect();
+        let pool = ClientPool {
+            clients,
+            router: Router::new(&options).unwrap(),
+        };
+
+        for _ in 0..3 {
+            pool.server_time().await.unwrap();
+        }
+
+        let sent: Vec<usize> = buses.iter().map(|bus| bus.request_messages().len()).collect();
+        assert_eq!(sent, vec![2, 1]);
+    }
+}
@@ -0,0 +1,152 @@
+//! Pools of connections with distinct client ids.
+//!
+//! TWS limits some resources, such as pacing of historical data requests, per client id, and only client id 0 can bind
+//! orders placed in TWS with `auto_open_orders`. A [ClientPool] opens one connection for each configured client id and
+//! routes each request to a member by [Route]:
+//!
+//! * [Route::Historical] spreads historical data and contract lookups across the members in turn.
+//! * [Route::Orders] pins order entry to one member, so order ids and order updates stay on one connection.
+//! * [Route::OrderBinding] uses client id 0, which must be part of the pool.
+//!
+//! Each member is an independent [Client](crate::Client) with its own dispatcher. With the blocking client, a lost
+//! connection is recovered by that member without affecting the others. The async client does not reconnect, so a
+//! member of an async pool stays disconnected once its connection is lost.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Error;
+
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg(feature = "async")]
+pub mod r#async;
+
+#[cfg(all(feature = "sync", not(feature = "async")))]
+pub use sync::ClientPool;
+
+#[cfg(feature = "async")]
+pub use r#async::ClientPool;
+
+/// Client id that can bind orders placed in TWS.
+const ORDER_BINDING_CLIENT_ID: i32 = 0;
+
+/// Selects the member of a [ClientPool] that handles a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Each member in turn. Used for historical data and contract lookups.
+    Historical,
+    /// The member pinned for order entry.
+    Orders,
+    /// The member connected with client id 0.
+    OrderBinding,
+}
+
+/// Options for a [ClientPool].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolOptions {
+    client_ids: Vec<i32>,
+    orders_client_id: Option<i32>,
+}
+
+impl PoolOptions {
+    /// Creates options for a pool with one connection per client id.
+    pub fn new(client_ids: impl IntoIterator<Item = i32>) -> Self {
+        Self {
+            client_ids: client_ids.into_iter().collect(),
+            orders_client_id: None,
+        }
+    }
+
+    /// Sets the client id used for order entry. Defaults to the first client id.
+    pub fn orders_client_id(mut self, client_id: i32) -> Self {
+        self.orders_client_id = Some(client_id);
+        self
+    }
+
+    /// Returns the client ids of the pool members.
+    pub fn client_ids(&self) -> &[i32] {
+        &self.client_ids
+    }
+}
+
+/// Picks member indexes for each [Route].
+#[derive(Debug)]
+pub(crate) struct Router {
+    members: usize,
+    next: AtomicUsize,
+    orders: usize,
+    order_binding: Option<usize>,
+}
+
+impl Router {
+    pub(crate) fn new(options: &PoolOptions) -> Result<Self, Error> {
+        let client_ids = &options.client_ids;
+        if client_ids.is_empty() {
+            return Err(Error::InvalidArgument("client pool needs at least one client id".into()));
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = client_ids.iter().find(|client_id| !seen.insert(**client_id)) {
+            return Err(Error::InvalidArgument(format!("duplicate client id {duplicate} in client pool")));
+        }
+
+        let orders = match options.orders_client_id {
+            Some(client_id) => client_ids
+                .iter()
+                .position(|id| *id == client_id)
+                .ok_or_else(|| Error::InvalidArgument(format!("orders client id {client_id} is not in the client pool")))?,
+            None => 0,
+        };
+
+        Ok(Self {
+            members: client_ids.len(),
+            next: AtomicUsize::new(0),
+            orders,
+            order_binding: client_ids.iter().position(|id| *id == ORDER_BINDING_CLIENT_ID),
+        })
+    }
+
+    pub(crate) fn route(&self, route: Route) -> Result<usize, Error> {
+        match route {
+            Route::Historical => Ok(self.next.fetch_add(1, Ordering::Relaxed) % self.members),
+            Route::Orders => Ok(self.orders),
+            Route::OrderBinding => self
+                .order_binding
+                .ok_or_else(|| Error::Simple("order binding requires client id 0 in the client pool".into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_historical_route_round_robin() {
+        let router = Router::new(&PoolOptions::new([1, 2, 3])).unwrap();
+
+        let routes: Vec<usize> = (0..5).map(|_| router.route(Route::Historical).unwrap()).collect();
+
+        assert_eq!(routes, vec![0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_orders_and_binding_routes() {
+        let router = Router::new(&PoolOptions::new([0, 1, 2]).orders_client_id(2)).unwrap();
+        assert_eq!(router.route(Route::Orders).unwrap(), 2);
+        assert_eq!(router.route(Route::OrderBinding).unwrap(), 0);
+
+        let router = Router::new(&PoolOptions::new([5, 6])).unwrap();
+        assert_eq!(router.route(Route::Orders).unwrap(), 0);
+        assert!(router.route(Route::OrderBinding).is_err());
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert!(Router::new(&PoolOptions::new([])).is_err());
+        assert!(Router::new(&PoolOptions::new([1, 2, 1])).is_err());
+        assert!(Router::new(&PoolOptions::new([1, 2]).orders_client_id(3)).is_err());
+    }
+}
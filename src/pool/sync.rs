@@ -0,0 +1,235 @@
+//! Connection pool for the blocking client.
+
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use super::{PoolOptions, Route, Router};
+use crate::client::sync::Client;
+use crate::contracts::{Contract, ContractDetails};
+use crate::errors::Error;
+use crate::market_data::historical::{self, HistogramEntry};
+use crate::metrics::Metrics;
+use crate::orders::{CancelOrder, ExecutionFilter, Executions, Order, OrderUpdate, Orders, PlaceOrder};
+use crate::subscriptions::sync::Subscription;
+
+/// Connections to TWS or Gateway with distinct client ids, with requests routed across them.
+///
+/// Methods named after [Client] methods forward to the member selected by their [Route]. Use [ClientPool::client]
+/// for requests without a forwarding method.
+///
+/// Each member reconnects on its own when its connection is lost.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::blocking::pool::{ClientPool, PoolOptions, Route};
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::historical::{BarSize, ToDuration, WhatToShow};
+///
+/// let options = PoolOptions::new([0, 1, 2]).orders_client_id(1);
+/// let pool = ClientPool::connect("127.0.0.1:4002", options).expect("connection failed");
+///
+/// // Spread across the members in turn.
+/// let contract = Contract::stock("AAPL");
+/// let bars = pool
+///     .historical_data(&contract, None, 5.days(), BarSize::Hour, WhatToShow::Trades, true)
+///     .expect("historical data request failed");
+/// println!("{} bars", bars.bars.len());
+///
+/// // Any other request, on the member chosen by route.
+/// let client = pool.client(Route::Historical).expect("no member");
+/// println!("server time: {:?}", client.server_time());
+/// ```
+pub struct ClientPool {
+    clients: Vec<Client>,
+    router: Router,
+}
+
+impl ClientPool {
+    /// Opens one connection for each client id in the options.
+    pub fn connect(address: &str, options: PoolOptions) -> Result<ClientPool, Error> {
+        let router = Router::new(&options)?;
+        let clients = options
+            .client_ids()
+            .iter()
+            .map(|client_id| Client::connect(address, *client_id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ClientPool { clients, router })
+    }
+
+    /// Returns the member that handles requests for the route.
+    pub fn client(&self, route: Route) -> Result<&Client, Error> {
+        Ok(&self.clients[self.router.route(route)?])
+    }
+
+    /// Returns all members, in the order of their client ids in the options.
+    pub fn clients(&self) -> &[Client] {
+        &self.clients
+    }
+
+    /// Installs the metrics on every member.
+    pub fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        for client in &self.clients {
+            client.set_metrics(Arc::clone(&metrics));
+        }
+    }
+
+    // Orders
+
+    /// Returns the next order id of the order entry member. See [Client::next_order_id].
+    pub fn next_order_id(&self) -> Result<i32, Error> {
+        Ok(self.client(Route::Orders)?.next_order_id())
+    }
+
+    /// See [Client::next_valid_order_id]. Routed to the order entry member.
+    pub fn next_valid_order_id(&self) -> Result<i32, Error> {
+        self.client(Route::Orders)?.next_valid_order_id()
+    }
+
+    /// See [Client::place_order]. Routed to the order entry member.
+    pub fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<'_, PlaceOrder>, Error> {
+        self.client(Route::Orders)?.place_order(order_id, contract, order)
+    }
+
+    /// See [Client::submit_order]. Routed to the order entry member.
+    pub fn submit_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<(), Error> {
+        self.client(Route::Orders)?.submit_order(order_id, contract, order)
+    }
+
+    /// See [Client::cancel_order]. Routed to the order entry member.
+    pub fn cancel_order(&self, order_id: i32, manual_order_cancel_time: &str) -> Result<Subscription<'_, CancelOrder>, Error> {
+        self.client(Route::Orders)?.cancel_order(order_id, manual_order_cancel_time)
+    }
+
+    /// See [Client::global_cancel]. Routed to the order entry member.
+    pub fn global_cancel(&self) -> Result<(), Error> {
+        self.client(Route::Orders)?.global_cancel()
+    }
+
+    /// See [Client::order_update_stream]. Routed to the order entry member.
+    pub fn order_update_stream(&self) -> Result<Subscription<'_, OrderUpdate>, Error> {
+        self.client(Route::Orders)?.order_update_stream()
+    }
+
+    /// See [Client::open_orders]. Routed to the order entry member.
+    pub fn open_orders(&self) -> Result<Subscription<'_, Orders>, Error> {
+        self.client(Route::Orders)?.open_orders()
+    }
+
+    /// See [Client::all_open_orders]. Routed to the order entry member.
+    pub fn all_open_orders(&self) -> Result<Subscription<'_, Orders>, Error> {
+        self.client(Route::Orders)?.all_open_orders()
+    }
+
+    /// See [Client::completed_orders]. Routed to the order entry member.
+    pub fn completed_orders(&self, api_only: bool) -> Result<Subscription<'_, Orders>, Error> {
+        self.client(Route::Orders)?.completed_orders(api_only)
+    }
+
+    /// See [Client::executions]. Routed to the order entry member.
+    pub fn executions(&self, filter: ExecutionFilter) -> Result<Subscription<'_, Executions>, Error> {
+        self.client(Route::Orders)?.executions(filter)
+    }
+
+    /// See [Client::auto_open_orders]. Routed to the member with client id 0.
+    pub fn auto_open_orders(&self, auto_bind: bool) -> Result<Subscription<'_, Orders>, Error> {
+        self.client(Route::OrderBinding)?.auto_open_orders(auto_bind)
+    }
+
+    // Historical data and contracts
+
+    /// See [Client::head_timestamp]. Routed to each member in turn.
+    pub fn head_timestamp(&self, contract: &Contract, what_to_show: historical::WhatToShow, use_rth: bool) -> Result<OffsetDateTime, Error> {
+        self.client(Route::Historical)?.head_timestamp(contract, what_to_show, use_rth)
+    }
+
+    /// See [Client::historical_data]. Routed to each member in turn.
+    pub fn historical_data(
+        &self,
+        contract: &Contract,
+        interval_end: Option<OffsetDateTime>,
+        duration: historical::Duration,
+        bar_size: historical::BarSize,
+        what_to_show: historical::WhatToShow,
+        use_rth: bool,
+    ) -> Result<historical::HistoricalData, Error> {
+        self.client(Route::Historical)?
+            .historical_data(contract, interval_end, duration, bar_size, what_to_show, use_rth)
+    }
+
+    /// See [Client::historical_schedules]. Routed to each member in turn.
+    pub fn historical_schedules(
+        &self,
+        contract: &Contract,
+        interval_end: OffsetDateTime,
+        duration: historical::Duration,
+    ) -> Result<historical::Schedule, Error> {
+        self.client(Route::Historical)?.historical_schedules(contract, interval_end, duration)
+    }
+
+    /// See [Client::histogram_data]. Routed to each member in turn.
+    pub fn histogram_data(&self, contract: &Contract, use_rth: bool, period: historical::BarSize) -> Result<Vec<HistogramEntry>, Error> {
+        self.client(Route::Historical)?.histogram_data(contract, use_rth, period)
+    }
+
+    /// See [Client::contract_details]. Routed to each member in turn.
+    pub fn contract_details(&self, contract: &Contract) -> Result<Vec<ContractDetails>, Error> {
+        self.client(Route::Historical)?.contract_details(contract)
+    }
+
+    /// See [Client::server_time]. Routed to each member in turn.
+    pub fn server_time(&self) -> Result<OffsetDateTime, Error> {
+        self.client(Route::Historical)?.server_time()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::server_versions;
+    use crate::stubs::MessageBusStub;
+
+    fn stubbed_pool(options: PoolOptions) -> (ClientPool, Vec<Arc<MessageBusStub>>) {
+        let buses: Vec<Arc<MessageBusStub>> = options
+            .client_ids()
+            .iter()
+            .map(|_| {
+                Arc::new(MessageBusStub {
+                    request_messages: RwLock::new(vec![]),
+                    response_messages: vec!["49|1|1678323335|".to_owned()],
+                })
+            })
+            .collect();
+
+        let clients = buses
+            .iter()
+            .map(|bus| Client::stubbed(bus.clone(), server_versions::SIZE_RULES))
+            .collect();
+        let router = Router::new(&options).unwrap();
+
+        (ClientPool { clients, router }, buses)
+    }
+
+    #[test]
+    fn test_requests_routed_round_robin() {
+        let (pool, buses) = stubbed_pool(PoolOptions::new([1, 2]));
+
+        for _ in 0..3 {
+            pool.server_time().unwrap();
+        }
+
+        let sent: Vec<usize> = buses.iter().map(|bus| bus.request_messages().len()).collect();
+        assert_eq!(sent, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_order_binding_requires_client_zero() {
+        let (pool, _) = stubbed_pool(PoolOptions::new([1, 2]));
+
+        assert!(pool.auto_open_orders(true).is_err());
+    }
+}
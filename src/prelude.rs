@@ -41,7 +41,7 @@ pub use crate::accounts::{
 };
 
 // Client subscription type
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use crate::client::Subscription;
 #[cfg(feature = "async")]
 pub use crate::subscriptions::Subscription;
@@ -1,2 +1,3 @@
 pub(crate) mod decoders;
 pub(crate) mod encoders;
+pub(super) mod stream_decoders;
@@ -0,0 +1,27 @@
+//! Common StreamDecoder implementations for scanner module
+//!
+//! This module contains the StreamDecoder trait implementations that are shared
+//! between sync and async versions, avoiding code duplication.
+
+use crate::messages::{IncomingMessages, RequestMessage, ResponseMessage};
+use crate::scanner::*;
+use crate::subscriptions::{ResponseContext, StreamDecoder};
+use crate::Error;
+
+use super::{decoders, encoders};
+
+impl StreamDecoder<Vec<ScannerData>> for Vec<ScannerData> {
+    const RESPONSE_MESSAGE_IDS: &'static [IncomingMessages] = &[IncomingMessages::ScannerData];
+
+    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Vec<ScannerData>, Error> {
+        match message.message_type() {
+            IncomingMessages::ScannerData => Ok(decoders::decode_scanner_data(message.clone())?),
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel scanner subscription.");
+        encoders::encode_cancel_scanner_subscription(request_id)
+    }
+}
@@ -11,7 +11,7 @@ mod common;
 
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -105,8 +105,5 @@ pub struct ScannerData {
 }
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
-pub(crate) use sync::{scanner_parameters, scanner_subscription};
-
 #[cfg(feature = "async")]
 pub(crate) use r#async::{scanner_parameters, scanner_subscription};
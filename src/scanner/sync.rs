@@ -2,24 +2,11 @@
 
 use super::common::{decoders, encoders};
 use super::*;
-use crate::client::{ResponseContext, StreamDecoder, Subscription};
-use crate::messages::{IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::client::sync::Client;
+use crate::messages::OutgoingMessages;
 use crate::orders::TagValue;
-use crate::{server_versions, Client, Error};
-
-impl StreamDecoder<Vec<ScannerData>> for Vec<ScannerData> {
-    fn decode(_server_version: i32, message: &mut ResponseMessage) -> Result<Vec<ScannerData>, Error> {
-        match message.message_type() {
-            IncomingMessages::ScannerData => Ok(decoders::decode_scanner_data(message.clone())?),
-            _ => Err(Error::UnexpectedResponse(message.clone())),
-        }
-    }
-
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: Option<&ResponseContext>) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel scanner subscription.");
-        encoders::encode_cancel_scanner_subscription(request_id)
-    }
-}
+use crate::subscriptions::sync::Subscription;
+use crate::{server_versions, Error};
 
 /// Requests an XML list of scanner parameters valid in TWS.
 pub(crate) fn scanner_parameters(client: &Client) -> Result<String, Error> {
@@ -217,6 +217,17 @@ impl<T> Subscription<T> {
 
         // The AsyncInternalSubscription's Drop will handle cleanup
     }
+
+    /// Returns the number of messages dropped because the subscription buffer was full.
+    ///
+    /// Always zero for unbounded buffers, the default, unless the consumer fell more than 1024 messages behind.
+    /// See [BufferConfig](crate::subscriptions::BufferConfig).
+    pub fn dropped_messages(&self) -> u64 {
+        match &self.inner {
+            SubscriptionInner::WithDecoder { subscription, .. } => subscription.dropped_messages(),
+            SubscriptionInner::PreDecoded { .. } => 0,
+        }
+    }
 }
 
 impl<T> Drop for Subscription<T> {
@@ -0,0 +1,69 @@
+//! Buffer configuration for subscriptions.
+
+/// What a subscription does with an incoming message once its buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Waits for the consumer to make room. Nothing is dropped, but the dispatcher stalls and delays every other subscription.
+    #[default]
+    Block,
+    /// Drops the oldest buffered message to make room for the new one.
+    DropOldest,
+    /// Drops the incoming message.
+    DropNewest,
+    /// Replaces the buffered tick of the same tick type, keeping only the latest value of price, size and generic ticks.
+    /// Other messages, such as market depth rows and trades, are never dropped: they push out the oldest of those ticks,
+    /// or wait for room like [OverflowPolicy::Block] when none is buffered.
+    ConflateByKey,
+}
+
+/// Capacity and overflow policy of a subscription buffer.
+///
+/// Buffers are unbounded by default. Errors, cancellations and shutdown notices are never dropped.
+///
+/// Buffers are configured per request type with `Client::set_subscription_buffer`, and the buffer of each subscription
+/// is fixed when the subscription starts.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::subscriptions::{BufferConfig, OverflowPolicy};
+///
+/// // Keep only the latest value of each tick type when the consumer falls behind.
+/// let config = BufferConfig::bounded(256, OverflowPolicy::ConflateByKey);
+///
+/// assert_eq!(config.capacity(), Some(256));
+/// assert_eq!(BufferConfig::default(), BufferConfig::unbounded());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BufferConfig {
+    capacity: Option<usize>,
+    policy: OverflowPolicy,
+}
+
+impl BufferConfig {
+    /// Buffer without a capacity limit.
+    pub const fn unbounded() -> Self {
+        Self {
+            capacity: None,
+            policy: OverflowPolicy::Block,
+        }
+    }
+
+    /// Buffer holding up to `capacity` messages, applying `policy` once full. A capacity of zero is treated as one.
+    pub const fn bounded(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: Some(if capacity == 0 { 1 } else { capacity }),
+            policy,
+        }
+    }
+
+    /// Maximum number of buffered messages, or `None` when unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Policy applied once the buffer is full.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+}
@@ -1,6 +1,8 @@
 //! Subscription types for sync/async streaming data
 
+mod buffer;
 mod common;
+pub use buffer::{BufferConfig, OverflowPolicy};
 pub(crate) use common::{ResponseContext, StreamDecoder};
 
 #[cfg(feature = "sync")]
@@ -10,7 +12,7 @@ pub mod sync;
 pub mod r#async;
 
 // Re-export the appropriate subscription types based on feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::{SharesChannel, Subscription, SubscriptionIter, SubscriptionOwnedIter, SubscriptionTimeoutIter, SubscriptionTryIter};
 
 #[cfg(feature = "async")]
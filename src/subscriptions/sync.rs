@@ -5,14 +5,16 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::Duration;
 
+use crossbeam::channel::{Receiver, Select};
+
 use log::{debug, error, warn};
 
 use super::common::{process_decode_result, should_retry_error, should_store_error, ProcessingResult};
 use super::{ResponseContext, StreamDecoder};
-use crate::client::Client;
+use crate::client::sync::Client;
 use crate::errors::Error;
 use crate::messages::{OutgoingMessages, ResponseMessage};
-use crate::transport::InternalSubscription;
+use crate::transport::{InternalSubscription, Response};
 
 /// A [Subscription] is a stream of responses returned from TWS. A [Subscription] is normally returned when invoking an API that can return more than one value.
 ///
@@ -100,7 +102,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -147,6 +149,23 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
         error.clone()
     }
 
+    /// Returns the number of messages dropped because the subscription buffer was full.
+    ///
+    /// Always zero for unbounded buffers, the default. See [BufferConfig](crate::subscriptions::BufferConfig).
+    pub fn dropped_messages(&self) -> u64 {
+        self.subscription.dropped_messages()
+    }
+
+    // Channel the responses are received on, for waiting on several subscriptions with [select].
+    pub(crate) fn receiver(&self) -> Option<&Receiver<Response>> {
+        self.subscription.receiver()
+    }
+
+    // Decodes a response received through [Subscription::receiver].
+    pub(crate) fn process(&self, response: Response) -> Option<T> {
+        self.process_response(Some(response))
+    }
+
     fn clear_error(&self) {
         let mut error = self.error.lock().unwrap();
         *error = None;
@@ -204,7 +223,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use std::thread;
     /// use std::time::Duration;
@@ -242,7 +261,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use ibapi::contracts::Contract;
     /// use std::time::Duration;
     ///
@@ -272,7 +291,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
@@ -300,7 +319,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use std::thread;
     /// use std::time::Duration;
     ///
@@ -339,7 +358,7 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use ibapi::Client;
+    /// use ibapi::blocking::Client;
     /// use std::time::Duration;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
@@ -362,6 +381,28 @@ impl<'a, T: StreamDecoder<T> + 'static> Subscription<'a, T> {
     }
 }
 
+/// Waits up to `timeout`, or indefinitely, for a response on any of `receivers`.
+///
+/// Returns the index of the receiver with the response, or with `None` once that receiver is disconnected. Returns
+/// `None` if the timeout expires or there are no receivers.
+pub(crate) fn select(receivers: &[&Receiver<Response>], timeout: Option<Duration>) -> Option<(usize, Option<Response>)> {
+    if receivers.is_empty() {
+        return None;
+    }
+
+    let mut select = Select::new();
+    for receiver in receivers {
+        select.recv(receiver);
+    }
+
+    let operation = match timeout {
+        Some(timeout) => select.select_timeout(timeout).ok()?,
+        None => select.select(),
+    };
+    let index = operation.index();
+    Some((index, operation.recv(receivers[index]).ok()))
+}
+
 impl<T: StreamDecoder<T> + 'static> Drop for Subscription<'_, T> {
     /// Cancel subscription on drop
     fn drop(&mut self) {
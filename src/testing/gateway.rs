@@ -0,0 +1,553 @@
+//! Socket level TWS/Gateway double.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use log::{debug, warn};
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+use super::script::{render, split_fields, Action, Exchange, Script};
+use crate::messages::encode_length;
+use crate::server_versions;
+use crate::Error;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Builder for a [MockGateway].
+#[derive(Debug, Clone)]
+pub struct MockGatewayBuilder {
+    server_version: i32,
+    server_time: Option<String>,
+    next_order_id: i32,
+    managed_accounts: String,
+    script: Script,
+}
+
+impl Default for MockGatewayBuilder {
+    fn default() -> Self {
+        Self {
+            server_version: server_versions::WSH_EVENT_DATA_FILTERS_DATE,
+            server_time: None,
+            next_order_id: 1,
+            managed_accounts: "DU1234567".to_string(),
+            script: Script::new(),
+        }
+    }
+}
+
+impl MockGatewayBuilder {
+    /// Sets the server version reported during the handshake. Lowered to the client's maximum if needed.
+    pub fn server_version(mut self, server_version: i32) -> Self {
+        self.server_version = server_version;
+        self
+    }
+
+    /// Sets the connection time reported during the handshake, e.g. `20230405 22:20:39 PST`. Defaults to the current time in UTC.
+    pub fn server_time(mut self, server_time: &str) -> Self {
+        self.server_time = Some(server_time.to_string());
+        self
+    }
+
+    /// Sets the next valid order id sent after the client starts the API.
+    pub fn next_order_id(mut self, next_order_id: i32) -> Self {
+        self.next_order_id = next_order_id;
+        self
+    }
+
+    /// Sets the comma separated managed accounts sent after the client starts the API.
+    pub fn managed_accounts(mut self, managed_accounts: &str) -> Self {
+        self.managed_accounts = managed_accounts.to_string();
+        self
+    }
+
+    /// Sets the script used to answer requests.
+    pub fn script(mut self, script: Script) -> Self {
+        self.script = script;
+        self
+    }
+
+    /// Adds an exchange to the script.
+    pub fn exchange(mut self, exchange: Exchange) -> Self {
+        self.script = self.script.exchange(exchange);
+        self
+    }
+
+    /// Starts the gateway on a free port of the loopback interface.
+    pub fn start(self) -> Result<MockGateway, Error> {
+        self.start_on("127.0.0.1:0")
+    }
+
+    /// Starts the gateway on the given address.
+    pub fn start_on(self, address: &str) -> Result<MockGateway, Error> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let server_time = self.server_time.unwrap_or_else(|| {
+            let format = format_description!("[year][month][day] [hour]:[minute]:[second]");
+            let now = OffsetDateTime::now_utc().format(format).unwrap_or_default();
+            format!("{now} UTC")
+        });
+
+        let state = Arc::new(State {
+            server_version: self.server_version,
+            server_time,
+            next_order_id: self.next_order_id,
+            managed_accounts: self.managed_accounts,
+            script: Mutex::new(self.script),
+            requests: Mutex::new(Vec::new()),
+            unmatched: Mutex::new(Vec::new()),
+            writers: Mutex::new(Vec::new()),
+            handlers: Mutex::new(Vec::new()),
+            connections: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let acceptor = {
+            let state = Arc::clone(&state);
+            thread::Builder::new()
+                .name("mock-gateway".to_string())
+                .spawn(move || accept_connections(listener, state))?
+        };
+
+        Ok(MockGateway {
+            address,
+            state,
+            acceptor: Some(acceptor),
+        })
+    }
+}
+
+/// A local TWS/Gateway stand-in for integration tests.
+///
+/// The gateway listens on a TCP port and performs the real connection handshake, so applications can be tested
+/// through `Client::connect` without a live TWS. Requests are answered from a [Script] of scripted or recorded
+/// exchanges, which can also inject delays, error messages and disconnects.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::messages::OutgoingMessages;
+/// use ibapi::testing::{Exchange, MockGateway};
+///
+/// let gateway = MockGateway::builder()
+///     .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|1752606307|"))
+///     .start()
+///     .expect("failed to start gateway");
+///
+/// // Connect the application under test to gateway.address(), e.g.
+/// // let client = ibapi::Client::connect(&gateway.address(), 100)?;
+///
+/// assert!(gateway.requests().is_empty());
+/// ```
+#[derive(Debug)]
+pub struct MockGateway {
+    address: SocketAddr,
+    state: Arc<State>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl MockGateway {
+    /// Returns a builder for configuring the gateway.
+    pub fn builder() -> MockGatewayBuilder {
+        MockGatewayBuilder::default()
+    }
+
+    /// Starts a gateway answering requests from the given script.
+    pub fn start(script: Script) -> Result<MockGateway, Error> {
+        Self::builder().script(script).start()
+    }
+
+    /// Returns the address clients should connect to, e.g. `127.0.0.1:43567`.
+    pub fn address(&self) -> String {
+        self.address.to_string()
+    }
+
+    /// Returns the port the gateway is listening on.
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    /// Returns the server version reported during the handshake.
+    pub fn server_version(&self) -> i32 {
+        self.state.server_version
+    }
+
+    /// Returns the number of connections accepted so far, including reconnections.
+    pub fn connection_count(&self) -> usize {
+        self.state.connections.load(Ordering::SeqCst)
+    }
+
+    /// Returns the requests received after the handshake, as pipe separated fields.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the requests no exchange matched, as pipe separated fields.
+    pub fn unmatched_requests(&self) -> Vec<String> {
+        self.state.unmatched.lock().unwrap().clone()
+    }
+
+    /// Adds an exchange to the running script.
+    pub fn add_exchange(&self, exchange: Exchange) {
+        let mut script = self.state.script.lock().unwrap();
+        script.exchanges.push(exchange);
+    }
+
+    /// Sends an unsolicited pipe separated message to every connected client, e.g. a connectivity error.
+    pub fn send(&self, message: &str) {
+        let fields = split_fields(message);
+        let writers = self.state.writers.lock().unwrap();
+        for writer in writers.iter() {
+            if let Err(err) = write_fields(writer, &fields) {
+                debug!("mock gateway failed to send message: {err}");
+            }
+        }
+    }
+
+    /// Closes all client connections. Clients see a connection reset and reconnect.
+    pub fn disconnect(&self) {
+        let mut writers = self.state.writers.lock().unwrap();
+        for writer in writers.drain(..) {
+            if let Ok(stream) = writer.lock() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+}
+
+impl Drop for MockGateway {
+    fn drop(&mut self) {
+        self.state.shutdown.store(true, Ordering::SeqCst);
+        self.disconnect();
+
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+
+        let handlers: Vec<_> = self.state.handlers.lock().unwrap().drain(..).collect();
+        for handler in handlers {
+            let _ = handler.join();
+        }
+    }
+}
+
+type Writer = Arc<Mutex<TcpStream>>;
+
+#[derive(Debug)]
+struct State {
+    server_version: i32,
+    server_time: String,
+    next_order_id: i32,
+    managed_accounts: String,
+    script: Mutex<Script>,
+    requests: Mutex<Vec<String>>,
+    unmatched: Mutex<Vec<String>>,
+    writers: Mutex<Vec<Writer>>,
+    handlers: Mutex<Vec<JoinHandle<()>>>,
+    connections: AtomicUsize,
+    shutdown: AtomicBool,
+}
+
+impl State {
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+}
+
+fn accept_connections(listener: TcpListener, state: Arc<State>) {
+    while !state.is_shutting_down() {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                debug!("mock gateway accepted connection from {peer}");
+                state.connections.fetch_add(1, Ordering::SeqCst);
+
+                let handler_state = Arc::clone(&state);
+                let spawned = thread::Builder::new().name("mock-gateway-connection".to_string()).spawn(move || {
+                    if let Err(err) = serve(stream, &handler_state) {
+                        debug!("mock gateway connection closed: {err}");
+                    }
+                });
+
+                match spawned {
+                    Ok(handler) => state.handlers.lock().unwrap().push(handler),
+                    Err(err) => warn!("mock gateway failed to spawn connection handler: {err}"),
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(err) => {
+                warn!("mock gateway failed to accept connection: {err}");
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn serve(stream: TcpStream, state: &State) -> Result<(), Error> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+
+    let mut reader = stream.try_clone()?;
+    let writer: Writer = Arc::new(Mutex::new(stream));
+
+    handshake(&mut reader, &writer, state)?;
+    state.writers.lock().unwrap().push(Arc::clone(&writer));
+
+    while let Some(request) = read_frame(&mut reader, state)? {
+        let fields: Vec<String> = request.split_terminator('\0').map(|field| field.to_string()).collect();
+        let simple = format!("{}|", fields.join("|"));
+        debug!("mock gateway <- {simple:?}");
+        state.requests.lock().unwrap().push(simple.clone());
+
+        let exchange = state.script.lock().unwrap().take_match(&fields);
+        let Some(exchange) = exchange else {
+            debug!("mock gateway has no exchange for {simple:?}");
+            state.unmatched.lock().unwrap().push(simple);
+            continue;
+        };
+
+        for action in exchange.actions() {
+            match action {
+                Action::Respond(template) => write_fields(&writer, &render(template, &fields))?,
+                Action::Delay(duration) => thread::sleep(*duration),
+                Action::Disconnect => {
+                    debug!("mock gateway disconnecting client");
+                    let _ = writer.lock()?.shutdown(Shutdown::Both);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Performs the server side of the handshake, answering with the negotiated server version and the account info.
+fn handshake(reader: &mut TcpStream, writer: &Writer, state: &State) -> Result<(), Error> {
+    let mut prefix = [0_u8; 4];
+    if !read_exact(reader, &mut prefix, state)? {
+        return Err(Error::Shutdown);
+    }
+    if &prefix != b"API\0" {
+        return Err(Error::Simple(format!("unexpected handshake prefix: {prefix:?}")));
+    }
+
+    let versions = read_frame(reader, state)?.ok_or(Error::Shutdown)?;
+    let server_version = negotiate_version(&versions, state.server_version)?;
+    write_fields(writer, &[server_version.to_string(), state.server_time.clone()])?;
+
+    let start_api = read_frame(reader, state)?.ok_or(Error::Shutdown)?;
+    debug!("mock gateway <- start api {start_api:?}");
+
+    write_fields(writer, &["9".to_string(), "1".to_string(), state.next_order_id.to_string()])?;
+    write_fields(writer, &["15".to_string(), "1".to_string(), state.managed_accounts.clone()])?;
+
+    Ok(())
+}
+
+// Parses the client's "v{min}..{max}" range and picks the highest version both sides support.
+fn negotiate_version(versions: &str, server_version: i32) -> Result<i32, Error> {
+    let range = versions.trim_start_matches('v');
+    let (min, max) = range
+        .split_once("..")
+        .ok_or_else(|| Error::Simple(format!("invalid version range: {versions}")))?;
+    let min: i32 = min.parse()?;
+    let max: i32 = max.parse()?;
+
+    if server_version < min {
+        return Err(Error::Simple(format!("server version {server_version} is below client minimum {min}")));
+    }
+    Ok(server_version.min(max))
+}
+
+fn write_fields(writer: &Writer, fields: &[String]) -> Result<(), Error> {
+    let mut data = fields.join("\0");
+    data.push('\0');
+    debug!("mock gateway -> {data:?}");
+
+    let mut stream = writer.lock()?;
+    stream.write_all(&encode_length(&data))?;
+    Ok(())
+}
+
+// Reads a length prefixed frame. Returns None when the connection is closed or the gateway is shutting down.
+fn read_frame(reader: &mut TcpStream, state: &State) -> Result<Option<String>, Error> {
+    let mut header = [0_u8; 4];
+    if !read_exact(reader, &mut header, state)? {
+        return Ok(None);
+    }
+
+    let mut data = vec![0_u8; u32::from_be_bytes(header) as usize];
+    if !read_exact(reader, &mut data, state)? {
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8(data)?))
+}
+
+// Fills the buffer, polling so shutdown is noticed. Returns false on end of stream or shutdown.
+fn read_exact(reader: &mut TcpStream, buffer: &mut [u8], state: &State) -> Result<bool, Error> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        if state.is_shutting_down() {
+            return Ok(false);
+        }
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(n) => filled += n,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => continue,
+            Err(err) if matches!(err.kind(), ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted) => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::OutgoingMessages;
+
+    fn connect_raw(gateway: &MockGateway) -> TcpStream {
+        let mut stream = TcpStream::connect(gateway.address()).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        let mut handshake = Vec::from(b"API\0");
+        handshake.extend_from_slice(&encode_length("v100..173"));
+        stream.write_all(&handshake).unwrap();
+        stream.write_all(&encode_length("71\02\0100\0\0")).unwrap();
+        stream
+    }
+
+    fn read_raw(stream: &mut TcpStream) -> String {
+        let mut header = [0_u8; 4];
+        stream.read_exact(&mut header).unwrap();
+        let mut data = vec![0_u8; u32::from_be_bytes(header) as usize];
+        stream.read_exact(&mut data).unwrap();
+        String::from_utf8(data).unwrap().replace('\0', "|")
+    }
+
+    fn send_raw(stream: &mut TcpStream, message: &str) {
+        stream.write_all(&encode_length(&message.replace('|', "\0"))).unwrap();
+    }
+
+    #[test]
+    fn test_negotiate_version() {
+        assert_eq!(negotiate_version("v100..173", 173).unwrap(), 173);
+        assert_eq!(negotiate_version("v100..173", 187).unwrap(), 173);
+        assert_eq!(negotiate_version("v100..173", 150).unwrap(), 150);
+        assert!(negotiate_version("v100..173", 90).is_err());
+        assert!(negotiate_version("garbage", 173).is_err());
+    }
+
+    #[test]
+    fn test_handshake_and_scripted_response() {
+        let gateway = MockGateway::builder()
+            .server_time("20230405 22:20:39 PST")
+            .next_order_id(90)
+            .managed_accounts("DU1234567,DU7654321")
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|1752606307|"))
+            .start()
+            .unwrap();
+
+        let mut stream = connect_raw(&gateway);
+        assert_eq!(read_raw(&mut stream), "173|20230405 22:20:39 PST|");
+        assert_eq!(read_raw(&mut stream), "9|1|90|");
+        assert_eq!(read_raw(&mut stream), "15|1|DU1234567,DU7654321|");
+
+        send_raw(&mut stream, "49|1|");
+        assert_eq!(read_raw(&mut stream), "49|1|1752606307|");
+
+        send_raw(&mut stream, "17|1|");
+        send_raw(&mut stream, "49|1|");
+        drop(stream);
+        for handler in gateway.state.handlers.lock().unwrap().drain(..) {
+            handler.join().unwrap();
+        }
+
+        assert_eq!(gateway.requests(), vec!["49|1|", "17|1|", "49|1|"]);
+        assert_eq!(gateway.unmatched_requests(), vec!["17|1|", "49|1|"]);
+        assert_eq!(gateway.connection_count(), 1);
+    }
+
+    #[test]
+    fn test_error_and_disconnect_injection() {
+        let gateway = MockGateway::builder()
+            .exchange(Exchange::on(OutgoingMessages::RequestContractData).error_for_field(2, 200, "No security definition"))
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).disconnect())
+            .start()
+            .unwrap();
+
+        let mut stream = connect_raw(&gateway);
+        for _ in 0..3 {
+            read_raw(&mut stream);
+        }
+
+        send_raw(&mut stream, "9|8|9000|AAPL|");
+        assert_eq!(read_raw(&mut stream), "4|2|9000|200|No security definition||");
+
+        send_raw(&mut stream, "49|1|");
+        let mut buffer = [0_u8; 1];
+        assert!(matches!(stream.read(&mut buffer), Ok(0) | Err(_)));
+    }
+
+    #[test]
+    fn test_send_unsolicited_message() {
+        let gateway = MockGateway::builder().start().unwrap();
+
+        let mut stream = connect_raw(&gateway);
+        for _ in 0..3 {
+            read_raw(&mut stream);
+        }
+
+        // the connection is registered once the handshake completes
+        while gateway.state.writers.lock().unwrap().is_empty() {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        gateway.send("4|2|-1|1100|Connectivity between IB and TWS has been lost.||");
+        assert_eq!(read_raw(&mut stream), "4|2|-1|1100|Connectivity between IB and TWS has been lost.||");
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_client_connects() {
+        let gateway = MockGateway::builder()
+            .next_order_id(42)
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|1752606307|"))
+            .start()
+            .unwrap();
+
+        let client = crate::client::sync::Client::connect(&gateway.address(), 100).unwrap();
+        assert_eq!(client.server_version(), 173);
+        assert_eq!(client.next_order_id(), 42);
+
+        let server_time = client.server_time().unwrap();
+        assert_eq!(server_time.unix_timestamp(), 1752606307);
+        assert_eq!(gateway.requests(), vec!["49|1|"]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_client_connects() {
+        let gateway = MockGateway::builder()
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|1752606307|"))
+            .start()
+            .unwrap();
+
+        let client = crate::client::r#async::Client::connect(&gateway.address(), 100).await.unwrap();
+        assert_eq!(client.server_version(), 173);
+
+        let server_time = client.server_time().await.unwrap();
+        assert_eq!(server_time.unix_timestamp(), 1752606307);
+    }
+}
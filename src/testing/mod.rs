@@ -0,0 +1,27 @@
+//! Test support for applications built on this crate.
+//!
+//! [MockGateway] is a local stand-in for TWS or IB Gateway. It listens on a TCP port, performs the real
+//! connection handshake and answers requests from a [Script], so an application can be exercised through
+//! `Client::connect` in CI without a live Gateway. Scripts are built from [Exchange]s or loaded from a
+//! recording in the `tws_interactions.yaml` format, and can inject delays, error codes and disconnects.
+//!
+//! The gateway is thread based and works with both the `sync` and `async` clients.
+//!
+//! ```no_run
+//! use ibapi::messages::OutgoingMessages;
+//! use ibapi::testing::{Exchange, MockGateway, Script};
+//! use std::time::Duration;
+//!
+//! let script = Script::from_yaml_file("tws_interactions.yaml")
+//!     .expect("invalid recording")
+//!     .exchange(Exchange::on(OutgoingMessages::RequestMarketData).delay(Duration::from_secs(1)).disconnect());
+//!
+//! let gateway = MockGateway::start(script).expect("failed to start gateway");
+//! println!("connect to {}", gateway.address());
+//! ```
+
+mod gateway;
+mod script;
+
+pub use gateway::{MockGateway, MockGatewayBuilder};
+pub use script::{Action, Exchange, RequestMatcher, Script};
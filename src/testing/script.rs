@@ -0,0 +1,412 @@
+//! Scripted request/response exchanges served by the [MockGateway](super::MockGateway).
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::messages::{order_id_index, request_id_index, IncomingMessages, OutgoingMessages};
+use crate::Error;
+
+/// Decides which incoming requests an [Exchange] answers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestMatcher {
+    /// Matches every request.
+    Any,
+    /// Matches requests with the given message type (first field).
+    MessageType(String),
+    /// Matches requests whose leading fields equal the given fields.
+    Prefix(Vec<String>),
+    /// Matches requests whose fields equal the given fields exactly.
+    Exact(Vec<String>),
+}
+
+impl RequestMatcher {
+    /// Returns true if the request fields are matched.
+    pub fn matches(&self, fields: &[String]) -> bool {
+        match self {
+            RequestMatcher::Any => true,
+            RequestMatcher::MessageType(message_type) => fields.first() == Some(message_type),
+            RequestMatcher::Prefix(prefix) => fields.starts_with(prefix),
+            RequestMatcher::Exact(expected) => fields == expected.as_slice(),
+        }
+    }
+}
+
+/// A step performed by the gateway after a request is matched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Sends a message to the client.
+    ///
+    /// A field of the form `{N}` is replaced with field `N` of the matched request, so responses can echo request and order ids.
+    Respond(Vec<String>),
+    /// Pauses before performing the next action.
+    Delay(Duration),
+    /// Closes the connection. The client sees a connection reset and reconnects.
+    Disconnect,
+}
+
+/// A scripted exchange: when a request is matched, the gateway performs the actions in order.
+///
+/// Response messages use the same pipe separated notation as the crate's own test fixtures.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::messages::OutgoingMessages;
+/// use ibapi::testing::Exchange;
+/// use std::time::Duration;
+///
+/// // Answer server time requests after a short delay.
+/// let server_time = Exchange::on(OutgoingMessages::RequestCurrentTime)
+///     .delay(Duration::from_millis(50))
+///     .respond("49|1|1752606307|");
+///
+/// // Reject contract details requests, echoing the request id from field 2.
+/// let contract_details = Exchange::on(OutgoingMessages::RequestContractData)
+///     .error_for_field(2, 200, "No security definition has been found for the request");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub(crate) matcher: RequestMatcher,
+    pub(crate) actions: Vec<Action>,
+    pub(crate) times: Option<usize>,
+}
+
+impl Exchange {
+    /// Creates an exchange for the given matcher.
+    pub fn new(matcher: RequestMatcher) -> Self {
+        Self {
+            matcher,
+            actions: Vec::new(),
+            times: Some(1),
+        }
+    }
+
+    /// Creates an exchange matching requests of the given message type.
+    pub fn on(message_type: OutgoingMessages) -> Self {
+        Self::new(RequestMatcher::MessageType(message_type.to_string()))
+    }
+
+    /// Creates an exchange matching requests that start with the given pipe separated fields.
+    pub fn on_prefix(fields: &str) -> Self {
+        Self::new(RequestMatcher::Prefix(split_fields(fields)))
+    }
+
+    /// Creates an exchange matching requests equal to the given pipe separated fields.
+    pub fn on_request(fields: &str) -> Self {
+        Self::new(RequestMatcher::Exact(split_fields(fields)))
+    }
+
+    /// Creates an exchange matching any request.
+    pub fn on_any() -> Self {
+        Self::new(RequestMatcher::Any)
+    }
+
+    /// Sends the given pipe separated message.
+    pub fn respond(mut self, message: &str) -> Self {
+        self.actions.push(Action::Respond(split_fields(message)));
+        self
+    }
+
+    /// Sends an error message for the given request id.
+    pub fn error(mut self, request_id: i32, code: i32, message: &str) -> Self {
+        self.actions.push(error_action(request_id.to_string(), code, message));
+        self
+    }
+
+    /// Sends an error message using field `field` of the matched request as the request id.
+    pub fn error_for_field(mut self, field: usize, code: i32, message: &str) -> Self {
+        self.actions.push(error_action(format!("{{{field}}}"), code, message));
+        self
+    }
+
+    /// Pauses before the next action.
+    pub fn delay(mut self, duration: Duration) -> Self {
+        self.actions.push(Action::Delay(duration));
+        self
+    }
+
+    /// Closes the connection.
+    pub fn disconnect(mut self) -> Self {
+        self.actions.push(Action::Disconnect);
+        self
+    }
+
+    /// Answers up to `times` matching requests. Defaults to once.
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+
+    /// Answers every matching request.
+    pub fn always(mut self) -> Self {
+        self.times = None;
+        self
+    }
+
+    /// Returns the actions performed when this exchange is matched.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.times == Some(0)
+    }
+
+    pub(crate) fn consume(&mut self) {
+        if let Some(times) = self.times.as_mut() {
+            *times = times.saturating_sub(1);
+        }
+    }
+}
+
+fn error_action(request_id: String, code: i32, message: &str) -> Action {
+    Action::Respond(vec![
+        "4".to_string(),
+        "2".to_string(),
+        request_id,
+        code.to_string(),
+        message.to_string(),
+        "".to_string(),
+    ])
+}
+
+/// Splits a pipe separated message into fields.
+pub(crate) fn split_fields(message: &str) -> Vec<String> {
+    message.split_terminator('|').map(|field| field.to_string()).collect()
+}
+
+/// Replaces `{N}` placeholders in a response with fields of the request.
+pub(crate) fn render(template: &[String], request: &[String]) -> Vec<String> {
+    template
+        .iter()
+        .map(|field| {
+            field
+                .strip_prefix('{')
+                .and_then(|rest| rest.strip_suffix('}'))
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| request.get(index).cloned())
+                .unwrap_or_else(|| field.clone())
+        })
+        .collect()
+}
+
+/// An ordered set of exchanges.
+///
+/// For each request the gateway uses the first exchange that matches and is not exhausted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    pub(crate) exchanges: Vec<Exchange>,
+}
+
+impl Script {
+    /// Creates an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an exchange to the script.
+    pub fn exchange(mut self, exchange: Exchange) -> Self {
+        self.exchanges.push(exchange);
+        self
+    }
+
+    /// Returns the exchanges in the script.
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    /// Loads a script from a recording in the `tws_interactions.yaml` format produced by `examples/record_interactions.rs`.
+    ///
+    /// Each recorded interaction answers one request of the same message type. Request and order ids in the recorded
+    /// responses are rewritten to echo the ids sent by the client.
+    pub fn from_yaml(yaml: &str) -> Result<Self, Error> {
+        let recording: Recording = serde_yaml::from_str(yaml).map_err(|e| Error::Simple(format!("invalid recording: {e}")))?;
+        Ok(recording.into())
+    }
+
+    /// Loads a script from a recording file. See [Script::from_yaml].
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let yaml = fs::read_to_string(path)?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Returns the first exchange matching the request, consuming one use of it.
+    pub(crate) fn take_match(&mut self, fields: &[String]) -> Option<Exchange> {
+        let exchange = self
+            .exchanges
+            .iter_mut()
+            .find(|exchange| !exchange.is_exhausted() && exchange.matcher.matches(fields))?;
+        exchange.consume();
+        Some(exchange.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    #[serde(default)]
+    interactions: Vec<RecordedInteraction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordedInteraction {
+    request: RecordedMessage,
+    #[serde(default)]
+    responses: Vec<RecordedMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordedMessage {
+    raw: String,
+}
+
+impl From<Recording> for Script {
+    fn from(recording: Recording) -> Self {
+        let mut script = Script::new();
+
+        for interaction in recording.interactions {
+            let request = split_raw(&interaction.request.raw);
+            let Some(message_type) = request.first() else {
+                continue;
+            };
+
+            let mut exchange = Exchange::new(RequestMatcher::MessageType(message_type.clone()));
+            for response in &interaction.responses {
+                exchange.actions.push(Action::Respond(echo_ids(split_raw(&response.raw), &request)));
+            }
+            script.exchanges.push(exchange);
+        }
+
+        script
+    }
+}
+
+fn split_raw(raw: &str) -> Vec<String> {
+    raw.split_terminator('\0').map(|field| field.to_string()).collect()
+}
+
+// Replaces recorded request and order ids with placeholders for the matching request fields.
+fn echo_ids(mut response: Vec<String>, request: &[String]) -> Vec<String> {
+    let Some(message_type) = response.first().and_then(|field| field.parse::<IncomingMessages>().ok()) else {
+        return response;
+    };
+
+    for index in [request_id_index(message_type), order_id_index(message_type)].into_iter().flatten() {
+        let Some(value) = response.get(index) else {
+            continue;
+        };
+        if value.parse::<i32>().is_err() {
+            continue;
+        }
+        if let Some(position) = request.iter().skip(1).position(|field| field == value) {
+            response[index] = format!("{{{}}}", position + 1);
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_matchers() {
+        let request = split_fields("49|1|");
+
+        assert!(RequestMatcher::Any.matches(&request));
+        assert!(RequestMatcher::MessageType("49".to_string()).matches(&request));
+        assert!(!RequestMatcher::MessageType("17".to_string()).matches(&request));
+        assert!(RequestMatcher::Prefix(split_fields("49|")).matches(&request));
+        assert!(RequestMatcher::Exact(split_fields("49|1|")).matches(&request));
+        assert!(!RequestMatcher::Exact(split_fields("49|")).matches(&request));
+    }
+
+    #[test]
+    fn test_take_match_consumes_exchanges() {
+        let mut script = Script::new()
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|1|"))
+            .exchange(Exchange::on(OutgoingMessages::RequestCurrentTime).respond("49|1|2|").always());
+        let request = split_fields("49|1|");
+
+        assert_eq!(
+            script.take_match(&request).unwrap().actions,
+            vec![Action::Respond(split_fields("49|1|1|"))]
+        );
+        for _ in 0..3 {
+            assert_eq!(
+                script.take_match(&request).unwrap().actions,
+                vec![Action::Respond(split_fields("49|1|2|"))]
+            );
+        }
+        assert!(script.take_match(&split_fields("17|1|")).is_none());
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let request = split_fields("9|8|9001|AAPL|");
+        let template = split_fields("4|2|{2}|200|{9}|");
+
+        assert_eq!(render(&template, &request), split_fields("4|2|9001|200|{9}|"));
+    }
+
+    #[test]
+    fn test_error_action() {
+        let exchange = Exchange::on_any()
+            .error(9000, 200, "No security definition")
+            .error_for_field(2, 162, "Pacing");
+
+        assert_eq!(
+            exchange.actions(),
+            &[
+                Action::Respond(split_fields("4|2|9000|200|No security definition||")),
+                Action::Respond(vec![
+                    "4".to_string(),
+                    "2".to_string(),
+                    "{2}".to_string(),
+                    "162".to_string(),
+                    "Pacing".to_string(),
+                    "".to_string()
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_yaml_echoes_request_ids() {
+        let yaml = r#"
+header:
+  server_version: 173
+interactions:
+- name: server_time
+  request:
+    raw: "49\u00001\u0000"
+  responses:
+  - raw: "49\u00001\u00001752606307\u0000"
+- name: contract_details
+  request:
+    raw: "9\u00008\u00009000\u0000AAPL\u0000"
+  responses:
+  - raw: "52\u00001\u00009000\u0000"
+"#;
+        let script = Script::from_yaml(yaml).unwrap();
+
+        assert_eq!(script.exchanges().len(), 2);
+        assert_eq!(script.exchanges()[0].matcher, RequestMatcher::MessageType("49".to_string()));
+        assert_eq!(script.exchanges()[0].actions, vec![Action::Respond(split_fields("49|1|1752606307|"))]);
+        assert_eq!(script.exchanges()[1].actions, vec![Action::Respond(split_fields("52|1|{2}|"))]);
+    }
+
+    #[test]
+    fn test_from_yaml_file_loads_repository_recording() {
+        let script = Script::from_yaml_file(concat!(env!("CARGO_MANIFEST_DIR"), "/tws_interactions.yaml")).unwrap();
+        assert!(!script.exchanges().is_empty());
+    }
+
+    #[test]
+    fn test_from_yaml_invalid() {
+        assert!(Script::from_yaml("interactions: 12").is_err());
+    }
+}
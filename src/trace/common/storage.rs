@@ -1,23 +1,14 @@
 use super::{Interaction, SharedInteraction};
 use std::sync::Arc;
 
-#[cfg(feature = "sync")]
-use std::sync::RwLock;
-
-#[cfg(feature = "async")]
-use tokio::sync::RwLock;
-
-/// Global storage for the current interaction
-#[cfg(feature = "sync")]
-static CURRENT_INTERACTION: RwLock<Option<Arc<RwLock<Interaction>>>> = RwLock::new(None);
-
-#[cfg(feature = "async")]
-static CURRENT_INTERACTION: RwLock<Option<Arc<RwLock<Interaction>>>> = RwLock::const_new(None);
-
 /// Storage operations for sync mode
 #[cfg(feature = "sync")]
 pub(in crate::trace) mod sync_ops {
     use super::*;
+    use std::sync::RwLock;
+
+    /// Global storage for the current interaction
+    static CURRENT_INTERACTION: RwLock<Option<Arc<RwLock<Interaction>>>> = RwLock::new(None);
 
     /// Gets the last interaction if any
     pub fn get_last_interaction() -> Option<SharedInteraction> {
@@ -60,6 +51,10 @@ pub(in crate::trace) mod sync_ops {
 #[cfg(feature = "async")]
 pub(in crate::trace) mod async_ops {
     use super::*;
+    use tokio::sync::RwLock;
+
+    /// Global storage for the current interaction
+    static CURRENT_INTERACTION: RwLock<Option<Arc<RwLock<Interaction>>>> = RwLock::const_new(None);
 
     /// Gets the last interaction if any
     pub async fn get_last_interaction() -> Option<SharedInteraction> {
@@ -6,9 +6,12 @@
 // Common types and storage
 mod common;
 
+// Request spans and events for the tracing feature
+pub(crate) mod spans;
+
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -17,7 +20,7 @@ mod r#async;
 pub use common::Interaction;
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::{last_interaction, record_request, record_response};
 
 #[cfg(feature = "async")]
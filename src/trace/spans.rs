@@ -0,0 +1,251 @@
+//! Spans and events for the `tracing` feature.
+//!
+//! Each request sent for a request or order id opens an `ibapi.request` span carrying the request id, order id,
+//! message type and contract symbol. The span stays open until the subscription is cleaned up, and every response
+//! routed to it is recorded as an `ibapi.response` event inside the span. The dispatcher and cleanup workers run
+//! inside `ibapi.worker` spans.
+//!
+//! Without the `tracing` feature these types are empty and their methods do nothing.
+
+#[cfg(feature = "tracing")]
+use std::collections::HashMap;
+#[cfg(feature = "tracing")]
+use std::sync::Mutex;
+
+use crate::messages::{RequestMessage, ResponseMessage};
+use crate::transport::recorder::Correlation;
+
+#[cfg(feature = "tracing")]
+use crate::messages::OutgoingMessages;
+
+/// Position of the contract symbol in requests that carry a contract, for current server versions.
+#[cfg(feature = "tracing")]
+fn symbol_index(kind: OutgoingMessages) -> Option<usize> {
+    match kind {
+        OutgoingMessages::RequestMarketData => Some(4),
+        OutgoingMessages::RequestContractData => Some(4),
+        OutgoingMessages::RequestMarketDepth => Some(4),
+        OutgoingMessages::RequestRealTimeBars => Some(4),
+        OutgoingMessages::ReqCalcImpliedVolat => Some(4),
+        OutgoingMessages::ReqCalcOptionPrice => Some(4),
+        OutgoingMessages::PlaceOrder => Some(3),
+        OutgoingMessages::RequestHistoricalData => Some(3),
+        OutgoingMessages::RequestHeadTimestamp => Some(3),
+        OutgoingMessages::RequestHistogramData => Some(3),
+        OutgoingMessages::RequestHistoricalTicks => Some(3),
+        OutgoingMessages::RequestTickByTickData => Some(3),
+        _ => None,
+    }
+}
+
+/// Returns the message type and contract symbol of a request, if known.
+#[cfg(feature = "tracing")]
+pub(crate) fn describe_request(message: &RequestMessage) -> (Option<OutgoingMessages>, Option<&str>) {
+    let kind = message.field(0).and_then(|field| field.parse::<OutgoingMessages>().ok());
+    let symbol = kind
+        .and_then(symbol_index)
+        .and_then(|index| message.field(index))
+        .filter(|symbol| !symbol.is_empty());
+    (kind, symbol)
+}
+
+/// Tracks the open request spans of a message bus, so responses can be recorded in the span of their request.
+#[derive(Debug, Default)]
+pub(crate) struct RequestSpans {
+    #[cfg(feature = "tracing")]
+    spans: Mutex<HashMap<Correlation, tracing::Span>>,
+}
+
+impl RequestSpans {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the span for a request. Requests without a request or order id get no span.
+    pub(crate) fn open(&self, message: &RequestMessage, correlation: Correlation) {
+        #[cfg(feature = "tracing")]
+        {
+            if correlation == Correlation::None {
+                return;
+            }
+
+            let (kind, symbol) = describe_request(message);
+            let span = tracing::info_span!(
+                "ibapi.request",
+                message_type = tracing::field::Empty,
+                request_id = tracing::field::Empty,
+                order_id = tracing::field::Empty,
+                symbol = tracing::field::Empty,
+            );
+
+            match correlation {
+                Correlation::Request(request_id) => {
+                    span.record("request_id", request_id);
+                }
+                Correlation::Order(order_id) => {
+                    span.record("order_id", order_id);
+                }
+                Correlation::None => {}
+            }
+            if let Some(kind) = kind {
+                span.record("message_type", tracing::field::debug(kind));
+            }
+            if let Some(symbol) = symbol {
+                span.record("symbol", symbol);
+            }
+
+            if let Ok(mut spans) = self.spans.lock() {
+                spans.insert(correlation, span);
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = (message, correlation);
+        }
+    }
+
+    /// Records a decoded response as an event, inside the span of its request or order when one is open.
+    pub(crate) fn response(&self, message: &ResponseMessage) {
+        #[cfg(feature = "tracing")]
+        {
+            let span = self.spans.lock().ok().and_then(|spans| {
+                let request_id = message.request_id();
+                let order_id = message.order_id();
+                request_id
+                    .and_then(|id| spans.get(&Correlation::Request(id)))
+                    .or_else(|| order_id.or(request_id).and_then(|id| spans.get(&Correlation::Order(id))))
+                    .cloned()
+            });
+
+            let message_type = message.message_type();
+            match span {
+                Some(span) => tracing::debug!(parent: &span, message_type = ?message_type, fields = message.len(), "ibapi.response"),
+                None => tracing::debug!(message_type = ?message_type, fields = message.len(), "ibapi.response"),
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = message;
+        }
+    }
+
+    /// Closes the span of a request once its subscription is cleaned up.
+    pub(crate) fn close(&self, correlation: Correlation) {
+        #[cfg(feature = "tracing")]
+        {
+            if let Ok(mut spans) = self.spans.lock() {
+                spans.remove(&correlation);
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = correlation;
+        }
+    }
+
+    /// Closes all open spans, when the connection is reset or shut down.
+    #[cfg(feature = "sync")]
+    pub(crate) fn clear(&self) {
+        #[cfg(feature = "tracing")]
+        {
+            if let Ok(mut spans) = self.spans.lock() {
+                spans.clear();
+            }
+        }
+    }
+
+    /// Returns the number of open request spans.
+    #[cfg(all(test, feature = "tracing"))]
+    pub(crate) fn len(&self) -> usize {
+        self.spans.lock().unwrap().len()
+    }
+}
+
+/// Guard keeping a worker span entered for the life of a background thread.
+#[cfg(feature = "sync")]
+#[derive(Debug)]
+pub(crate) struct WorkerSpan {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+/// Enters an `ibapi.worker` span for the current thread.
+#[cfg(feature = "sync")]
+pub(crate) fn enter_worker(worker: &'static str) -> WorkerSpan {
+    #[cfg(feature = "tracing")]
+    {
+        WorkerSpan {
+            _entered: tracing::info_span!("ibapi.worker", worker).entered(),
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = worker;
+        WorkerSpan {}
+    }
+}
+
+/// Runs a background task inside an `ibapi.worker` span.
+#[cfg(feature = "async")]
+pub(crate) fn instrument_worker<F: std::future::Future>(worker: &'static str, future: F) -> impl std::future::Future<Output = F::Output> {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::Instrument::instrument(future, tracing::info_span!("ibapi.worker", worker))
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = worker;
+        future
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_request() {
+        let message = RequestMessage::from_simple("1|11|9000|0|AAPL|STK||0|||SMART||USD|||0|||");
+        assert_eq!(describe_request(&message), (Some(OutgoingMessages::RequestMarketData), Some("AAPL")));
+
+        let message = RequestMessage::from_simple("3|13|0|TSLA|STK|");
+        assert_eq!(describe_request(&message), (Some(OutgoingMessages::PlaceOrder), Some("TSLA")));
+
+        let message = RequestMessage::from_simple("49|1|");
+        assert_eq!(describe_request(&message), (Some(OutgoingMessages::RequestCurrentTime), None));
+
+        let message = RequestMessage::from_simple("1|11|9000|12345||STK|");
+        assert_eq!(describe_request(&message).1, None);
+    }
+
+    #[test]
+    fn test_request_spans_lifecycle() {
+        let spans = RequestSpans::new();
+        let message = RequestMessage::from_simple("1|11|9000|0|AAPL|STK|");
+
+        spans.open(&message, Correlation::Request(9000));
+        spans.open(&RequestMessage::from_simple("49|1|"), Correlation::None);
+        assert_eq!(spans.len(), 1);
+
+        spans.response(&ResponseMessage::from_simple("1|6|9000|1|185.50|100|3|"));
+        spans.close(Correlation::Request(9000));
+        assert_eq!(spans.len(), 0);
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_request_spans_clear() {
+        let spans = RequestSpans::new();
+        spans.open(&RequestMessage::from_simple("3|13|0|TSLA|STK|"), Correlation::Order(13));
+        spans.open(&RequestMessage::from_simple("1|11|9000|0|AAPL|STK|"), Correlation::Request(9000));
+        assert_eq!(spans.len(), 2);
+
+        spans.clear();
+        assert_eq!(spans.len(), 0);
+    }
+}
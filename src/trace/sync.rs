@@ -6,7 +6,7 @@ use crate::trace::common::{storage::sync_ops, Interaction};
 ///
 /// # Example
 /// ```no_run
-/// use ibapi::trace;
+/// use ibapi::blocking::trace;
 ///
 /// if let Some(interaction) = trace::last_interaction() {
 ///     println!("Last request: {}", interaction.request);
@@ -28,7 +28,7 @@ pub fn last_interaction() -> Option<Interaction> {
 ///
 /// # Example
 /// ```no_run
-/// use ibapi::trace;
+/// use ibapi::blocking::trace;
 ///
 /// trace::record_request("REQ|123|AAPL|".to_string());
 /// ```
@@ -46,7 +46,7 @@ pub fn record_request(message: String) {
 ///
 /// # Example
 /// ```no_run
-/// use ibapi::trace;
+/// use ibapi::blocking::trace;
 ///
 /// trace::record_request("REQ|123|AAPL|".to_string());
 /// trace::record_response("RESP|123|150.00|".to_string());
@@ -1,7 +1,7 @@
 //! Asynchronous transport implementation
 
 use std::collections::HashMap;
-use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -24,8 +24,13 @@ pub enum CleanupSignal {
 
 use crate::connection::r#async::AsyncConnection;
 use crate::messages::{shared_channel_configuration, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::metrics::Metrics;
+use crate::subscriptions::BufferConfig;
+use crate::trace::spans::{instrument_worker, RequestSpans};
 use crate::Error;
 
+use super::buffer::{AsyncBuffer, AsyncBufferSender, BufferConfigs};
+use super::recorder::Correlation;
 use super::routing::{determine_routing, is_warning_error, map_incoming_to_outgoing, RoutingDecision, UNSPECIFIED_REQUEST_ID};
 
 /// Asynchronous message bus trait
@@ -52,6 +57,15 @@ pub trait AsyncMessageBus: Send + Sync {
     /// Order update stream
     async fn create_order_update_subscription(&self) -> Result<AsyncInternalSubscription, Error>;
 
+    /// Sets the buffer of subscriptions started by the given request type
+    fn set_buffer_config(&self, _message_type: OutgoingMessages, _config: BufferConfig) {}
+
+    /// Sets the buffer of subscriptions without a request-specific buffer
+    fn set_default_buffer_config(&self, _config: BufferConfig) {}
+
+    /// Installs the metrics emitted by the transport
+    fn set_metrics(&self, _metrics: Arc<dyn Metrics>) {}
+
     #[cfg(test)]
     fn request_messages(&self) -> Vec<RequestMessage> {
         vec![]
@@ -60,16 +74,32 @@ pub trait AsyncMessageBus: Send + Sync {
 
 /// Internal subscription for async implementation
 pub struct AsyncInternalSubscription {
-    pub(crate) receiver: broadcast::Receiver<ResponseMessage>,
+    inbox: Inbox,
+    dropped: Arc<AtomicU64>,
     cleanup_sender: Option<mpsc::UnboundedSender<CleanupSignal>>,
     cleanup_signal: Option<CleanupSignal>,
     cleanup_sent: bool,
 }
 
+/// Receiving half of a subscription channel.
+enum Inbox {
+    Broadcast(broadcast::Receiver<ResponseMessage>),
+    Buffer(Arc<AsyncBuffer>),
+}
+
 impl Clone for AsyncInternalSubscription {
     fn clone(&self) -> Self {
+        let inbox = match &self.inbox {
+            Inbox::Broadcast(receiver) => Inbox::Broadcast(receiver.resubscribe()),
+            Inbox::Buffer(buffer) => {
+                buffer.add_receiver();
+                Inbox::Buffer(buffer.clone())
+            }
+        };
+
         Self {
-            receiver: self.receiver.resubscribe(),
+            inbox,
+            dropped: self.dropped.clone(),
             cleanup_sender: self.cleanup_sender.clone(),
             cleanup_signal: self.cleanup_signal.clone(),
             cleanup_sent: false, // Each clone should handle its own cleanup
@@ -80,7 +110,8 @@ impl Clone for AsyncInternalSubscription {
 impl AsyncInternalSubscription {
     pub fn new(receiver: broadcast::Receiver<ResponseMessage>) -> Self {
         Self {
-            receiver,
+            inbox: Inbox::Broadcast(receiver),
+            dropped: Arc::new(AtomicU64::new(0)),
             cleanup_sender: None,
             cleanup_signal: None,
             cleanup_sent: false,
@@ -93,7 +124,24 @@ impl AsyncInternalSubscription {
         cleanup_signal: CleanupSignal,
     ) -> Self {
         Self {
-            receiver,
+            inbox: Inbox::Broadcast(receiver),
+            dropped: Arc::new(AtomicU64::new(0)),
+            cleanup_sender: Some(cleanup_sender),
+            cleanup_signal: Some(cleanup_signal),
+            cleanup_sent: false,
+        }
+    }
+
+    /// Creates a subscription receiving from a bounded buffer.
+    pub(crate) fn with_buffer(
+        buffer: Arc<AsyncBuffer>,
+        dropped: Arc<AtomicU64>,
+        cleanup_sender: mpsc::UnboundedSender<CleanupSignal>,
+        cleanup_signal: CleanupSignal,
+    ) -> Self {
+        Self {
+            inbox: Inbox::Buffer(buffer),
+            dropped,
             cleanup_sender: Some(cleanup_sender),
             cleanup_signal: Some(cleanup_signal),
             cleanup_sent: false,
@@ -101,29 +149,25 @@ impl AsyncInternalSubscription {
     }
 
     pub async fn next(&mut self) -> Option<ResponseMessage> {
-        loop {
-            match self.receiver.recv().await {
-                Ok(msg) => return Some(msg),
-                Err(broadcast::error::RecvError::Closed) => return None,
-                Err(broadcast::error::RecvError::Lagged(_)) => {
-                    // If we lagged, continue the loop to try again
-                    continue;
+        match &mut self.inbox {
+            Inbox::Broadcast(receiver) => loop {
+                match receiver.recv().await {
+                    Ok(msg) => return Some(msg),
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // If we lagged, count the skipped messages and try again
+                        self.dropped.fetch_add(skipped, Ordering::Relaxed);
+                        continue;
+                    }
                 }
-            }
+            },
+            Inbox::Buffer(buffer) => buffer.recv().await,
         }
     }
 
-    /// Extract the receiver for use in subscriptions (disables cleanup)
-    pub fn take_receiver(mut self) -> broadcast::Receiver<ResponseMessage> {
-        // Disable cleanup by clearing the cleanup info - the subscription will now own the receiver
-        self.cleanup_sender = None;
-        self.cleanup_signal = None;
-        self.cleanup_sent = true; // Mark as sent to prevent Drop from sending
-
-        // Create a dummy receiver to replace the original one
-        let (dummy_sender, dummy_receiver) = broadcast::channel(1);
-        drop(dummy_sender); // Close the channel immediately
-        mem::replace(&mut self.receiver, dummy_receiver)
+    /// Number of messages dropped because the buffer was full.
+    pub fn dropped_messages(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
     }
 
     /// Manually send cleanup signal
@@ -140,25 +184,58 @@ impl AsyncInternalSubscription {
 /// Send cleanup signal when subscription is dropped
 impl Drop for AsyncInternalSubscription {
     fn drop(&mut self) {
+        if let Inbox::Buffer(buffer) = &self.inbox {
+            buffer.remove_receiver();
+        }
         self.send_cleanup_signal();
     }
 }
 
 type BroadcastSender = broadcast::Sender<ResponseMessage>;
 
+/// Sending half of a request or order channel.
+#[derive(Clone)]
+enum ResponseSender {
+    Broadcast(BroadcastSender),
+    Buffer(Arc<AsyncBufferSender>),
+}
+
+impl ResponseSender {
+    async fn send(&self, message: ResponseMessage) {
+        match self {
+            ResponseSender::Broadcast(sender) => {
+                let _ = sender.send(message);
+            }
+            ResponseSender::Buffer(sender) => sender.send(message).await,
+        }
+    }
+
+    /// Number of messages waiting to be received.
+    fn len(&self) -> usize {
+        match self {
+            ResponseSender::Broadcast(sender) => sender.len(),
+            ResponseSender::Buffer(sender) => sender.len(),
+        }
+    }
+}
+
 /// Asynchronous TCP message bus implementation
 pub struct AsyncTcpMessageBus {
     connection: Arc<AsyncConnection>,
     /// Maps request IDs to their response channels
-    request_channels: Arc<RwLock<HashMap<i32, BroadcastSender>>>,
+    request_channels: Arc<RwLock<HashMap<i32, ResponseSender>>>,
     /// Maps shared channel types to their response channels
     shared_channels: Arc<RwLock<HashMap<OutgoingMessages, BroadcastSender>>>,
     /// Maps order IDs to their response channels
-    order_channels: Arc<RwLock<HashMap<i32, BroadcastSender>>>,
+    order_channels: Arc<RwLock<HashMap<i32, ResponseSender>>>,
     /// Optional channel for order update stream
     order_update_stream: Arc<RwLock<Option<BroadcastSender>>>,
     /// Channel for cleanup signals
     cleanup_sender: mpsc::UnboundedSender<CleanupSignal>,
+    /// Open request spans when the tracing feature is enabled
+    spans: Arc<RequestSpans>,
+    /// Buffer configuration of request and order channels
+    buffers: BufferConfigs,
 }
 
 impl AsyncTcpMessageBus {
@@ -180,25 +257,33 @@ impl AsyncTcpMessageBus {
             order_channels: Arc::new(RwLock::new(HashMap::new())),
             order_update_stream: Arc::new(RwLock::new(None)),
             cleanup_sender,
+            spans: Arc::new(RequestSpans::new()),
+            buffers: BufferConfigs::new(),
         };
 
         // Start cleanup task
         let request_channels = message_bus.request_channels.clone();
         let shared_channels = message_bus.shared_channels.clone();
         let order_channels = message_bus.order_channels.clone();
+        let spans = message_bus.spans.clone();
+        let connection = message_bus.connection.clone();
 
-        task::spawn(async move {
+        task::spawn(instrument_worker("cleanup", async move {
             let mut receiver = cleanup_receiver;
             while let Some(signal) = receiver.recv().await {
                 match signal {
                     CleanupSignal::Request(request_id) => {
                         let mut channels = request_channels.write().await;
                         channels.remove(&request_id);
+                        spans.close(Correlation::Request(request_id));
+                        connection.metrics.active_subscriptions("request", channels.len());
                         debug!("Cleaned up request channel for ID: {request_id}");
                     }
                     CleanupSignal::Order(order_id) => {
                         let mut channels = order_channels.write().await;
                         channels.remove(&order_id);
+                        spans.close(Correlation::Order(order_id));
+                        connection.metrics.active_subscriptions("order", channels.len());
                         debug!("Cleaned up order channel for ID: {order_id}");
                     }
                     CleanupSignal::Shared(message_type) => {
@@ -207,7 +292,7 @@ impl AsyncTcpMessageBus {
                     }
                 }
             }
-        });
+        }));
 
         Ok(message_bus)
     }
@@ -216,12 +301,13 @@ impl AsyncTcpMessageBus {
     pub fn process_messages(self: Arc<Self>, _server_version: i32, reconnect_delay: Duration) -> Result<(), Error> {
         let message_bus = self.clone();
 
-        let _handle = task::spawn(async move {
+        let _handle = task::spawn(instrument_worker("dispatcher", async move {
             loop {
                 match message_bus.read_and_route_message().await {
                     Ok(_) => continue,
                     Err(Error::ConnectionReset) => {
                         error!("Connection reset, attempting to reconnect...");
+                        message_bus.connection.metrics.connection_reset();
                         sleep(reconnect_delay).await;
                         // TODO: Implement reconnection logic
                         continue;
@@ -232,7 +318,7 @@ impl AsyncTcpMessageBus {
                     }
                 }
             }
-        });
+        }));
 
         Ok(())
     }
@@ -240,6 +326,8 @@ impl AsyncTcpMessageBus {
     /// Read a message and route it to the appropriate channel
     async fn read_and_route_message(&self) -> Result<(), Error> {
         let message = self.connection.read_message().await?;
+        self.spans.response(&message);
+        self.connection.metrics.message_received(message.message_type());
 
         // Use common routing logic
         match determine_routing(&message) {
@@ -264,9 +352,9 @@ impl AsyncTcpMessageBus {
 
         // Route to request-specific channel if exists
         if request_id >= 0 {
-            let channels = self.request_channels.read().await;
-            if let Some(sender) = channels.get(&request_id) {
-                let _ = sender.send(message);
+            let sender = self.request_channels.read().await.get(&request_id).cloned();
+            if let Some(sender) = sender {
+                sender.send(message).await;
             }
         }
 
@@ -293,10 +381,7 @@ impl AsyncTcpMessageBus {
         } else {
             // Route to request-specific channel
             info!("Error message - Request ID: {request_id}, Code: {error_code}, Message: {error_msg}");
-            let channels = self.request_channels.read().await;
-            if let Some(sender) = channels.get(&request_id) {
-                let _ = sender.send(message);
-            }
+            self.route_to_request_channel(request_id, message).await?;
         }
 
         Ok(())
@@ -304,25 +389,48 @@ impl AsyncTcpMessageBus {
 
     /// Route message to request-specific channel
     async fn route_to_request_channel(&self, request_id: i32, message: ResponseMessage) -> Result<(), Error> {
-        let channels = self.request_channels.read().await;
-        if let Some(sender) = channels.get(&request_id) {
-            let _ = sender.send(message);
-        }
+        // Sending may wait on a full buffer, so the lock is released first.
+        let sender = self.request_channels.read().await.get(&request_id).cloned();
+        self.deliver(sender, message).await;
         Ok(())
     }
 
     /// Route message to order-specific channel
     async fn route_to_order_channel(&self, order_id: i32, message: ResponseMessage) -> Result<(), Error> {
         // Send to order update stream if it exists
-        self.send_order_update(&message).await;
+        let sent_to_update_stream = self.send_order_update(&message).await;
 
-        let channels = self.order_channels.read().await;
-        if let Some(sender) = channels.get(&order_id) {
-            let _ = sender.send(message);
+        let sender = self.order_channels.read().await.get(&order_id).cloned();
+        if sender.is_some() || !sent_to_update_stream {
+            self.deliver(sender, message).await;
         }
         Ok(())
     }
 
+    /// Sends a message to its subscriber, recording the backlog left in the channel or the missing subscriber.
+    async fn deliver(&self, sender: Option<ResponseSender>, message: ResponseMessage) {
+        let message_type = message.message_type();
+        let metrics = &self.connection.metrics;
+
+        match sender {
+            Some(sender) => {
+                sender.send(message).await;
+                if metrics.is_enabled() {
+                    metrics.backlog(message_type, sender.len());
+                }
+            }
+            None => metrics.response_orphaned(message_type),
+        }
+    }
+
+    async fn record_active_subscriptions(&self) {
+        let metrics = &self.connection.metrics;
+        if metrics.is_enabled() {
+            metrics.active_subscriptions("request", self.request_channels.read().await.len());
+            metrics.active_subscriptions("order", self.order_channels.read().await.len());
+        }
+    }
+
     /// Route message to shared channel
     async fn route_to_shared_channel(&self, message_type: IncomingMessages, message: ResponseMessage) -> Result<(), Error> {
         // Send order-related messages to order update stream
@@ -349,6 +457,22 @@ impl AsyncTcpMessageBus {
         Ok(())
     }
 
+    /// Creates the channel of a request or order subscription, bounded when a buffer is configured for the request.
+    fn subscription_channel(&self, message: &RequestMessage, cleanup_signal: CleanupSignal) -> (ResponseSender, AsyncInternalSubscription) {
+        let config = self.buffers.for_request(message);
+
+        if config.capacity().is_none() {
+            let (sender, receiver) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+            let subscription = AsyncInternalSubscription::with_cleanup(receiver, self.cleanup_sender.clone(), cleanup_signal);
+            return (ResponseSender::Broadcast(sender), subscription);
+        }
+
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (sender, buffer) = AsyncBuffer::bounded(config, dropped.clone());
+        let subscription = AsyncInternalSubscription::with_buffer(buffer, dropped, self.cleanup_sender.clone(), cleanup_signal);
+        (ResponseSender::Buffer(Arc::new(sender)), subscription)
+    }
+
     /// Send message to order update stream if it exists
     async fn send_order_update(&self, message: &ResponseMessage) -> bool {
         let order_update_stream = self.order_update_stream.read().await;
@@ -366,42 +490,39 @@ impl AsyncTcpMessageBus {
 #[async_trait]
 impl AsyncMessageBus for AsyncTcpMessageBus {
     async fn send_request(&self, request_id: i32, message: RequestMessage) -> Result<AsyncInternalSubscription, Error> {
-        // Create broadcast channel with reasonable buffer
-        let (sender, receiver) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (sender, subscription) = self.subscription_channel(&message, CleanupSignal::Request(request_id));
 
         // Insert into map BEFORE sending
         {
             let mut channels = self.request_channels.write().await;
             channels.insert(request_id, sender);
         }
+        self.spans.open(&message, Correlation::Request(request_id));
+        self.record_active_subscriptions().await;
 
         // Now send the request - any response will find the channel
-        self.connection.write_message(&message).await?;
+        self.connection
+            .write_correlated_message(&message, Correlation::Request(request_id))
+            .await?;
 
         // Return subscription with cleanup
-        Ok(AsyncInternalSubscription::with_cleanup(
-            receiver,
-            self.cleanup_sender.clone(),
-            CleanupSignal::Request(request_id),
-        ))
+        Ok(subscription)
     }
 
     async fn send_order_request(&self, order_id: i32, message: RequestMessage) -> Result<AsyncInternalSubscription, Error> {
         // Same pattern for orders
-        let (sender, receiver) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (sender, subscription) = self.subscription_channel(&message, CleanupSignal::Order(order_id));
 
         {
             let mut channels = self.order_channels.write().await;
             channels.insert(order_id, sender);
         }
+        self.spans.open(&message, Correlation::Order(order_id));
+        self.record_active_subscriptions().await;
 
-        self.connection.write_message(&message).await?;
+        self.connection.write_correlated_message(&message, Correlation::Order(order_id)).await?;
 
-        Ok(AsyncInternalSubscription::with_cleanup(
-            receiver,
-            self.cleanup_sender.clone(),
-            CleanupSignal::Order(order_id),
-        ))
+        Ok(subscription)
     }
 
     async fn send_shared_request(&self, message_type: OutgoingMessages, message: RequestMessage) -> Result<AsyncInternalSubscription, Error> {
@@ -435,33 +556,39 @@ impl AsyncMessageBus for AsyncTcpMessageBus {
     }
 
     async fn cancel_subscription(&self, request_id: i32, message: RequestMessage) -> Result<(), Error> {
-        self.connection.write_message(&message).await?;
+        self.connection
+            .write_correlated_message(&message, Correlation::Request(request_id))
+            .await?;
 
-        let channels = self.request_channels.read().await;
-        if let Some(sender) = channels.get(&request_id) {
+        let sender = self.request_channels.read().await.get(&request_id).cloned();
+        if let Some(sender) = sender {
             // Send cancellation error to the channel
-            let _ = sender.send(ResponseMessage::from("Cancelled"));
+            sender.send(ResponseMessage::from("Cancelled")).await;
         }
 
         // Remove channel
-        let mut channels = self.request_channels.write().await;
-        channels.remove(&request_id);
+        self.request_channels.write().await.remove(&request_id);
+        self.spans.close(Correlation::Request(request_id));
+        self.connection.metrics.subscription_cancelled(&message);
+        self.record_active_subscriptions().await;
 
         Ok(())
     }
 
     async fn cancel_order_subscription(&self, order_id: i32, message: RequestMessage) -> Result<(), Error> {
-        self.connection.write_message(&message).await?;
+        self.connection.write_correlated_message(&message, Correlation::Order(order_id)).await?;
 
-        let channels = self.order_channels.read().await;
-        if let Some(sender) = channels.get(&order_id) {
+        let sender = self.order_channels.read().await.get(&order_id).cloned();
+        if let Some(sender) = sender {
             // Send cancellation error to the channel
-            let _ = sender.send(ResponseMessage::from("Cancelled"));
+            sender.send(ResponseMessage::from("Cancelled")).await;
         }
 
         // Remove channel
-        let mut channels = self.order_channels.write().await;
-        channels.remove(&order_id);
+        self.order_channels.write().await.remove(&order_id);
+        self.spans.close(Correlation::Order(order_id));
+        self.connection.metrics.subscription_cancelled(&message);
+        self.record_active_subscriptions().await;
 
         Ok(())
     }
@@ -479,4 +606,16 @@ impl AsyncMessageBus for AsyncTcpMessageBus {
 
         Ok(AsyncInternalSubscription::new(receiver))
     }
+
+    fn set_buffer_config(&self, message_type: OutgoingMessages, config: BufferConfig) {
+        self.buffers.set(message_type, config);
+    }
+
+    fn set_default_buffer_config(&self, config: BufferConfig) {
+        self.buffers.set_default(config);
+    }
+
+    fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        self.connection.metrics.install(metrics);
+    }
 }
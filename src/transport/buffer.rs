@@ -0,0 +1,713 @@
+//! Bounded subscription buffers and their overflow policies.
+//!
+//! Subscriptions are unbounded unless a [BufferConfig] is registered for their request type. Bounded buffers apply
+//! the configured [OverflowPolicy] once full and count every message they drop. Errors, cancellations and shutdown
+//! notices are control messages: they always get into the buffer, evicting the oldest message if needed.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+use crate::messages::{request_id_index, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::subscriptions::{BufferConfig, OverflowPolicy};
+
+#[cfg(feature = "sync")]
+pub(crate) use self::sync::{channel, BufferedSender};
+
+#[cfg(feature = "async")]
+pub(crate) use self::r#async::{AsyncBuffer, AsyncBufferSender};
+
+/// Buffer configuration by request type.
+#[derive(Debug, Default)]
+pub(crate) struct BufferConfigs {
+    default: RwLock<BufferConfig>,
+    by_request: RwLock<HashMap<OutgoingMessages, BufferConfig>>,
+}
+
+impl BufferConfigs {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the buffer of subscriptions without a request-specific buffer.
+    pub(crate) fn set_default(&self, config: BufferConfig) {
+        if let Ok(mut default) = self.default.write() {
+            *default = config;
+        }
+    }
+
+    /// Sets the buffer of subscriptions started by the given request type.
+    pub(crate) fn set(&self, message_type: OutgoingMessages, config: BufferConfig) {
+        if let Ok(mut by_request) = self.by_request.write() {
+            by_request.insert(message_type, config);
+        }
+    }
+
+    /// Returns the buffer configuration for the subscription started by a request.
+    pub(crate) fn for_request(&self, message: &RequestMessage) -> BufferConfig {
+        let message_type = message.field(0).and_then(|field| field.parse::<OutgoingMessages>().ok());
+
+        let configured =
+            message_type.and_then(|message_type| self.by_request.read().ok().and_then(|by_request| by_request.get(&message_type).copied()));
+
+        configured.unwrap_or_else(|| self.default.read().map(|default| *default).unwrap_or_default())
+    }
+}
+
+/// Items held by a subscription buffer.
+pub(crate) trait Buffered {
+    /// The response message, if the item carries one.
+    fn message(&self) -> Option<&ResponseMessage>;
+
+    /// Control messages are never dropped.
+    fn is_control(&self) -> bool {
+        self.message().is_none_or(|message| message.message_type() == IncomingMessages::Error)
+    }
+}
+
+impl Buffered for ResponseMessage {
+    fn message(&self) -> Option<&ResponseMessage> {
+        Some(self)
+    }
+}
+
+impl<E> Buffered for Result<ResponseMessage, E> {
+    fn message(&self) -> Option<&ResponseMessage> {
+        self.as_ref().ok()
+    }
+}
+
+/// Key under which [OverflowPolicy::ConflateByKey] keeps only the latest message: the message and tick type of a
+/// market data tick.
+///
+/// Only ticks carrying the last value of a field are keyed. Depth rows are positional inserts, updates and deletes,
+/// and tick-by-tick and RTVolume ticks each report a trade, so dropping any of them loses data.
+fn conflation_key(message: &ResponseMessage) -> Option<(IncomingMessages, &str)> {
+    let message_type = message.message_type();
+
+    match message_type {
+        IncomingMessages::TickPrice | IncomingMessages::TickSize | IncomingMessages::TickGeneric => {
+            let tick_type_index = request_id_index(message_type)? + 1;
+            Some((message_type, message.field(tick_type_index)?))
+        }
+        _ => None,
+    }
+}
+
+/// Outcome of adding an item to a [MessageQueue].
+#[derive(Debug)]
+pub(crate) enum Push<T> {
+    /// The item was queued, after dropping the given number of buffered items.
+    Queued(u64),
+    /// The item was dropped.
+    Dropped,
+    /// The queue is full and the policy is to wait for room.
+    Full(T),
+}
+
+/// Queue applying an overflow policy once it holds `capacity` items.
+#[derive(Debug)]
+pub(crate) struct MessageQueue<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl<T: Buffered> MessageQueue<T> {
+    pub(crate) fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+            policy,
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub(crate) fn push(&mut self, item: T) -> Push<T> {
+        if self.items.len() < self.capacity {
+            self.items.push_back(item);
+            return Push::Queued(0);
+        }
+
+        if item.is_control() {
+            let evicted = self.items.pop_front().is_some();
+            self.items.push_back(item);
+            return Push::Queued(u64::from(evicted));
+        }
+
+        match self.policy {
+            OverflowPolicy::Block => Push::Full(item),
+            OverflowPolicy::DropNewest => Push::Dropped,
+            OverflowPolicy::DropOldest => {
+                self.items.pop_front();
+                self.items.push_back(item);
+                Push::Queued(1)
+            }
+            OverflowPolicy::ConflateByKey => {
+                let key = item.message().and_then(conflation_key);
+                let replaced = key.and_then(|key| {
+                    self.items
+                        .iter()
+                        .rposition(|buffered| buffered.message().and_then(conflation_key) == Some(key))
+                });
+                // Without a match, the oldest keyed tick makes room. Messages without a key are never dropped.
+                let position = replaced.or_else(|| {
+                    self.items
+                        .iter()
+                        .position(|buffered| buffered.message().and_then(conflation_key).is_some())
+                });
+
+                match position {
+                    Some(position) => {
+                        self.items.remove(position);
+                        self.items.push_back(item);
+                        Push::Queued(1)
+                    }
+                    None => Push::Full(item),
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "async", test))]
+    pub(crate) fn pop(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    #[cfg(feature = "sync")]
+    fn into_items(self) -> VecDeque<T> {
+        self.items
+    }
+}
+
+#[cfg(feature = "sync")]
+mod sync {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Weak};
+    use std::time::Duration;
+
+    use crossbeam::channel::{self, Receiver, SendError, SendTimeoutError, Sender, TrySendError};
+
+    use super::{Buffered, MessageQueue, Push};
+    use crate::subscriptions::{BufferConfig, OverflowPolicy};
+    use crate::transport::Response;
+
+    /// How often a blocked sender checks whether its subscriber is still alive.
+    const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates the channel of a subscription, returning the sender, the receiver and the dropped-message counter.
+    pub(crate) fn channel(config: BufferConfig) -> (BufferedSender, Receiver<Response>, Arc<AtomicU64>) {
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let (sender, receiver, evictor) = match config.capacity() {
+            Some(capacity) => {
+                let (sender, receiver) = channel::bounded(capacity);
+                let evictor = receiver.clone();
+                (sender, receiver, Some(evictor))
+            }
+            None => {
+                let (sender, receiver) = channel::unbounded();
+                (sender, receiver, None)
+            }
+        };
+
+        let sender = BufferedSender {
+            sender,
+            evictor,
+            policy: config.policy(),
+            dropped: Arc::downgrade(&dropped),
+        };
+
+        (sender, receiver, dropped)
+    }
+
+    /// Sending half of a subscription channel, applying the overflow policy of bounded channels.
+    #[derive(Debug, Clone)]
+    pub(crate) struct BufferedSender {
+        sender: Sender<Response>,
+        // Receiving handle used to evict buffered messages. Only bounded channels have one.
+        evictor: Option<Receiver<Response>>,
+        policy: OverflowPolicy,
+        // Held by the subscription, so it also tells whether the subscriber is still alive.
+        dropped: Weak<AtomicU64>,
+    }
+
+    impl BufferedSender {
+        pub(crate) fn send(&self, response: Response) -> Result<(), SendError<Response>> {
+            let Some(evictor) = &self.evictor else {
+                return self.sender.send(response);
+            };
+
+            let response = match self.sender.try_send(response) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(response)) => return Err(SendError(response)),
+                Err(TrySendError::Full(response)) => response,
+            };
+
+            if response.is_control() {
+                return self.evict_and_send(evictor, response);
+            }
+
+            match self.policy {
+                OverflowPolicy::Block => self.send_blocking(response),
+                OverflowPolicy::DropNewest => {
+                    self.record_dropped(1);
+                    Ok(())
+                }
+                OverflowPolicy::DropOldest => self.evict_and_send(evictor, response),
+                OverflowPolicy::ConflateByKey => self.conflate(evictor, response),
+            }
+        }
+
+        /// Number of messages waiting to be received.
+        pub(crate) fn len(&self) -> usize {
+            self.sender.len()
+        }
+
+        fn record_dropped(&self, count: u64) {
+            if let Some(dropped) = self.dropped.upgrade() {
+                dropped.fetch_add(count, Ordering::Relaxed);
+            }
+        }
+
+        fn is_subscribed(&self) -> bool {
+            self.dropped.strong_count() > 0
+        }
+
+        // Waits for room, giving up once the subscriber is gone.
+        fn send_blocking(&self, mut response: Response) -> Result<(), SendError<Response>> {
+            loop {
+                match self.sender.send_timeout(response, BLOCK_POLL_INTERVAL) {
+                    Ok(()) => return Ok(()),
+                    Err(SendTimeoutError::Disconnected(pending)) => return Err(SendError(pending)),
+                    Err(SendTimeoutError::Timeout(pending)) => {
+                        if !self.is_subscribed() {
+                            return Err(SendError(pending));
+                        }
+                        response = pending;
+                    }
+                }
+            }
+        }
+
+        fn evict_and_send(&self, evictor: &Receiver<Response>, mut response: Response) -> Result<(), SendError<Response>> {
+            loop {
+                if evictor.try_recv().is_ok() {
+                    self.record_dropped(1);
+                }
+
+                match self.sender.try_send(response) {
+                    Ok(()) => return Ok(()),
+                    Err(TrySendError::Disconnected(pending)) => return Err(SendError(pending)),
+                    Err(TrySendError::Full(pending)) => response = pending,
+                }
+            }
+        }
+
+        // Drains the channel, conflates the new message into the drained messages and refills the channel.
+        fn conflate(&self, evictor: &Receiver<Response>, response: Response) -> Result<(), SendError<Response>> {
+            let buffered: Vec<Response> = evictor.try_iter().collect();
+
+            let mut queue = MessageQueue::new(buffered.len(), self.policy);
+            for item in buffered {
+                let _ = queue.push(item);
+            }
+            let pending = match queue.push(response) {
+                Push::Queued(dropped) => {
+                    self.record_dropped(dropped);
+                    None
+                }
+                Push::Dropped => {
+                    self.record_dropped(1);
+                    None
+                }
+                Push::Full(response) => Some(response),
+            };
+
+            for item in queue.into_items() {
+                if let Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) = self.sender.try_send(item) {
+                    self.record_dropped(1);
+                }
+            }
+
+            // Nothing could be conflated, so the message waits for room like with OverflowPolicy::Block.
+            match pending {
+                Some(response) => self.send_blocking(response),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+mod r#async {
+    use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use tokio::sync::Notify;
+
+    use super::{MessageQueue, Push};
+    use crate::messages::ResponseMessage;
+    use crate::subscriptions::BufferConfig;
+
+    /// How often a blocked sender checks whether its subscriber is still alive.
+    const BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Bounded buffer between the dispatcher and an async subscription.
+    #[derive(Debug)]
+    pub(crate) struct AsyncBuffer {
+        queue: Mutex<MessageQueue<ResponseMessage>>,
+        readable: Notify,
+        writable: Notify,
+        dropped: Arc<AtomicU64>,
+        receivers: AtomicUsize,
+        closed: AtomicBool,
+    }
+
+    impl AsyncBuffer {
+        /// Creates a bounded buffer, returning its sending half and the buffer the subscription receives from.
+        pub(crate) fn bounded(config: BufferConfig, dropped: Arc<AtomicU64>) -> (AsyncBufferSender, Arc<AsyncBuffer>) {
+            let buffer = Arc::new(AsyncBuffer {
+                queue: Mutex::new(MessageQueue::new(config.capacity().unwrap_or(usize::MAX), config.policy())),
+                readable: Notify::new(),
+                writable: Notify::new(),
+                dropped,
+                receivers: AtomicUsize::new(1),
+                closed: AtomicBool::new(false),
+            });
+
+            (AsyncBufferSender(buffer.clone()), buffer)
+        }
+
+        /// Waits for the next message. Returns `None` once the sender is gone and the buffer is drained.
+        pub(crate) async fn recv(&self) -> Option<ResponseMessage> {
+            loop {
+                let readable = self.readable.notified();
+
+                if let Some(message) = self.queue.lock().ok()?.pop() {
+                    self.writable.notify_one();
+                    return Some(message);
+                }
+                if self.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+
+                readable.await;
+            }
+        }
+
+        /// Registers another receiver sharing this buffer.
+        pub(crate) fn add_receiver(&self) {
+            self.receivers.fetch_add(1, Ordering::AcqRel);
+        }
+
+        /// Unregisters a receiver, so a blocked sender stops waiting once none are left.
+        pub(crate) fn remove_receiver(&self) {
+            self.receivers.fetch_sub(1, Ordering::AcqRel);
+            self.writable.notify_waiters();
+        }
+    }
+
+    /// Sending half of an [AsyncBuffer]. Dropping it closes the buffer.
+    #[derive(Debug)]
+    pub(crate) struct AsyncBufferSender(Arc<AsyncBuffer>);
+
+    impl AsyncBufferSender {
+        /// Number of messages waiting to be received.
+        pub(crate) fn len(&self) -> usize {
+            self.0.queue.lock().map(|queue| queue.len()).unwrap_or_default()
+        }
+
+        /// Adds a message to the buffer, waiting for room when the policy is to block.
+        pub(crate) async fn send(&self, message: ResponseMessage) {
+            let buffer = &self.0;
+            let mut message = message;
+
+            loop {
+                let writable = buffer.writable.notified();
+
+                let pushed = match buffer.queue.lock() {
+                    Ok(mut queue) => queue.push(message),
+                    Err(_) => return,
+                };
+
+                match pushed {
+                    Push::Queued(dropped) => {
+                        buffer.dropped.fetch_add(dropped, Ordering::Relaxed);
+                        buffer.readable.notify_one();
+                        return;
+                    }
+                    Push::Dropped => {
+                        buffer.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    Push::Full(pending) => {
+                        if buffer.receivers.load(Ordering::Acquire) == 0 {
+                            return;
+                        }
+                        message = pending;
+                        let _ = tokio::time::timeout(BLOCK_POLL_INTERVAL, writable).await;
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for AsyncBufferSender {
+        fn drop(&mut self) {
+            self.0.closed.store(true, Ordering::Release);
+            self.0.readable.notify_waiters();
+            self.0.readable.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_of(capacity: usize, policy: OverflowPolicy, messages: &[&str]) -> MessageQueue<ResponseMessage> {
+        let mut queue = MessageQueue::new(capacity, policy);
+        for message in messages {
+            let _ = queue.push(ResponseMessage::from_simple(message));
+        }
+        queue
+    }
+
+    fn drain(queue: &mut MessageQueue<ResponseMessage>) -> Vec<String> {
+        std::iter::from_fn(|| queue.pop()).map(|message| message.encode_simple()).collect()
+    }
+
+    #[test]
+    fn test_queue_below_capacity() {
+        let mut queue = queue_of(3, OverflowPolicy::DropNewest, &["1|6|9000|1|185.50|100|3|", "1|6|9000|2|185.55|200|3|"]);
+
+        assert_eq!(drain(&mut queue), vec!["1|6|9000|1|185.50|100|3|", "1|6|9000|2|185.55|200|3|"]);
+    }
+
+    #[test]
+    fn test_queue_drop_newest() {
+        let mut queue = queue_of(1, OverflowPolicy::DropNewest, &["1|6|9000|1|185.50|100|3|"]);
+
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|2|185.55|200|3|")),
+            Push::Dropped
+        ));
+        assert_eq!(drain(&mut queue), vec!["1|6|9000|1|185.50|100|3|"]);
+    }
+
+    #[test]
+    fn test_queue_drop_oldest() {
+        let mut queue = queue_of(2, OverflowPolicy::DropOldest, &["1|6|9000|1|185.50|100|3|", "1|6|9000|2|185.55|200|3|"]);
+
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|4|185.52|0|0|")),
+            Push::Queued(1)
+        ));
+        assert_eq!(drain(&mut queue), vec!["1|6|9000|2|185.55|200|3|", "1|6|9000|4|185.52|0|0|"]);
+    }
+
+    #[test]
+    fn test_queue_block() {
+        let mut queue = queue_of(1, OverflowPolicy::Block, &["1|6|9000|1|185.50|100|3|"]);
+
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|2|185.55|200|3|")),
+            Push::Full(_)
+        ));
+        assert_eq!(queue.pop().unwrap().encode_simple(), "1|6|9000|1|185.50|100|3|");
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|2|185.55|200|3|")),
+            Push::Queued(0)
+        ));
+    }
+
+    #[test]
+    fn test_queue_conflate_by_tick_type() {
+        let mut queue = queue_of(
+            3,
+            OverflowPolicy::ConflateByKey,
+            &["1|6|9000|1|185.50|100|3|", "1|6|9000|2|185.55|200|3|", "2|6|9000|0|300|"],
+        );
+
+        // A newer bid replaces the buffered bid.
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|1|185.51|100|3|")),
+            Push::Queued(1)
+        ));
+        // A tick type with no buffered match pushes out the oldest tick.
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("1|6|9000|4|185.52|0|0|")),
+            Push::Queued(1)
+        ));
+
+        assert_eq!(
+            drain(&mut queue),
+            vec!["2|6|9000|0|300|", "1|6|9000|1|185.51|100|3|", "1|6|9000|4|185.52|0|0|"]
+        );
+    }
+
+    #[test]
+    fn test_queue_never_conflates_depth_or_trades() {
+        let depth = ["12|1|9000|0|1|1|185.50|100|", "12|1|9000|0|1|1|185.49|300|"];
+        let mut queue = queue_of(2, OverflowPolicy::ConflateByKey, &depth);
+
+        // Depth rows are operations on the book, so a full buffer of them waits for room.
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("12|1|9000|0|2|1|185.48|100|")),
+            Push::Full(_)
+        ));
+
+        // A trade pushes out a buffered last-value tick rather than another trade.
+        let mut queue = queue_of(
+            2,
+            OverflowPolicy::ConflateByKey,
+            &["46|6|9000|48|185.50;100;1678740829;300;185.5;true|", "1|6|9000|1|185.50|100|3|"],
+        );
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple("46|6|9000|48|185.51;100;1678740830;400;185.5;true|")),
+            Push::Queued(1)
+        ));
+        assert_eq!(
+            drain(&mut queue),
+            vec![
+                "46|6|9000|48|185.50;100;1678740829;300;185.5;true|",
+                "46|6|9000|48|185.51;100;1678740830;400;185.5;true|"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queue_never_drops_control_messages() {
+        let mut queue = queue_of(1, OverflowPolicy::DropNewest, &["1|6|9000|1|185.50|100|3|"]);
+
+        assert!(matches!(
+            queue.push(ResponseMessage::from_simple(
+                "4|2|9000|10197|No market data during competing live session|"
+            )),
+            Push::Queued(1)
+        ));
+        assert_eq!(drain(&mut queue), vec!["4|2|9000|10197|No market data during competing live session|"]);
+    }
+
+    #[test]
+    fn test_buffer_configs() {
+        let configs = BufferConfigs::new();
+        let market_data = RequestMessage::from_simple("1|11|9000|0|AAPL|STK|");
+        let market_depth = RequestMessage::from_simple("10|5|9001|0|AAPL|STK|");
+
+        assert_eq!(configs.for_request(&market_data), BufferConfig::unbounded());
+
+        configs.set_default(BufferConfig::bounded(1000, OverflowPolicy::DropOldest));
+        configs.set(
+            OutgoingMessages::RequestMarketData,
+            BufferConfig::bounded(100, OverflowPolicy::ConflateByKey),
+        );
+
+        assert_eq!(
+            configs.for_request(&market_data),
+            BufferConfig::bounded(100, OverflowPolicy::ConflateByKey)
+        );
+        assert_eq!(
+            configs.for_request(&market_depth),
+            BufferConfig::bounded(1000, OverflowPolicy::DropOldest)
+        );
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_channel_counts_dropped_messages() {
+        let (sender, receiver, dropped) = channel(BufferConfig::bounded(2, OverflowPolicy::DropOldest));
+
+        for price in ["185.50", "185.51", "185.52", "185.53"] {
+            sender
+                .send(Ok(ResponseMessage::from_simple(&format!("1|6|9000|1|{price}|100|3|"))))
+                .unwrap();
+        }
+
+        assert_eq!(dropped.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(receiver.len(), 2);
+        assert_eq!(receiver.recv().unwrap().unwrap().peek_string(4), "185.52");
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_channel_conflates() {
+        let (sender, receiver, dropped) = channel(BufferConfig::bounded(2, OverflowPolicy::ConflateByKey));
+
+        sender.send(Ok(ResponseMessage::from_simple("1|6|9000|1|185.50|100|3|"))).unwrap();
+        sender.send(Ok(ResponseMessage::from_simple("1|6|9000|2|185.55|200|3|"))).unwrap();
+        sender.send(Ok(ResponseMessage::from_simple("1|6|9000|1|185.51|100|3|"))).unwrap();
+
+        assert_eq!(dropped.load(std::sync::atomic::Ordering::Relaxed), 1);
+        let prices: Vec<String> = receiver.try_iter().map(|response| response.unwrap().peek_string(4)).collect();
+        assert_eq!(prices, vec!["185.55", "185.51"]);
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_channel_keeps_control_messages() {
+        let (sender, receiver, dropped) = channel(BufferConfig::bounded(1, OverflowPolicy::Block));
+
+        sender.send(Ok(ResponseMessage::from_simple("1|6|9000|1|185.50|100|3|"))).unwrap();
+        sender.send(Err(crate::Error::Cancelled)).unwrap();
+
+        assert_eq!(dropped.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert!(matches!(receiver.recv().unwrap(), Err(crate::Error::Cancelled)));
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_channel_stops_blocking_without_subscriber() {
+        let (sender, receiver, dropped) = channel(BufferConfig::bounded(1, OverflowPolicy::Block));
+
+        sender.send(Ok(ResponseMessage::from_simple("1|6|9000|1|185.50|100|3|"))).unwrap();
+        drop(receiver);
+        drop(dropped);
+
+        assert!(sender.send(Ok(ResponseMessage::from_simple("1|6|9000|1|185.51|100|3|"))).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_buffer() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (sender, buffer) = AsyncBuffer::bounded(BufferConfig::bounded(2, OverflowPolicy::DropNewest), dropped.clone());
+
+        for price in ["185.50", "185.51", "185.52"] {
+            sender.send(ResponseMessage::from_simple(&format!("1|6|9000|1|{price}|100|3|"))).await;
+        }
+        drop(sender);
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(buffer.recv().await.unwrap().peek_string(4), "185.50");
+        assert_eq!(buffer.recv().await.unwrap().peek_string(4), "185.51");
+        assert!(buffer.recv().await.is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_buffer_blocks_until_read() {
+        use std::sync::atomic::AtomicU64;
+        use std::sync::Arc;
+
+        let (sender, buffer) = AsyncBuffer::bounded(BufferConfig::bounded(1, OverflowPolicy::Block), Arc::new(AtomicU64::new(0)));
+
+        sender.send(ResponseMessage::from_simple("1|6|9000|1|185.50|100|3|")).await;
+        let blocked = tokio::spawn(async move {
+            sender.send(ResponseMessage::from_simple("1|6|9000|1|185.51|100|3|")).await;
+        });
+
+        assert_eq!(buffer.recv().await.unwrap().peek_string(4), "185.50");
+        blocked.await.unwrap();
+        assert_eq!(buffer.recv().await.unwrap().peek_string(4), "185.51");
+        assert!(buffer.recv().await.is_none());
+    }
+}
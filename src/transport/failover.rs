@@ -0,0 +1,148 @@
+//! Failover between TWS or Gateway endpoints.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crossbeam::channel::{self, Receiver, Sender};
+
+use crate::errors::Error;
+
+/// Change in the connection to TWS or Gateway, reported after the dispatcher recovers from a lost connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Reconnected to the endpoint that was active when the connection was lost.
+    Reconnected {
+        /// Address of the active endpoint.
+        endpoint: String,
+    },
+    /// Reconnection to `from` gave up and the client connected to the next endpoint, `to`.
+    FailedOver {
+        /// Address of the endpoint that was lost.
+        from: String,
+        /// Address of the active endpoint.
+        to: String,
+    },
+    /// Every endpoint was tried without success. The client shuts down.
+    Disconnected,
+}
+
+/// Receives [ConnectionEvent]s from a client.
+#[derive(Debug)]
+pub struct ConnectionEvents {
+    receiver: Receiver<ConnectionEvent>,
+}
+
+impl ConnectionEvents {
+    // Events for a transport that never reconnects.
+    pub(crate) fn closed() -> Self {
+        let (_, receiver) = channel::unbounded();
+        Self { receiver }
+    }
+
+    /// Blocks until the next event. Returns `None` once the client has shut down.
+    pub fn next_event(&self) -> Option<ConnectionEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// Returns the next event if one is available.
+    pub fn try_next_event(&self) -> Option<ConnectionEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits up to `timeout` for the next event.
+    pub fn next_event_timeout(&self, timeout: Duration) -> Option<ConnectionEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+/// Sends [ConnectionEvent]s to every subscriber.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionEventSenders {
+    senders: Mutex<Vec<Sender<ConnectionEvent>>>,
+}
+
+impl ConnectionEventSenders {
+    pub(crate) fn subscribe(&self) -> ConnectionEvents {
+        let (sender, receiver) = channel::unbounded();
+        if let Ok(mut senders) = self.senders.lock() {
+            senders.push(sender);
+        }
+        ConnectionEvents { receiver }
+    }
+
+    pub(crate) fn send(&self, event: ConnectionEvent) {
+        if let Ok(mut senders) = self.senders.lock() {
+            // Subscribers that dropped their receiver are removed.
+            senders.retain(|sender| sender.send(event.clone()).is_ok());
+        }
+    }
+}
+
+/// Ordered list of endpoint addresses and the one in use.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    addresses: Vec<String>,
+    active: AtomicUsize,
+}
+
+impl Endpoints {
+    pub(crate) fn new(addresses: &[&str], active: usize) -> Result<Self, Error> {
+        if addresses.is_empty() {
+            return Err(Error::InvalidArgument("at least one endpoint is required".into()));
+        }
+
+        Ok(Self {
+            addresses: addresses.iter().map(|address| address.to_string()).collect(),
+            active: AtomicUsize::new(active % addresses.len()),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub(crate) fn active(&self) -> &str {
+        &self.addresses[self.active.load(Ordering::Acquire)]
+    }
+
+    /// Makes the next endpoint active, wrapping to the first after the last.
+    pub(crate) fn advance(&self) -> &str {
+        let next = (self.active.load(Ordering::Acquire) + 1) % self.addresses.len();
+        self.active.store(next, Ordering::Release);
+        &self.addresses[next]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_advance_in_order() {
+        let endpoints = Endpoints::new(&["primary:4002", "standby:4002"], 0).unwrap();
+
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints.active(), "primary:4002");
+        assert_eq!(endpoints.advance(), "standby:4002");
+        assert_eq!(endpoints.active(), "standby:4002");
+        assert_eq!(endpoints.advance(), "primary:4002");
+
+        assert!(Endpoints::new(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_events_sent_to_every_subscriber() {
+        let senders = ConnectionEventSenders::default();
+        let first = senders.subscribe();
+        let second = senders.subscribe();
+        drop(senders.subscribe());
+
+        senders.send(ConnectionEvent::Disconnected);
+
+        assert_eq!(first.try_next_event(), Some(ConnectionEvent::Disconnected));
+        assert_eq!(second.try_next_event(), Some(ConnectionEvent::Disconnected));
+        assert_eq!(senders.senders.lock().unwrap().len(), 2);
+        assert_eq!(ConnectionEvents::closed().next_event(), None);
+    }
+}
@@ -1,5 +1,7 @@
 //! Transport layer for TWS communication with sync/async support
 
+#[cfg(feature = "sync")]
+use std::sync::atomic::{AtomicU64, Ordering};
 #[cfg(feature = "sync")]
 use std::sync::Arc;
 #[cfg(feature = "sync")]
@@ -13,6 +15,18 @@ use crate::messages::{RequestMessage, ResponseMessage};
 
 #[cfg(feature = "sync")]
 use crate::messages::OutgoingMessages;
+#[cfg(feature = "sync")]
+use crate::metrics::Metrics;
+#[cfg(feature = "sync")]
+use crate::subscriptions::BufferConfig;
+
+#[cfg(feature = "sync")]
+use crate::client::id_generator::ClientIdManager;
+#[cfg(feature = "sync")]
+use failover::ConnectionEvents;
+
+#[cfg(feature = "sync")]
+use buffer::BufferedSender;
 
 #[cfg(feature = "sync")]
 pub mod sync;
@@ -63,6 +77,31 @@ pub(crate) trait MessageBus: Send + Sync {
 
     fn ensure_shutdown(&self);
 
+    /// Sets the buffer of subscriptions started by the given request type.
+    fn set_buffer_config(&self, _message_type: OutgoingMessages, _config: BufferConfig) {}
+
+    /// Sets the buffer of subscriptions without a request-specific buffer.
+    fn set_default_buffer_config(&self, _config: BufferConfig) {}
+
+    /// Installs the metrics emitted by the transport.
+    fn set_metrics(&self, _metrics: Arc<dyn Metrics>) {}
+
+    /// Drops the connection so the dispatcher reconnects, for connections that stopped responding.
+    fn force_reconnect(&self) {}
+
+    /// Subscribes to changes in the connection after it is lost.
+    fn connection_events(&self) -> ConnectionEvents {
+        ConnectionEvents::closed()
+    }
+
+    /// Returns the address of the endpoint in use.
+    fn active_endpoint(&self) -> Option<String> {
+        None
+    }
+
+    /// Sets the order ids to resynchronize with the server after reconnecting.
+    fn track_order_ids(&self, _order_ids: Arc<ClientIdManager>) {}
+
     // Testing interface. Tracks requests sent messages when Bus is stubbed.
     #[cfg(test)]
     fn request_messages(&self) -> Vec<RequestMessage> {
@@ -75,9 +114,10 @@ pub(crate) trait MessageBus: Send + Sync {
 #[derive(Debug, Default)]
 pub(crate) struct InternalSubscription {
     receiver: Option<Receiver<Response>>,              // requests with request ids receive responses via this channel
-    sender: Option<Sender<Response>>,                  // requests with request ids receive responses via this channel
+    sender: Option<BufferedSender>,                    // requests with request ids receive responses via this channel
     shared_receiver: Option<Arc<Receiver<Response>>>,  // this channel is for responses that share channel based on message type
     signaler: Option<Sender<Signal>>,                  // for client to signal termination
+    dropped: Option<Arc<AtomicU64>>,                   // messages dropped by a bounded buffer
     pub(crate) request_id: Option<i32>,                // initiating request id
     pub(crate) order_id: Option<i32>,                  // initiating order id
     pub(crate) message_type: Option<OutgoingMessages>, // initiating message type
@@ -118,6 +158,16 @@ impl InternalSubscription {
         }
     }
 
+    // Channel the responses are received on.
+    pub(crate) fn receiver(&self) -> Option<&Receiver<Response>> {
+        self.receiver.as_ref().or(self.shared_receiver.as_deref())
+    }
+
+    // Number of messages dropped because the buffer was full.
+    pub(crate) fn dropped_messages(&self) -> u64 {
+        self.dropped.as_ref().map_or(0, |dropped| dropped.load(Ordering::Relaxed))
+    }
+
     pub(crate) fn cancel(&self) {
         if let Some(sender) = &self.sender {
             if let Err(e) = sender.send(Err(Error::Cancelled)) {
@@ -173,9 +223,10 @@ pub enum Signal {
 #[cfg(feature = "sync")]
 pub(crate) struct SubscriptionBuilder {
     receiver: Option<Receiver<Response>>,
-    sender: Option<Sender<Response>>,
+    sender: Option<BufferedSender>,
     shared_receiver: Option<Arc<Receiver<Response>>>,
     signaler: Option<Sender<Signal>>,
+    dropped: Option<Arc<AtomicU64>>,
     order_id: Option<i32>,
     request_id: Option<i32>,
     message_type: Option<OutgoingMessages>,
@@ -189,6 +240,7 @@ impl SubscriptionBuilder {
             sender: None,
             shared_receiver: None,
             signaler: None,
+            dropped: None,
             order_id: None,
             request_id: None,
             message_type: None,
@@ -200,7 +252,7 @@ impl SubscriptionBuilder {
         self
     }
 
-    pub(crate) fn sender(mut self, sender: Sender<Response>) -> Self {
+    pub(crate) fn sender(mut self, sender: BufferedSender) -> Self {
         self.sender = Some(sender);
         self
     }
@@ -215,6 +267,11 @@ impl SubscriptionBuilder {
         self
     }
 
+    pub(crate) fn dropped(mut self, dropped: Arc<AtomicU64>) -> Self {
+        self.dropped = Some(dropped);
+        self
+    }
+
     pub(crate) fn order_id(mut self, order_id: i32) -> Self {
         self.order_id = Some(order_id);
         self
@@ -237,6 +294,7 @@ impl SubscriptionBuilder {
                 sender: self.sender,
                 shared_receiver: None,
                 signaler: Some(signaler),
+                dropped: self.dropped,
                 request_id: self.request_id,
                 order_id: self.order_id,
                 message_type: self.message_type,
@@ -247,6 +305,7 @@ impl SubscriptionBuilder {
                 sender: None,
                 shared_receiver: Some(receiver),
                 signaler: None,
+                dropped: None,
                 request_id: self.request_id,
                 order_id: self.order_id,
                 message_type: self.message_type,
@@ -268,6 +327,11 @@ pub(crate) use sync::TcpSocket;
 #[cfg(feature = "async")]
 pub use r#async::{AsyncInternalSubscription, AsyncMessageBus};
 
+pub(crate) mod buffer;
 pub mod connection;
+#[cfg(feature = "sync")]
+pub(crate) mod failover;
 pub mod recorder;
+#[cfg(feature = "sync")]
+pub(crate) mod replay;
 pub mod routing;
@@ -5,62 +5,175 @@
 //! e.g.  set to /tmp/logs
 //! /tmp/logs/0001-request.msg
 //! /tmp/logs/0002-response.msg
+//!
+//! IBAPI_RECORDING_FORMAT selects a structured, append-only log instead of one file per message:
+//! * `files` (default) - one `NNNN-request.msg`/`NNNN-response.msg` file per message
+//! * `jsonl` - `recording-NNNN.jsonl`, one JSON object per line
+//! * `binary` - `recording-NNNN.bin`, length-prefixed records
+//!
+//! Structured logs rotate to a new file once IBAPI_RECORDING_MAX_BYTES (default 64 MiB) would be exceeded.
+//! Each entry carries a sequence number, a monotonic timestamp (nanoseconds since the recorder started), the
+//! wall-clock time, the direction, the client id, the message type, the request or order id and the raw fields.
+//!
+//! A binary record is laid out as, all integers big-endian:
+//! `u32 length of the rest | u64 sequence | u64 monotonic_ns | u64 unix_ns | u8 direction (0 request, 1 response) |
+//! i32 client_id | i32 message_type | i32 request_id | i32 order_id | fields`, where missing ids are -1 and the
+//! fields are `\0` terminated as on the wire.
+//!
+//! Recording never fails the connection. I/O errors are logged and the message is skipped.
 
 use std::env;
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use byteorder::{BigEndian, WriteBytesExt};
+use log::error;
+use serde::Serialize;
 use time::macros::format_description;
 use time::OffsetDateTime;
 
 use super::{RequestMessage, ResponseMessage};
+use crate::messages::{IncomingMessages, OutgoingMessages};
 
 static RECORDING_SEQ: AtomicUsize = AtomicUsize::new(0);
 static RECORDER_ID: AtomicUsize = AtomicUsize::new(0);
 
+const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Layout of a recording.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum RecordingFormat {
+    /// One file per message.
+    #[default]
+    Files,
+    /// Append-only JSON lines.
+    JsonLines,
+    /// Append-only length-prefixed binary records.
+    Binary,
+}
+
+impl FromStr for RecordingFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "files" => Ok(RecordingFormat::Files),
+            "jsonl" | "json" => Ok(RecordingFormat::JsonLines),
+            "binary" | "bin" => Ok(RecordingFormat::Binary),
+            other => Err(format!("unknown recording format: {other}")),
+        }
+    }
+}
+
+/// Request or order id a request was sent for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) enum Correlation {
+    #[default]
+    None,
+    Request(i32),
+    Order(i32),
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct MessageRecorder {
     enabled: bool,
     recording_dir: String,
+    client_id: Option<i32>,
+    log: Option<Arc<Mutex<StructuredLog>>>,
 }
 
 impl MessageRecorder {
     pub fn new(enabled: bool, recording_dir: String) -> Self {
-        Self { enabled, recording_dir }
+        Self {
+            enabled,
+            recording_dir,
+            client_id: None,
+            log: None,
+        }
     }
+
     pub fn from_env() -> Self {
         match env::var("IBAPI_RECORDING_DIR") {
             Ok(dir) => {
                 if dir.is_empty() {
-                    MessageRecorder {
-                        enabled: false,
-                        recording_dir: String::from(""),
-                    }
+                    MessageRecorder::new(false, String::from(""))
                 } else {
                     let format = format_description!("[year]-[month]-[day]-[hour]-[minute]");
                     let now = OffsetDateTime::now_utc();
                     let instance_id = RECORDER_ID.fetch_add(1, Ordering::SeqCst);
-                    let recording_dir = format!("{}/{}-{}", dir, now.format(&format).unwrap(), instance_id);
+                    let timestamp = now.format(&format).unwrap_or_default();
+                    let recording_dir = format!("{dir}/{timestamp}-{instance_id}");
+
+                    if let Err(err) = fs::create_dir_all(&recording_dir) {
+                        error!("recording disabled, could not create {recording_dir}: {err}");
+                        return MessageRecorder::new(false, String::from(""));
+                    }
 
-                    fs::create_dir_all(&recording_dir).unwrap();
+                    let recording_format = env::var("IBAPI_RECORDING_FORMAT").unwrap_or_default();
+                    let recording_format = RecordingFormat::from_str(&recording_format).unwrap_or_else(|err| {
+                        error!("{err}, recording one file per message");
+                        RecordingFormat::Files
+                    });
 
-                    MessageRecorder::new(true, recording_dir)
+                    let max_bytes = env::var("IBAPI_RECORDING_MAX_BYTES")
+                        .ok()
+                        .and_then(|max_bytes| max_bytes.parse().ok())
+                        .unwrap_or(DEFAULT_MAX_BYTES);
+
+                    MessageRecorder::new(true, recording_dir).format(recording_format, max_bytes)
                 }
             }
-            _ => MessageRecorder {
-                enabled: false,
-                recording_dir: String::from(""),
-            },
+            _ => MessageRecorder::new(false, String::from("")),
         }
     }
 
-    pub fn record_request(&self, message: &RequestMessage) {
+    /// Switches an enabled recorder to the given format. Structured logs rotate once `max_bytes` would be exceeded.
+    pub fn format(mut self, format: RecordingFormat, max_bytes: u64) -> Self {
+        self.log = match format {
+            RecordingFormat::Files => None,
+            _ if !self.enabled => None,
+            _ => Some(Arc::new(Mutex::new(StructuredLog::new(
+                PathBuf::from(&self.recording_dir),
+                format,
+                max_bytes,
+            )))),
+        };
+        self
+    }
+
+    /// Sets the client id included in structured entries.
+    pub fn client_id(mut self, client_id: i32) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+
+    pub fn record_request(&self, message: &RequestMessage, correlation: Correlation) {
         if !self.enabled {
             return;
         }
 
+        if let Some(log) = &self.log {
+            let (request_id, order_id) = match correlation {
+                Correlation::None => (None, None),
+                Correlation::Request(request_id) => (Some(request_id), None),
+                Correlation::Order(order_id) => (None, Some(order_id)),
+            };
+            let message_type = message
+                .field(0)
+                .and_then(|field| OutgoingMessages::from_str(field).ok())
+                .map(|message_type| format!("{message_type:?}"));
+            let fields: Vec<&str> = message.fields().collect();
+            self.append(log, Direction::Request, message_type, request_id, order_id, &fields);
+            return;
+        }
+
         let record_id = RECORDING_SEQ.fetch_add(1, Ordering::SeqCst);
-        fs::write(self.request_file(record_id), message.encode().replace('\0', "|")).unwrap();
+        self.write_file(self.request_file(record_id), message.encode());
     }
 
     pub fn record_response(&self, message: &ResponseMessage) {
@@ -68,8 +181,56 @@ impl MessageRecorder {
             return;
         }
 
+        if let Some(log) = &self.log {
+            let message_type = match message.message_type() {
+                IncomingMessages::NotValid => None,
+                message_type => Some(format!("{message_type:?}")),
+            };
+            let fields: Vec<&str> = message.fields().collect();
+            self.append(log, Direction::Response, message_type, message.request_id(), message.order_id(), &fields);
+            return;
+        }
+
         let record_id = RECORDING_SEQ.fetch_add(1, Ordering::SeqCst);
-        fs::write(self.response_file(record_id), message.encode().replace('\0', "|")).unwrap();
+        self.write_file(self.response_file(record_id), message.encode());
+    }
+
+    fn write_file(&self, path: String, encoded: String) {
+        if let Err(err) = fs::write(&path, encoded.replace('\0', "|")) {
+            error!("failed to record message to {path}: {err}");
+        }
+    }
+
+    fn append(
+        &self,
+        log: &Mutex<StructuredLog>,
+        direction: Direction,
+        message_type: Option<String>,
+        request_id: Option<i32>,
+        order_id: Option<i32>,
+        fields: &[&str],
+    ) {
+        let Ok(mut log) = log.lock() else {
+            error!("failed to record message: recording log lock poisoned");
+            return;
+        };
+
+        let entry = Entry {
+            sequence: log.sequence,
+            monotonic_ns: log.started.elapsed().as_nanos() as u64,
+            unix_ns: (OffsetDateTime::now_utc().unix_timestamp_nanos()) as u64,
+            direction,
+            client_id: self.client_id,
+            message_type,
+            request_id,
+            order_id,
+            fields,
+        };
+        log.sequence += 1;
+
+        if let Err(err) = log.append(&entry) {
+            error!("failed to record message to {}: {err}", log.dir.display());
+        }
     }
 
     fn request_file(&self, record_id: usize) -> String {
@@ -81,6 +242,117 @@ impl MessageRecorder {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Direction {
+    Request,
+    Response,
+}
+
+#[derive(Debug, Serialize)]
+struct Entry<'a> {
+    sequence: u64,
+    monotonic_ns: u64,
+    unix_ns: u64,
+    direction: Direction,
+    client_id: Option<i32>,
+    message_type: Option<String>,
+    request_id: Option<i32>,
+    order_id: Option<i32>,
+    fields: &'a [&'a str],
+}
+
+impl Entry<'_> {
+    fn to_json_line(&self) -> std::io::Result<Vec<u8>> {
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+
+    fn to_binary(&self) -> std::io::Result<Vec<u8>> {
+        let mut body = Vec::new();
+        body.write_u64::<BigEndian>(self.sequence)?;
+        body.write_u64::<BigEndian>(self.monotonic_ns)?;
+        body.write_u64::<BigEndian>(self.unix_ns)?;
+        body.write_u8(match self.direction {
+            Direction::Request => 0,
+            Direction::Response => 1,
+        })?;
+        body.write_i32::<BigEndian>(self.client_id.unwrap_or(-1))?;
+        body.write_i32::<BigEndian>(self.fields.first().and_then(|field| field.parse().ok()).unwrap_or(-1))?;
+        body.write_i32::<BigEndian>(self.request_id.unwrap_or(-1))?;
+        body.write_i32::<BigEndian>(self.order_id.unwrap_or(-1))?;
+        for field in self.fields {
+            body.write_all(field.as_bytes())?;
+            body.write_u8(0)?;
+        }
+
+        let mut record = Vec::with_capacity(body.len() + 4);
+        record.write_u32::<BigEndian>(body.len() as u32)?;
+        record.extend_from_slice(&body);
+        Ok(record)
+    }
+}
+
+// Append-only log, rotated by size.
+#[derive(Debug)]
+struct StructuredLog {
+    dir: PathBuf,
+    format: RecordingFormat,
+    max_bytes: u64,
+    file: Option<File>,
+    file_index: usize,
+    file_bytes: u64,
+    sequence: u64,
+    started: Instant,
+}
+
+impl StructuredLog {
+    fn new(dir: PathBuf, format: RecordingFormat, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            format,
+            max_bytes,
+            file: None,
+            file_index: 0,
+            file_bytes: 0,
+            sequence: 0,
+            started: Instant::now(),
+        }
+    }
+
+    fn append(&mut self, entry: &Entry) -> std::io::Result<()> {
+        let record = match self.format {
+            RecordingFormat::Binary => entry.to_binary()?,
+            _ => entry.to_json_line()?,
+        };
+
+        if self.file.is_none() || (self.file_bytes > 0 && self.file_bytes + record.len() as u64 > self.max_bytes) {
+            self.rotate()?;
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(&record)?;
+            self.file_bytes += record.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let extension = match self.format {
+            RecordingFormat::Binary => "bin",
+            _ => "jsonl",
+        };
+        let path = self.dir.join(format!("recording-{:04}.{extension}", self.file_index));
+        self.file_index += 1;
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        self.file_bytes = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::messages::OutgoingMessages;
@@ -124,7 +396,7 @@ mod tests {
             message.push_field(&9000);
 
             let recorder = MessageRecorder::from_env();
-            recorder.record_request(&message);
+            recorder.record_request(&message, Correlation::None);
 
             let files = fs::read_dir(&recorder.recording_dir)
                 .unwrap()
@@ -179,7 +451,7 @@ mod tests {
 
             let recorder = MessageRecorder::from_env();
 
-            recorder.record_request(&request);
+            recorder.record_request(&request, Correlation::None);
             recorder.record_response(&response);
 
             let files = fs::read_dir(&recorder.recording_dir)
@@ -223,8 +495,125 @@ mod tests {
             let response = ResponseMessage::from_simple(MANAGED_ACCOUNT);
 
             // These should not panic or create any files
-            recorder.record_request(&request);
+            recorder.record_request(&request, Correlation::None);
             recorder.record_response(&response);
         });
     }
+
+    fn structured_recorder(dir: &TempDir, format: RecordingFormat, max_bytes: u64) -> MessageRecorder {
+        MessageRecorder::new(true, dir.path().to_str().unwrap().to_string())
+            .format(format, max_bytes)
+            .client_id(100)
+    }
+
+    fn recorded_files(dir: &TempDir) -> Vec<std::path::PathBuf> {
+        let mut files: Vec<_> = fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().path()).collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_recording_format_from_str() {
+        assert_eq!(RecordingFormat::from_str("").unwrap(), RecordingFormat::Files);
+        assert_eq!(RecordingFormat::from_str("files").unwrap(), RecordingFormat::Files);
+        assert_eq!(RecordingFormat::from_str("JSONL").unwrap(), RecordingFormat::JsonLines);
+        assert_eq!(RecordingFormat::from_str("binary").unwrap(), RecordingFormat::Binary);
+        assert!(RecordingFormat::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn test_json_lines_recording() {
+        let temp_dir = TempDir::new().unwrap();
+        let recorder = structured_recorder(&temp_dir, RecordingFormat::JsonLines, DEFAULT_MAX_BYTES);
+
+        let mut request = RequestMessage::new();
+        request.push_field(&OutgoingMessages::CancelAccountSummary);
+        request.push_field(&9000);
+        recorder.record_request(&request, Correlation::Request(9000));
+        recorder.record_response(&ResponseMessage::from_simple("4|2|9000|200|No security definition||"));
+
+        let files = recorded_files(&temp_dir);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("recording-0000.jsonl"));
+
+        let content = fs::read_to_string(&files[0]).unwrap();
+        let entries: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0]["sequence"], 0);
+        assert_eq!(entries[0]["direction"], "request");
+        assert_eq!(entries[0]["client_id"], 100);
+        assert_eq!(entries[0]["message_type"], "CancelAccountSummary");
+        assert_eq!(entries[0]["request_id"], 9000);
+        assert_eq!(entries[0]["order_id"], serde_json::Value::Null);
+        assert_eq!(entries[0]["fields"], serde_json::json!(["63", "9000"]));
+
+        assert_eq!(entries[1]["sequence"], 1);
+        assert_eq!(entries[1]["direction"], "response");
+        assert_eq!(entries[1]["message_type"], "Error");
+        assert_eq!(entries[1]["request_id"], 9000);
+        assert!(entries[1]["monotonic_ns"].as_u64().unwrap() >= entries[0]["monotonic_ns"].as_u64().unwrap());
+    }
+
+    #[test]
+    fn test_binary_recording() {
+        let temp_dir = TempDir::new().unwrap();
+        let recorder = structured_recorder(&temp_dir, RecordingFormat::Binary, DEFAULT_MAX_BYTES);
+
+        let mut request = RequestMessage::new();
+        request.push_field(&OutgoingMessages::CancelOrder);
+        request.push_field(&1);
+        request.push_field(&42);
+        recorder.record_request(&request, Correlation::Order(42));
+
+        let files = recorded_files(&temp_dir);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("recording-0000.bin"));
+
+        let content = fs::read(&files[0]).unwrap();
+        let length = u32::from_be_bytes(content[0..4].try_into().unwrap()) as usize;
+        assert_eq!(length, content.len() - 4);
+
+        let body = &content[4..];
+        assert_eq!(u64::from_be_bytes(body[0..8].try_into().unwrap()), 0); // sequence
+        assert_eq!(body[24], 0); // request
+        assert_eq!(i32::from_be_bytes(body[25..29].try_into().unwrap()), 100); // client id
+        assert_eq!(i32::from_be_bytes(body[29..33].try_into().unwrap()), 4); // message type
+        assert_eq!(i32::from_be_bytes(body[33..37].try_into().unwrap()), -1); // request id
+        assert_eq!(i32::from_be_bytes(body[37..41].try_into().unwrap()), 42); // order id
+        assert_eq!(&body[41..], b"4\x001\x0042\x00");
+    }
+
+    #[test]
+    fn test_structured_recording_rotates_by_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let recorder = structured_recorder(&temp_dir, RecordingFormat::JsonLines, 200);
+
+        for _ in 0..3 {
+            recorder.record_response(&ResponseMessage::from_simple(MANAGED_ACCOUNT));
+        }
+
+        let files = recorded_files(&temp_dir);
+        assert_eq!(files.len(), 3);
+        for file in files {
+            assert_eq!(fs::read_to_string(file).unwrap().lines().count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_recording_failures_do_not_panic() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing").to_str().unwrap().to_string();
+
+        let request = RequestMessage::new();
+        let response = ResponseMessage::from_simple(MANAGED_ACCOUNT);
+
+        let recorder = MessageRecorder::new(true, missing.clone());
+        recorder.record_request(&request, Correlation::None);
+        recorder.record_response(&response);
+
+        let recorder = MessageRecorder::new(true, missing).format(RecordingFormat::JsonLines, DEFAULT_MAX_BYTES);
+        recorder.record_request(&request, Correlation::None);
+        recorder.record_response(&response);
+    }
 }
@@ -0,0 +1,508 @@
+//! Replays a session captured by the [MessageRecorder](super::recorder::MessageRecorder).
+//!
+//! The recorder writes numbered `NNNN-request.msg` and `NNNN-response.msg` files, or `recording-NNNN.jsonl` and
+//! `recording-NNNN.bin` logs when `IBAPI_RECORDING_FORMAT` selects a structured format. [ReplaySocket] serves the
+//! recorded responses to the normal connection and dispatcher, releasing the responses that followed each
+//! recorded request when the client sends the matching request.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use log::{debug, warn};
+use serde::Deserialize;
+
+use super::sync::{Io, Reconnect, Stream};
+use crate::errors::Error;
+use crate::messages::OutgoingMessages;
+
+const REPLAY_READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How requests sent by the client are paired with recorded requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplayMatching {
+    /// Each request releases the responses of the next recorded request, whatever its content.
+    #[default]
+    Sequence,
+    /// Each request releases the responses of the first unused recorded request with the same fields,
+    /// falling back to the first unused recorded request of the same message type.
+    Content,
+}
+
+/// Options for [Client::replay](crate::client::sync::Client::replay).
+///
+/// Replay is only supported by the blocking client. The async client connects to TWS or IB Gateway.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayOptions {
+    matching: ReplayMatching,
+    preserve_timing: bool,
+}
+
+impl ReplayOptions {
+    /// Sets how client requests are matched with recorded requests. Defaults to [ReplayMatching::Sequence].
+    pub fn matching(mut self, matching: ReplayMatching) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Delays each response by the gap observed when it was recorded, based on the recorded files' modification times.
+    pub fn preserve_timing(mut self, preserve_timing: bool) -> Self {
+        self.preserve_timing = preserve_timing;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Direction {
+    Request,
+    Response,
+}
+
+#[derive(Debug, Clone)]
+struct RecordedMessage {
+    direction: Direction,
+    fields: Vec<String>,
+    recorded_at: Option<SystemTime>,
+}
+
+// A recorded request and the responses received before the next request.
+#[derive(Debug)]
+struct Block {
+    request: Vec<String>,
+    responses: Vec<(Vec<String>, Duration)>,
+    used: bool,
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    pending: VecDeque<(Vec<String>, Duration)>,
+    blocks: Vec<Block>,
+    next_block: usize,
+}
+
+/// A [Stream] that serves a recorded session.
+#[derive(Debug)]
+pub(crate) struct ReplaySocket {
+    state: Mutex<ReplayState>,
+    available: Condvar,
+    options: ReplayOptions,
+    client_id: i32,
+}
+
+impl ReplaySocket {
+    /// Loads the recording in `dir`.
+    pub(crate) fn open(dir: impl AsRef<Path>, options: ReplayOptions) -> Result<Self, Error> {
+        let messages = load_recording(dir.as_ref())?;
+        if messages.is_empty() {
+            return Err(Error::Simple(format!("no recorded messages found in {}", dir.as_ref().display())));
+        }
+        Ok(Self::new(messages, options))
+    }
+
+    fn new(messages: Vec<RecordedMessage>, options: ReplayOptions) -> Self {
+        let mut state = ReplayState::default();
+        let mut previous_at = None;
+
+        for message in messages {
+            let delay = match (previous_at, message.recorded_at) {
+                (Some(previous), Some(current)) => current.duration_since(previous).unwrap_or_default(),
+                _ => Duration::ZERO,
+            };
+            previous_at = message.recorded_at.or(previous_at);
+
+            match message.direction {
+                Direction::Request => state.blocks.push(Block {
+                    request: message.fields,
+                    responses: Vec::new(),
+                    used: false,
+                }),
+                Direction::Response => match state.blocks.last_mut() {
+                    Some(block) => block.responses.push((message.fields, delay)),
+                    // responses before the first request (handshake acknowledgement) are available immediately
+                    None => state.pending.push_back((message.fields, delay)),
+                },
+            }
+        }
+
+        let client_id = state
+            .blocks
+            .iter()
+            .find(|block| block.request.first().map(String::as_str) == Some(&OutgoingMessages::StartApi.to_string()))
+            .and_then(|block| block.request.get(2))
+            .and_then(|client_id| client_id.parse().ok())
+            .unwrap_or_default();
+
+        Self {
+            state: Mutex::new(state),
+            available: Condvar::new(),
+            options,
+            client_id,
+        }
+    }
+
+    /// The client id used when the session was recorded.
+    pub(crate) fn client_id(&self) -> i32 {
+        self.client_id
+    }
+
+    fn release(&self, request: &[String]) -> Result<(), Error> {
+        let mut state = self.state.lock()?;
+
+        let index = match self.options.matching {
+            ReplayMatching::Sequence => {
+                let index = state.next_block;
+                if index < state.blocks.len() {
+                    state.next_block += 1;
+                    if state.blocks[index].request != request {
+                        debug!("replayed request {request:?} differs from recorded {:?}", state.blocks[index].request);
+                    }
+                    Some(index)
+                } else {
+                    None
+                }
+            }
+            ReplayMatching::Content => {
+                let unused = || state.blocks.iter().enumerate().filter(|(_, block)| !block.used);
+                unused()
+                    .find(|(_, block)| block.request == request)
+                    .or_else(|| unused().find(|(_, block)| block.request.first() == request.first()))
+                    .map(|(index, _)| index)
+            }
+        };
+
+        let Some(index) = index else {
+            warn!("no recorded request matches {request:?}");
+            return Ok(());
+        };
+
+        let block = &mut state.blocks[index];
+        block.used = true;
+        let responses = block.responses.clone();
+        state.pending.extend(responses);
+        self.available.notify_all();
+
+        Ok(())
+    }
+}
+
+impl Io for ReplaySocket {
+    fn read_message(&self) -> Result<Vec<u8>, Error> {
+        let state = self.state.lock()?;
+        let (mut state, _) = self
+            .available
+            .wait_timeout_while(state, REPLAY_READ_TIMEOUT, |state| state.pending.is_empty())?;
+
+        let Some((fields, delay)) = state.pending.pop_front() else {
+            return Err(std::io::Error::from(std::io::ErrorKind::WouldBlock).into());
+        };
+        drop(state);
+
+        if self.options.preserve_timing && !delay.is_zero() {
+            thread::sleep(delay);
+        }
+
+        let mut data = fields.join("\0");
+        data.push('\0');
+        Ok(data.into_bytes())
+    }
+
+    fn write_all(&self, buf: &[u8]) -> Result<(), Error> {
+        // The handshake is not recorded; its acknowledgement is served from the start of the recording.
+        if buf.starts_with(b"API\0") || buf.len() < 4 {
+            return Ok(());
+        }
+
+        let message = String::from_utf8(buf[4..].to_vec())?;
+        let fields: Vec<String> = message.split_terminator('\0').map(|field| field.to_string()).collect();
+        self.release(&fields)
+    }
+}
+
+impl Reconnect for ReplaySocket {
+    fn reconnect(&self) -> Result<(), Error> {
+        Err(Error::Simple("a replayed session cannot reconnect".into()))
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+
+    fn endpoint(&self) -> String {
+        "replay".into()
+    }
+}
+
+impl Stream for ReplaySocket {}
+
+// Reads a recording in sequence order: NNNN-request.msg and NNNN-response.msg files, or the structured
+// recording-NNNN.jsonl and recording-NNNN.bin logs.
+fn load_recording(dir: &Path) -> Result<Vec<RecordedMessage>, Error> {
+    let mut messages = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+
+        if file_name.starts_with("recording-") && file_name.ends_with(".jsonl") {
+            messages.extend(read_json_lines(&entry.path())?);
+            continue;
+        }
+        if file_name.starts_with("recording-") && file_name.ends_with(".bin") {
+            messages.extend(read_binary(&entry.path())?);
+            continue;
+        }
+
+        let (sequence, direction) = if let Some(sequence) = file_name.strip_suffix("-request.msg") {
+            (sequence, Direction::Request)
+        } else if let Some(sequence) = file_name.strip_suffix("-response.msg") {
+            (sequence, Direction::Response)
+        } else {
+            continue;
+        };
+        let Ok(sequence) = sequence.parse::<u64>() else {
+            continue;
+        };
+
+        let content = fs::read_to_string(entry.path())?;
+        let recorded_at = entry.metadata().and_then(|metadata| metadata.modified()).ok();
+
+        messages.push((
+            sequence,
+            RecordedMessage {
+                direction,
+                fields: content.split_terminator('|').map(|field| field.to_string()).collect(),
+                recorded_at,
+            },
+        ));
+    }
+
+    messages.sort_by_key(|(sequence, _)| *sequence);
+    Ok(messages.into_iter().map(|(_, message)| message).collect())
+}
+
+// The fields of a JSON-lines entry needed for replay.
+#[derive(Deserialize)]
+struct JsonEntry {
+    sequence: u64,
+    unix_ns: u64,
+    direction: Direction,
+    fields: Vec<String>,
+}
+
+fn read_json_lines(path: &Path) -> Result<Vec<(u64, RecordedMessage)>, Error> {
+    let content = fs::read_to_string(path)?;
+    let mut messages = Vec::new();
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: JsonEntry = serde_json::from_str(line).map_err(|err| Error::Simple(format!("invalid entry in {}: {err}", path.display())))?;
+        messages.push((
+            entry.sequence,
+            RecordedMessage {
+                direction: entry.direction,
+                fields: entry.fields,
+                recorded_at: Some(UNIX_EPOCH + Duration::from_nanos(entry.unix_ns)),
+            },
+        ));
+    }
+
+    Ok(messages)
+}
+
+// Header of a binary record after the length: sequence, monotonic and unix times, direction, client id,
+// message type, request id and order id.
+const BINARY_HEADER_LEN: usize = 8 + 8 + 8 + 1 + 4 * 4;
+
+fn read_binary(path: &Path) -> Result<Vec<(u64, RecordedMessage)>, Error> {
+    let content = fs::read(path)?;
+    let mut reader = content.as_slice();
+    let mut messages = Vec::new();
+
+    while !reader.is_empty() {
+        let length = reader.read_u32::<BigEndian>()? as usize;
+        if length < BINARY_HEADER_LEN || length > reader.len() {
+            return Err(Error::Simple(format!("truncated record in {}", path.display())));
+        }
+        let (mut body, rest) = reader.split_at(length);
+        reader = rest;
+
+        let sequence = body.read_u64::<BigEndian>()?;
+        let _monotonic_ns = body.read_u64::<BigEndian>()?;
+        let unix_ns = body.read_u64::<BigEndian>()?;
+        let direction = match body.read_u8()? {
+            0 => Direction::Request,
+            _ => Direction::Response,
+        };
+        // client id, message type, request id and order id are not needed to replay
+        let fields = String::from_utf8(body[16..].to_vec())?;
+
+        messages.push((
+            sequence,
+            RecordedMessage {
+                direction,
+                fields: fields.split_terminator('\0').map(|field| field.to_string()).collect(),
+                recorded_at: Some(UNIX_EPOCH + Duration::from_nanos(unix_ns)),
+            },
+        ));
+    }
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{encode_length, RequestMessage, ResponseMessage};
+    use crate::transport::recorder::{Correlation, MessageRecorder, RecordingFormat};
+    use tempfile::TempDir;
+
+    fn message(direction: Direction, fields: &str) -> RecordedMessage {
+        RecordedMessage {
+            direction,
+            fields: fields.split_terminator('|').map(|field| field.to_string()).collect(),
+            recorded_at: None,
+        }
+    }
+
+    fn read(socket: &ReplaySocket) -> String {
+        String::from_utf8(socket.read_message().unwrap()).unwrap().replace('\0', "|")
+    }
+
+    fn write(socket: &ReplaySocket, request: &str) {
+        socket.write_all(&encode_length(&request.replace('|', "\0"))).unwrap();
+    }
+
+    fn session() -> Vec<RecordedMessage> {
+        vec![
+            message(Direction::Response, "173|20230405 22:20:39 PST|"),
+            message(Direction::Request, "71|2|100||"),
+            message(Direction::Response, "9|1|90|"),
+            message(Direction::Request, "49|1|"),
+            message(Direction::Response, "49|1|1752606307|"),
+            message(Direction::Request, "17|1|"),
+            message(Direction::Response, "15|1|DU1234567|"),
+        ]
+    }
+
+    #[test]
+    fn test_sequence_matching() {
+        let socket = ReplaySocket::new(session(), ReplayOptions::default());
+        assert_eq!(socket.client_id(), 100);
+
+        socket.write_all(b"API\0").unwrap();
+        assert_eq!(read(&socket), "173|20230405 22:20:39 PST|");
+
+        write(&socket, "71|2|100||");
+        assert_eq!(read(&socket), "9|1|90|");
+
+        // sequence matching ignores content
+        write(&socket, "17|1|");
+        assert_eq!(read(&socket), "49|1|1752606307|");
+
+        assert!(socket.read_message().is_err());
+    }
+
+    #[test]
+    fn test_content_matching() {
+        let socket = ReplaySocket::new(session(), ReplayOptions::default().matching(ReplayMatching::Content));
+        assert_eq!(read(&socket), "173|20230405 22:20:39 PST|");
+
+        write(&socket, "17|1|");
+        assert_eq!(read(&socket), "15|1|DU1234567|");
+
+        write(&socket, "49|1|");
+        assert_eq!(read(&socket), "49|1|1752606307|");
+
+        write(&socket, "49|1|");
+        assert!(socket.read_message().is_err());
+    }
+
+    #[test]
+    fn test_load_recording_orders_by_sequence() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("0002-response.msg"), "9|1|90|").unwrap();
+        fs::write(dir.path().join("0000-response.msg"), "173|20230405 22:20:39 PST|").unwrap();
+        fs::write(dir.path().join("0001-request.msg"), "71|2|100||").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let messages = load_recording(dir.path()).unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].direction, Direction::Response);
+        assert_eq!(messages[0].fields, vec!["173", "20230405 22:20:39 PST"]);
+        assert_eq!(messages[1].direction, Direction::Request);
+        assert_eq!(messages[1].fields, vec!["71", "2", "100", ""]);
+        assert_eq!(messages[2].fields, vec!["9", "1", "90"]);
+    }
+
+    fn record_session(dir: &TempDir, format: RecordingFormat, max_bytes: u64) {
+        let recorder = MessageRecorder::new(true, dir.path().to_str().unwrap().to_string()).format(format, max_bytes);
+        for message in session() {
+            match message.direction {
+                Direction::Request => recorder.record_request(&RequestMessage::from_fields(&message.fields), Correlation::None),
+                Direction::Response => recorder.record_response(&ResponseMessage::from(&(message.fields.join("\0") + "\0"))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_structured_recordings() {
+        for format in [RecordingFormat::JsonLines, RecordingFormat::Binary] {
+            let dir = TempDir::new().unwrap();
+            // a small limit rotates the log, so messages are spread over several files
+            record_session(&dir, format, 64);
+            assert!(fs::read_dir(dir.path()).unwrap().count() > 1, "{format:?} log not rotated");
+
+            let messages = load_recording(dir.path()).unwrap();
+
+            let expected = session();
+            assert_eq!(messages.len(), expected.len(), "{format:?}");
+            for (message, expected) in messages.iter().zip(&expected) {
+                assert_eq!(message.direction, expected.direction, "{format:?}");
+                assert_eq!(message.fields, expected.fields, "{format:?}");
+                assert!(message.recorded_at.is_some(), "{format:?}");
+            }
+
+            let socket = ReplaySocket::open(dir.path(), ReplayOptions::default()).unwrap();
+            assert_eq!(socket.client_id(), 100);
+        }
+    }
+
+    #[test]
+    fn test_load_truncated_binary_recording() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("recording-0000.bin"), [0, 0, 0, 64, 1, 2]).unwrap();
+        assert!(load_recording(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_open_empty_recording() {
+        let dir = TempDir::new().unwrap();
+        assert!(ReplaySocket::open(dir.path(), ReplayOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_preserve_timing() {
+        let start = SystemTime::now();
+        let mut messages = session();
+        messages[3].recorded_at = Some(start);
+        messages[4].recorded_at = Some(start + Duration::from_millis(200));
+
+        let socket = ReplaySocket::new(messages, ReplayOptions::default().preserve_timing(true));
+        read(&socket);
+        write(&socket, "71|2|100||");
+        read(&socket);
+        write(&socket, "49|1|");
+
+        let began = std::time::Instant::now();
+        assert_eq!(read(&socket), "49|1|1752606307|");
+        assert!(began.elapsed() >= Duration::from_millis(200));
+    }
+}
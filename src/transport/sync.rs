@@ -15,11 +15,18 @@ use byteorder::{BigEndian, ReadBytesExt};
 use crossbeam::channel::{self, Receiver, Sender};
 use log::{debug, error, info, warn};
 
+use crate::client::id_generator::ClientIdManager;
 use crate::connection::sync::Connection;
 
+use super::buffer::{self, BufferConfigs, BufferedSender};
+use super::failover::{ConnectionEvents, Endpoints};
+use super::recorder::Correlation;
 use super::routing::{determine_routing, is_warning_error, RoutingDecision, UNSPECIFIED_REQUEST_ID};
 use super::{InternalSubscription, MessageBus, Response, Signal, SubscriptionBuilder};
 use crate::messages::{shared_channel_configuration, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::metrics::Metrics;
+use crate::subscriptions::BufferConfig;
+use crate::trace::spans::{enter_worker, RequestSpans};
 use crate::{server_versions, Error};
 
 // pub(crate) const MIN_SERVER_VERSION: i32 = 100;
@@ -113,14 +120,17 @@ impl SharedChannels {
 pub struct TcpMessageBus<S: Stream> {
     connection: Connection<S>,
     handles: Mutex<Vec<JoinHandle<()>>>,
-    requests: SenderHash<i32, Response>,
-    orders: SenderHash<i32, Response>,
-    executions: SenderHash<String, Response>,
+    requests: SenderHash<i32>,
+    orders: SenderHash<i32>,
+    executions: SenderHash<String>,
     shared_channels: SharedChannels,
     signals_send: Sender<Signal>,
     signals_recv: Receiver<Signal>,
     shutdown_requested: AtomicBool,
     order_update_stream: Mutex<Option<Sender<Response>>>, // Optional receiver for order updates
+    spans: RequestSpans,
+    buffers: BufferConfigs,
+    order_ids: Mutex<Option<Arc<ClientIdManager>>>, // Resynchronized after reconnecting
 }
 
 impl<S: Stream> TcpMessageBus<S> {
@@ -138,6 +148,9 @@ impl<S: Stream> TcpMessageBus<S> {
             signals_recv,
             shutdown_requested: AtomicBool::new(false),
             order_update_stream: Mutex::new(None),
+            spans: RequestSpans::new(),
+            buffers: BufferConfigs::new(),
+            order_ids: Mutex::new(None),
         })
     }
 
@@ -155,6 +168,8 @@ impl<S: Stream> TcpMessageBus<S> {
         self.requests.clear();
         self.orders.clear();
         self.executions.clear();
+        self.spans.clear();
+        self.record_active_subscriptions();
 
         self.shutdown_requested.store(true, Ordering::Relaxed);
     }
@@ -169,15 +184,21 @@ impl<S: Stream> TcpMessageBus<S> {
         self.requests.clear();
         self.orders.clear();
         self.executions.clear();
+        self.spans.clear();
+        self.record_active_subscriptions();
     }
 
     fn clean_request(&self, request_id: i32) {
         self.requests.remove(&request_id);
+        self.spans.close(Correlation::Request(request_id));
+        self.record_active_subscriptions();
         debug!("released request_id {}, requests.len()={}", request_id, self.requests.len());
     }
 
     fn clean_order(&self, order_id: i32) {
         self.orders.remove(&order_id);
+        self.spans.close(Correlation::Order(order_id));
+        self.record_active_subscriptions();
         debug!("released order_id {}, orders.len()={}", order_id, self.orders.len());
     }
 
@@ -196,6 +217,33 @@ impl<S: Stream> TcpMessageBus<S> {
     fn read_message(&self) -> Response {
         self.connection.read_message()
     }
+
+    // Continues the order id sequence from the next valid id received when reconnecting.
+    fn sync_order_ids(&self) {
+        if let Ok(order_ids) = self.order_ids.lock() {
+            if let Some(order_ids) = order_ids.as_ref() {
+                order_ids.set_order_id(self.connection.connection_metadata().next_order_id);
+            }
+        }
+    }
+
+    fn record_active_subscriptions(&self) {
+        let metrics = &self.connection.metrics;
+        if metrics.is_enabled() {
+            metrics.active_subscriptions("request", self.requests.len());
+            metrics.active_subscriptions("order", self.orders.len());
+        }
+    }
+
+    // Records how many messages wait in the channel that just received a message.
+    fn record_backlog<K: std::hash::Hash + Eq + std::fmt::Debug>(&self, senders: &SenderHash<K>, id: &K, message_type: IncomingMessages) {
+        let metrics = &self.connection.metrics;
+        if metrics.is_enabled() {
+            if let Some(queued) = senders.queued(id) {
+                metrics.backlog(message_type, queued);
+            }
+        }
+    }
     pub(crate) fn dispatch(&self, server_version: i32) -> Result<(), Error> {
         use crate::client::error_handler::{is_connection_error, is_timeout_error};
 
@@ -213,14 +261,18 @@ impl<S: Stream> TcpMessageBus<S> {
             }
             Err(ref err) if is_connection_error(err) => {
                 error!("error reading next message (will attempt reconnect): {err:?}");
+                self.connection.metrics.connection_reset();
 
                 if let Err(reconnect_err) = self.connection.reconnect() {
                     error!("failed to reconnect to TWS/Gateway: {reconnect_err:?}");
+                    self.connection.metrics.reconnected(false);
                     self.request_shutdown();
                     return Err(Error::ConnectionFailed);
                 }
 
                 info!("successfully reconnected to TWS/Gateway");
+                self.connection.metrics.reconnected(true);
+                self.sync_order_ids();
                 self.reset();
                 Ok(())
             }
@@ -237,6 +289,7 @@ impl<S: Stream> TcpMessageBus<S> {
     fn start_dispatcher_thread(self: &Arc<Self>, server_version: i32) -> JoinHandle<()> {
         let message_bus = Arc::clone(self);
         thread::spawn(move || {
+            let _span = enter_worker("dispatcher");
             loop {
                 match message_bus.dispatch(server_version) {
                     Ok(_) => {}
@@ -252,6 +305,9 @@ impl<S: Stream> TcpMessageBus<S> {
     }
 
     fn dispatch_message(&self, server_version: i32, message: ResponseMessage) {
+        self.spans.response(&message);
+        self.connection.metrics.message_received(message.message_type());
+
         // Use common routing logic
         match determine_routing(&message) {
             RoutingDecision::Error { request_id, error_code } => {
@@ -275,14 +331,18 @@ impl<S: Stream> TcpMessageBus<S> {
 
     fn process_response(&self, message: ResponseMessage) {
         let request_id = message.request_id().unwrap_or(-1); // pass in request id?
+        let message_type = message.message_type();
         if self.requests.contains(&request_id) {
             self.requests.send(&request_id, Ok(message)).unwrap();
+            self.record_backlog(&self.requests, &request_id, message_type);
         } else if self.orders.contains(&request_id) {
             self.orders.send(&request_id, Ok(message)).unwrap();
-        } else if self.shared_channels.contains_sender(message.message_type()) {
-            self.shared_channels.send_message(message.message_type(), &message);
+            self.record_backlog(&self.orders, &request_id, message_type);
+        } else if self.shared_channels.contains_sender(message_type) {
+            self.shared_channels.send_message(message_type, &message);
         } else {
-            info!("no recipient found for: {message:?}")
+            info!("no recipient found for: {message:?}");
+            self.connection.metrics.response_orphaned(message_type);
         }
     }
 
@@ -319,6 +379,7 @@ impl<S: Stream> TcpMessageBus<S> {
                     _ => {
                         if !sent_to_update_stream {
                             warn!("could not route message {message:?}");
+                            self.connection.metrics.response_orphaned(message.message_type());
                         }
                     }
                 }
@@ -338,6 +399,7 @@ impl<S: Stream> TcpMessageBus<S> {
                     }
                     _ => {
                         warn!("could not route message {message:?}");
+                        self.connection.metrics.response_orphaned(message.message_type());
                     }
                 }
             }
@@ -353,9 +415,11 @@ impl<S: Stream> TcpMessageBus<S> {
                         self.shared_channels.send_message(message.message_type(), &message);
                     } else if !sent_to_update_stream {
                         warn!("could not route message {message:?}");
+                        self.connection.metrics.response_orphaned(message.message_type());
                     }
                 } else if !sent_to_update_stream {
                     warn!("could not route message {message:?}");
+                    self.connection.metrics.response_orphaned(message.message_type());
                 }
             }
             IncomingMessages::CompletedOrder | IncomingMessages::OpenOrderEnd | IncomingMessages::CompletedOrdersEnd => {
@@ -370,6 +434,7 @@ impl<S: Stream> TcpMessageBus<S> {
                     }
                 } else if !sent_to_update_stream {
                     warn!("could not route commission report {message:?}");
+                    self.connection.metrics.response_orphaned(message.message_type());
                 }
             }
             _ => (),
@@ -397,6 +462,7 @@ impl<S: Stream> TcpMessageBus<S> {
         let message_bus = Arc::clone(self);
 
         thread::spawn(move || {
+            let _span = enter_worker("cleanup");
             let signal_recv = message_bus.signals_recv.clone();
 
             loop {
@@ -450,48 +516,57 @@ impl<S: Stream> TcpMessageBus<S> {
 
 impl<S: Stream> MessageBus for TcpMessageBus<S> {
     fn send_request(&self, request_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
-        let (sender, receiver) = channel::unbounded();
+        let (sender, receiver, dropped) = buffer::channel(self.buffers.for_request(message));
         let sender_copy = sender.clone();
 
         self.requests.insert(request_id, sender);
+        self.spans.open(message, Correlation::Request(request_id));
+        self.record_active_subscriptions();
 
-        self.connection.write_message(message)?;
+        self.connection.write_correlated_message(message, Correlation::Request(request_id))?;
 
         let subscription = SubscriptionBuilder::new()
             .receiver(receiver)
             .sender(sender_copy)
             .signaler(self.signals_send.clone())
             .request_id(request_id)
+            .dropped(dropped)
             .build();
 
         Ok(subscription)
     }
 
     fn cancel_subscription(&self, request_id: i32, message: &RequestMessage) -> Result<(), Error> {
-        self.connection.write_message(message)?;
+        self.connection.write_correlated_message(message, Correlation::Request(request_id))?;
 
         if let Err(e) = self.requests.send(&request_id, Err(Error::Cancelled)) {
             info!("error sending cancel notification: {e}");
         }
 
         self.requests.remove(&request_id);
+        self.spans.close(Correlation::Request(request_id));
+        self.connection.metrics.subscription_cancelled(message);
+        self.record_active_subscriptions();
 
         Ok(())
     }
 
     fn send_order_request(&self, order_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
-        let (sender, receiver) = channel::unbounded();
+        let (sender, receiver, dropped) = buffer::channel(self.buffers.for_request(message));
         let sender_copy = sender.clone();
 
         self.orders.insert(order_id, sender);
+        self.spans.open(message, Correlation::Order(order_id));
+        self.record_active_subscriptions();
 
-        self.connection.write_message(message)?;
+        self.connection.write_correlated_message(message, Correlation::Order(order_id))?;
 
         let subscription = SubscriptionBuilder::new()
             .receiver(receiver)
             .sender(sender_copy)
             .signaler(self.signals_send.clone())
             .order_id(order_id)
+            .dropped(dropped)
             .build();
 
         Ok(subscription)
@@ -519,13 +594,16 @@ impl<S: Stream> MessageBus for TcpMessageBus<S> {
     }
 
     fn cancel_order_subscription(&self, request_id: i32, message: &RequestMessage) -> Result<(), Error> {
-        self.connection.write_message(message)?;
+        self.connection.write_correlated_message(message, Correlation::Order(request_id))?;
 
         if let Err(e) = self.orders.send(&request_id, Err(Error::Cancelled)) {
             info!("error sending cancel notification: {e}");
         }
 
         self.orders.remove(&request_id);
+        self.spans.close(Correlation::Order(request_id));
+        self.connection.metrics.subscription_cancelled(message);
+        self.record_active_subscriptions();
 
         Ok(())
     }
@@ -545,6 +623,7 @@ impl<S: Stream> MessageBus for TcpMessageBus<S> {
 
     fn cancel_shared_subscription(&self, _message_type: OutgoingMessages, message: &RequestMessage) -> Result<(), Error> {
         self.connection.write_message(message)?;
+        self.connection.metrics.subscription_cancelled(message);
         // TODO send cancel
         Ok(())
     }
@@ -553,6 +632,39 @@ impl<S: Stream> MessageBus for TcpMessageBus<S> {
         self.request_shutdown();
         self.join();
     }
+
+    fn set_buffer_config(&self, message_type: OutgoingMessages, config: BufferConfig) {
+        self.buffers.set(message_type, config);
+    }
+
+    fn set_default_buffer_config(&self, config: BufferConfig) {
+        self.buffers.set_default(config);
+    }
+
+    fn set_metrics(&self, metrics: Arc<dyn Metrics>) {
+        self.connection.metrics.install(metrics);
+    }
+
+    fn force_reconnect(&self) {
+        // The dispatcher sees the closed socket as a lost connection and reconnects.
+        if let Err(e) = self.connection.socket.disconnect() {
+            warn!("error closing connection: {e}");
+        }
+    }
+
+    fn connection_events(&self) -> ConnectionEvents {
+        self.connection.events.subscribe()
+    }
+
+    fn active_endpoint(&self) -> Option<String> {
+        Some(self.connection.socket.endpoint())
+    }
+
+    fn track_order_ids(&self, order_ids: Arc<ClientIdManager>) {
+        if let Ok(mut tracked) = self.order_ids.lock() {
+            *tracked = Some(order_ids);
+        }
+    }
 }
 
 fn error_event(server_version: i32, mut packet: ResponseMessage) -> Result<(), Error> {
@@ -589,21 +701,25 @@ fn error_event(server_version: i32, mut packet: ResponseMessage) -> Result<(), E
 }
 
 #[derive(Debug)]
-struct SenderHash<K, V> {
-    senders: RwLock<HashMap<K, Sender<V>>>,
+struct SenderHash<K> {
+    senders: RwLock<HashMap<K, BufferedSender>>,
 }
 
-impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> SenderHash<K, V> {
+impl<K: std::hash::Hash + Eq + std::fmt::Debug> SenderHash<K> {
     pub fn new() -> Self {
         Self {
             senders: RwLock::new(HashMap::new()),
         }
     }
 
-    pub fn send(&self, id: &K, message: V) -> Result<(), Error> {
-        let senders = self.senders.read().unwrap();
-        debug!("senders: {senders:?}");
-        if let Some(sender) = senders.get(id) {
+    pub fn send(&self, id: &K, message: Response) -> Result<(), Error> {
+        // Sending may block on a full buffer, so the lock is released first.
+        let sender = {
+            let senders = self.senders.read().unwrap();
+            debug!("senders: {senders:?}");
+            senders.get(id).cloned()
+        };
+        if let Some(sender) = sender {
             if let Err(err) = sender.send(message) {
                 warn!("error sending: {id:?}, {err}")
             }
@@ -613,21 +729,27 @@ impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> Send
         Ok(())
     }
 
-    pub fn copy_sender(&self, id: K) -> Option<Sender<V>> {
+    pub fn copy_sender(&self, id: K) -> Option<BufferedSender> {
         let senders = self.senders.read().unwrap();
         senders.get(&id).cloned()
     }
 
-    pub fn insert(&self, id: K, message: Sender<V>) -> Option<Sender<V>> {
+    pub fn insert(&self, id: K, message: BufferedSender) -> Option<BufferedSender> {
         let mut senders = self.senders.write().unwrap();
         senders.insert(id, message)
     }
 
-    pub fn remove(&self, id: &K) -> Option<Sender<V>> {
+    pub fn remove(&self, id: &K) -> Option<BufferedSender> {
         let mut senders = self.senders.write().unwrap();
         senders.remove(id)
     }
 
+    // Number of messages waiting in the channel of the given id.
+    pub fn queued(&self, id: &K) -> Option<usize> {
+        let senders = self.senders.read().unwrap();
+        senders.get(id).map(|sender| sender.len())
+    }
+
     pub fn contains(&self, id: &K) -> bool {
         let senders = self.senders.read().unwrap();
         senders.contains_key(id)
@@ -643,7 +765,7 @@ impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> Send
         senders.clear();
     }
 
-    pub fn notify_all(&self, message: &V) {
+    pub fn notify_all(&self, message: &Response) {
         let senders = self.senders.read().unwrap();
         for sender in senders.values() {
             if let Err(e) = sender.send(message.clone()) {
@@ -657,10 +779,15 @@ impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> Send
 pub(crate) struct TcpSocket {
     reader: Mutex<TcpStream>,
     writer: Mutex<TcpStream>,
-    connection_url: String,
+    endpoints: Endpoints,
 }
 impl TcpSocket {
     pub fn new(stream: TcpStream, connection_url: &str) -> Result<Self, Error> {
+        Self::with_endpoints(stream, Endpoints::new(&[connection_url], 0)?)
+    }
+
+    /// Creates a socket that can fail over to the other endpoints. `stream` is connected to the active endpoint.
+    pub(crate) fn with_endpoints(stream: TcpStream, endpoints: Endpoints) -> Result<Self, Error> {
         let writer = stream.try_clone()?;
 
         stream.set_read_timeout(Some(TWS_READ_TIMEOUT))?;
@@ -668,14 +795,14 @@ impl TcpSocket {
         Ok(Self {
             reader: Mutex::new(stream),
             writer: Mutex::new(writer),
-            connection_url: connection_url.to_string(),
+            endpoints,
         })
     }
 }
 
 impl Reconnect for TcpSocket {
     fn reconnect(&self) -> Result<(), Error> {
-        match TcpStream::connect(&self.connection_url) {
+        match TcpStream::connect(self.endpoints.active()) {
             Ok(stream) => {
                 stream.set_read_timeout(Some(TWS_READ_TIMEOUT))?;
 
@@ -693,11 +820,37 @@ impl Reconnect for TcpSocket {
     fn sleep(&self, duration: std::time::Duration) {
         thread::sleep(duration)
     }
+    fn disconnect(&self) -> Result<(), Error> {
+        let writer = self.writer.lock()?;
+        writer.shutdown(std::net::Shutdown::Both)?;
+        Ok(())
+    }
+    fn endpoint(&self) -> String {
+        self.endpoints.active().to_string()
+    }
+    fn endpoint_count(&self) -> usize {
+        self.endpoints.len()
+    }
+    fn next_endpoint(&self) {
+        self.endpoints.advance();
+    }
 }
 
 pub(crate) trait Reconnect {
     fn reconnect(&self) -> Result<(), Error>;
     fn sleep(&self, duration: std::time::Duration);
+    // Closes the connection, failing pending reads.
+    fn disconnect(&self) -> Result<(), Error> {
+        Ok(())
+    }
+    // Address of the endpoint reconnect() connects to.
+    fn endpoint(&self) -> String;
+    // Number of endpoints to try before giving up.
+    fn endpoint_count(&self) -> usize {
+        1
+    }
+    // Makes the next endpoint the one reconnect() connects to.
+    fn next_endpoint(&self) {}
 }
 
 pub(crate) trait Stream: Io + Reconnect + Sync + Send + 'static + std::fmt::Debug {}
@@ -769,11 +922,13 @@ mod tests {
     use super::*;
     use crate::connection::sync::Connection;
     use crate::tests::assert_send_and_sync;
+    use crate::transport::failover::ConnectionEvent;
 
     // Additional imports for connection tests
-    use crate::client::Client;
+    use crate::client::sync::Client;
     use crate::contracts::Contract;
     use crate::messages::{encode_length, OutgoingMessages, RequestMessage};
+    use crate::metrics::Label;
     use crate::orders::common::encoders::encode_place_order;
     use crate::orders::{order_builder, Action};
     use log::{debug, trace};
@@ -867,6 +1022,7 @@ mod tests {
         exchanges: Vec<Exchange>,
         expected_retries: usize,
         reconnect_call_count: AtomicUsize,
+        endpoints: Endpoints,
 
         // Accessed from reader thread
         // Mutated by reader thread
@@ -889,11 +1045,17 @@ mod tests {
                 expected_retries,
                 keep_alive: AtomicBool::new(false),
                 reconnect_call_count: AtomicUsize::new(0),
+                endpoints: Endpoints::new(&["mock"], 0).unwrap(),
                 write_call_count: AtomicUsize::new(0),
                 responses_len: AtomicUsize::new(0),
                 read_call_count: AtomicUsize::new(0),
             }
         }
+
+        fn with_endpoints(mut self, addresses: &[&str]) -> Self {
+            self.endpoints = Endpoints::new(addresses, 0).unwrap();
+            self
+        }
     }
 
     impl Reconnect for MockSocket {
@@ -908,6 +1070,15 @@ mod tests {
             Err(mock_socket_error(ErrorKind::ConnectionRefused))
         }
         fn sleep(&self, _duration: std::time::Duration) {}
+        fn endpoint(&self) -> String {
+            self.endpoints.active().to_string()
+        }
+        fn endpoint_count(&self) -> usize {
+            self.endpoints.len()
+        }
+        fn next_endpoint(&self) {
+            self.endpoints.advance();
+        }
     }
 
     impl Stream for MockSocket {}
@@ -945,7 +1116,7 @@ mod tests {
             let response = responses.get(read_call_count).unwrap();
 
             // disconnect if a null byte response is encountered
-            if response.fields[0] == "\0" {
+            if response.field(0) == Some("\0") {
                 return Err(mock_socket_error(ErrorKind::ConnectionReset));
             }
 
@@ -986,7 +1157,7 @@ mod tests {
             // Message encode() cannot be used to encode the handshake
             let expected = if is_handshake {
                 assert_eq!(request.len(), 1);
-                &encode_length(&request.fields[0])
+                &encode_length(&request[0])
             } else {
                 &encode_length(&request.encode())
             };
@@ -1076,6 +1247,69 @@ mod tests {
         Ok(())
     }
 
+    #[derive(Default)]
+    struct CountingMetrics {
+        counters: Mutex<HashMap<String, u64>>,
+        gauges: Mutex<HashMap<String, f64>>,
+        histograms: AtomicUsize,
+    }
+
+    impl Metrics for CountingMetrics {
+        fn increment_counter(&self, name: &'static str, labels: &[Label], value: u64) {
+            *self.counters.lock().unwrap().entry(format!("{name}{labels:?}")).or_default() += value;
+        }
+
+        fn set_gauge(&self, name: &'static str, labels: &[Label], value: f64) {
+            self.gauges.lock().unwrap().insert(format!("{name}{labels:?}"), value);
+        }
+
+        fn record_histogram(&self, _name: &'static str, _labels: &[Label], _value: f64) {
+            self.histograms.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_bus_emits_metrics() -> Result<(), Error> {
+        let request = RequestMessage::from_simple("2|2|9000|");
+
+        let events = vec![
+            Exchange::simple("v100..173", &["173|20250415 19:38:30 British Summer Time|"]),
+            Exchange::simple("71|2|28||", &["15|1|DU1234567|", "9|1|5|"]),
+            Exchange::simple("1|11|9000|", &["1|6|9000|1|185.50|100|7|", "2|6|9001|0|100|"]),
+            Exchange::request(request.clone(), &[]),
+        ];
+
+        let stream = MockSocket::new(events, 0);
+        let connection = Connection::connect(stream, 28)?;
+        let server_version = connection.server_version();
+        let bus = Arc::new(TcpMessageBus::new(connection)?);
+
+        let metrics = Arc::new(CountingMetrics::default());
+        bus.set_metrics(metrics.clone());
+
+        let subscription = bus.send_request(9000, &RequestMessage::from_simple("1|11|9000|"))?;
+
+        bus.dispatch(server_version)?;
+        bus.dispatch(server_version)?;
+        subscription.next().unwrap()?;
+
+        bus.cancel_subscription(9000, &request)?;
+
+        let counters = metrics.counters.lock().unwrap().clone();
+        assert_eq!(counters[r#"ibapi_requests_sent_total[("message_type", "RequestMarketData")]"#], 1);
+        assert_eq!(counters[r#"ibapi_messages_received_total[("message_type", "TickPrice")]"#], 1);
+        assert_eq!(counters[r#"ibapi_messages_received_total[("message_type", "TickSize")]"#], 1);
+        assert_eq!(counters[r#"ibapi_orphaned_responses_total[("message_type", "TickSize")]"#], 1);
+        assert_eq!(counters[r#"ibapi_cancellations_total[("message_type", "CancelMarketData")]"#], 1);
+        assert_eq!(metrics.histograms.load(Ordering::SeqCst), 2, "decode latency of each frame");
+
+        let gauges = metrics.gauges.lock().unwrap().clone();
+        assert_eq!(gauges[r#"ibapi_subscription_backlog[("message_type", "TickPrice")]"#], 1.0);
+        assert_eq!(gauges[r#"ibapi_active_subscriptions[("kind", "request")]"#], 0.0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_connection_establish_connection() -> Result<(), Error> {
         let events = vec![
@@ -1137,6 +1371,59 @@ mod tests {
         connection.reconnect()
     }
 
+    #[test]
+    fn test_reconnect_fails_over_to_next_endpoint() -> Result<(), Error> {
+        let events = vec![
+            Exchange::simple("v100..173", &["173|20250323 22:21:01 Greenwich Mean Time|"]),
+            Exchange::simple("71|2|28||", &["15|1|DU1234567|", "9|1|1|", "\0"]), // RESTART
+            Exchange::simple("v100..173", &["173|20250323 22:21:01 Greenwich Mean Time|"]),
+            Exchange::simple("71|2|28||", &["15|1|DU1234567|", "9|1|500|"]),
+        ];
+        // The primary exhausts its retries, the standby answers on its third attempt.
+        let socket = MockSocket::new(events, MAX_RETRIES as usize + 2).with_endpoints(&["primary:4002", "standby:4002"]);
+
+        let connection = Connection::stubbed(socket, 28);
+        connection.establish_connection()?;
+        let events = connection.events.subscribe();
+
+        // simulated dispatcher thread read to trigger disconnection
+        let _ = connection.read_message();
+
+        connection.reconnect()?;
+
+        assert_eq!(
+            events.try_next_event(),
+            Some(ConnectionEvent::FailedOver {
+                from: "primary:4002".into(),
+                to: "standby:4002".into()
+            })
+        );
+        assert_eq!(connection.socket.endpoint(), "standby:4002");
+        assert_eq!(connection.connection_metadata().next_order_id, 500);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconnect_failed_on_every_endpoint() -> Result<(), Error> {
+        let events = vec![
+            Exchange::simple("v100..173", &["173|20250323 22:21:01 Greenwich Mean Time|"]),
+            Exchange::simple("71|2|28||", &["15|1|DU1234567|", "9|1|1|", "\0"]), // RESTART
+        ];
+        let socket = MockSocket::new(events, 2 * MAX_RETRIES as usize + 1).with_endpoints(&["primary:4002", "standby:4002"]);
+
+        let connection = Connection::stubbed(socket, 28);
+        connection.establish_connection()?;
+        let events = connection.events.subscribe();
+
+        let _ = connection.read_message();
+
+        assert!(matches!(connection.reconnect(), Err(Error::ConnectionFailed)));
+        assert_eq!(events.try_next_event(), Some(ConnectionEvent::Disconnected));
+
+        Ok(())
+    }
+
     #[test]
     fn test_client_reconnect() -> Result<(), Error> {
         let events = vec![
@@ -1294,7 +1581,7 @@ mod tests {
     fn test_request_simple_encoding_roundtrip() {
         let expected = "17|1|";
         let req = RequestMessage::from_simple(expected);
-        assert_eq!(req.fields, vec!["17", "1"]);
+        assert_eq!(req.fields().collect::<Vec<_>>(), vec!["17", "1"]);
         let simple_encoded = req.encode_simple();
         assert_eq!(simple_encoded, expected);
     }
@@ -1303,7 +1590,7 @@ mod tests {
     fn test_request_encoding_roundtrip() {
         let expected = "17\01\0";
         let req = RequestMessage::from(expected);
-        assert_eq!(req.fields, vec!["17", "1"]);
+        assert_eq!(req.fields().collect::<Vec<_>>(), vec!["17", "1"]);
         let encoded = req.encode();
         assert_eq!(encoded, expected);
     }
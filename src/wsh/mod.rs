@@ -17,7 +17,7 @@ use common::encoders;
 
 // Feature-specific implementations
 #[cfg(feature = "sync")]
-mod sync;
+pub(crate) mod sync;
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -59,7 +59,7 @@ impl AutoFill {
 }
 
 // Re-export API functions based on active feature
-#[cfg(feature = "sync")]
+#[cfg(all(feature = "sync", not(feature = "async")))]
 pub use sync::{wsh_event_data_by_contract, wsh_event_data_by_filter, wsh_metadata};
 
 #[cfg(feature = "async")]
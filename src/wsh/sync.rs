@@ -3,11 +3,12 @@
 use time::Date;
 
 use crate::{
-    client::Subscription,
-    common::request_helpers,
+    client::sync::Client,
+    common::request_helpers::sync_helpers as request_helpers,
     messages::OutgoingMessages,
     protocol::{check_version, Features},
-    Client, Error,
+    subscriptions::sync::Subscription,
+    Error,
 };
 
 use super::{common::decoders, encoders, AutoFill, WshEventData, WshMetadata};